@@ -716,6 +716,1508 @@ mod tests {
 
 
 
+        Ok(())
+    }
+
+    #[test]
+    fn single_box_signed_distance_field_test() -> Result<(), String> {
+        use crate::utils::utils_collisions::prelude::*;
+        use nalgebra::Vector3;
+
+        // build a signed distance field from environment "single_box" (included in assets/mesh_environments)
+        let collision_environment = CollisionEnvironment::new_with_environment_name("single_box")?;
+        let signed_distance_field = SignedDistanceField::new_from_collision_environment(&collision_environment, 0.05, 0.3)?;
+
+        // the center of the box should be inside geometry, and a point far away should not be
+        let box_center = collision_environment.environment_obbs[0][0].curr_pose.translation.clone();
+        assert!(signed_distance_field.distance(&box_center) < 0.0);
+        assert!(signed_distance_field.distance(&(box_center + Vector3::new(0.0, 0.0, 5.0))) > 4.0);
+
+        // the analytic gradient of the interpolated field should match finite differencing
+        let point = box_center + Vector3::new(0.13, -0.21, 0.37);
+        let gradient = signed_distance_field.gradient(&point);
+        let p = 0.000001;
+        for k in 0..3 {
+            let mut point_h = point.clone();
+            point_h[k] += p;
+            let gradient_fd = (signed_distance_field.distance(&point_h) - signed_distance_field.distance(&point)) / p;
+            assert!((gradient[k] - gradient_fd).abs() < 0.001);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn voxel_occupancy_grid_ray_clearing_test() -> Result<(), String> {
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_pointclouds::pointcloud::PointCloud;
+        use nalgebra::Vector3;
+
+        let mut occupancy_grid = VoxelOccupancyGrid::new(0.1)?;
+        let sensor_origin = Vector3::new(0.05, 0.05, 0.05);
+
+        // a wall of points at x = 1.0 marks the wall as occupied and the space in front of it as free
+        let mut wall = Vec::new();
+        for i in 0..10 { for j in 0..10 { wall.push(Vector3::new(1.05, -0.45 + i as f64 * 0.1, -0.45 + j as f64 * 0.1)); } }
+        occupancy_grid.insert_point_cloud(&PointCloud::new(wall.clone()), &sensor_origin, None);
+
+        assert!(occupancy_grid.is_occupied(&Vector3::new(1.05, 0.05, 0.05)));
+        assert!(occupancy_grid.is_known(&Vector3::new(0.55, 0.05, 0.05)));
+        assert!(!occupancy_grid.is_occupied(&Vector3::new(0.55, 0.05, 0.05)));
+        assert!(!occupancy_grid.is_known(&Vector3::new(1.55, 0.05, 0.05)));
+
+        // after the wall moves back, repeated observations clear its old voxels
+        let moved_wall = PointCloud::new(wall.iter().map(|p| p + Vector3::new(1.0, 0.0, 0.0)).collect());
+        for _ in 0..5 { occupancy_grid.insert_point_cloud(&moved_wall, &sensor_origin, None); }
+
+        assert!(!occupancy_grid.is_occupied(&Vector3::new(1.05, 0.05, 0.05)));
+        assert!(occupancy_grid.is_occupied(&Vector3::new(2.05, 0.05, 0.05)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn environment_broad_phase_matches_exhaustive_queries_test() -> Result<(), String> {
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_pointclouds::pointcloud::PointCloud;
+        use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
+        use nalgebra::Vector3;
+
+        let mut occupancy_grid = VoxelOccupancyGrid::new(0.2)?;
+        let mut points = Vec::new();
+        for i in 0..8 { for j in 0..8 { points.push(Vector3::new(-0.7 + i as f64 * 0.2, -0.7 + j as f64 * 0.2, 0.1)); } }
+        occupancy_grid.insert_point_cloud(&PointCloud::new(points), &Vector3::new(0.0, 0.0, 2.0), None);
+        let (components, trimesh_engines) = occupancy_grid.get_occupied_voxel_collision_objects("floor");
+
+        let mut collision_environment = CollisionEnvironment::new_empty("broad_phase_test");
+        collision_environment.set_object_components_by_name("floor", components, trimesh_engines)?;
+
+        let mut group = vec![ vec![CollisionObject::new_cuboid(0.1, 0.1, 0.1, None, None)], vec![CollisionObject::new_cuboid(0.05, 0.3, 0.05, None, None)] ];
+        group[0][0].set_curr_translation(0.3, 0.3, 0.25);
+        group[1][0].set_curr_translation(-0.5, 0.0, 0.5);
+        for g in &mut group { for c in g { c.update_all_bounding_volumes(); } }
+
+        for _ in 0..2 {
+            let exhaustive = intersect_check_between_multiple_collision_objects(&collision_environment.environment_obbs, &group, false, None)?;
+            let broad_phase = intersect_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, None)?;
+            assert!(exhaustive.get_intersect_check_multiple_info_ref().get_intersection_idxs() == broad_phase.get_intersect_check_multiple_info_ref().get_intersection_idxs());
+
+            let exhaustive = contact_check_between_multiple_collision_objects(&collision_environment.environment_obbs, &group, false, Some(0.1), None, None)?;
+            let broad_phase = contact_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, Some(0.1), None, None)?;
+            assert!(exhaustive.get_contact_check_multiple_info_ref().get_contact_check_idxs() == broad_phase.get_contact_check_multiple_info_ref().get_contact_check_idxs());
+
+            let exhaustive = distance_check_between_multiple_collision_objects(&collision_environment.environment_obbs, &group, false, None, None)?;
+            let broad_phase = distance_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, None, None)?;
+            assert!(exhaustive.get_distance_check_multiple_info_ref().get_distance_check_idxs() == broad_phase.get_distance_check_multiple_info_ref().get_distance_check_idxs());
+
+            // moving the environment object must refit the broad phase
+            collision_environment.update_object_transform_by_name("floor", &ImplicitDualQuaternion::new_from_euler_angles(0., 0., 0., Vector3::new(0.0, 0.0, 0.2)))?;
+        }
+
+        collision_environment.broad_phase.set_distance_check_cutoff(Some(0.1));
+        let broad_phase = distance_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, None, None)?;
+        let info = broad_phase.get_distance_check_multiple_info_ref();
+        assert!(!info.get_distance_check_idxs().is_empty());
+        assert!(info.get_distance_check_idxs().len() < collision_environment.broad_phase.get_num_components() * 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn collision_object_padding_test() -> Result<(), String> {
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
+        use nalgebra::Vector3;
+
+        // two unit cubes with a gap of 0.1 between them
+        let mut collision_environment = CollisionEnvironment::new_empty("padding_test");
+        let box_engine = TriMeshEngine::new(Vec::new(), Vec::new());
+        collision_environment.set_object_components_by_name("box", vec![CollisionObject::new_cuboid(0.5, 0.5, 0.5, None, None)], vec![box_engine])?;
+        let mut group = vec![ vec![CollisionObject::new_cuboid(0.5, 0.5, 0.5, Some(ImplicitDualQuaternion::new_from_euler_angles(0., 0., 0., Vector3::new(1.1, 0., 0.))), None)] ];
+        group[0][0].update_all_bounding_volumes();
+
+        let res = distance_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, None, None)?;
+        assert!((res.get_distance_check_multiple_info_ref().get_distance_check_distances()[0] - 0.1).abs() < 0.0001);
+        assert!(!intersect_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, None)?.is_in_collision());
+
+        group[0][0].set_padding(0.03)?;
+        collision_environment.set_object_padding_by_name("box", 0.03)?;
+        let res = distance_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, None, None)?;
+        assert!((res.get_distance_check_multiple_info_ref().get_distance_check_distances()[0] - 0.04).abs() < 0.0001);
+        assert!(!intersect_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, None)?.is_in_collision());
+
+        collision_environment.set_object_padding_by_name("box", 0.09)?;
+        let res = distance_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, None, None)?;
+        assert!((res.get_distance_check_multiple_info_ref().get_distance_check_distances()[0] + 0.02).abs() < 0.0001);
+        assert!(intersect_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, None)?.is_in_collision());
+        let res = contact_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, Some(0.0), None, None)?;
+        assert!((res.get_contact_check_multiple_info_ref().get_contact_check_contacts()[0].depth - 0.02).abs() < 0.0001);
+
+        assert!(group[0][0].set_padding(-0.1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn time_varying_environment_test() -> Result<(), String> {
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
+        use crate::utils::utils_paths::{timed_path::TimedPath, linear_spline_path::LinearSplinePath};
+        use ncollide3d::math::Point;
+        use nalgebra::Vector3;
+
+        // unit cube that slides from x = 0 to x = 4 over two seconds
+        let mut vertices = Vec::new();
+        for x in &[-0.5, 0.5] { for y in &[-0.5, 0.5] { for z in &[-0.5, 0.5] { vertices.push(Point::new(*x, *y, *z)); } } }
+        let indices = vec![ [0,1,3], [0,3,2], [4,6,7], [4,7,5], [0,4,5], [0,5,1], [2,3,7], [2,7,6], [0,2,6], [0,6,4], [1,5,7], [1,7,3] ];
+        let box_engine = TriMeshEngine::new(vertices, indices.iter().map(|t| Point::new(t[0], t[1], t[2])).collect());
+        let mut collision_environment = CollisionEnvironment::new_empty("time_varying_test");
+        collision_environment.set_object_components_by_name("box", vec![CollisionObject::new_cuboid_from_trimesh_engine(&box_engine, Some("box_0".to_string()))], vec![box_engine])?;
+
+        let mut trajectory = ObjectPoseTrajectory::new_empty(false);
+        trajectory.add_waypoint(0.0, &ImplicitDualQuaternion::new_identity())?;
+        trajectory.add_waypoint(2.0, &ImplicitDualQuaternion::new_from_euler_angles(0., 0., 0., Vector3::new(4.0, 0., 0.)))?;
+        assert!(trajectory.add_waypoint(1.0, &ImplicitDualQuaternion::new_identity()).is_err());
+        assert!((trajectory.get_pose_at_time(0.5)?.translation - Vector3::new(1.0, 0., 0.)).norm() < 0.000001);
+        assert!((trajectory.get_pose_at_time(5.0)?.translation - Vector3::new(4.0, 0., 0.)).norm() < 0.000001);
+        collision_environment.set_object_trajectory_by_name("box", Some(trajectory.clone()))?;
+
+        let mut group = vec![ vec![CollisionObject::new_cuboid(0.25, 0.25, 0.25, Some(ImplicitDualQuaternion::new_from_euler_angles(0., 0., 0., Vector3::new(2.0, 0., 0.))), None)] ];
+        group[0][0].update_all_bounding_volumes();
+
+        for (time, in_collision) in vec![ (0.0, false), (1.0, true), (2.0, false) ] {
+            collision_environment.set_time(time)?;
+            assert_eq!(intersect_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, None)?.is_in_collision(), in_collision);
+        }
+
+        // looping trajectories repeat with period equal to their duration
+        let looping_trajectory = ObjectPoseTrajectory::new(trajectory.get_waypoints_ref().clone(), true)?;
+        assert!((looping_trajectory.get_pose_at_time(2.5)?.translation - Vector3::new(1.0, 0., 0.)).norm() < 0.000001);
+
+        // trajectories are saved with the environment metadata
+        let reloaded = CollisionEnvironment::new_with_json_string(collision_environment.get_json_string())?;
+        assert!(reloaded.has_moving_objects());
+        assert_eq!(reloaded.get_trajectories_end_time(), Some(2.0));
+
+        // timed paths assign times by arclength and interpolate between waypoints
+        let timed_path = TimedPath::new_from_linear_spline_path(&LinearSplinePath::new_from_vecs(vec![vec![0.0], vec![1.0], vec![3.0]]), 1.0, 3.0)?;
+        assert_eq!(timed_path.times, vec![1.0, 2.0, 4.0]);
+        assert!((timed_path.get_state_at_time(3.0)?[0] - 2.0).abs() < 0.000001);
+        assert!(TimedPath::new_from_vecs(vec![vec![0.0], vec![1.0]], vec![1.0, 1.0]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn point_cloud_file_round_trip_test() -> Result<(), String> {
+        use crate::utils::utils_pointclouds::pointcloud::PointCloud;
+        use nalgebra::Vector3;
+
+        let points = vec![Vector3::new(0.1, -0.2, 0.3), Vector3::new(1.5, 2.25, -3.0)];
+        let normals = vec![Vector3::new(0.0, 0.0, 1.0), Vector3::new(1.0, 0.0, 0.0)];
+        let colors = vec![[255, 0, 10], [3, 128, 200]];
+
+        // each format (and binary variant, where supported) should load back the same points, normals, and colors,
+        // including colors without normals
+        let dir = std::env::temp_dir();
+        for with_normals in vec![true, false] {
+            let point_cloud = PointCloud::new_with_normals_and_colors(points.clone(), if with_normals { Some(normals.clone()) } else { None }, Some(colors.clone()))?;
+            for (file_name, binary) in vec![("lynx_round_trip.ply", false), ("lynx_round_trip_binary.ply", true), ("lynx_round_trip.pcd", false), ("lynx_round_trip_binary.pcd", true), ("lynx_round_trip.xyz", false)] {
+                let fp = dir.join(file_name).to_str().unwrap().to_string();
+                point_cloud.output_to_path(fp.clone(), binary)?;
+                let loaded = PointCloud::new_from_path(fp)?;
+
+                assert_eq!(loaded.get_num_points(), 2);
+                assert_eq!(loaded.get_normals_ref().is_some(), with_normals);
+                for i in 0..2 {
+                    assert!((loaded.get_points_ref()[i] - points[i]).norm() < 0.000001);
+                    if with_normals { assert!((loaded.get_normals_ref().as_ref().unwrap()[i] - normals[i]).norm() < 0.000001); }
+                    assert_eq!(loaded.get_colors_ref().as_ref().unwrap()[i], colors[i]);
+                }
+            }
+        }
+
+        // multi-valued fields are skipped over as a whole, but fields read into the point cloud must have one value
+        let fp = dir.join("lynx_count.pcd").to_str().unwrap().to_string();
+        std::fs::write(&fp, "VERSION 0.7\nFIELDS x y z feature\nSIZE 4 4 4 4\nTYPE F F F F\nCOUNT 1 1 1 3\nWIDTH 2\nHEIGHT 1\nPOINTS 2\nDATA ascii\n0.1 -0.2 0.3 7 8 9\n1.5 2.25 -3 7 8 9\n").unwrap();
+        let loaded = PointCloud::new_from_path(fp.clone())?;
+        for i in 0..2 { assert!((loaded.get_points_ref()[i] - points[i]).norm() < 0.000001); }
+        std::fs::write(&fp, "VERSION 0.7\nFIELDS x y z\nSIZE 4 4 4\nTYPE F F F\nCOUNT 2 1 1\nWIDTH 1\nHEIGHT 1\nPOINTS 1\nDATA ascii\n0.1 0.2 -0.2 0.3\n").unwrap();
+        assert!(PointCloud::new_from_path(fp).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn srdf_round_trip_test() -> Result<(), String> {
+        use crate::utils::utils_parsing::srdf_parsing_utils::*;
+
+        let srdf_string = r#"<?xml version="1.0" ?>
+<robot name="arm">
+    <group name="manipulator">
+        <chain base_link="base_link" tip_link="tool0" />
+    </group>
+    <group name="gripper">
+        <link name="finger" />
+        <joint name="finger_joint" />
+    </group>
+    <group_state name="home" group="manipulator">
+        <joint name="shoulder_pan_joint" value="0.5" />
+        <joint name="elbow_joint" value="-1.25" />
+        <joint name="caster_joint" value="0.1 -0.2 0.3" />
+    </group_state>
+    <end_effector name="hand" parent_link="tool0" group="gripper" parent_group="manipulator" />
+    <virtual_joint name="fixed_base" type="fixed" parent_frame="world" child_link="base_link" />
+    <passive_joint name="caster_joint" />
+    <disable_collisions link1="base_link" link2="shoulder_link" reason="Adjacent" />
+</robot>"#.to_string();
+
+        let srdf_data = parse_srdf_string(&srdf_string)?;
+        let fp = std::env::temp_dir().join("lynx_round_trip.srdf").to_str().unwrap().to_string();
+        write_srdf_file(fp.clone(), &srdf_data)?;
+        let loaded = read_srdf_file(fp)?;
+
+        assert_eq!(loaded.robot_name, "arm");
+        assert_eq!(loaded.groups.len(), 2);
+        assert_eq!(loaded.groups[0].chains[0], ("base_link".to_string(), "tool0".to_string()));
+        assert_eq!(loaded.groups[1].links, vec!["finger".to_string()]);
+        assert_eq!(loaded.groups[1].joints, vec!["finger_joint".to_string()]);
+        assert_eq!(loaded.group_states[0].joint_values, vec![("shoulder_pan_joint".to_string(), vec![0.5]), ("elbow_joint".to_string(), vec![-1.25]), ("caster_joint".to_string(), vec![0.1, -0.2, 0.3])]);
+        assert_eq!(loaded.end_effectors[0].parent_group, Some("manipulator".to_string()));
+        assert_eq!(loaded.virtual_joints[0].joint_type, "fixed");
+        assert_eq!(loaded.passive_joints, vec!["caster_joint".to_string()]);
+        assert_eq!(loaded.disable_collisions[0].link2, "shoulder_link");
+        assert_eq!(loaded.disable_collisions[0].reason, "Adjacent");
+
+        assert!(parse_srdf_string(&"<robot name=\"arm\"><group /></robot>".to_string()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sphere_fit_covers_mesh_test() -> Result<(), String> {
+        use crate::utils::utils_collisions::triangle_mesh_engine::TriMeshEngine;
+        use crate::utils::utils_preprocessing::mesh_preprocessing_utils::fit_spheres_to_trimesh;
+        use ncollide3d::math::Point;
+
+        // elongated box, 2 x 0.4 x 0.2
+        let mut vertices = Vec::new();
+        for x in &[-1.0, 1.0] { for y in &[-0.2, 0.2] { for z in &[-0.1, 0.1] { vertices.push(Point::new(*x, *y, *z)); } } }
+        let indices = vec![ [0,1,3], [0,3,2], [4,6,7], [4,7,5], [0,4,5], [0,5,1], [2,3,7], [2,7,6], [0,2,6], [0,6,4], [1,5,7], [1,7,3] ];
+        let trimesh = TriMeshEngine::new(vertices, indices.iter().map(|t| Point::new(t[0], t[1], t[2])).collect());
+
+        for num_spheres in vec![1, 4, 8] {
+            let spheres = fit_spheres_to_trimesh(&trimesh, num_spheres);
+            assert!(spheres.len() >= 1 && spheres.len() <= num_spheres);
+
+            // every triangle has all of its vertices inside a single sphere
+            for t in &trimesh.indices {
+                let covered = spheres.iter().any(|(c, r)| (0..3).all(|k| (trimesh.vertices[t[k]].coords - c).norm() <= r + 0.000001));
+                assert!(covered);
+            }
+        }
+
+        // on a finely triangulated ribbon, more spheres should give a much tighter fit
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for i in 0..11 {
+            vertices.push(Point::new(-1.0 + 0.2 * i as f64, -0.1, 0.0));
+            vertices.push(Point::new(-1.0 + 0.2 * i as f64, 0.1, 0.0));
+            if i > 0 {
+                indices.push(Point::new(2*i - 2, 2*i - 1, 2*i + 1));
+                indices.push(Point::new(2*i - 2, 2*i + 1, 2*i));
+            }
+        }
+        let ribbon = TriMeshEngine::new(vertices, indices);
+        let max_radius_1 = fit_spheres_to_trimesh(&ribbon, 1).iter().map(|s| s.1).fold(0.0, f64::max);
+        let max_radius_8 = fit_spheres_to_trimesh(&ribbon, 8).iter().map(|s| s.1).fold(0.0, f64::max);
+        assert!(max_radius_8 < 0.5 * max_radius_1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn collision_avoidance_analytic_gradient_test() -> Result<(), String> {
+        use crate::robot_modules::prelude::*;
+        use crate::utils::utils_vars::prelude::*;
+        use crate::utils::utils_optimization::prelude::*;
+        use crate::utils::utils_recorders::prelude::*;
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
+        use crate::utils::utils_math::prelude::vec_to_dvec;
+        use rayon::prelude::*;
+
+        let mut lynx_vars = LynxVarsGeneric::new_single_threaded_packaged_with_robot_world(vec!["ur5"], vec![None], Some("single_box"))?;
+        let recorder = RecorderArcMutexOption::new_none();
+
+        // the link point jacobian should match finite differencing through fk
+        let robot = Robot::new("ur5", None)?;
+        let x = vec_to_dvec(&vec![0.3, -0.4, 0.8, 0.2, -0.5, 0.1]);
+        let fk_res = robot.get_fk_module_ref().compute_fk(&x)?;
+        let point = fk_res.get_link_frames_ref()[7].as_ref().unwrap().multiply_by_vector3(&nalgebra::Vector3::new(0.05, -0.02, 0.1));
+        let jacobian = robot.get_fk_module_ref().compute_link_point_jacobian(&fk_res, 7, &point)?;
+        let p = 0.000001;
+        for i in 0..6 {
+            let mut x_h = x.clone();
+            x_h[i] += p;
+            let fk_res_h = robot.get_fk_module_ref().compute_fk(&x_h)?;
+            let point_h = fk_res_h.get_link_frames_ref()[7].as_ref().unwrap().multiply_by_vector3(&nalgebra::Vector3::new(0.05, -0.02, 0.1));
+            for k in 0..3 {
+                assert!((jacobian[(k, i)] - (point_h[k] - point[k]) / p).abs() < 0.0001);
+            }
+        }
+
+        // analytic gradients should match the default finite differencing gradients, near the box and in self collision
+        let environment_term = RobotEnvironmentCollisionAvoidance::new(LinkGeometryType::OBBs, 0.2);
+        let x = vec_to_dvec(&vec![1.4, 0.1, -1.4, 0.2, 0.1, 0.0]);
+        assert!(environment_term.call(&x, &mut lynx_vars, &recorder)? > 0.0);
+        let gradient = environment_term.gradient(&x, &mut lynx_vars, &recorder)?;
+        let gradient_fd = environment_term.gradient_finite_differencing(&x, &mut lynx_vars, &recorder)?;
+        for i in 0..6 {
+            assert!((gradient[i] - gradient_fd[i]).abs() < 0.01 * gradient_fd[i].abs().max(1.0));
+        }
+
+        let self_term = RobotSelfCollisionAvoidance::new(LinkGeometryType::OBBs, 0.05);
+        let x = vec_to_dvec(&vec![0.0, 0.0, 2.8, 0.1, 0.2, 0.0]);
+        assert!(self_term.call(&x, &mut lynx_vars, &recorder)? > 0.0);
+        let gradient = self_term.gradient(&x, &mut lynx_vars, &recorder)?;
+        let gradient_fd = self_term.gradient_finite_differencing(&x, &mut lynx_vars, &recorder)?;
+        for i in 0..6 {
+            assert!((gradient[i] - gradient_fd[i]).abs() < 0.01 * gradient_fd[i].abs().max(1.0));
+        }
+
+        // the signed distance field term's analytic gradient comes from the field gradient at each sphere center
+        let collision_environment = CollisionEnvironment::new_with_environment_name("single_box")?;
+        let signed_distance_field = SignedDistanceField::new_from_collision_environment(&collision_environment, 0.05, 0.5)?;
+        set_or_add_lynx_var_generic!(&mut lynx_vars, SignedDistanceField, "signed_distance_field", signed_distance_field)?;
+        let sdf_term = RobotSignedDistanceFieldCollisionAvoidance::new(lynx_vars.get_robot_set_ref_via_robot_world(None)?, LinkGeometryType::OBBs, 0.2);
+        let x = vec_to_dvec(&vec![1.4, 0.1, -1.4, 0.2, 0.1, 0.0]);
+        assert!(sdf_term.call(&x, &mut lynx_vars, &recorder)? > 0.0);
+        let gradient = sdf_term.gradient(&x, &mut lynx_vars, &recorder)?;
+        let gradient_fd = sdf_term.gradient_finite_differencing(&x, &mut lynx_vars, &recorder)?;
+        for i in 0..6 {
+            assert!((gradient[i] - gradient_fd[i]).abs() < 0.01 * gradient_fd[i].abs().max(1.0));
+        }
+
+        // links on a mounted robot also move with the parent robot's dofs
+        let mut lynx_vars = LynxVarsGeneric::new_single_threaded_packaged_with_robot_world(vec!["ur5", "ur5"], vec![None, None], None)?;
+        let x = vec_to_dvec(&vec![0.3, -0.8, 1.0, 0.2, 0.5, 0.1, 0.0, 0.0, 2.8, 0.1, 0.2, 0.0]);
+        {
+            let robot_world = get_lynx_var_mut_ref_generic!(&mut lynx_vars, RobotWorld, "robot_world")?;
+            robot_world.get_robot_set_mut_ref().mount_robot_on_link(1, 0, "wrist_3_link")?;
+            let fk_res = robot_world.get_robot_set_ref().compute_fk(&x)?;
+            let forearm_idx = robot_world.get_robot_set_ref().get_robots_ref()[1].get_configuration_module_ref().robot_model_module.get_link_idx_from_name(&"forearm_link".to_string()).unwrap();
+            let forearm_frame = fk_res.get_robot_fk_results_ref()[1].get_link_frames_ref()[forearm_idx].as_ref().unwrap().clone();
+            let obstacle_pose = ImplicitDualQuaternion::new_from_euler_angles(0., 0., 0., forearm_frame.multiply_by_vector3(&nalgebra::Vector3::new(0.15, 0.0, 0.2)));
+            let mut collision_environment = CollisionEnvironment::new_empty("mounted_gradient_test");
+            collision_environment.set_object_components_by_name("obstacle", vec![CollisionObject::new_cuboid(0.05, 0.05, 0.05, Some(obstacle_pose), None)], vec![TriMeshEngine::new(Vec::new(), Vec::new())])?;
+            robot_world.set_collision_environment(collision_environment)?;
+        }
+
+        // (the obstacle sits next to the child's forearm, so the environment term must depend on the parent's dofs)
+        for (term, depends_on_parent) in vec![ (RobotEnvironmentCollisionAvoidance::new(LinkGeometryType::OBBs, 0.2).to_isolated_objective_term_box(), true), (RobotSelfCollisionAvoidance::new(LinkGeometryType::OBBs, 0.05).to_isolated_objective_term_box(), false) ] {
+            assert!(term.call(&x, &mut lynx_vars, &recorder)? > 0.0);
+            let gradient = term.gradient(&x, &mut lynx_vars, &recorder)?;
+            let gradient_fd = term.gradient_finite_differencing(&x, &mut lynx_vars, &recorder)?;
+            if depends_on_parent { assert!((0..6).any(|i| gradient_fd[i].abs() > 0.001)); }
+            for i in 0..12 {
+                assert!((gradient[i] - gradient_fd[i]).abs() < 0.01 * gradient_fd[i].abs().max(1.0));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn environment_scene_round_trip_test() -> Result<(), String> {
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_parsing::environment_scene_parsing_utils::*;
+        use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
+        use nalgebra::Vector3;
+
+        let yaml_string = "
+name: scene_test
+objects:
+  - name: table
+    shape: {type: box, half_extents: [0.5, 0.8, 0.02]}
+    translation: [0.6, 0, 0.7]
+  - name: ball
+    shape: {type: sphere, radius: 0.1}
+    translation: [2.0, 0.0, 0.0]
+    padding: 0.05
+  - name: can
+    shape: {type: cylinder, radius: 0.04, length: 0.5}
+    translation: [-1.0, 0.0, 0.0]
+    rotation: [0.0, 0.0, 1.0]
+  - name: pole
+    shape: {type: capsule, radius: 0.05, length: 1.0}
+    translation: [0.0, -2.0, 0.0]
+  - name: floor
+    shape: {type: plane, size: [4.0, 4.0]}
+    translation: [0.0, 0.0, -1.0]
+".to_string();
+        let scene = parse_environment_scene_yaml_string(&yaml_string)?;
+        assert_eq!(scene.objects.len(), 5);
+        assert_eq!(scene.objects[0].shape, SceneShape::Box { half_extents: [0.5, 0.8, 0.02] });
+        assert!(parse_environment_scene_json_string(&"{\"objects\": [{\"name\": \"a\", \"shape\": {\"type\": \"cone\"}}]}".to_string()).is_err());
+
+        let collision_environment = environment_scene_to_collision_environment(&scene, None)?;
+        assert_eq!(collision_environment.get_num_objects(), 5);
+
+        // cylinders are aligned with their local z axis, and paddings are applied
+        let probe = |t: Vector3<f64>| -> Result<bool, String> {
+            let mut group = vec![ vec![CollisionObject::new_ball(0.01, Some(ImplicitDualQuaternion::new_from_euler_angles(0., 0., 0., t)), None)] ];
+            group[0][0].update_all_bounding_volumes();
+            return Ok(intersect_check_between_environment_and_multiple_collision_objects(&collision_environment, &group, false, None)?.is_in_collision());
+        };
+        assert!(probe(Vector3::new(-1.0, 0.0, 0.2))?);
+        assert!(!probe(Vector3::new(-1.0, 0.2, 0.0))?);
+        assert!(probe(Vector3::new(2.0, 0.0, 0.14))?);
+        assert!(probe(Vector3::new(0.0, -2.0, 0.54))?);
+        assert!(!probe(Vector3::new(0.0, -2.0, 0.6))?);
+
+        // exporting should give back the same scene, through both json and yaml
+        let exported = collision_environment_to_environment_scene(&collision_environment)?;
+        for reloaded in vec![ parse_environment_scene_json_string(&environment_scene_to_json_string(&exported))?, parse_environment_scene_yaml_string(&environment_scene_to_yaml_string(&exported)?)? ] {
+            assert_eq!(reloaded.name, "scene_test");
+            assert_eq!(reloaded.objects.len(), 5);
+            for (a, b) in scene.objects.iter().zip(reloaded.objects.iter()) {
+                assert_eq!(a.name, b.name);
+                assert!((a.padding - b.padding).abs() < 0.000001);
+                assert!((a.get_pose().translation - b.get_pose().translation).norm() < 0.000001);
+                assert!(a.get_pose().quat.angle_to(&b.get_pose().quat) < 0.000001);
+                match (&a.shape, &b.shape) {
+                    (SceneShape::Box { half_extents: h1 }, SceneShape::Box { half_extents: h2 }) => { for k in 0..3 { assert!((h1[k] - h2[k]).abs() < 0.000001); } }
+                    (SceneShape::Sphere { radius: r1 }, SceneShape::Sphere { radius: r2 }) => { assert!((r1 - r2).abs() < 0.000001); }
+                    (SceneShape::Cylinder { radius: r1, length: l1 }, SceneShape::Cylinder { radius: r2, length: l2 }) |
+                    (SceneShape::Capsule { radius: r1, length: l1 }, SceneShape::Capsule { radius: r2, length: l2 }) => { assert!((r1 - r2).abs() < 0.000001 && (l1 - l2).abs() < 0.000001); }
+                    (SceneShape::Plane { size: s1 }, SceneShape::Plane { size: s2 }) => { assert!((s1[0] - s2[0]).abs() < 0.000001 && (s1[1] - s2[1]).abs() < 0.000001); }
+                    _ => { panic!("shape of {:?} changed from {:?} to {:?}", a.name, a.shape, b.shape); }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn trimesh_ply_and_gltf_round_trip_test() -> Result<(), String> {
+        use crate::utils::utils_collisions::triangle_mesh_engine::TriMeshEngine;
+        use crate::utils::utils_files_and_strings::gltf_utils::base64_encode;
+        use ncollide3d::math::Point;
+
+        let mut vertices = Vec::new();
+        for x in &[-0.5, 0.5] { for y in &[-0.5, 0.5] { for z in &[-0.5, 0.5] { vertices.push(Point::new(*x, *y, *z)); } } }
+        let indices = vec![ [0,1,3], [0,3,2], [4,6,7], [4,7,5], [0,4,5], [0,5,1], [2,3,7], [2,7,6], [0,2,6], [0,6,4], [1,5,7], [1,7,3] ];
+        let box_engine = TriMeshEngine::new(vertices, indices.iter().map(|t| Point::new(t[0], t[1], t[2])).collect());
+
+        let dir = std::env::temp_dir();
+        for file_name in vec!["lynx_mesh_round_trip.ply", "lynx_mesh_round_trip_binary.ply", "lynx_mesh_round_trip.gltf", "lynx_mesh_round_trip.glb"] {
+            let fp = dir.join(file_name).to_str().unwrap().to_string();
+            if file_name.ends_with(".ply") { box_engine.output_to_ply(fp.clone(), file_name.contains("binary"))?; }
+            else { box_engine.output_to_gltf(fp.clone())?; }
+
+            let loaded = TriMeshEngine::new_from_path(fp)?;
+            assert_eq!(loaded.vertices, box_engine.vertices);
+            assert_eq!(loaded.indices, box_engine.indices);
+        }
+
+        // node transforms are accumulated down the hierarchy, and primitives are merged
+        let mut buffer = Vec::new();
+        for v in &[[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] { for k in 0..3 { buffer.extend_from_slice(&v[k].to_le_bytes()); } }
+        for i in &[0u16, 1, 2] { buffer.extend_from_slice(&i.to_le_bytes()); }
+        let gltf_string = serde_json::json!({
+            "asset": { "version": "2.0" },
+            "scenes": [ { "nodes": [0] } ],
+            "nodes": [ { "translation": [1.0, 0.0, 0.0], "children": [1] }, { "scale": [2.0, 2.0, 2.0], "rotation": [0.0, 0.0, 0.7071067811865476, 0.7071067811865476], "mesh": 0 } ],
+            "meshes": [ { "primitives": [ { "attributes": { "POSITION": 0 }, "indices": 1 }, { "attributes": { "POSITION": 0 } } ] } ],
+            "accessors": [
+                { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" },
+                { "bufferView": 0, "byteOffset": 36, "componentType": 5123, "count": 3, "type": "SCALAR" }
+            ],
+            "bufferViews": [ { "buffer": 0, "byteLength": 42 } ],
+            "buffers": [ { "byteLength": 42, "uri": "data:application/octet-stream;base64,".to_string() + base64_encode(&buffer).as_str() } ]
+        }).to_string();
+        let fp = dir.join("lynx_mesh_hierarchy.gltf").to_str().unwrap().to_string();
+        crate::utils::utils_files_and_strings::file_utils::write_string_to_file(dir.to_str().unwrap().to_string(), "lynx_mesh_hierarchy.gltf".to_string(), gltf_string, true);
+
+        let loaded = TriMeshEngine::new_from_gltf_path(fp)?;
+        assert_eq!(loaded.vertices.len(), 6);
+        assert_eq!(loaded.indices, vec![Point::new(0, 1, 2), Point::new(3, 4, 5)]);
+        assert!((loaded.vertices[1] - Point::new(1.0, 2.0, 0.0)).norm() < 0.000001);
+        assert!((loaded.vertices[5] - Point::new(-1.0, 0.0, 0.0)).norm() < 0.000001);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mesh_validation_and_repair_test() -> Result<(), String> {
+        use crate::utils::utils_collisions::triangle_mesh_engine::TriMeshEngine;
+        use crate::utils::utils_preprocessing::mesh_preprocessing_utils::*;
+        use ncollide3d::math::Point;
+
+        let mut vertices = Vec::new();
+        for x in &[-0.5, 0.5] { for y in &[-0.5, 0.5] { for z in &[-0.5, 0.5] { vertices.push(Point::new(*x, *y, *z)); } } }
+        let indices: Vec<Point<usize>> = vec![ [0,1,3], [0,3,2], [4,6,7], [4,7,5], [0,4,5], [0,5,1], [2,3,7], [2,7,6], [0,2,6], [0,6,4], [1,5,7], [1,7,3] ].iter().map(|t| Point::new(t[0], t[1], t[2])).collect();
+        let box_engine = TriMeshEngine::new(vertices.clone(), indices.clone());
+        assert!(validate_trimesh(&box_engine, 0.000001).is_valid());
+
+        // a single flipped triangle, and a whole box with inward normals
+        let mut flipped = box_engine.clone();
+        flipped.indices[0] = Point::new(0, 3, 1);
+        assert_eq!(validate_trimesh(&flipped, 0.000001).num_flipped_triangles, 1);
+        assert_eq!(repair_trimesh(&flipped, &MeshRepairOptions::new_default()).indices, indices);
+
+        let mut inverted = box_engine.clone();
+        for t in &mut inverted.indices { *t = Point::new(t[0], t[2], t[1]); }
+        assert_eq!(validate_trimesh(&inverted, 0.000001).num_flipped_triangles, 12);
+        assert_eq!(repair_trimesh(&inverted, &MeshRepairOptions::new_default()).indices, indices);
+
+        // an unwelded seam, a degenerate triangle, and a hole
+        let mut broken = box_engine.clone();
+        broken.vertices.push(Point::new(-0.5, -0.5, -0.5 + 0.0000000001));
+        broken.indices[1] = Point::new(8, 3, 2);
+        broken.indices.push(Point::new(0, 0, 1));
+        let report = validate_trimesh(&broken, 0.000001);
+        assert_eq!(report.num_duplicate_vertices, 1);
+        assert_eq!(report.num_degenerate_triangles, 1);
+        assert!(!report.is_watertight());
+        assert!(validate_trimesh(&repair_trimesh(&broken, &MeshRepairOptions::new_default()), 0.000001).is_valid());
+        assert!(!validate_trimesh(&repair_trimesh(&broken, &MeshRepairOptions::new_validate_only()), 0.000001).is_valid());
+
+        let mut open = box_engine.clone();
+        open.indices.pop();
+        let report = validate_trimesh(&open, 0.000001);
+        assert_eq!(report.num_open_edges, 3);
+        assert_eq!(report.num_flipped_triangles, 0);
+
+        for mode in vec!["repair", "validate_only", "off"] {
+            assert_eq!(MeshRepairOptions::new_from_mode(mode)?.get_mode(), mode);
+        }
+        assert!(MeshRepairOptions::new_from_mode("fix").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn link_preprocessing_overrides_test() -> Result<(), String> {
+        use crate::utils::utils_preprocessing::link_preprocessing_overrides::*;
+        use crate::utils::utils_preprocessing::mesh_preprocessing_utils::fit_primitive_to_trimesh;
+        use crate::utils::utils_collisions::triangle_mesh_engine::TriMeshEngine;
+        use ncollide3d::procedural;
+
+        let yaml_string = "
+- link_name: \"left_finger\"
+  decomposition_error: 0.1
+  max_subcomponents: 3
+  simplification_target: 200
+- link_name: \"camera_mount\"
+  primitive_replacement: \"capsule\"
+".to_string();
+        let overrides = parse_link_preprocessing_overrides_yaml_string(&yaml_string)?;
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides[0].decomposition_error, Some(0.1));
+        assert_eq!(overrides[0].max_subcomponents, Some(3));
+        assert_eq!(overrides[1].primitive_replacement, Some(PrimitiveReplacementType::Capsule));
+        assert!(parse_link_preprocessing_overrides_yaml_string(&"- link_name: \"a\"\n  primitive_replacement: \"cone\"".to_string()).is_err());
+        assert!(parse_link_preprocessing_overrides_yaml_string(&"- link_name: \"a\"\n  max_subcomponents: -2".to_string()).is_err());
+        assert!(parse_link_preprocessing_overrides_yaml_string(&"- link_name: \"a\"\n  max_subcomponents: 2.5".to_string()).is_err());
+        assert!(parse_link_preprocessing_overrides_yaml_string(&"- link_name: \"a\"\n  simplification_target: 100.5".to_string()).is_err());
+        assert!(parse_link_preprocessing_overrides_yaml_string(&"# only comments".to_string())?.is_empty());
+
+        // only links whose entries were added, removed, or changed are recomputed
+        let mut changed = overrides.clone();
+        changed[0].max_subcomponents = Some(4);
+        changed.remove(1);
+        changed.push(LinkPreprocessingOverride::new_empty("gripper_base"));
+        assert!(get_links_with_changed_preprocessing_overrides(&overrides, &overrides).is_empty());
+        assert_eq!(get_links_with_changed_preprocessing_overrides(&changed, &overrides), vec!["left_finger".to_string(), "gripper_base".to_string(), "camera_mount".to_string()]);
+
+        let mut t = procedural::sphere(1.0, 32, 32, false);
+        t.unify_index_buffer();
+        let sphere = TriMeshEngine::new_from_procedural_trimesh(&t);
+        let simplified = sphere.simplify_to_num_triangles(200);
+        assert!(simplified.indices.len() <= 200 && simplified.indices.len() > 20);
+
+        // fitted primitives should contain the whole mesh, here a long box along z
+        let mut t = procedural::cuboid(&nalgebra::Vector3::new(0.2, 0.1, 1.0));
+        t.unify_index_buffer();
+        let mut bar = TriMeshEngine::new_from_procedural_trimesh(&t);
+        bar.transform_vertices(&crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion::new_from_euler_angles(0., 0., 0., nalgebra::Vector3::new(1.0, 2.0, 3.0)));
+        for primitive_type in vec![PrimitiveReplacementType::Box, PrimitiveReplacementType::Cylinder, PrimitiveReplacementType::Capsule] {
+            let primitive = fit_primitive_to_trimesh(&bar, &primitive_type).compute_convex_hull();
+            let convex = ncollide3d::shape::ConvexHull::try_from_points(&primitive.vertices).unwrap();
+            for v in &bar.vertices {
+                let p = ncollide3d::query::PointQuery::project_point(&convex, &nalgebra::Isometry3::identity(), v, true);
+                assert!((p.point - v).norm() < 0.001, "{:?} does not contain {:?}", primitive_type, v);
+            }
+            assert!((primitive.compute_center() - nalgebra::Vector3::new(1.0, 2.0, 3.0)).norm() < 0.05);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn seeded_parallel_collision_tensor_generation_test() -> Result<(), String> {
+        use crate::robot_modules::prelude::*;
+        use crate::utils::utils_collisions::prelude::*;
+
+        let robot = Robot::new("ur5", None)?;
+        let core_collision_module = robot.get_core_collision_module_ref();
+        let fk_module = robot.get_fk_module_ref();
+        let bounds_module = robot.get_bounds_module_ref();
+
+        // same seed gives the same tensors and reports no matter how many threads are used
+        let settings_1 = CollisionCheckTensorGenerationSettings::new(1000, 300, 7, Some(1));
+        let settings_2 = CollisionCheckTensorGenerationSettings::new(1000, 300, 7, Some(4));
+        let (skip_tensor_1, skip_report_1) = core_collision_module.compute_link_skip_collision_check_tensor(&LinkGeometryType::OBBs, fk_module, bounds_module, &settings_1)?;
+        let (skip_tensor_2, skip_report_2) = core_collision_module.compute_link_skip_collision_check_tensor(&LinkGeometryType::OBBs, fk_module, bounds_module, &settings_2)?;
+        assert_eq!(skip_tensor_1, skip_tensor_2);
+        assert_eq!(skip_report_1, skip_report_2);
+        assert_eq!(skip_report_1.num_samples, 1000);
+        assert!(!skip_report_1.link_pairs.is_empty());
+        for p in &skip_report_1.link_pairs {
+            assert!(p.confidence_interval_95[0] <= p.mean && p.mean <= p.confidence_interval_95[1]);
+            assert_eq!(p.skip_collision_check.is_some(), true);
+        }
+
+        let (distance_tensor_1, distance_report_1) = core_collision_module.compute_link_average_distance_tensor(&LinkGeometryType::OBBs, fk_module, bounds_module, &settings_1)?;
+        let (_, distance_report_2) = core_collision_module.compute_link_average_distance_tensor(&LinkGeometryType::OBBs, fk_module, bounds_module, &settings_2)?;
+        assert_eq!(distance_report_1, distance_report_2);
+        assert_eq!(distance_tensor_1.get_total_num_collision_checks(), 300.0);
+
+        let settings_3 = CollisionCheckTensorGenerationSettings::new(1000, 300, 8, Some(4));
+        let (_, distance_report_3) = core_collision_module.compute_link_average_distance_tensor(&LinkGeometryType::OBBs, fk_module, bounds_module, &settings_3)?;
+        assert_ne!(distance_report_1, distance_report_3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn virtual_depth_camera_test() -> Result<(), String> {
+        use crate::robot_modules::prelude::*;
+        use crate::utils::utils_sensors::prelude::*;
+        use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
+        use crate::utils::utils_math::prelude::vec_to_dvec;
+        use nalgebra::Vector3;
+
+        let mut robot_world = RobotWorld::new(vec!["ur5"], vec![None], Some("single_box"))?;
+        let state = vec_to_dvec(&vec![0.,0.,0.,0.,0.,0.]);
+        let box_center = robot_world.get_collision_environment_option_ref().as_ref().unwrap().environment_obbs[0][0].curr_pose.translation.clone();
+
+        // fixed camera 3 meters above the box, looking straight down (camera +z is world -z)
+        let intrinsics = CameraIntrinsics::new_from_horizontal_field_of_view(32, 24, 1.0)?;
+        let camera_pose = ImplicitDualQuaternion::new_from_euler_angles(std::f64::consts::PI, 0., 0., box_center + Vector3::new(0., 0., 3.));
+        let camera = VirtualDepthCamera::new_fixed(intrinsics.clone(), camera_pose.clone());
+        let render_result = camera.render(&mut robot_world, &state)?;
+
+        let label = render_result.get_label(16, 12);
+        assert!(label.is_some());
+        match label.unwrap() {
+            DepthCameraHitLabel::EnvironmentObject { object_idx, .. } => { assert_eq!(*object_idx, 0); }
+            _ => { panic!("center pixel should see the box"); }
+        }
+        let depth = render_result.get_depth(16, 12).unwrap();
+        assert!(depth > 0.0 && depth < 3.0);
+
+        // points lie on the surface that was hit, and their camera frame z is the depth
+        let point = render_result.get_points_ref()[12 * 32 + 16].unwrap();
+        let box_object = &robot_world.get_collision_environment_option_ref().as_ref().unwrap().environment_obbs[0][0];
+        assert!(box_object.distance_to_point(&nalgebra::Point3::from(point), false) < 0.001);
+        assert!((camera_pose.inverse().multiply_by_vector3(&point)[2] - depth).abs() < 0.000001);
+        let point_cloud = render_result.to_point_cloud(true);
+        assert_eq!(point_cloud.get_num_points(), render_result.get_num_hits());
+        assert_eq!(point_cloud.get_normals_ref().as_ref().unwrap().len(), point_cloud.get_num_points());
+
+        // camera attached to the end of the arm follows fk
+        let offset = ImplicitDualQuaternion::new_from_euler_angles(0., 1.57, 0., Vector3::new(0.1, 0., 0.));
+        let link_camera = VirtualDepthCamera::new_attached_to_link(intrinsics, &robot_world, 0, "ee_link", offset.clone())?;
+        let link_render_result = link_camera.render(&mut robot_world, &state)?;
+        let fk_res = robot_world.get_robot_set_ref().compute_fk(&state)?;
+        let link_idx = robot_world.get_robot_set_ref().get_robots_ref()[0].get_configuration_module_ref().robot_model_module.get_link_idx_from_name(&"ee_link".to_string()).unwrap();
+        let expected_pose = fk_res.get_robot_fk_results_ref()[0].get_link_frames_ref()[link_idx].as_ref().unwrap().multiply(&offset);
+        assert!((link_render_result.get_camera_pose_ref().translation - expected_pose.translation).norm() < 0.000001);
+
+        Ok(())
+    }
+
+    #[test]
+    fn visibility_check_test() -> Result<(), String> {
+        use crate::robot_modules::prelude::*;
+        use crate::utils::utils_vars::prelude::*;
+        use crate::utils::utils_optimization::prelude::*;
+        use crate::utils::utils_recorders::prelude::*;
+        use crate::utils::utils_math::prelude::vec_to_dvec;
+
+        let mut robot_world = RobotWorld::new(vec!["ur5"], vec![None], Some("single_box"))?;
+        let state = vec_to_dvec(&vec![0.,0.,0.,0.,0.,0.]);
+        let fk_res = robot_world.get_robot_set_ref().compute_fk(&state)?;
+        let link_idx = robot_world.get_robot_set_ref().get_robots_ref()[0].get_configuration_module_ref().robot_model_module.get_link_idx_from_name(&"ee_link".to_string()).unwrap();
+        let viewpoint = fk_res.get_robot_fk_results_ref()[0].get_link_frames_ref()[link_idx].as_ref().unwrap().translation.clone();
+        let box_center = robot_world.get_collision_environment_option_ref().as_ref().unwrap().environment_obbs[0][0].curr_pose.translation.clone();
+        let box_name = robot_world.get_collision_environment_option_ref().as_ref().unwrap().object_names[0].clone();
+
+        // line of sight through the middle of the box
+        let occluded_target = 2.0 * box_center - viewpoint;
+        let res = robot_world.visibility_check_from_link_name(&fk_res, 0, "ee_link", &occluded_target, LinkGeometryType::OBBs, &vec![])?;
+        assert!(!res.is_visible());
+        assert!(res.get_occluders_ref().iter().any(|o| o.robot_idx.is_none() && o.name == box_name));
+        assert!(res.get_occluded_length() > 0.0);
+
+        // line of sight pointing away from the box.  Links that contain the viewpoint are not occluders
+        let ignore_links: Vec<[usize; 2]> = vec![];
+        let visible_target = viewpoint - 0.05 * (box_center - viewpoint).normalize();
+        let res_visible = robot_world.visibility_check(&fk_res, 0, link_idx, &visible_target, LinkGeometryType::OBBs, &ignore_links)?;
+        assert!(res_visible.is_visible());
+        assert_eq!(res_visible.get_occluded_length(), 0.0);
+
+        // batched checks match single checks
+        let multiple_res = robot_world.visibility_check_multiple(&fk_res, 0, link_idx, &vec![occluded_target.clone(), visible_target.clone()], LinkGeometryType::OBBs, &ignore_links)?;
+        let single_res = robot_world.visibility_check(&fk_res, 0, link_idx, &occluded_target, LinkGeometryType::OBBs, &ignore_links)?;
+        assert_eq!(multiple_res.len(), 2);
+        assert_eq!(multiple_res[0].get_occluders_ref().len(), single_res.get_occluders_ref().len());
+        assert!((multiple_res[0].get_occluded_length() - single_res.get_occluded_length()).abs() < 0.000001);
+        assert!(multiple_res[1].is_visible());
+
+        // objective term cost is the occluded length
+        let mut lynx_vars = LynxVarsGeneric::new_single_threaded_packaged_with_robot_world(vec!["ur5"], vec![None], Some("single_box"))?;
+        let term = RobotLinkOcclusionAvoidance::new(0, link_idx, vec![occluded_target.clone()], LinkGeometryType::OBBs, vec![]);
+        let cost = term.call(&state, &mut lynx_vars, &RecorderArcMutexOption::new_none())?;
+        assert!((cost - res.get_occluded_length()).abs() < 0.000001);
+
+        Ok(())
+    }
+
+    #[test]
+    fn path_validation_report_test() -> Result<(), String> {
+        use crate::robot_modules::prelude::*;
+        use crate::utils::utils_paths::{linear_spline_path::LinearSplinePath, path_validation_report::PathValidationReport};
+
+        let robot_world = RobotWorld::new(vec!["ur5"], vec![None], Some("single_box"))?;
+        let path = LinearSplinePath::new_from_vecs(vec![vec![0.,0.,0.,0.,0.,0.], vec![0.5,-0.5,0.5,0.,0.,0.], vec![0.5,-0.5,100.,0.,0.,0.]]);
+
+        let report = robot_world.validate_path(&path, LinkGeometryType::OBBs, 0.1, Some(1))?;
+        assert_eq!(report.num_waypoints, 3);
+        assert_eq!(report.segments.len(), 2);
+        assert_eq!(report.segments[0].num_states_checked, 6);
+        for s in &report.segments {
+            if s.first_colliding_parameter.is_some() {
+                assert!(!s.colliding_pairs.is_empty());
+                assert_eq!(s.min_clearance, Some(0.0));
+            }
+        }
+
+        // last waypoint is out of bounds
+        assert!(!report.is_valid());
+        let out_of_bounds_states = &report.segments[1].out_of_bounds_states;
+        assert!(!out_of_bounds_states.is_empty());
+        assert_eq!(out_of_bounds_states.last().unwrap().parameter, 1.0);
+        assert_eq!(out_of_bounds_states.last().unwrap().state[2], 100.0);
+
+        // same report regardless of the number of threads, and it survives a json round trip
+        let report_parallel = robot_world.validate_path(&path, LinkGeometryType::OBBs, 0.1, Some(3))?;
+        assert_eq!(report.to_json_string(), report_parallel.to_json_string());
+        let loaded = PathValidationReport::new_from_json_string(&report.to_json_string())?;
+        assert_eq!(loaded.segments.len(), report.segments.len());
+        assert_eq!(loaded.get_first_invalid_segment_idx(), report.get_first_invalid_segment_idx());
+        assert_eq!(loaded.segments[1].out_of_bounds_states.len(), out_of_bounds_states.len());
+
+        assert!(robot_world.validate_path(&LinearSplinePath::new_empty(), LinkGeometryType::OBBs, 0.1, None).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn collision_check_cache_test() -> Result<(), String> {
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_vars::prelude::*;
+        use crate::robot_modules::prelude::*;
+        use crate::utils::utils_math::prelude::vec_to_dvec;
+        use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
+        use nalgebra::Vector3;
+        use rayon::prelude::*;
+
+        let mut lynx_vars = LynxVarsGeneric::new_empty_single_threaded();
+        let cached = CachedCollisionChecker::new(SphereCollisionChecker::new(1.0, &vec![0., 0.]).to_collision_checker_box(), 0.01, 2)?;
+
+        // states in the same quantization cell share a result
+        assert!(cached.in_collision(&vec_to_dvec(&vec![0., 0.]), &mut lynx_vars)?.is_in_collision());
+        assert!(cached.in_collision(&vec_to_dvec(&vec![0.001, 0.]), &mut lynx_vars)?.is_in_collision());
+        assert!(!cached.in_collision(&vec_to_dvec(&vec![5., 5.]), &mut lynx_vars)?.is_in_collision());
+        cached.in_collision(&vec_to_dvec(&vec![0., 0.]), &mut lynx_vars)?;
+        // capacity is 2, so this evicts [5, 5] (the least recently used entry)
+        cached.in_collision(&vec_to_dvec(&vec![3., 3.]), &mut lynx_vars)?;
+        cached.in_collision(&vec_to_dvec(&vec![0., 0.]), &mut lynx_vars)?;
+        cached.in_collision(&vec_to_dvec(&vec![5., 5.]), &mut lynx_vars)?;
+        assert_eq!(cached.get_cache_statistics(), CollisionCheckCacheStatistics { num_hits: 3, num_misses: 4, num_evictions: 2, num_entries: 2 });
+
+        // clones share the cache, and segments are cached separately from states
+        let cached_clone = cached.clone();
+        cached_clone.in_collision(&vec_to_dvec(&vec![5., 5.]), &mut lynx_vars)?;
+        assert!(!cached_clone.segment_in_collision(&vec_to_dvec(&vec![5., 5.]), &vec_to_dvec(&vec![5., 5.]), &mut lynx_vars)?.is_in_collision());
+        let statistics = cached.get_cache_statistics();
+        assert_eq!(statistics.num_hits, 4);
+        assert_eq!(statistics.num_misses, 5);
+        cached.clear_cache();
+        assert_eq!(cached.get_cache_statistics().num_entries, 0);
+
+        // changing the robot world environment invalidates cached results
+        let mut lynx_vars = LynxVarsGeneric::new_single_threaded_packaged_with_robot_world(vec!["ur5"], vec![None], Some("single_box"))?;
+        let cached = CachedCollisionChecker::new(RobotWorldCollisionChecker.to_collision_checker_box(), 0.001, 100)?;
+        let state = vec_to_dvec(&vec![0., 0., 0., 0., 0., 0.]);
+        let res1 = cached.in_collision(&state, &mut lynx_vars)?;
+        let res2 = cached.in_collision(&state, &mut lynx_vars)?;
+        assert_eq!(res1.is_in_collision(), res2.is_in_collision());
+        assert_eq!(cached.get_cache_statistics().num_hits, 1);
+
+        let robot_world = get_lynx_var_mut_ref_generic!(&mut lynx_vars, RobotWorld, "robot_world")?;
+        let version = robot_world.get_environment_version();
+        let object_name = robot_world.get_collision_environment_option_ref().as_ref().unwrap().object_names[0].clone();
+        robot_world.set_environment_object_padding(&object_name, 0.01)?;
+        assert_ne!(robot_world.get_environment_version(), version);
+        assert_eq!(robot_world.clone().get_environment_version(), robot_world.get_environment_version());
+
+        cached.in_collision(&state, &mut lynx_vars)?;
+        assert_eq!(cached.get_cache_statistics().num_hits, 1);
+        assert_eq!(cached.get_cache_statistics().num_misses, 2);
+
+        // setting the time without any object trajectories does not change anything, so the cached result is reused
+        let robot_world = get_lynx_var_mut_ref_generic!(&mut lynx_vars, RobotWorld, "robot_world")?;
+        let version = robot_world.get_environment_version();
+        robot_world.set_environment_time(1.0)?;
+        assert_eq!(robot_world.get_environment_version(), version);
+        cached.in_collision(&state, &mut lynx_vars)?;
+        assert_eq!(cached.get_cache_statistics().num_hits, 2);
+
+        // moving an object directly through the mutable collision environment is a cache miss
+        let robot_world = get_lynx_var_mut_ref_generic!(&mut lynx_vars, RobotWorld, "robot_world")?;
+        let collision_environment = robot_world.get_collision_environment_option_mut_ref().as_mut().unwrap();
+        collision_environment.update_object_transform_by_name(&object_name, &ImplicitDualQuaternion::new_from_euler_angles(0., 0., 0., Vector3::new(5., 5., 5.)))?;
+        cached.in_collision(&state, &mut lynx_vars)?;
+        assert_eq!(cached.get_cache_statistics().num_hits, 2);
+        assert_eq!(cached.get_cache_statistics().num_misses, 3);
+
+        // replacing the signed distance field is a cache miss for the signed distance field checker, even though the
+        // robot world is unchanged
+        let mut lynx_vars = LynxVarsGeneric::new_single_threaded_packaged_with_robot_world(vec!["ur5"], vec![None], Some("single_box"))?;
+        let mut collision_environment = CollisionEnvironment::new_with_environment_name("single_box")?;
+        set_or_add_lynx_var_generic!(&mut lynx_vars, SignedDistanceField, "signed_distance_field", SignedDistanceField::new_from_collision_environment(&collision_environment, 0.05, 0.3)?)?;
+        let sdf_checker = SignedDistanceFieldCollisionChecker::new(lynx_vars.get_robot_set_ref_via_robot_world(None)?, LinkGeometryType::OBBs);
+        let cached = CachedCollisionChecker::new(sdf_checker.to_collision_checker_box(), 0.001, 100)?;
+        let state = vec_to_dvec(&vec![1.4, 0.1, -1.4, 0.2, 0.1, 0.0]);
+        cached.in_collision(&state, &mut lynx_vars)?;
+        cached.in_collision(&state, &mut lynx_vars)?;
+        assert_eq!(cached.get_cache_statistics().num_hits, 1);
+
+        let object_name = collision_environment.object_names[0].clone();
+        collision_environment.update_object_transform_by_name(&object_name, &ImplicitDualQuaternion::new_from_euler_angles(0., 0., 0., Vector3::new(5., 5., 5.)))?;
+        set_or_add_lynx_var_generic!(&mut lynx_vars, SignedDistanceField, "signed_distance_field", SignedDistanceField::new_from_collision_environment(&collision_environment, 0.05, 0.3)?)?;
+        let res = cached.in_collision(&state, &mut lynx_vars)?;
+        assert_eq!(cached.get_cache_statistics().num_hits, 1);
+        assert_eq!(cached.get_cache_statistics().num_misses, 2);
+        assert_eq!(res.is_in_collision(), sdf_checker.in_collision(&state, &mut lynx_vars)?.is_in_collision());
+
+        Ok(())
+    }
+
+    #[test]
+    fn prm_and_lazy_prm_test() -> Result<(), String> {
+        use crate::path_planning::prelude::*;
+        use crate::utils::utils_path_planning::prelude::*;
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_sampling::prelude::*;
+        use crate::utils::utils_vars::prelude::*;
+        use crate::utils::utils_recorders::prelude::*;
+        use crate::utils::utils_runtime_management::termination_util::TerminationUtilOption;
+        use crate::utils::utils_math::prelude::vec_to_dvec;
+
+        let mut lynx_vars = LynxVarsGeneric::new_empty_single_threaded();
+        let recorder = RecorderArcMutexOption::new_none();
+        let mut terminate = TerminationUtilOption::new_none();
+
+        let collision_checker = SphereCollisionChecker::new(0.5, &vec![0., 0.]).to_collision_checker_box();
+        let base_sampler = RectangleFloatVecSampler::new(vec![(-2., 2.), (-2., 2.)]).to_lynx_float_vec_sampler_box();
+        let milestone_sampler = FreeSpaceSampler::new(base_sampler.clone(), collision_checker.clone()).to_lynx_multi_float_vec_sampler_box();
+        let local_search = StraightLineLocalSearch::new(collision_checker.clone(), 0.05).to_local_search_box();
+        let q_init = vec_to_dvec(&vec![-1.5, 0.]);
+        let q_goal = vec_to_dvec(&vec![1.5, 0.]);
+
+        let check_solution = |res: &PathPlannerResult| {
+            match res {
+                PathPlannerResult::SolutionFound(p) => {
+                    assert!((&p.waypoints[0] - &q_init).norm() < 0.000001);
+                    assert!((p.waypoints.last().unwrap() - &q_goal).norm() < 0.000001);
+                    for w in &p.waypoints { assert!(w.norm() >= 0.5); }
+                }
+                _ => { panic!("expected a solution"); }
+            }
+        };
+
+        let prm = PRM::new(milestone_sampler.clone(), 200, 10);
+        let res = prm.solve_global(&q_init, &q_goal, &local_search, &mut lynx_vars, &recorder, &mut terminate)?;
+        check_solution(&res);
+        // start and goal are not kept in the roadmap, so the roadmap persists unchanged across queries
+        let roadmap = prm.get_roadmap().unwrap();
+        assert_eq!(roadmap.get_num_nodes(), 200);
+        assert!(roadmap.get_edges_ref().iter().all(|e| e.status == RoadmapEdgeStatus::Valid));
+        let res = prm.clone().solve_global(&vec_to_dvec(&vec![0., -1.5]), &vec_to_dvec(&vec![0., 1.5]), &local_search, &mut lynx_vars, &recorder, &mut terminate)?;
+        match res { PathPlannerResult::SolutionFound(_) => {}, _ => { panic!("expected a solution"); } }
+        assert_eq!(prm.get_roadmap().unwrap().get_num_edges(), roadmap.get_num_edges());
+
+        // saved roadmaps are tagged, and only load into matching lynx vars
+        let dir = std::env::temp_dir().join("lynx_prm_test");
+        let dir_string = dir.to_str().unwrap().to_string();
+        prm.save_roadmap_to_file(dir_string.clone(), "roadmap.json".to_string())?;
+        let loaded_prm = PRM::new(milestone_sampler.clone(), 200, 10);
+        loaded_prm.load_roadmap_from_file(dir_string.clone(), "roadmap.json".to_string(), &lynx_vars)?;
+        assert_eq!(loaded_prm.get_roadmap().unwrap().get_num_nodes(), 200);
+        check_solution(&loaded_prm.solve_global(&q_init, &q_goal, &local_search, &mut lynx_vars, &recorder, &mut terminate)?);
+        let robot_world_lynx_vars = LynxVarsGeneric::new_single_threaded_packaged_with_robot_world(vec!["ur5"], vec![None], Some("single_box"))?;
+        assert!(loaded_prm.load_roadmap_from_file(dir_string.clone(), "roadmap.json".to_string(), &robot_world_lynx_vars).is_err());
+
+        // lazy prm samples without collision checks, and only checks edges along candidate paths
+        let lazy_prm = LazyPRM::new(FreeSpaceSampler::new(base_sampler.clone(), NullCollisionChecker.to_collision_checker_box()).to_lynx_multi_float_vec_sampler_box(), 200, 10);
+        let res = lazy_prm.solve_global(&q_init, &q_goal, &local_search, &mut lynx_vars, &recorder, &mut terminate)?;
+        check_solution(&res);
+        let lazy_roadmap = lazy_prm.get_roadmap().unwrap();
+        assert_eq!(lazy_roadmap.get_num_nodes(), 200);
+        assert!(lazy_roadmap.get_edges_ref().iter().any(|e| e.status == RoadmapEdgeStatus::Unchecked));
+        assert!(lazy_roadmap.get_edges_ref().iter().any(|e| e.status == RoadmapEdgeStatus::Invalid));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rrt_star_and_informed_rrt_star_test() -> Result<(), String> {
+        use crate::path_planning::prelude::*;
+        use crate::utils::utils_path_planning::prelude::*;
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_sampling::prelude::*;
+        use crate::utils::utils_vars::prelude::*;
+        use crate::utils::utils_recorders::prelude::*;
+        use crate::utils::utils_runtime_management::termination_util::TerminationUtilOption;
+        use crate::utils::utils_math::prelude::vec_to_dvec;
+
+        let mut lynx_vars = LynxVarsGeneric::new_empty_single_threaded();
+        let mut terminate = TerminationUtilOption::new_none();
+
+        let collision_checker = SphereCollisionChecker::new(0.5, &vec![0., 0.]).to_collision_checker_box();
+        let sampler = RectangleFloatVecSampler::new(vec![(-2., 2.), (-2., 2.)]).to_lynx_float_vec_sampler_box();
+        assert_eq!(sampler.lynx_float_vec_sampler_bounds(), Some(vec![(-2., 2.), (-2., 2.)]));
+        let local_search = StraightLineLocalSearch::new(collision_checker.clone(), 0.05).to_local_search_box();
+        let q_init = vec_to_dvec(&vec![-1.5, 0.]);
+        let q_goal = vec_to_dvec(&vec![1.5, 0.]);
+
+        let rrt_star = RRTStar::new_joint_space_length(sampler.clone(), collision_checker.clone(), 0.3, None, 3000);
+        let informed_rrt_star = InformedRRTStar::new_joint_space_length(sampler.clone(), collision_checker.clone(), 0.3, None, 3000);
+        let planners: Vec<(Box<dyn GlobalSearch>, &PathCostBox)> = vec![(Box::new(rrt_star.clone()), rrt_star.get_path_cost_ref()), (Box::new(informed_rrt_star.clone()), informed_rrt_star.get_path_cost_ref())];
+        for (planner, path_cost) in planners {
+            let recorder = RecorderArcMutexOption::new();
+            let res = planner.solve_global(&q_init, &q_goal, &local_search, &mut lynx_vars, &recorder, &mut terminate)?;
+            let cost = match res {
+                PathPlannerResult::SolutionFound(p) => {
+                    assert!((&p.waypoints[0] - &q_init).norm() < 0.000001);
+                    assert!((p.waypoints.last().unwrap() - &q_goal).norm() < 0.000001);
+                    for w in &p.waypoints { assert!(w.norm() >= 0.5); }
+                    // informed samples outside of the sampler's bounds are rejected
+                    for w in &p.waypoints { assert!(w.iter().all(|x| *x >= -2.0 && *x <= 2.0)); }
+                    path_cost.path_cost(&p)
+                }
+                _ => { panic!("expected a solution from {:?}", planner.name_global()); }
+            };
+            // the shortest path around the sphere is about 3.17 long
+            assert!(cost > 3.0 && cost < 4.0);
+
+            // recorded costs only ever improve, and the last one is the cost of the returned path
+            let recorder_option_guard = recorder.recorder_arc_mutex_option.lock().unwrap();
+            let r = recorder_option_guard.0.as_ref().unwrap();
+            let mut cost_over_time: Vec<(f64, f64)> = Vec::new();
+            for i in 0..r.labels.len() {
+                if r.labels[i] == "rrt_star_best_cost" { cost_over_time.push(serde_json::from_str(&r.variable_json_strings[i]).unwrap()); }
+            }
+            assert!(!cost_over_time.is_empty());
+            for i in 1..cost_over_time.len() {
+                assert!(cost_over_time[i].0 >= cost_over_time[i - 1].0);
+                assert!(cost_over_time[i].1 < cost_over_time[i - 1].1);
+            }
+            assert!((cost_over_time.last().unwrap().1 - cost).abs() < 0.000001);
+        }
+
+        // the time budget and the terminate signal both stop refinement
+        let timed_rrt_star = InformedRRTStar::new_joint_space_length(sampler.clone(), collision_checker.clone(), 0.3, Some(0.1), usize::max_value());
+        let start = std::time::Instant::now();
+        timed_rrt_star.solve_global(&q_init, &q_goal, &local_search, &mut lynx_vars, &RecorderArcMutexOption::new_none(), &mut terminate)?;
+        assert!(start.elapsed().as_secs_f64() < 2.0);
+        let mut terminated = TerminationUtilOption::new();
+        terminated.set_to_terminate();
+        let res = rrt_star.solve_global(&q_init, &q_goal, &local_search, &mut lynx_vars, &RecorderArcMutexOption::new_none(), &mut terminated)?;
+        match res { PathPlannerResult::SolutionNotFound(_) => {}, _ => { panic!("expected no solution after termination"); } }
+
+        // weighted joint space length
+        let weighted_cost = JointSpaceLengthPathCost::new_weighted(vec![1., 10.])?.to_path_cost_box();
+        assert!((weighted_cost.segment_cost(&vec_to_dvec(&vec![0., 0.]), &vec_to_dvec(&vec![1., 1.])) - 101.0_f64.sqrt()).abs() < 0.000001);
+        assert!(JointSpaceLengthPathCost::new_weighted(vec![1., 0.]).is_err());
+        let weighted_rrt_star = InformedRRTStar::new(sampler.clone(), collision_checker.clone(), weighted_cost.clone(), 0.3, None, 3000);
+        let res = weighted_rrt_star.solve_global(&q_init, &q_goal, &local_search, &mut lynx_vars, &RecorderArcMutexOption::new_none(), &mut terminate)?;
+        match res { PathPlannerResult::SolutionFound(p) => {
+            assert!(weighted_cost.path_cost(&p) >= 3.0);
+            for w in &p.waypoints { assert!(w.iter().all(|x| *x >= -2.0 && *x <= 2.0)); }
+        }, _ => { panic!("expected a solution"); } }
+        let wrong_dim_rrt_star = RRTStar::new(sampler.clone(), collision_checker.clone(), JointSpaceLengthPathCost::new_weighted(vec![1., 1., 1.])?.to_path_cost_box(), 0.3, None, 3000);
+        assert!(wrong_dim_rrt_star.solve_global(&q_init, &q_goal, &local_search, &mut lynx_vars, &RecorderArcMutexOption::new_none(), &mut terminate).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn trajectory_optimizer_test() -> Result<(), String> {
+        use crate::path_planning::prelude::*;
+        use crate::utils::utils_optimization::prelude::*;
+        use crate::utils::utils_paths::{linear_spline_path::LinearSplinePath, path_splitter_and_gluer::*};
+        use crate::utils::utils_vars::prelude::*;
+        use crate::utils::utils_recorders::prelude::*;
+        use nalgebra::DVector;
+
+        // stand-ins for robot collision and goal terms: a disk obstacle at the origin, and a pull toward a point
+        #[derive(Clone)]
+        struct DiskAvoidance { clearance: f64 }
+        impl IsolatedObjectiveTerm for DiskAvoidance {
+            fn name(&self) -> String { return "disk_avoidance".to_string() }
+            fn call(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<f64, String> {
+                let penetration = self.clearance - x.norm();
+                return Ok(if penetration > 0.0 { penetration * penetration } else { 0.0 });
+            }
+        }
+        impl LynxVarsUser for DiskAvoidance { }
+        #[derive(Clone)]
+        struct PullToPoint { point: DVector<f64> }
+        impl IsolatedObjectiveTerm for PullToPoint {
+            fn name(&self) -> String { return "pull_to_point".to_string() }
+            fn call(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<f64, String> {
+                return Ok((x - &self.point).norm_squared());
+            }
+        }
+        impl LynxVarsUser for PullToPoint { }
+
+        let mut lynx_vars = LynxVarsGeneric::new_empty_single_threaded();
+        let recorder = RecorderArcMutexOption::new_none();
+        let engine = OpenNonlinearOptimizationEngine.to_nonlinear_optimization_engine_box();
+        let interior = |p: &LinearSplinePath| { LinearSplinePath::new(p.waypoints[1..p.waypoints.len() - 1].to_vec()) };
+
+        // smoothness alone straightens a zig-zag seed, keeping both ends fixed
+        let mut zig_zag = Vec::new();
+        for i in 0..31 {
+            let y = if i == 0 || i == 30 { 0.0 } else if i % 2 == 0 { 0.2 } else { -0.2 };
+            zig_zag.push(vec![-1.5 + 0.1 * i as f64, y]);
+        }
+        let zig_zag = LinearSplinePath::new_from_vecs(zig_zag);
+        let smoother = TrajectoryOptimizer::new(engine.clone(), 1.0, 0.0);
+        let out = smoother.optimize(&zig_zag, &mut lynx_vars, Some(300), None, &recorder, false)?;
+        assert_eq!(out.waypoints.len(), 31);
+        assert_eq!(out.waypoints[0], zig_zag.waypoints[0]);
+        assert_eq!(out.waypoints[30], zig_zag.waypoints[30]);
+        for w in &out.waypoints { assert!(w[1].abs() < 0.05); }
+        let (objective_function, x0) = smoother.get_objective_function_and_initial_condition(&zig_zag)?;
+        let x_star = glue_linear_spline_path_into_concatenated_optimization_vector(&interior(&out))?;
+        assert!(objective_function.call(&x_star, &mut lynx_vars, &recorder, false)?.get_obj_val() < objective_function.call(&x0, &mut lynx_vars, &recorder, false)?.get_obj_val());
+
+        // a waypoint term pushes every waypoint of a straight seed out of the disk
+        let seed = LinearSplinePath::new_linear_interpolation(&DVector::from_vec(vec![-1.5, 0.1]), &DVector::from_vec(vec![1.5, 0.1]), 0.1);
+        let mut optimizer = TrajectoryOptimizer::new(engine.clone(), 1.0, 0.0);
+        optimizer.add_waypoint_term(DiskAvoidance { clearance: 0.6 }.to_isolated_objective_term_box(), 100.0);
+        let out = optimizer.optimize(&seed, &mut lynx_vars, Some(300), None, &recorder, false)?;
+        assert!(seed.waypoints.iter().any(|w| w.norm() < 0.5));
+        for w in &out.waypoints { assert!(w.norm() >= 0.5); }
+
+        // bounds apply to every free waypoint, and the engine's bounds are put back afterwards
+        optimizer.set_bounds(Some(vec![(-2.0, 2.0), (-2.0, 0.3)]));
+        let out = optimizer.optimize(&seed, &mut lynx_vars, Some(100), None, &recorder, false)?;
+        for w in &out.waypoints { assert!(w[1] <= 0.3 + 0.000001); }
+        type f64VecOption = Option<Vec<f64>>;
+        assert!(get_lynx_var_ref_generic!(&lynx_vars, f64VecOption, "upper_bounds_option")?.is_none());
+        optimizer.set_bounds(Some(vec![(-2.0, 2.0)]));
+        assert!(optimizer.optimize(&seed, &mut lynx_vars, Some(100), None, &recorder, false).is_err());
+        optimizer.set_bounds(None);
+
+        // resampling
+        optimizer.set_num_waypoints(Some(11));
+        assert_eq!(optimizer.optimize(&seed, &mut lynx_vars, Some(100), None, &recorder, false)?.waypoints.len(), 11);
+        assert!(optimizer.optimize(&LinearSplinePath::new_from_vecs(vec![vec![0., 0.]]), &mut lynx_vars, Some(100), None, &recorder, false).is_err());
+
+        // goal terms only matter once the goal is free, and then trade off against the pull to the seed's goal
+        let straight = LinearSplinePath::new_linear_interpolation(&DVector::from_vec(vec![-1.5, 0.0]), &DVector::from_vec(vec![1.5, 0.0]), 0.1);
+        let mut goal_optimizer = TrajectoryOptimizer::new(engine.clone(), 0.1, 0.0);
+        goal_optimizer.add_goal_term(PullToPoint { point: DVector::from_vec(vec![1.5, 1.0]) }.to_isolated_objective_term_box(), 1.0);
+        let out = goal_optimizer.optimize(&straight, &mut lynx_vars, Some(300), None, &recorder, false)?;
+        assert_eq!(out.waypoints.last().unwrap(), straight.waypoints.last().unwrap());
+        goal_optimizer.set_goal_pull_weight(Some(1.0));
+        let out = goal_optimizer.optimize(&straight, &mut lynx_vars, Some(300), None, &recorder, false)?;
+        assert_eq!(out.waypoints.len(), straight.waypoints.len());
+        assert_eq!(out.waypoints[0], straight.waypoints[0]);
+        assert!(out.waypoints.last().unwrap()[1] > 0.25);
+
+        Ok(())
+    }
+
+    #[test]
+    fn path_shortcutting_test() -> Result<(), String> {
+        use crate::utils::utils_paths::{linear_spline_path::LinearSplinePath, path_shortcutting::*};
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_vars::prelude::*;
+
+        let mut lynx_vars = LynxVarsGeneric::new_empty_single_threaded();
+        let collision_checker = SphereCollisionChecker::new(0.5, &vec![0., 0.]).to_collision_checker_box();
+        let shortcutter = PathShortcutter::new(collision_checker.clone(), 0.05)?;
+        assert!(PathShortcutter::new(collision_checker.clone(), 0.0).is_err());
+
+        let jagged = LinearSplinePath::new_from_vecs(vec![vec![-1.5, 0.], vec![-1., 1.], vec![-0.5, 0.7], vec![0., 1.2], vec![0.5, 0.7], vec![1., 1.], vec![1.5, 0.]]);
+        assert!(shortcutter.is_path_collision_free(&jagged, &mut lynx_vars)?);
+        assert!(!shortcutter.is_segment_collision_free(&jagged.waypoints[0], jagged.waypoints.last().unwrap(), &mut lynx_vars)?);
+        let jagged_length = get_linear_spline_path_length(&jagged);
+
+        let check = |p: &LinearSplinePath, lynx_vars: &mut LynxVarsGeneric| -> Result<f64, String> {
+            assert_eq!(p.waypoints[0], jagged.waypoints[0]);
+            assert_eq!(p.waypoints.last().unwrap(), jagged.waypoints.last().unwrap());
+            assert!(shortcutter.is_path_collision_free(p, lynx_vars)?);
+            return Ok(get_linear_spline_path_length(p));
+        };
+
+        // (-1.5, 0) sees (1, 1) past the obstacle, but not the goal
+        let pruned = shortcutter.prune_waypoints(&jagged, &mut lynx_vars)?;
+        assert_eq!(pruned.waypoints.len(), 3);
+        assert!(check(&pruned, &mut lynx_vars)? < jagged_length);
+
+        let random = shortcutter.random_shortcutting(&jagged, &mut lynx_vars, 200, None)?;
+        assert!(check(&random, &mut lynx_vars)? < jagged_length);
+        let partial = shortcutter.partial_shortcutting(&jagged, &mut lynx_vars, 200, None)?;
+        assert!(check(&partial, &mut lynx_vars)? < jagged_length);
+        let shortcut = shortcutter.shortcut(&jagged, &mut lynx_vars, 400, Some(5.0))?;
+        let shortcut_length = check(&shortcut, &mut lynx_vars)?;
+        assert!(shortcut_length <= get_linear_spline_path_length(&pruned) + 0.000001);
+        // no path around the obstacle is shorter than about 3.17
+        assert!(shortcut_length > 3.1);
+
+        // nothing changes without any budget
+        let unchanged = shortcutter.random_shortcutting(&jagged, &mut lynx_vars, 200, Some(0.0))?;
+        assert_eq!(unchanged.waypoints, jagged.waypoints);
+        let unchanged = shortcutter.partial_shortcutting(&jagged, &mut lynx_vars, 0, None)?;
+        assert_eq!(unchanged.waypoints, jagged.waypoints);
+
+        Ok(())
+    }
+
+    #[test]
+    fn time_parameterization_test() -> Result<(), String> {
+        use crate::utils::utils_paths::{linear_spline_path::LinearSplinePath, time_parameterization::*};
+
+        let limits = TimeParameterizationLimits::new(vec![1.0, 0.5], vec![2.0, 1.0], None)?;
+        let jerk_limits = TimeParameterizationLimits::new(vec![1.0, 0.5], vec![2.0, 1.0], Some(vec![10.0, 5.0]))?;
+        assert!(TimeParameterizationLimits::new(vec![1.0, 0.0], vec![2.0, 1.0], None).is_err());
+        assert!(TimeParameterizationLimits::new(vec![1.0, 0.5], vec![2.0], None).is_err());
+        assert!(TimeParameterizer::new(limits.clone(), 1).is_err());
+
+        let parameterizer = TimeParameterizer::new(limits.clone(), 200)?;
+        let jerk_parameterizer = TimeParameterizer::new(jerk_limits.clone(), 200)?;
+        let path = LinearSplinePath::new_from_vecs(vec![vec![0., 0.], vec![1.5, 0.3], vec![2., 1.], vec![2., 1.05]]);
+        assert!(parameterizer.parameterize_jerk_limited(&path).is_err());
+        assert!(parameterizer.parameterize(&LinearSplinePath::new_from_vecs(vec![vec![0., 0., 0.]])).is_err());
+
+        let time_optimal = parameterizer.parameterize_time_optimal(&path)?;
+        let trapezoidal = parameterizer.parameterize_trapezoidal(&path)?;
+        let jerk_limited = jerk_parameterizer.parameterize_jerk_limited(&path)?;
+        for trajectory in vec![&time_optimal, &trapezoidal, &jerk_limited] {
+            limits.check_trajectory(trajectory, 0.01)?;
+            let l = trajectory.get_num_knots();
+            assert_eq!(trajectory.times[0], 0.0);
+            assert!((&trajectory.positions[0] - &path.waypoints[0]).norm() < 0.000001);
+            assert!((&trajectory.positions[l-1] - &path.waypoints[3]).norm() < 0.000001);
+            assert!(trajectory.velocities[0].norm() < 0.000001 && trajectory.velocities[l-1].norm() < 0.000001);
+
+            let resampled = trajectory.resample_at_control_rate(100.0)?;
+            limits.check_trajectory(&resampled, 0.01)?;
+            assert_eq!(resampled.get_end_time(), trajectory.get_end_time());
+            for i in 1..resampled.get_num_knots() - 1 { assert!((resampled.times[i] - resampled.times[i-1] - 0.01).abs() < 0.000001); }
+            assert_eq!(resampled.to_timed_path()?.get_num_waypoints(), resampled.get_num_knots());
+        }
+
+        // the trapezoidal profile stops at every waypoint, and limiting jerk only slows things down further
+        assert!(time_optimal.get_duration() < trapezoidal.get_duration());
+        assert!(trapezoidal.get_duration() < jerk_limited.get_duration());
+        assert_eq!(parameterizer.parameterize(&path)?.get_duration(), time_optimal.get_duration());
+        assert_eq!(jerk_parameterizer.parameterize(&path)?.get_duration(), jerk_limited.get_duration());
+
+        // along x alone: 0.5s to reach 1.0, 2.5s of cruising, 0.5s to stop
+        let straight = LinearSplinePath::new_from_vecs(vec![vec![0., 0.], vec![3., 0.]]);
+        assert!((parameterizer.parameterize_trapezoidal(&straight)?.get_duration() - 3.5).abs() < 0.000001);
+        assert!((parameterizer.parameterize_time_optimal(&straight)?.get_duration() - 3.5).abs() < 0.01);
+
+        // mid-phase samples hold the knot's acceleration, and velocities are the derivative of the interpolated positions
+        let straight_trapezoidal = parameterizer.parameterize_trapezoidal(&straight)?;
+        let (p, v, a) = straight_trapezoidal.get_state_at_time(0.25)?;
+        assert!((p[0] - 0.0625).abs() < 0.000001 && (v[0] - 0.5).abs() < 0.000001 && (a[0] - 2.0).abs() < 0.000001);
+        let (_, v, a) = straight_trapezoidal.get_state_at_time(1.75)?;
+        assert!((v[0] - 1.0).abs() < 0.000001 && a[0].abs() < 0.000001);
+        let (_, v, a) = straight_trapezoidal.get_state_at_time(3.25)?;
+        assert!((v[0] - 0.5).abs() < 0.000001 && (a[0] + 2.0).abs() < 0.000001);
+        // along y alone, the acceleration limit is 1.0
+        let straight_y = parameterizer.parameterize_trapezoidal(&LinearSplinePath::new_from_vecs(vec![vec![0., 0.], vec![0., 3.]]))?;
+        let (_, v, a) = straight_y.get_state_at_time(0.25)?;
+        assert!((v[1] - 0.25).abs() < 0.000001 && (a[1] - 1.0).abs() < 0.000001);
+
+        // sampled velocities are the derivative of sampled positions
+        for trajectory in vec![&time_optimal, &trapezoidal, &jerk_limited] {
+            let dt = 0.000001;
+            let mut t = 0.013;
+            while t < trajectory.get_end_time() - 0.01 {
+                let (p1, _, _) = trajectory.get_state_at_time(t - dt)?;
+                let (p2, _, _) = trajectory.get_state_at_time(t + dt)?;
+                let (_, v, _) = trajectory.get_state_at_time(t)?;
+                assert!(((&p2 - &p1) / (2.0 * dt) - &v).norm() < 0.001);
+                t += 0.037;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn robot_mount_test() -> Result<(), String> {
+        use crate::robot_modules::prelude::*;
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_math::prelude::vec_to_dvec;
+
+        let mut robot_set = RobotSet::new(vec!["ur5", "ur5"], vec![None, None])?;
+        let state = vec_to_dvec(&vec![0.3, -0.8, 1.0, 0.2, 0.5, 0.1, 0.1, -0.4, 0.6, 0., 0., 0.]);
+        let unmounted_fk = robot_set.compute_fk(&state)?;
+
+        let model_module = &robot_set.get_robots_ref()[0].get_configuration_module_ref().robot_model_module;
+        let parent_link_idx = model_module.get_link_idx_from_name(&"wrist_3_link".to_string()).unwrap();
+        let child_base_link_idx = model_module.get_link_idx_from_name(&"base_link".to_string()).unwrap();
+
+        // every link on the child follows the parent link's frame
+        robot_set.mount_robot_on_link(1, 0, "wrist_3_link")?;
+        let mounted_fk = robot_set.compute_fk(&state)?;
+        let parent_link_frame = mounted_fk.get_robot_fk_results_ref()[0].get_link_frames_ref()[parent_link_idx].as_ref().unwrap().clone();
+        let unmounted_child_frames = unmounted_fk.get_robot_fk_results_ref()[1].get_link_frames_ref();
+        let mounted_child_frames = mounted_fk.get_robot_fk_results_ref()[1].get_link_frames_ref();
+        for i in 0..unmounted_child_frames.len() {
+            if unmounted_child_frames[i].is_none() { continue; }
+            let expected = parent_link_frame.multiply(unmounted_child_frames[i].as_ref().unwrap());
+            let mounted = mounted_child_frames[i].as_ref().unwrap();
+            assert!((&expected.translation - &mounted.translation).norm() < 0.000001);
+            assert!(expected.quat.angle_to(&mounted.quat) < 0.000001);
+        }
+
+        // the child's base sits inside the parent link, but that pair is skipped while mounted
+        let has_mount_pair = |res: &VecOfIntersectCheckMultipleResult| -> bool {
+            for r in res.get_intersect_check_multiple_results_ref() {
+                if let IntersectCheckMultipleResult::IntersectionFound(info) = r {
+                    for idxs in info.get_intersection_idxs() {
+                        if idxs[0][0] == parent_link_idx && idxs[1][0] == child_base_link_idx { return true; }
+                    }
+                }
+            }
+            return false;
+        };
+        let mounted_res = robot_set.multi_robot_intersect_check(&mounted_fk, LinkGeometryType::OBBs, false)?;
+        assert!(!has_mount_pair(&mounted_res));
+
+        // unmounting restores the original base and stops skipping the pair
+        robot_set.unmount_robot(1)?;
+        assert!(robot_set.get_robot_mounts_ref()[1].is_none());
+        let unmounted_res = robot_set.multi_robot_intersect_check(&mounted_fk, LinkGeometryType::OBBs, false)?;
+        assert!(has_mount_pair(&unmounted_res));
+        let restored_fk = robot_set.compute_fk(&state)?;
+        let restored_child_frames = restored_fk.get_robot_fk_results_ref()[1].get_link_frames_ref();
+        for i in 0..unmounted_child_frames.len() {
+            if unmounted_child_frames[i].is_none() { continue; }
+            let original = unmounted_child_frames[i].as_ref().unwrap();
+            let restored = restored_child_frames[i].as_ref().unwrap();
+            assert!((&original.translation - &restored.translation).norm() < 0.000001);
+            assert!(original.quat.angle_to(&restored.quat) < 0.000001);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn segment_intersect_check_catches_thin_obstacle_test() -> Result<(), String> {
+        use crate::robot_modules::prelude::*;
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
+        use crate::utils::utils_math::prelude::vec_to_dvec;
+        use nalgebra::Vector3;
+
+        let mut robot_world = RobotWorld::new(vec!["ur5"], vec![None], None)?;
+
+        // thin plate across the forearm at the middle of a shoulder pan sweep
+        let mid_state = vec_to_dvec(&vec![0., 0.9, 0., 0., 0., 0.]);
+        let fk = robot_world.get_robot_set_ref().compute_fk(&mid_state)?;
+        let model_module = &robot_world.get_robot_set_ref().get_robots_ref()[0].get_configuration_module_ref().robot_model_module;
+        let forearm_idx = model_module.get_link_idx_from_name(&"forearm_link".to_string()).unwrap();
+        let wrist_1_idx = model_module.get_link_idx_from_name(&"wrist_1_link".to_string()).unwrap();
+        let frames = fk.get_robot_fk_results_ref()[0].get_link_frames_ref();
+        let center = (&frames[forearm_idx].as_ref().unwrap().translation + &frames[wrist_1_idx].as_ref().unwrap().translation) * 0.5;
+        let plate_pose = ImplicitDualQuaternion::new_from_euler_angles(0., 0., center[0].atan2(-center[1]), center.clone());
+
+        let mut collision_environment = CollisionEnvironment::new_empty("thin_plate");
+        collision_environment.set_object_components_by_name("plate", vec![CollisionObject::new_cuboid(0.002, 0.05, 0.05, Some(plate_pose), None)], vec![TriMeshEngine::new(Vec::new(), Vec::new())])?;
+
+        let q_a = vec_to_dvec(&vec![-1.25, 0.9, 0., 0., 0., 0.]);
+        let q_b = vec_to_dvec(&vec![1.25, 0.9, 0., 0., 0., 0.]);
+
+        // the sweep itself is collision free without the plate
+        let res = robot_world.segment_intersect_check(&q_a, &q_b, LinkGeometryType::OBBs, 0.001)?;
+        assert!(!res.is_in_collision());
+
+        robot_world.set_collision_environment(collision_environment)?;
+
+        // dense sampling at the old local search resolution (0.5) steps over the plate
+        for i in 0..6 {
+            let state = vec_to_dvec(&vec![-1.25 + i as f64 * 0.5, 0.9, 0., 0., 0., 0.]);
+            let fk = robot_world.get_robot_set_ref().compute_fk(&state)?;
+            assert!(!robot_world.environment_intersect_check(&fk, LinkGeometryType::OBBs, true)?.in_collision());
+        }
+        let fk = robot_world.get_robot_set_ref().compute_fk(&mid_state)?;
+        assert!(robot_world.environment_intersect_check(&fk, LinkGeometryType::OBBs, true)?.in_collision());
+
+        let res = robot_world.segment_intersect_check(&q_a, &q_b, LinkGeometryType::OBBs, 0.001)?;
+        assert!(res.is_in_collision());
+
+        Ok(())
+    }
+
+    #[test]
+    fn segment_intersect_check_agrees_with_fine_sampling_test() -> Result<(), String> {
+        use crate::robot_modules::prelude::*;
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_sampling::prelude::*;
+
+        let mut robot_world = RobotWorld::new(vec!["ur5"], vec![None], Some("single_box"))?;
+
+        for _ in 0..6 {
+            // short segments between in bounds states stay in bounds since the bounds are a box
+            let q_a = robot_world.get_robot_set_ref().float_vec_sampler_sample()?;
+            let q_c = robot_world.get_robot_set_ref().float_vec_sampler_sample()?;
+            let q_b = &q_a + (&q_c - &q_a) * 0.2;
+
+            let continuous = robot_world.segment_intersect_check(&q_a, &q_b, LinkGeometryType::OBBs, 0.001)?.is_in_collision();
+
+            let num_steps = ((&q_b - &q_a).norm() / 0.002).ceil() as usize + 1;
+            let mut discrete = false;
+            for i in 0..num_steps + 1 {
+                let state = &q_a + (&q_b - &q_a) * (i as f64 / num_steps as f64);
+                let fk = robot_world.get_robot_set_ref().compute_fk(&state)?;
+                if robot_world.self_intersect_check(&fk, LinkGeometryType::OBBs, true)?.in_collision() ||
+                    robot_world.environment_intersect_check(&fk, LinkGeometryType::OBBs, true)?.in_collision() {
+                    discrete = true;
+                    break;
+                }
+            }
+
+            assert_eq!(continuous, discrete);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn occupancy_grid_incremental_sync_test() -> Result<(), String> {
+        use crate::robot_modules::prelude::*;
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_pointclouds::pointcloud::PointCloud;
+        use nalgebra::Vector3;
+
+        let mut robot_world = RobotWorld::new(vec!["ur5"], vec![None], Some("single_box"))?;
+        robot_world.set_occupancy_grid(VoxelOccupancyGrid::new(0.1)?)?;
+        let sensor_origin = Vector3::new(0.05, 0.05, 0.05);
+
+        let mut wall = Vec::new();
+        for i in 0..10 { for j in 0..10 { wall.push(Vector3::new(1.05, -0.45 + i as f64 * 0.1, -0.45 + j as f64 * 0.1)); } }
+        let moved_wall = PointCloud::new(wall.iter().map(|p| p + Vector3::new(1.0, 0.0, 0.0)).collect());
+
+        let mut group = vec![ vec![CollisionObject::new_cuboid(0.2, 0.2, 0.2, None, None)], vec![CollisionObject::new_cuboid(0.2, 0.2, 0.2, None, None)] ];
+        group[0][0].set_curr_translation(1.05, 0.0, 0.0);
+        group[1][0].set_curr_translation(2.05, 0.0, 0.0);
+        for g in &mut group { for c in g { c.update_all_bounding_volumes(); } }
+
+        // voxels are added and cleared one point cloud at a time, and must always match the grid and a rebuilt broad phase
+        robot_world.insert_point_cloud_into_occupancy_grid(&PointCloud::new(wall.clone()), &sensor_origin, None)?;
+        for _ in 0..5 {
+            robot_world.insert_point_cloud_into_occupancy_grid(&moved_wall, &sensor_origin, None)?;
+
+            let occupancy_grid = robot_world.get_occupancy_grid_option_ref().as_ref().unwrap();
+            let collision_environment = robot_world.get_collision_environment_option_ref().as_ref().unwrap();
+            let object_idx = collision_environment.object_names.iter().position(|n| n.as_str() == "occupancy_grid").unwrap();
+            let mut component_names: Vec<String> = collision_environment.environment_obbs[object_idx].iter().map(|c| c.name.clone()).collect();
+            component_names.sort();
+            let mut expected_names: Vec<String> = occupancy_grid.get_occupied_voxel_collision_objects("occupancy_grid").0.iter().map(|c| c.name.clone()).collect();
+            expected_names.sort();
+            assert!(component_names == expected_names);
+            assert_eq!(collision_environment.broad_phase.get_num_components(), collision_environment.environment_obbs.iter().map(|o| o.len()).sum::<usize>());
+
+            let exhaustive = intersect_check_between_multiple_collision_objects(&collision_environment.environment_obbs, &group, false, None)?;
+            let broad_phase = intersect_check_between_environment_and_multiple_collision_objects(collision_environment, &group, false, None)?;
+            assert!(exhaustive.get_intersect_check_multiple_info_ref().get_intersection_idxs() == broad_phase.get_intersect_check_multiple_info_ref().get_intersection_idxs());
+        }
+
+        let collision_environment = robot_world.get_collision_environment_option_ref().as_ref().unwrap();
+        let broad_phase = intersect_check_between_environment_and_multiple_collision_objects(collision_environment, &group, false, None)?;
+        let intersection_idxs = broad_phase.get_intersect_check_multiple_info_ref().get_intersection_idxs();
+        assert!(!intersection_idxs.iter().any(|idxs| idxs[1][0] == 0));
+        assert!(intersection_idxs.iter().any(|idxs| idxs[1][0] == 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn link_sphere_approximation_padding_test() -> Result<(), String> {
+        use crate::robot_modules::prelude::*;
+        use crate::utils::utils_collisions::prelude::*;
+        use crate::utils::utils_math::prelude::vec_to_dvec;
+
+        let mut robot_set = RobotSet::new(vec!["ur5"], vec![None])?;
+        let link_sphere_approximation = LinkSphereApproximation::new(robot_set.get_robots_ref()[0].get_core_collision_module_ref(), &LinkGeometryType::OBBs);
+        let fk = robot_set.compute_fk(&vec_to_dvec(&vec![0.; 6]))?;
+        let forearm_idx = robot_set.get_robots_ref()[0].get_configuration_module_ref().robot_model_module.get_link_idx_from_name(&"forearm_link".to_string()).unwrap();
+
+        // paddings set after the approximation is built still inflate its spheres
+        let before = link_sphere_approximation.get_spheres_in_world_frame(&fk.get_robot_fk_results_ref()[0], robot_set.get_robots_ref()[0].get_core_collision_module_ref().get_link_paddings_ref());
+        robot_set.set_link_padding(0, "forearm_link", 0.05)?;
+        let after = link_sphere_approximation.get_spheres_in_world_frame(&fk.get_robot_fk_results_ref()[0], robot_set.get_robots_ref()[0].get_core_collision_module_ref().get_link_paddings_ref());
+
+        assert_eq!(before.len(), after.len());
+        assert!(before.iter().any(|s| s.0 == forearm_idx));
+        for (b, a) in before.iter().zip(after.iter()) {
+            let expected_padding = if b.0 == forearm_idx { 0.05 } else { 0.0 };
+            assert!((a.2 - b.2 - expected_padding).abs() < 0.000001);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn srdf_export_end_effector_test() -> Result<(), String> {
+        use crate::utils::utils_parsing::srdf_parsing_utils::*;
+
+        let fp = std::env::temp_dir().join("lynx_ur5_export.srdf").to_str().unwrap().to_string();
+        export_srdf_from_robot_directory("ur5", fp.clone())?;
+        let srdf_data = read_srdf_file(fp)?;
+
+        // ee_link gets its own group, attached to the planning group that moves it
+        assert_eq!(srdf_data.end_effectors.len(), 1);
+        let end_effector = &srdf_data.end_effectors[0];
+        assert_eq!(end_effector.parent_link, "ee_link");
+        assert_eq!(end_effector.parent_group, Some("planar_base".to_string()));
+        assert_ne!(end_effector.group, "planar_base");
+
+        let end_effector_group = srdf_data.get_group_ref(&end_effector.group).unwrap();
+        assert_eq!(end_effector_group.links, vec!["ee_link".to_string()]);
+        assert!(end_effector_group.joints.is_empty() && end_effector_group.chains.is_empty());
+
+        let planar_base_group = srdf_data.get_group_ref("planar_base").unwrap();
+        assert!(planar_base_group.joints.contains(&"shoulder_pan_joint".to_string()));
+
         Ok(())
     }
 }
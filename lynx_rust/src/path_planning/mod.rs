@@ -1,4 +1,6 @@
 pub mod sprint;
 pub mod surge_global;
 pub mod rrt;
+pub mod prm;
+pub mod trajectory_optimization;
 pub mod prelude;
\ No newline at end of file
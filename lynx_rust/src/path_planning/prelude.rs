@@ -1,3 +1,5 @@
 pub use crate::path_planning::{sprint::prelude::*,
                                surge_global::*,
-                               rrt::prelude::*};
\ No newline at end of file
+                               rrt::prelude::*,
+                               prm::prelude::*,
+                               trajectory_optimization::prelude::*};
\ No newline at end of file
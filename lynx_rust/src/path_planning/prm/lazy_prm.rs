@@ -0,0 +1,141 @@
+use crate::path_planning::prm::probabilistic_roadmap::*;
+use crate::utils::utils_path_planning::{local_search::*, global_search::*};
+use crate::utils::utils_recorders::prelude::*;
+use crate::utils::utils_vars::prelude::*;
+use crate::utils::utils_runtime_management::termination_util::TerminationUtilOption;
+use crate::utils::utils_path_planning::path_planner_result::PathPlannerResult;
+use crate::utils::utils_sampling::prelude::*;
+use crate::utils::utils_files_and_strings::string_utils::*;
+use nalgebra::DVector;
+use std::sync::{Arc, RwLock};
+
+/* lazy probabilistic roadmap.  The roadmap is built without any local searches: every sampled state is connected to its
+num_neighbors nearest states with an unchecked edge.  Queries repeatedly take the shortest path through edges that are not
+known to be invalid, and run the local search only on unchecked edges along that path (from the start side), marking them
+valid or invalid, until a path of valid edges is found.  Edge checks between roadmap states are kept, so later queries in the
+same environment get cheaper.  Clones share the same roadmap. */
+#[derive(Clone)]
+pub struct LazyPRM {
+    _milestone_sampler: LynxMultiFloatVecSamplerBox,
+    _num_samples: usize,
+    _num_neighbors: usize,
+    _roadmap: Arc<RwLock<Option<ProbabilisticRoadmap>>>
+}
+
+impl LazyPRM {
+    pub fn new(milestone_sampler: LynxMultiFloatVecSamplerBox, num_samples: usize, num_neighbors: usize) -> Self {
+        return Self { _milestone_sampler: milestone_sampler, _num_samples: num_samples, _num_neighbors: num_neighbors, _roadmap: Arc::new(RwLock::new(None)) };
+    }
+
+    pub fn build_roadmap(&self, lynx_vars: &mut LynxVarsGeneric) -> Result<(), String> {
+        let mut roadmap = ProbabilisticRoadmap::new_empty_tagged_from_lynx_vars(lynx_vars);
+
+        let samples = self._milestone_sampler.lynx_multi_float_vec_sampler_sample(lynx_vars, self._num_samples, 10 * self._num_samples, false)?;
+        for s in &samples { roadmap.add_node(s); }
+
+        let l = roadmap.get_num_nodes();
+        for i in 0..l {
+            let neighbors = roadmap.get_k_nearest_node_idxs(roadmap.get_node_ref(i), self._num_neighbors, Some(i));
+            for j in neighbors {
+                if roadmap.get_edge_idx_between(i, j).is_some() { continue; }
+                let cost = (roadmap.get_node_ref(i) - roadmap.get_node_ref(j)).norm();
+                roadmap.add_edge(i, j, cost, RoadmapEdgeStatus::Unchecked, Vec::new())?;
+            }
+        }
+
+        *self._roadmap.write().unwrap() = Some(roadmap);
+        return Ok(());
+    }
+
+    pub fn set_roadmap(&self, roadmap: ProbabilisticRoadmap) {
+        *self._roadmap.write().unwrap() = Some(roadmap);
+    }
+
+    pub fn get_roadmap(&self) -> Option<ProbabilisticRoadmap> {
+        return self._roadmap.read().unwrap().clone();
+    }
+
+    pub fn save_roadmap_to_file(&self, fp_to_dir: String, file_name: String) -> Result<(), String> {
+        let roadmap = self._roadmap.read().unwrap();
+        if roadmap.is_none() { return Err("LazyPRM roadmap has not been built, so it cannot be saved".to_string()); }
+        roadmap.as_ref().unwrap().save_to_file(fp_to_dir, file_name);
+        return Ok(());
+    }
+
+    // fails if the saved roadmap was built for a different robot world or environment than the one in lynx_vars
+    pub fn load_roadmap_from_file(&self, fp_to_dir: String, file_name: String, lynx_vars: &LynxVarsGeneric) -> Result<(), String> {
+        let roadmap = ProbabilisticRoadmap::load_from_file(fp_to_dir, file_name)?;
+        roadmap.check_tags_match(lynx_vars)?;
+        self.set_roadmap(roadmap);
+        return Ok(());
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    fn _solve_on_roadmap(&self, roadmap: &mut ProbabilisticRoadmap, q_init: &DVector<f64>, q_goal: &DVector<f64>, local_search: &LocalSearchBox, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption, terminate: &mut TerminationUtilOption) -> Result<PathPlannerResult, String> {
+        let start_neighbors = roadmap.get_k_nearest_node_idxs(q_init, self._num_neighbors, None);
+        let goal_neighbors = roadmap.get_k_nearest_node_idxs(q_goal, self._num_neighbors, None);
+        let start_idx = roadmap.add_node(q_init);
+        let goal_idx = roadmap.add_node(q_goal);
+        for j in start_neighbors {
+            let cost = (q_init - roadmap.get_node_ref(j)).norm();
+            roadmap.add_edge(start_idx, j, cost, RoadmapEdgeStatus::Unchecked, Vec::new())?;
+        }
+        for j in goal_neighbors {
+            let cost = (q_goal - roadmap.get_node_ref(j)).norm();
+            roadmap.add_edge(j, goal_idx, cost, RoadmapEdgeStatus::Unchecked, Vec::new())?;
+        }
+        roadmap.add_edge(start_idx, goal_idx, (q_init - q_goal).norm(), RoadmapEdgeStatus::Unchecked, Vec::new())?;
+
+        loop {
+            if terminate.get_terminate() { return Ok(PathPlannerResult::SolutionNotFound("LazyPRM was terminated".to_string())); }
+
+            let shortest_path = roadmap.get_shortest_path(start_idx, goal_idx, true);
+            if shortest_path.is_none() {
+                return Ok(PathPlannerResult::SolutionNotFound(format!("no path through the LazyPRM roadmap ({:?} nodes) remains after removing invalid edges", roadmap.get_num_nodes() - 2)));
+            }
+            let (node_idxs, edge_idxs) = shortest_path.unwrap();
+
+            let mut all_valid = true;
+            for e in &edge_idxs {
+                let edge = roadmap.get_edge_ref(*e).clone();
+                if edge.status == RoadmapEdgeStatus::Valid { continue; }
+
+                let q_a = roadmap.get_node_ref(edge.node_idxs[0]).clone();
+                let q_b = roadmap.get_node_ref(edge.node_idxs[1]).clone();
+                let res = local_search.solve_local(&q_a, &q_b, lynx_vars, recorder, terminate)?;
+                match res {
+                    PathPlannerResult::SolutionFound(s) => { roadmap.set_edge_status(*e, RoadmapEdgeStatus::Valid, s.waypoints)?; }
+                    _ => {
+                        roadmap.set_edge_status(*e, RoadmapEdgeStatus::Invalid, Vec::new())?;
+                        all_valid = false;
+                        break;
+                    }
+                }
+            }
+
+            if all_valid {
+                return Ok(PathPlannerResult::SolutionFound(roadmap.get_linear_spline_path(&node_idxs, &edge_idxs)?));
+            }
+        }
+    }
+}
+
+impl GlobalSearch for LazyPRM {
+    fn solve_global(&self, q_init: &DVector<f64>, q_goal: &DVector<f64>, local_search: &LocalSearchBox, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption, terminate: &mut TerminationUtilOption) -> Result<PathPlannerResult, String> {
+        if self._roadmap.read().unwrap().is_none() { self.build_roadmap(lynx_vars)?; }
+
+        let mut roadmap_guard = self._roadmap.write().unwrap();
+        let roadmap = roadmap_guard.as_mut().unwrap();
+        let checkpoint = roadmap.get_checkpoint();
+
+        // the start and goal (and their edges) are removed afterwards, but checks on roadmap edges are kept
+        let out = self._solve_on_roadmap(roadmap, q_init, q_goal, local_search, lynx_vars, recorder, terminate);
+        roadmap.restore_checkpoint(checkpoint);
+        return out;
+    }
+    fn name_global(&self) -> String {
+        return "LazyPRM_".to_string() + usize_to_string(self._num_samples).as_str() + "_" + usize_to_string(self._num_neighbors).as_str();
+    }
+}
+impl LynxVarsUser for LazyPRM { }
@@ -0,0 +1,4 @@
+pub mod probabilistic_roadmap;
+pub mod prm;
+pub mod lazy_prm;
+pub mod prelude;
\ No newline at end of file
@@ -0,0 +1 @@
+pub use crate::path_planning::prm::{probabilistic_roadmap::*, prm::*, lazy_prm::*};
\ No newline at end of file
@@ -0,0 +1,135 @@
+use crate::path_planning::prm::probabilistic_roadmap::*;
+use crate::utils::utils_paths::arclength_parameterization_util::get_waypoints_length;
+use crate::utils::utils_path_planning::{local_search::*, global_search::*};
+use crate::utils::utils_recorders::prelude::*;
+use crate::utils::utils_vars::prelude::*;
+use crate::utils::utils_runtime_management::termination_util::TerminationUtilOption;
+use crate::utils::utils_path_planning::path_planner_result::PathPlannerResult;
+use crate::utils::utils_sampling::prelude::*;
+use crate::utils::utils_files_and_strings::string_utils::*;
+use nalgebra::DVector;
+use std::sync::{Arc, RwLock};
+
+/* probabilistic roadmap.  The roadmap is built on the first query (or with build_roadmap) from num_samples states of the
+milestone sampler (which should only return collision free states, e.g., a FreeSpaceSampler), with each state connected to
+its num_neighbors nearest states wherever the local search succeeds.  Later queries only connect the start and goal to the
+roadmap.  Clones share the same roadmap. */
+#[derive(Clone)]
+pub struct PRM {
+    _milestone_sampler: LynxMultiFloatVecSamplerBox,
+    _num_samples: usize,
+    _num_neighbors: usize,
+    _roadmap: Arc<RwLock<Option<ProbabilisticRoadmap>>>
+}
+
+impl PRM {
+    pub fn new(milestone_sampler: LynxMultiFloatVecSamplerBox, num_samples: usize, num_neighbors: usize) -> Self {
+        return Self { _milestone_sampler: milestone_sampler, _num_samples: num_samples, _num_neighbors: num_neighbors, _roadmap: Arc::new(RwLock::new(None)) };
+    }
+
+    pub fn build_roadmap(&self, local_search: &LocalSearchBox, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption, terminate: &mut TerminationUtilOption) -> Result<(), String> {
+        let mut roadmap = ProbabilisticRoadmap::new_empty_tagged_from_lynx_vars(lynx_vars);
+
+        let samples = self._milestone_sampler.lynx_multi_float_vec_sampler_sample(lynx_vars, self._num_samples, 10 * self._num_samples, false)?;
+        for s in &samples { roadmap.add_node(s); }
+
+        let l = roadmap.get_num_nodes();
+        for i in 0..l {
+            if terminate.get_terminate() { break; }
+            let neighbors = roadmap.get_k_nearest_node_idxs(roadmap.get_node_ref(i), self._num_neighbors, Some(i));
+            for j in neighbors {
+                if roadmap.get_edge_idx_between(i, j).is_some() { continue; }
+                let q_a = roadmap.get_node_ref(i).clone();
+                let q_b = roadmap.get_node_ref(j).clone();
+                let res = local_search.solve_local(&q_a, &q_b, lynx_vars, recorder, terminate)?;
+                match res {
+                    PathPlannerResult::SolutionFound(s) => { roadmap.add_edge(i, j, get_waypoints_length(&s.waypoints), RoadmapEdgeStatus::Valid, s.waypoints)?; }
+                    _ => {}
+                }
+            }
+        }
+
+        *self._roadmap.write().unwrap() = Some(roadmap);
+        return Ok(());
+    }
+
+    pub fn set_roadmap(&self, roadmap: ProbabilisticRoadmap) {
+        *self._roadmap.write().unwrap() = Some(roadmap);
+    }
+
+    pub fn get_roadmap(&self) -> Option<ProbabilisticRoadmap> {
+        return self._roadmap.read().unwrap().clone();
+    }
+
+    pub fn save_roadmap_to_file(&self, fp_to_dir: String, file_name: String) -> Result<(), String> {
+        let roadmap = self._roadmap.read().unwrap();
+        if roadmap.is_none() { return Err("PRM roadmap has not been built, so it cannot be saved".to_string()); }
+        roadmap.as_ref().unwrap().save_to_file(fp_to_dir, file_name);
+        return Ok(());
+    }
+
+    // fails if the saved roadmap was built for a different robot world or environment than the one in lynx_vars
+    pub fn load_roadmap_from_file(&self, fp_to_dir: String, file_name: String, lynx_vars: &LynxVarsGeneric) -> Result<(), String> {
+        let roadmap = ProbabilisticRoadmap::load_from_file(fp_to_dir, file_name)?;
+        roadmap.check_tags_match(lynx_vars)?;
+        self.set_roadmap(roadmap);
+        return Ok(());
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // adds the given state to the roadmap, connected to its nearest roadmap states.  Edges point from the new state
+    // into the roadmap if from_new_state is true, and from the roadmap to the new state otherwise.
+    fn _connect_state_to_roadmap(&self, roadmap: &mut ProbabilisticRoadmap, q: &DVector<f64>, from_new_state: bool, local_search: &LocalSearchBox, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption, terminate: &mut TerminationUtilOption) -> Result<usize, String> {
+        let neighbors = roadmap.get_k_nearest_node_idxs(q, self._num_neighbors, None);
+        let new_idx = roadmap.add_node(q);
+        for j in neighbors {
+            let q_j = roadmap.get_node_ref(j).clone();
+            let res = if from_new_state { local_search.solve_local(q, &q_j, lynx_vars, recorder, terminate)? } else { local_search.solve_local(&q_j, q, lynx_vars, recorder, terminate)? };
+            match res {
+                PathPlannerResult::SolutionFound(s) => {
+                    if from_new_state { roadmap.add_edge(new_idx, j, get_waypoints_length(&s.waypoints), RoadmapEdgeStatus::Valid, s.waypoints)?; }
+                    else { roadmap.add_edge(j, new_idx, get_waypoints_length(&s.waypoints), RoadmapEdgeStatus::Valid, s.waypoints)?; }
+                }
+                _ => {}
+            }
+        }
+        return Ok(new_idx);
+    }
+}
+
+impl GlobalSearch for PRM {
+    fn solve_global(&self, q_init: &DVector<f64>, q_goal: &DVector<f64>, local_search: &LocalSearchBox, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption, terminate: &mut TerminationUtilOption) -> Result<PathPlannerResult, String> {
+        let direct_res = local_search.solve_local(q_init, q_goal, lynx_vars, recorder, terminate)?;
+        match direct_res {
+            PathPlannerResult::SolutionFound(_) => { return Ok(direct_res); }
+            _ => {}
+        }
+
+        if self._roadmap.read().unwrap().is_none() { self.build_roadmap(local_search, lynx_vars, recorder, terminate)?; }
+
+        let mut roadmap_guard = self._roadmap.write().unwrap();
+        let roadmap = roadmap_guard.as_mut().unwrap();
+        let checkpoint = roadmap.get_checkpoint();
+
+        let start_idx = self._connect_state_to_roadmap(roadmap, q_init, true, local_search, lynx_vars, recorder, terminate);
+        let goal_idx = self._connect_state_to_roadmap(roadmap, q_goal, false, local_search, lynx_vars, recorder, terminate);
+        if start_idx.is_err() || goal_idx.is_err() {
+            roadmap.restore_checkpoint(checkpoint);
+            return Err(if start_idx.is_err() { start_idx.err().unwrap() } else { goal_idx.err().unwrap() });
+        }
+
+        let shortest_path = roadmap.get_shortest_path(start_idx.unwrap(), goal_idx.unwrap(), false);
+        let out = match shortest_path {
+            Some((node_idxs, edge_idxs)) => { roadmap.get_linear_spline_path(&node_idxs, &edge_idxs).map(|p| PathPlannerResult::SolutionFound(p)) }
+            None => { Ok(PathPlannerResult::SolutionNotFound(format!("start and goal could not be connected through the PRM roadmap ({:?} nodes)", checkpoint.0))) }
+        };
+
+        roadmap.restore_checkpoint(checkpoint);
+        return out;
+    }
+    fn name_global(&self) -> String {
+        return "PRM_".to_string() + usize_to_string(self._num_samples).as_str() + "_" + usize_to_string(self._num_neighbors).as_str();
+    }
+}
+impl LynxVarsUser for PRM { }
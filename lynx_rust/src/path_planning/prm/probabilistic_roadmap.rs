@@ -0,0 +1,261 @@
+use crate::robot_modules::robot_world::RobotWorld;
+use crate::utils::utils_vars::prelude::*;
+use crate::utils::utils_paths::linear_spline_path::LinearSplinePath;
+use crate::utils::utils_paths::arclength_parameterization_util::get_waypoints_length;
+use crate::utils::utils_files_and_strings::file_utils::*;
+use serde::{Serialize, Deserialize};
+use nalgebra::DVector;
+use std::collections::BinaryHeap;
+use std::cmp::Ordering;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RoadmapEdgeStatus {
+    Unchecked,
+    Valid,
+    Invalid
+}
+
+// waypoints go from node_idxs[0] to node_idxs[1], and are only filled in once the edge is found to be valid
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RoadmapEdge {
+    pub node_idxs: [usize; 2],
+    pub cost: f64,
+    pub status: RoadmapEdgeStatus,
+    pub waypoints: Vec<DVector<f64>>
+}
+
+/* undirected graph of states shared by PRM and LazyPRM.  Roadmaps are tagged with the names of the robot world and collision
+environment they were built in (None when the lynx vars have no robot world), so a saved roadmap is not reused against a
+different workcell. */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProbabilisticRoadmap {
+    _robot_world_name: Option<String>,
+    _environment_name: Option<String>,
+    _nodes: Vec<DVector<f64>>,
+    _edges: Vec<RoadmapEdge>,
+    _adjacency: Vec<Vec<usize>> // edge idxs per node
+}
+
+impl ProbabilisticRoadmap {
+    pub fn new_empty(robot_world_name: Option<String>, environment_name: Option<String>) -> Self {
+        return Self { _robot_world_name: robot_world_name, _environment_name: environment_name, _nodes: Vec::new(), _edges: Vec::new(), _adjacency: Vec::new() };
+    }
+
+    // tagged with the names of the "robot_world" lynx var, if there is one
+    pub fn new_empty_tagged_from_lynx_vars(lynx_vars: &LynxVarsGeneric) -> Self {
+        let (robot_world_name, environment_name) = Self::get_tags_from_lynx_vars(lynx_vars);
+        return Self::new_empty(robot_world_name, environment_name);
+    }
+
+    pub fn get_tags_from_lynx_vars(lynx_vars: &LynxVarsGeneric) -> (Option<String>, Option<String>) {
+        let robot_world = get_lynx_var_ref_generic!(lynx_vars, RobotWorld, "robot_world");
+        if robot_world.is_err() { return (None, None); }
+        let robot_world = robot_world.ok().unwrap();
+        return (Some(robot_world.get_robot_world_name()), robot_world.get_environment_name());
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn add_node(&mut self, q: &DVector<f64>) -> usize {
+        self._nodes.push(q.clone());
+        self._adjacency.push(Vec::new());
+        return self._nodes.len() - 1;
+    }
+
+    pub fn add_edge(&mut self, node_idx_a: usize, node_idx_b: usize, cost: f64, status: RoadmapEdgeStatus, waypoints: Vec<DVector<f64>>) -> Result<usize, String> {
+        if node_idx_a >= self._nodes.len() || node_idx_b >= self._nodes.len() {
+            return Err(format!("node idxs {:?} are too high for number of roadmap nodes ({:?})", [node_idx_a, node_idx_b], self._nodes.len()));
+        }
+        self._edges.push(RoadmapEdge { node_idxs: [node_idx_a, node_idx_b], cost, status, waypoints });
+        let edge_idx = self._edges.len() - 1;
+        self._adjacency[node_idx_a].push(edge_idx);
+        if node_idx_b != node_idx_a { self._adjacency[node_idx_b].push(edge_idx); }
+        return Ok(edge_idx);
+    }
+
+    pub fn set_edge_status(&mut self, edge_idx: usize, status: RoadmapEdgeStatus, waypoints: Vec<DVector<f64>>) -> Result<(), String> {
+        if edge_idx >= self._edges.len() { return Err(format!("edge idx {:?} is too high for number of roadmap edges ({:?})", edge_idx, self._edges.len())); }
+        if status == RoadmapEdgeStatus::Valid && !waypoints.is_empty() { self._edges[edge_idx].cost = get_waypoints_length(&waypoints); }
+        self._edges[edge_idx].status = status;
+        self._edges[edge_idx].waypoints = waypoints;
+        return Ok(());
+    }
+
+    pub fn get_edge_idx_between(&self, node_idx_a: usize, node_idx_b: usize) -> Option<usize> {
+        for e in &self._adjacency[node_idx_a] {
+            let n = self._edges[*e].node_idxs;
+            if (n[0] == node_idx_a && n[1] == node_idx_b) || (n[0] == node_idx_b && n[1] == node_idx_a) { return Some(*e); }
+        }
+        return None;
+    }
+
+    // brute force, sorted by distance.  exclude_idx is left out (e.g., the node whose neighbors are being found)
+    pub fn get_k_nearest_node_idxs(&self, q: &DVector<f64>, k: usize, exclude_idx: Option<usize>) -> Vec<usize> {
+        let mut distances: Vec<(usize, f64)> = Vec::new();
+        let l = self._nodes.len();
+        for i in 0..l {
+            if exclude_idx.is_some() && exclude_idx.unwrap() == i { continue; }
+            distances.push((i, (&self._nodes[i] - q).norm()));
+        }
+        distances.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        return distances.iter().take(k).map(|x| x.0).collect();
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // A* from start to goal over edges that are not invalid (and, if allow_unchecked_edges is false, only over valid edges).
+    // Returns the node idxs and edge idxs along the path.  The straight line distance to the goal is an admissible heuristic
+    // since every edge costs at least the straight line distance between its nodes.
+    pub fn get_shortest_path(&self, start_idx: usize, goal_idx: usize, allow_unchecked_edges: bool) -> Option<(Vec<usize>, Vec<usize>)> {
+        let l = self._nodes.len();
+        if start_idx >= l || goal_idx >= l { return None; }
+
+        let mut cost_to_come = vec![std::f64::INFINITY; l];
+        let mut inflow_edge: Vec<Option<usize>> = vec![None; l];
+        let mut closed = vec![false; l];
+        let mut queue = BinaryHeap::new();
+
+        cost_to_come[start_idx] = 0.0;
+        queue.push(AStarQueueEntry { _f: (&self._nodes[start_idx] - &self._nodes[goal_idx]).norm(), _node_idx: start_idx });
+
+        while let Some(entry) = queue.pop() {
+            let n = entry._node_idx;
+            if closed[n] { continue; }
+            closed[n] = true;
+            if n == goal_idx { break; }
+
+            for e in &self._adjacency[n] {
+                let edge = &self._edges[*e];
+                match edge.status {
+                    RoadmapEdgeStatus::Invalid => { continue; }
+                    RoadmapEdgeStatus::Unchecked => { if !allow_unchecked_edges { continue; } }
+                    RoadmapEdgeStatus::Valid => {}
+                }
+                let m = if edge.node_idxs[0] == n { edge.node_idxs[1] } else { edge.node_idxs[0] };
+                if closed[m] { continue; }
+                let c = cost_to_come[n] + edge.cost;
+                if c < cost_to_come[m] {
+                    cost_to_come[m] = c;
+                    inflow_edge[m] = Some(*e);
+                    queue.push(AStarQueueEntry { _f: c + (&self._nodes[m] - &self._nodes[goal_idx]).norm(), _node_idx: m });
+                }
+            }
+        }
+
+        if !closed[goal_idx] { return None; }
+
+        let mut node_idxs = vec![goal_idx];
+        let mut edge_idxs = Vec::new();
+        let mut curr = goal_idx;
+        while curr != start_idx {
+            let e = inflow_edge[curr].unwrap();
+            edge_idxs.push(e);
+            let n = self._edges[e].node_idxs;
+            curr = if n[0] == curr { n[1] } else { n[0] };
+            node_idxs.push(curr);
+        }
+        node_idxs.reverse();
+        edge_idxs.reverse();
+
+        return Some((node_idxs, edge_idxs));
+    }
+
+    // joins the waypoints of valid edges along a path returned by get_shortest_path
+    pub fn get_linear_spline_path(&self, node_idxs: &Vec<usize>, edge_idxs: &Vec<usize>) -> Result<LinearSplinePath, String> {
+        if node_idxs.len() != edge_idxs.len() + 1 { return Err("roadmap path must have exactly one more node than edges".to_string()); }
+
+        let mut out_path = LinearSplinePath::new_empty();
+        out_path.add_waypoint(&self._nodes[node_idxs[0]]);
+        let l = edge_idxs.len();
+        for i in 0..l {
+            let edge = &self._edges[edge_idxs[i]];
+            if edge.status != RoadmapEdgeStatus::Valid || edge.waypoints.is_empty() {
+                return Err(format!("roadmap edge {:?} has not been found to be valid", edge_idxs[i]));
+            }
+            let mut waypoints = edge.waypoints.clone();
+            if edge.node_idxs[0] != node_idxs[i] { waypoints.reverse(); }
+            for w in waypoints.iter().skip(1) { out_path.add_waypoint(w); }
+        }
+
+        return Ok(out_path);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // (num nodes, num edges).  Everything added after a checkpoint (e.g., the start and goal of a query) can be removed with
+    // restore_checkpoint, while changes to the status of earlier edges are kept.
+    pub fn get_checkpoint(&self) -> (usize, usize) {
+        return (self._nodes.len(), self._edges.len());
+    }
+
+    pub fn restore_checkpoint(&mut self, checkpoint: (usize, usize)) {
+        let (num_nodes, num_edges) = checkpoint;
+        self._nodes.truncate(num_nodes);
+        self._edges.truncate(num_edges);
+        self._adjacency.truncate(num_nodes);
+        for a in &mut self._adjacency { a.retain(|e| *e < num_edges); }
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn check_tags_match(&self, lynx_vars: &LynxVarsGeneric) -> Result<(), String> {
+        let (robot_world_name, environment_name) = Self::get_tags_from_lynx_vars(lynx_vars);
+        if robot_world_name != self._robot_world_name || environment_name != self._environment_name {
+            return Err(format!("roadmap was built for robot world {:?} in environment {:?}, but lynx vars have robot world {:?} in environment {:?}", self._robot_world_name, self._environment_name, robot_world_name, environment_name));
+        }
+        return Ok(());
+    }
+
+    pub fn save_to_file(&self, fp_to_dir: String, file_name: String) {
+        let serialized = serde_json::to_string(&self).unwrap();
+        write_string_to_file(fp_to_dir, file_name, serialized, true);
+    }
+
+    pub fn load_from_file(fp_to_dir: String, file_name: String) -> Result<Self, String> {
+        let json_string = read_file_contents_separated_args(fp_to_dir.clone(), file_name.clone());
+        if json_string.is_none() { return Err(format!("no file {:?} found when trying to load a ProbabilisticRoadmap", fp_to_dir + "/" + file_name.as_str())); }
+        let out_self = serde_json::from_str(&json_string.unwrap());
+        if out_self.is_err() { return Err(format!("file {:?} could not be parsed as a ProbabilisticRoadmap", fp_to_dir + "/" + file_name.as_str())); }
+        return Ok(out_self.unwrap());
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn get_num_nodes(&self) -> usize { return self._nodes.len(); }
+
+    pub fn get_num_edges(&self) -> usize { return self._edges.len(); }
+
+    pub fn get_node_ref(&self, idx: usize) -> &DVector<f64> { return &self._nodes[idx]; }
+
+    pub fn get_edge_ref(&self, idx: usize) -> &RoadmapEdge { return &self._edges[idx]; }
+
+    pub fn get_nodes_ref(&self) -> &Vec<DVector<f64>> { return &self._nodes; }
+
+    pub fn get_edges_ref(&self) -> &Vec<RoadmapEdge> { return &self._edges; }
+
+    pub fn get_robot_world_name_ref(&self) -> &Option<String> { return &self._robot_world_name; }
+
+    pub fn get_environment_name_ref(&self) -> &Option<String> { return &self._environment_name; }
+
+    pub fn print_summary(&self) {
+        let num_valid = self._edges.iter().filter(|e| e.status == RoadmapEdgeStatus::Valid).count();
+        let num_invalid = self._edges.iter().filter(|e| e.status == RoadmapEdgeStatus::Invalid).count();
+        println!("roadmap for robot world {:?} in environment {:?}: {:?} nodes, {:?} edges ({:?} valid, {:?} invalid, {:?} unchecked)", self._robot_world_name, self._environment_name, self._nodes.len(), self._edges.len(), num_valid, num_invalid, self._edges.len() - num_valid - num_invalid);
+    }
+}
+
+// min heap entry on f = cost to come + heuristic
+struct AStarQueueEntry {
+    _f: f64,
+    _node_idx: usize
+}
+impl PartialEq for AStarQueueEntry {
+    fn eq(&self, other: &Self) -> bool { return self._f == other._f; }
+}
+impl Eq for AStarQueueEntry { }
+impl PartialOrd for AStarQueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { return Some(self.cmp(other)); }
+}
+impl Ord for AStarQueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering { return other._f.partial_cmp(&self._f).unwrap_or(Ordering::Equal); }
+}
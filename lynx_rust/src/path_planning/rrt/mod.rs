@@ -1,3 +1,4 @@
 pub mod rrt;
 pub mod rrt_connect;
+pub mod rrt_star;
 pub mod prelude;
\ No newline at end of file
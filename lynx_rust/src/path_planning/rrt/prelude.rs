@@ -1 +1 @@
-pub use crate::path_planning::rrt::{rrt_connect::*, rrt::*};
\ No newline at end of file
+pub use crate::path_planning::rrt::{rrt_connect::*, rrt::*, rrt_star::*};
\ No newline at end of file
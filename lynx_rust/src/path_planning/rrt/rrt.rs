@@ -49,16 +49,24 @@ impl RRT {
                 q_new = q_closest_point_ref + self._lambda * ( &dir / dir_n );
             }
 
-            let collision_check_res = self._collision_checker.in_collision(&q_new, lynx_vars)?;
+            let collision_check_res = self._collision_checker.segment_in_collision(q_closest_point_ref, &q_new, lynx_vars)?;
             num_collision_checks += 1;
             match collision_check_res {
                 CollisionCheckResult::NotInCollision => {
                     let add_idx = planning_tree.add_node_with_auto_two_waypoint_inflow_edge(&q_new, q_closest_res.0)?;
                     kdtree.add_point(&q_new)?;
                     if (&q_new - q_goal).norm() < self._lambda * 1.5 {
-                        let add_idx = planning_tree.add_node_with_auto_two_waypoint_inflow_edge(q_goal, add_idx)?;
-                        let solution_path = planning_tree.get_path_from_tree_root_to_node(add_idx)?;
-                        return Ok(PathPlannerResult::SolutionFound(solution_path));
+                        let goal_collision_check_res = self._collision_checker.segment_in_collision(&q_new, q_goal, lynx_vars)?;
+                        num_collision_checks += 1;
+                        match goal_collision_check_res {
+                            CollisionCheckResult::NotInCollision => {
+                                let add_idx = planning_tree.add_node_with_auto_two_waypoint_inflow_edge(q_goal, add_idx)?;
+                                let solution_path = planning_tree.get_path_from_tree_root_to_node(add_idx)?;
+                                return Ok(PathPlannerResult::SolutionFound(solution_path));
+                            }
+                            CollisionCheckResult::InCollision(_) => { }
+                            CollisionCheckResult::Error(s) => { return Err(s); }
+                        }
                     }
                 }
                 CollisionCheckResult::InCollision(_) => { }
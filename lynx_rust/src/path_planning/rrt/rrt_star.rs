@@ -0,0 +1,335 @@
+use crate::utils::utils_path_planning::{local_search::*, global_search::*, path_cost::*};
+use crate::utils::utils_collisions::collision_checker::*;
+use crate::utils::utils_recorders::prelude::*;
+use crate::utils::utils_vars::prelude::*;
+use crate::utils::utils_runtime_management::termination_util::TerminationUtilOption;
+use crate::utils::utils_path_planning::path_planner_result::PathPlannerResult;
+use crate::utils::utils_sampling::prelude::*;
+use crate::utils::utils_nearest_neighbor::kdtree_utils::*;
+use crate::utils::utils_collisions::collision_check_result_enum::CollisionCheckResult;
+use crate::utils::utils_paths::linear_spline_path::LinearSplinePath;
+use crate::utils::utils_sampling::float_vec_sampler_traits::FloatVecSampler;
+use crate::utils::utils_files_and_strings::string_utils::*;
+use nalgebra::DVector;
+use rand::distributions::{Distribution, Uniform};
+
+/* asymptotically optimal RRT (k-nearest RRT*).  Unlike RRT, the search does not stop at the first solution: the tree keeps
+growing and rewiring until the terminate signal is set, the time budget runs out, or max_num_collision_checks is reached, and
+the best path found by then is returned.  Every time the best cost improves, a (seconds since start of solve, cost) tuple is
+written to the recorder with the label "rrt_star_best_cost", so cost over time can be compared across planners. */
+#[derive(Clone)]
+pub struct RRTStar {
+    _sampler: LynxFloatVecSamplerBox,
+    _collision_checker: CollisionCheckerBox,
+    _path_cost: PathCostBox,
+    _lambda: f64,
+    _time_budget_in_seconds: Option<f64>,
+    _max_num_collision_checks: usize
+}
+
+impl RRTStar {
+    pub fn new(sampler: LynxFloatVecSamplerBox, collision_checker: CollisionCheckerBox, path_cost: PathCostBox, lambda: f64, time_budget_in_seconds: Option<f64>, max_num_collision_checks: usize) -> Self {
+        Self { _sampler: sampler, _collision_checker: collision_checker, _path_cost: path_cost, _lambda: lambda, _time_budget_in_seconds: time_budget_in_seconds, _max_num_collision_checks: max_num_collision_checks }
+    }
+
+    pub fn new_joint_space_length(sampler: LynxFloatVecSamplerBox, collision_checker: CollisionCheckerBox, lambda: f64, time_budget_in_seconds: Option<f64>, max_num_collision_checks: usize) -> Self {
+        return Self::new(sampler, collision_checker, PathCostBox::new_joint_space_length(), lambda, time_budget_in_seconds, max_num_collision_checks);
+    }
+
+    pub fn get_path_cost_ref(&self) -> &PathCostBox {
+        return &self._path_cost;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    fn _solve_single_threaded(&self, q_init: &DVector<f64>, q_goal: &DVector<f64>, informed: bool, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption, terminate: &mut TerminationUtilOption) -> Result<PathPlannerResult, String> {
+        let start = Instant::now();
+        let dim = q_init.len();
+        self._path_cost.check_dimension(dim)?;
+        let heuristic_scaling = if informed { self._path_cost.get_heuristic_scaling(dim) } else { None };
+
+        let to_goal_sampler = RangeFloatVecSampler::new(0.0, 1.0, 1);
+        let mut tree = RRTStarTree::new(q_init);
+        let mut kdtree = KDTree::new_empty(dim);
+        kdtree.add_point(q_init)?;
+        let mut goal_parent_idxs: Vec<usize> = Vec::new();
+        let mut best_cost = std::f64::INFINITY;
+        let mut num_collision_checks = 0;
+
+        loop {
+            if terminate.get_terminate() { break; }
+            if self._time_budget_in_seconds.is_some() && start.elapsed().as_secs_f64() >= self._time_budget_in_seconds.unwrap() { break; }
+            if num_collision_checks >= self._max_num_collision_checks { break; }
+
+            // goal biasing only helps until the first solution is found
+            let q_rand = if best_cost == std::f64::INFINITY && to_goal_sampler.float_vec_sampler_sample()?[0] < 0.1 {
+                q_goal.clone()
+            } else if best_cost < std::f64::INFINITY && heuristic_scaling.is_some() {
+                self._sample_informed(q_init, q_goal, heuristic_scaling.as_ref().unwrap(), best_cost, lynx_vars)?
+            } else {
+                self._sampler.lynx_float_vec_sampler_sample(lynx_vars)?
+            };
+
+            let q_nearest_res = kdtree.get_closest(&q_rand)?;
+            let q_nearest = kdtree.get_point_ref_from_tuple(q_nearest_res)?.clone();
+            let mut q_new = q_rand.clone();
+            let dir = &q_rand - &q_nearest;
+            let dir_n = dir.norm();
+            if dir_n < 0.000001 { continue; }
+            if dir_n > self._lambda { q_new = &q_nearest + self._lambda * (&dir / dir_n); }
+
+            // k_n = 2e log(n) nearest neighbors keeps the k-nearest variant asymptotically optimal
+            let n = tree.get_num_nodes() as f64;
+            let k = ((2.0 * std::f64::consts::E * (n + 1.0).ln()).ceil() as usize).max(1);
+            let mut neighbor_idxs: Vec<usize> = kdtree.get_closest_k(&q_new, k).iter().map(|x| x.0).collect();
+            if !neighbor_idxs.contains(&q_nearest_res.0) { neighbor_idxs.push(q_nearest_res.0); }
+
+            // choose the parent giving the lowest cost to q_new, only collision checking candidates that could win
+            let mut candidates: Vec<(usize, f64)> = neighbor_idxs.iter().map(|i| (*i, tree.get_cost(*i) + self._path_cost.segment_cost(tree.get_node_ref(*i), &q_new))).collect();
+            candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            let mut parent: Option<(usize, f64)> = None;
+            for c in &candidates {
+                if num_collision_checks >= self._max_num_collision_checks { break; }
+                let res = self._collision_checker.segment_in_collision(tree.get_node_ref(c.0), &q_new, lynx_vars)?;
+                num_collision_checks += 1;
+                match res {
+                    CollisionCheckResult::NotInCollision => { parent = Some(*c); break; }
+                    CollisionCheckResult::InCollision(_) => { }
+                    CollisionCheckResult::Error(s) => { return Err(s); }
+                }
+            }
+            if parent.is_none() { continue; }
+            let (parent_idx, new_cost) = parent.unwrap();
+
+            let new_idx = tree.add_node(&q_new, parent_idx, new_cost);
+            kdtree.add_point(&q_new)?;
+
+            // rewire neighbors through q_new where that is cheaper
+            for i in &neighbor_idxs {
+                if *i == parent_idx || num_collision_checks >= self._max_num_collision_checks { continue; }
+                let cost_through_new = new_cost + self._path_cost.segment_cost(&q_new, tree.get_node_ref(*i));
+                if cost_through_new >= tree.get_cost(*i) { continue; }
+                let res = self._collision_checker.segment_in_collision(&q_new, tree.get_node_ref(*i), lynx_vars)?;
+                num_collision_checks += 1;
+                match res {
+                    CollisionCheckResult::NotInCollision => { tree.set_parent(*i, new_idx, cost_through_new); }
+                    CollisionCheckResult::InCollision(_) => { }
+                    CollisionCheckResult::Error(s) => { return Err(s); }
+                }
+            }
+
+            if (&q_new - q_goal).norm() < self._lambda * 1.5 && num_collision_checks < self._max_num_collision_checks {
+                let res = self._collision_checker.segment_in_collision(&q_new, q_goal, lynx_vars)?;
+                num_collision_checks += 1;
+                match res {
+                    CollisionCheckResult::NotInCollision => { goal_parent_idxs.push(new_idx); }
+                    CollisionCheckResult::InCollision(_) => { }
+                    CollisionCheckResult::Error(s) => { return Err(s); }
+                }
+            }
+
+            // rewiring can lower the cost of existing goal connections, so all of them are re-evaluated
+            let curr_best = self._get_best_goal_parent(&tree, &goal_parent_idxs, q_goal);
+            if curr_best.is_some() && curr_best.unwrap().1 < best_cost {
+                best_cost = curr_best.unwrap().1;
+                let cost_over_time = (start.elapsed().as_secs_f64(), best_cost);
+                write_to_recorder_arc_mutex_option!(recorder, &cost_over_time, "rrt_star_best_cost");
+            }
+        }
+
+        let best = self._get_best_goal_parent(&tree, &goal_parent_idxs, q_goal);
+        return match best {
+            Some((idx, _)) => {
+                let mut path = tree.get_path_from_root_to_node(idx);
+                path.add_waypoint(q_goal);
+                Ok(PathPlannerResult::SolutionFound(path))
+            }
+            None => { Ok(PathPlannerResult::SolutionNotFound(format!("RRT* did not reach the goal with {:?} tree nodes and {:?} collision checks", tree.get_num_nodes(), num_collision_checks))) }
+        }
+    }
+
+    fn _get_best_goal_parent(&self, tree: &RRTStarTree, goal_parent_idxs: &Vec<usize>, q_goal: &DVector<f64>) -> Option<(usize, f64)> {
+        let mut out_val: Option<(usize, f64)> = None;
+        for i in goal_parent_idxs {
+            let c = tree.get_cost(*i) + self._path_cost.segment_cost(tree.get_node_ref(*i), q_goal);
+            if out_val.is_none() || c < out_val.unwrap().1 { out_val = Some((*i, c)); }
+        }
+        return out_val;
+    }
+
+    /* uniform sample from the prolate hyperspheroid (in the space scaled by the path cost's heuristic scaling) of states
+    that could lie on a path cheaper than best_cost.  If the sampler has bounds, samples outside of them are rejected; if
+    the hyperspheroid barely overlaps the bounds and no sample lands inside after many tries, a regular sample is returned. */
+    fn _sample_informed(&self, q_init: &DVector<f64>, q_goal: &DVector<f64>, scaling: &DVector<f64>, best_cost: f64, lynx_vars: &mut LynxVarsGeneric) -> Result<DVector<f64>, String> {
+        let x_init = q_init.component_mul(scaling);
+        let x_goal = q_goal.component_mul(scaling);
+        let c_min = (&x_goal - &x_init).norm();
+        if !(best_cost > c_min) || c_min < 0.000001 { return self._sampler.lynx_float_vec_sampler_sample(lynx_vars); }
+
+        let bounds = self._sampler.lynx_float_vec_sampler_bounds();
+        let max_num_tries = 1000;
+        for _ in 0..max_num_tries {
+            let q = Self::_sample_prolate_hyperspheroid(&x_init, &x_goal, c_min, best_cost).component_div(scaling);
+            if bounds.is_none() { return Ok(q); }
+            let b = bounds.as_ref().unwrap();
+            if b.len() == q.len() && (0..q.len()).all(|i| q[i] >= b[i].0 && q[i] <= b[i].1) { return Ok(q); }
+        }
+
+        return self._sampler.lynx_float_vec_sampler_sample(lynx_vars);
+    }
+
+    fn _sample_prolate_hyperspheroid(x_init: &DVector<f64>, x_goal: &DVector<f64>, c_min: f64, best_cost: f64) -> DVector<f64> {
+        let dim = x_init.len();
+
+        // uniform in the unit ball: gaussian direction (box-muller) with radius u^(1/dim)
+        let mut rng = rand::thread_rng();
+        let uniform = Uniform::new(0.0, 1.0);
+        let mut ball = DVector::from_element(dim, 0.0);
+        for i in 0..dim {
+            let u1: f64 = 1.0 - uniform.sample(&mut rng);
+            let u2: f64 = uniform.sample(&mut rng);
+            ball[i] = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+        }
+        let ball_n = ball.norm();
+        if ball_n < 0.000001 { return (x_init + x_goal) / 2.0; }
+        let r: f64 = uniform.sample(&mut rng);
+        ball = ball * (r.powf(1.0 / dim as f64) / ball_n);
+
+        // stretch along the first axis, then map the first axis onto the start to goal direction with a householder reflection
+        let mut y = ball;
+        y[0] *= best_cost / 2.0;
+        let minor_radius = (best_cost * best_cost - c_min * c_min).sqrt() / 2.0;
+        for i in 1..dim { y[i] *= minor_radius; }
+
+        let a1 = (x_goal - x_init) / c_min;
+        let mut v = -a1;
+        v[0] += 1.0;
+        let v_n2 = v.dot(&v);
+        if v_n2 > 0.000000001 { y = &y - &v * (2.0 * v.dot(&y) / v_n2); }
+
+        return (x_init + x_goal) / 2.0 + y;
+    }
+}
+
+impl GlobalSearch for RRTStar {
+    fn solve_global(&self, q_init: &DVector<f64>, q_goal: &DVector<f64>, local_search: &LocalSearchBox, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption, terminate: &mut TerminationUtilOption) -> Result<PathPlannerResult, String> {
+        return self._solve_single_threaded(q_init, q_goal, false, lynx_vars, recorder, terminate);
+    }
+    fn name_global(&self) -> String {
+        return "RRTStar_".to_string() + self._path_cost.name().as_str() + "_" + usize_to_string(self._max_num_collision_checks).as_str();
+    }
+}
+
+impl LocalSearch for RRTStar {
+    fn solve_local(&self, q_init: &DVector<f64>, q_goal: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption, terminate: &mut TerminationUtilOption) -> Result<PathPlannerResult, String> {
+        return self._solve_single_threaded(q_init, q_goal, false, lynx_vars, recorder, terminate);
+    }
+    fn name_local(&self) -> String {
+        return self.name_global();
+    }
+}
+impl LynxVarsUser for RRTStar { }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/* RRT* that, once a solution of cost c is found, only samples states whose heuristic cost through them (start to state to
+goal) is below c.  This needs the path cost to provide a heuristic scaling (JointSpaceLengthPathCost does, weighted or not);
+otherwise it behaves exactly like RRTStar.  Cost improvements are recorded with the same "rrt_star_best_cost" label. */
+#[derive(Clone)]
+pub struct InformedRRTStar {
+    _rrt_star: RRTStar
+}
+
+impl InformedRRTStar {
+    pub fn new(sampler: LynxFloatVecSamplerBox, collision_checker: CollisionCheckerBox, path_cost: PathCostBox, lambda: f64, time_budget_in_seconds: Option<f64>, max_num_collision_checks: usize) -> Self {
+        return Self { _rrt_star: RRTStar::new(sampler, collision_checker, path_cost, lambda, time_budget_in_seconds, max_num_collision_checks) };
+    }
+
+    pub fn new_joint_space_length(sampler: LynxFloatVecSamplerBox, collision_checker: CollisionCheckerBox, lambda: f64, time_budget_in_seconds: Option<f64>, max_num_collision_checks: usize) -> Self {
+        return Self::new(sampler, collision_checker, PathCostBox::new_joint_space_length(), lambda, time_budget_in_seconds, max_num_collision_checks);
+    }
+
+    pub fn get_path_cost_ref(&self) -> &PathCostBox {
+        return self._rrt_star.get_path_cost_ref();
+    }
+}
+
+impl GlobalSearch for InformedRRTStar {
+    fn solve_global(&self, q_init: &DVector<f64>, q_goal: &DVector<f64>, local_search: &LocalSearchBox, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption, terminate: &mut TerminationUtilOption) -> Result<PathPlannerResult, String> {
+        return self._rrt_star._solve_single_threaded(q_init, q_goal, true, lynx_vars, recorder, terminate);
+    }
+    fn name_global(&self) -> String {
+        return "InformedRRTStar_".to_string() + self._rrt_star._path_cost.name().as_str() + "_" + usize_to_string(self._rrt_star._max_num_collision_checks).as_str();
+    }
+}
+
+impl LocalSearch for InformedRRTStar {
+    fn solve_local(&self, q_init: &DVector<f64>, q_goal: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption, terminate: &mut TerminationUtilOption) -> Result<PathPlannerResult, String> {
+        return self._rrt_star._solve_single_threaded(q_init, q_goal, true, lynx_vars, recorder, terminate);
+    }
+    fn name_local(&self) -> String {
+        return self.name_global();
+    }
+}
+impl LynxVarsUser for InformedRRTStar { }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/* search tree with mutable parents.  Costs are costs to come from the root, and are pushed down to all descendants when a
+node is rewired */
+struct RRTStarTree {
+    _nodes: Vec<DVector<f64>>,
+    _parents: Vec<Option<usize>>,
+    _children: Vec<Vec<usize>>,
+    _costs: Vec<f64>
+}
+impl RRTStarTree {
+    pub fn new(root: &DVector<f64>) -> Self {
+        return Self { _nodes: vec![root.clone()], _parents: vec![None], _children: vec![Vec::new()], _costs: vec![0.0] };
+    }
+
+    pub fn add_node(&mut self, q: &DVector<f64>, parent_idx: usize, cost: f64) -> usize {
+        let idx = self._nodes.len();
+        self._nodes.push(q.clone());
+        self._parents.push(Some(parent_idx));
+        self._children.push(Vec::new());
+        self._costs.push(cost);
+        self._children[parent_idx].push(idx);
+        return idx;
+    }
+
+    pub fn set_parent(&mut self, idx: usize, parent_idx: usize, cost: f64) {
+        let old_parent = self._parents[idx];
+        if old_parent.is_some() { self._children[old_parent.unwrap()].retain(|c| *c != idx); }
+        self._parents[idx] = Some(parent_idx);
+        self._children[parent_idx].push(idx);
+
+        let delta = cost - self._costs[idx];
+        let mut stack = vec![idx];
+        while !stack.is_empty() {
+            let i = stack.pop().unwrap();
+            self._costs[i] += delta;
+            for c in &self._children[i] { stack.push(*c); }
+        }
+    }
+
+    pub fn get_path_from_root_to_node(&self, idx: usize) -> LinearSplinePath {
+        let mut idxs = vec![idx];
+        let mut curr = idx;
+        while self._parents[curr].is_some() {
+            curr = self._parents[curr].unwrap();
+            idxs.push(curr);
+        }
+        idxs.reverse();
+
+        let mut out_path = LinearSplinePath::new_empty();
+        for i in idxs { out_path.add_waypoint(&self._nodes[i]); }
+        return out_path;
+    }
+
+    pub fn get_node_ref(&self, idx: usize) -> &DVector<f64> { return &self._nodes[idx]; }
+
+    pub fn get_cost(&self, idx: usize) -> f64 { return self._costs[idx]; }
+
+    pub fn get_num_nodes(&self) -> usize { return self._nodes.len(); }
+}
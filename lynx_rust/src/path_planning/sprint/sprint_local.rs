@@ -53,7 +53,7 @@ impl SprintLocal {
                 let q_c_star = probability_heuristic_3(q_x_idx, &sprint_local_data_manager, &planning_tree, self._lambda, self._debug)?;
                 if self._debug { println!("{}{}    q_c_star was just calculated to be {:?} {}", color::Fg(color::White), style::Bold, q_c_star.data.as_vec(), style::Reset); }
 
-                let in_collision = self._collision_checker.in_collision(&q_c_star, lynx_vars)?;
+                let in_collision = self._collision_checker.segment_in_collision(planning_tree.get_node_ref(q_x_idx)?, &q_c_star, lynx_vars)?;
 
                 match in_collision {
                     CollisionCheckResult::InCollision(s) => {
@@ -73,7 +73,7 @@ impl SprintLocal {
                         let new_idx_redundancy_check = planning_tree.add_node_with_auto_two_waypoint_inflow_edge(&q_c_star, q_x_idx)?;
                         if !(new_idx == new_idx_redundancy_check) { return Err(format!("looks like there was an index misalignment in sprint local search.  data manager was on idx {:?} and planning tree was on idx {:?}", new_idx, new_idx_redundancy_check)); }
                         if self._debug { println!("{}{}    q_c_star is not in collision! added as node {:?} {}", color::Fg(color::LightGreen), style::Bold, new_idx, style::Reset); }
-                        if (q_goal - &q_c_star).norm() < 2.0 * self._lambda {
+                        if (q_goal - &q_c_star).norm() < 2.0 * self._lambda && !self._collision_checker.segment_in_collision(&q_c_star, q_goal, lynx_vars)?.is_in_collision() {
                             if self._debug { println!("{}{}>>>> Solution found! {}", color::Fg(color::Green), style::Bold, style::Reset); }
                             let goal_idx = planning_tree.add_node_with_auto_linear_inflow_edge(q_goal, new_idx, self._lambda)?;
                             return Ok(PathPlannerResult::SolutionFound(planning_tree.get_path_from_tree_root_to_node(goal_idx)?));
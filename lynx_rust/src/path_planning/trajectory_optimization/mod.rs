@@ -0,0 +1,3 @@
+pub mod trajectory_objective_terms;
+pub mod trajectory_optimizer;
+pub mod prelude;
\ No newline at end of file
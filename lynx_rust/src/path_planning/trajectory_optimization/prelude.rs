@@ -0,0 +1 @@
+pub use crate::path_planning::trajectory_optimization::{trajectory_objective_terms::*, trajectory_optimizer::*};
\ No newline at end of file
@@ -0,0 +1,188 @@
+use crate::utils::utils_optimization::isolated_objective_term::*;
+use crate::utils::utils_vars::prelude::*;
+use crate::utils::utils_recorders::prelude::*;
+use nalgebra::DVector;
+
+/* objective terms over a whole trajectory.  x is the concatenation of the trajectory's free waypoints (as glued by
+glue_linear_spline_path_into_concatenated_optimization_vector), each of dimension dim.  The start state is held fixed, so it
+is stored in the terms that need it rather than being part of x. */
+
+// sum of squared differences between consecutive waypoints
+#[derive(Clone)]
+pub struct TrajectoryMinimizeJointVelocity {
+    _start_state: DVector<f64>,
+    _end_state: Option<DVector<f64>>,
+    _dim: usize
+}
+impl TrajectoryMinimizeJointVelocity {
+    // end_state is the fixed goal state if the last waypoint is not part of x
+    pub fn new(start_state: &DVector<f64>, end_state: Option<&DVector<f64>>) -> Self {
+        return Self { _start_state: start_state.clone(), _end_state: end_state.map(|e| e.clone()), _dim: start_state.len() };
+    }
+}
+impl IsolatedObjectiveTerm for TrajectoryMinimizeJointVelocity {
+    fn name(&self) -> String { return "trajectory_minimize_joint_velocity".to_string() }
+    fn call(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<f64, String> {
+        let states = get_trajectory_states(x, &self._start_state, &self._end_state, self._dim)?;
+        let mut out_val = 0.0;
+        let l = states.len();
+        for i in 1..l { out_val += (&states[i] - &states[i-1]).norm_squared(); }
+        return Ok(out_val);
+    }
+    fn gradient(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<DVector<f64>, String> {
+        let states = get_trajectory_states(x, &self._start_state, &self._end_state, self._dim)?;
+        let mut out_gradient = DVector::from_element(x.len(), 0.0);
+        let l = states.len();
+        for i in 1..l {
+            let d = 2.0 * (&states[i] - &states[i-1]);
+            // states[0] is the fixed start, so states[i] is free waypoint i - 1 (if it is in x at all)
+            add_to_waypoint_gradient(&mut out_gradient, i as isize - 1, &d, self._dim);
+            add_to_waypoint_gradient(&mut out_gradient, i as isize - 2, &(-d), self._dim);
+        }
+        return Ok(out_gradient);
+    }
+}
+impl LynxVarsUser for TrajectoryMinimizeJointVelocity { }
+
+// sum of squared second differences over consecutive waypoint triples
+#[derive(Clone)]
+pub struct TrajectoryMinimizeJointAcceleration {
+    _start_state: DVector<f64>,
+    _end_state: Option<DVector<f64>>,
+    _dim: usize
+}
+impl TrajectoryMinimizeJointAcceleration {
+    pub fn new(start_state: &DVector<f64>, end_state: Option<&DVector<f64>>) -> Self {
+        return Self { _start_state: start_state.clone(), _end_state: end_state.map(|e| e.clone()), _dim: start_state.len() };
+    }
+}
+impl IsolatedObjectiveTerm for TrajectoryMinimizeJointAcceleration {
+    fn name(&self) -> String { return "trajectory_minimize_joint_acceleration".to_string() }
+    fn call(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<f64, String> {
+        let states = get_trajectory_states(x, &self._start_state, &self._end_state, self._dim)?;
+        let mut out_val = 0.0;
+        let l = states.len();
+        for i in 2..l { out_val += (&states[i] - 2.0 * &states[i-1] + &states[i-2]).norm_squared(); }
+        return Ok(out_val);
+    }
+    fn gradient(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<DVector<f64>, String> {
+        let states = get_trajectory_states(x, &self._start_state, &self._end_state, self._dim)?;
+        let mut out_gradient = DVector::from_element(x.len(), 0.0);
+        let l = states.len();
+        for i in 2..l {
+            let a = 2.0 * (&states[i] - 2.0 * &states[i-1] + &states[i-2]);
+            add_to_waypoint_gradient(&mut out_gradient, i as isize - 1, &a, self._dim);
+            add_to_waypoint_gradient(&mut out_gradient, i as isize - 2, &(-2.0 * &a), self._dim);
+            add_to_waypoint_gradient(&mut out_gradient, i as isize - 3, &a, self._dim);
+        }
+        return Ok(out_gradient);
+    }
+}
+impl LynxVarsUser for TrajectoryMinimizeJointAcceleration { }
+
+/* squared distance of the last free waypoint from a configuration space goal.  Used when the goal is not held fixed, so
+that goal terms (e.g., link pose matching) can trade off against staying near the seed's goal */
+#[derive(Clone)]
+pub struct TrajectoryCSpaceGoal {
+    _cspace_goal: DVector<f64>
+}
+impl TrajectoryCSpaceGoal {
+    pub fn new(cspace_goal: &DVector<f64>) -> Self {
+        return Self { _cspace_goal: cspace_goal.clone() };
+    }
+}
+impl IsolatedObjectiveTerm for TrajectoryCSpaceGoal {
+    fn name(&self) -> String { return "trajectory_cspace_goal".to_string() }
+    fn call(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<f64, String> {
+        let dim = self._cspace_goal.len();
+        if x.len() < dim { return Err(format!("trajectory optimization vector of length {:?} has no waypoint of dimension {:?}", x.len(), dim)); }
+        let last = x.rows(x.len() - dim, dim);
+        return Ok((&last - &self._cspace_goal).norm_squared());
+    }
+    fn gradient(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<DVector<f64>, String> {
+        let dim = self._cspace_goal.len();
+        if x.len() < dim { return Err(format!("trajectory optimization vector of length {:?} has no waypoint of dimension {:?}", x.len(), dim)); }
+        let mut out_gradient = DVector::from_element(x.len(), 0.0);
+        let last = x.rows(x.len() - dim, dim);
+        out_gradient.rows_mut(x.len() - dim, dim).copy_from(&(2.0 * (&last - &self._cspace_goal)));
+        return Ok(out_gradient);
+    }
+}
+impl LynxVarsUser for TrajectoryCSpaceGoal { }
+
+/* applies a single state objective term (e.g., RobotEnvironmentCollisionAvoidance) to every free waypoint and sums the
+results, or only to the last free waypoint for goal terms.  Gradients come from the wrapped term's gradient at each
+waypoint, so analytic gradients are kept. */
+#[derive(Clone)]
+pub struct TrajectoryWaypointTerm {
+    _term: IsolatedObjectiveTermBox,
+    _dim: usize,
+    _final_waypoint_only: bool
+}
+impl TrajectoryWaypointTerm {
+    pub fn new(term: IsolatedObjectiveTermBox, dim: usize) -> Self {
+        return Self { _term: term, _dim: dim, _final_waypoint_only: false };
+    }
+
+    pub fn new_final_waypoint_only(term: IsolatedObjectiveTermBox, dim: usize) -> Self {
+        return Self { _term: term, _dim: dim, _final_waypoint_only: true };
+    }
+
+    fn _get_waypoint_idxs(&self, x: &DVector<f64>) -> Result<Vec<usize>, String> {
+        if self._dim == 0 || x.len() % self._dim != 0 || x.len() == 0 {
+            return Err(format!("trajectory optimization vector of length {:?} cannot be split into waypoints of dimension {:?}", x.len(), self._dim));
+        }
+        let num_waypoints = x.len() / self._dim;
+        if self._final_waypoint_only { return Ok(vec![num_waypoints - 1]); }
+        return Ok((0..num_waypoints).collect());
+    }
+}
+impl IsolatedObjectiveTerm for TrajectoryWaypointTerm {
+    fn name(&self) -> String {
+        return if self._final_waypoint_only { "trajectory_goal_".to_string() + self._term.name().as_str() } else { "trajectory_waypoint_".to_string() + self._term.name().as_str() };
+    }
+    fn call(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<f64, String> {
+        let mut out_val = 0.0;
+        for i in self._get_waypoint_idxs(x)? {
+            let waypoint = DVector::from_iterator(self._dim, x.rows(i * self._dim, self._dim).iter().cloned());
+            out_val += self._term.call(&waypoint, lynx_vars, recorder)?;
+        }
+        return Ok(out_val);
+    }
+    fn gradient(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<DVector<f64>, String> {
+        let mut out_gradient = DVector::from_element(x.len(), 0.0);
+        for i in self._get_waypoint_idxs(x)? {
+            let waypoint = DVector::from_iterator(self._dim, x.rows(i * self._dim, self._dim).iter().cloned());
+            let g = self._term.gradient(&waypoint, lynx_vars, recorder)?;
+            out_gradient.rows_mut(i * self._dim, self._dim).copy_from(&g);
+        }
+        return Ok(out_gradient);
+    }
+}
+impl LynxVarsUser for TrajectoryWaypointTerm {
+    fn get_lynx_vars_types(&self) -> Vec<(&'static str, &'static str)> {
+        return self._term.get_lynx_vars_types();
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// start state, then the free waypoints in x, then the fixed end state if there is one
+fn get_trajectory_states(x: &DVector<f64>, start_state: &DVector<f64>, end_state: &Option<DVector<f64>>, dim: usize) -> Result<Vec<DVector<f64>>, String> {
+    if dim == 0 || x.len() % dim != 0 {
+        return Err(format!("trajectory optimization vector of length {:?} cannot be split into waypoints of dimension {:?}", x.len(), dim));
+    }
+    let mut out_vec = vec![start_state.clone()];
+    let num_waypoints = x.len() / dim;
+    for i in 0..num_waypoints { out_vec.push(DVector::from_iterator(dim, x.rows(i * dim, dim).iter().cloned())); }
+    if end_state.is_some() { out_vec.push(end_state.as_ref().unwrap().clone()); }
+    return Ok(out_vec);
+}
+
+// waypoint_idx is the index of a free waypoint in x, so negative indices (the start) and indices past x (the end) are fixed
+fn add_to_waypoint_gradient(gradient: &mut DVector<f64>, waypoint_idx: isize, d: &DVector<f64>, dim: usize) {
+    if waypoint_idx < 0 { return; }
+    let start = waypoint_idx as usize * dim;
+    if start + dim > gradient.len() { return; }
+    for j in 0..dim { gradient[start + j] += d[j]; }
+}
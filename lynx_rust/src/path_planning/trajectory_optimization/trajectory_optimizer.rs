@@ -0,0 +1,163 @@
+use crate::path_planning::trajectory_optimization::trajectory_objective_terms::*;
+use crate::utils::utils_optimization::{objective_function::*, isolated_objective_term::*, nonlinear_optimization_engine::*};
+use crate::utils::utils_paths::{linear_spline_path::LinearSplinePath, path_resample::*, path_splitter_and_gluer::*};
+use crate::utils::utils_vars::prelude::*;
+use crate::utils::utils_recorders::prelude::*;
+use nalgebra::DVector;
+
+/* optimizes a whole seed trajectory at once (in the spirit of CHOMP and TrajOpt).  All waypoints after the start are
+optimization variables, glued into one vector, and the objective is joint velocity and acceleration smoothness plus the
+added waypoint terms (collision avoidance, clearance, etc.) evaluated at every waypoint.  The goal is held fixed unless a goal
+pull weight is set, in which case the last waypoint is also free and is pulled toward the seed's goal, alongside any added
+goal terms evaluated at the last waypoint only.
+
+Collision terms only see the waypoints, so the seed should be dense enough (or be resampled with set_num_waypoints) that
+nothing can fit between consecutive waypoints. */
+#[derive(Clone)]
+pub struct TrajectoryOptimizer {
+    _engine: NonlinearOptimizationEngineBox,
+    _velocity_weight: f64,
+    _acceleration_weight: f64,
+    _waypoint_terms: Vec<(IsolatedObjectiveTermBox, f64)>,
+    _goal_terms: Vec<(IsolatedObjectiveTermBox, f64)>,
+    _goal_pull_weight: Option<f64>,
+    _num_waypoints: Option<usize>,
+    _bounds: Option<Vec<(f64, f64)>>
+}
+
+impl TrajectoryOptimizer {
+    pub fn new(engine: NonlinearOptimizationEngineBox, velocity_weight: f64, acceleration_weight: f64) -> Self {
+        return Self { _engine: engine, _velocity_weight: velocity_weight, _acceleration_weight: acceleration_weight, _waypoint_terms: Vec::new(),
+            _goal_terms: Vec::new(), _goal_pull_weight: None, _num_waypoints: None, _bounds: None };
+    }
+
+    pub fn add_waypoint_term(&mut self, term: IsolatedObjectiveTermBox, weight: f64) {
+        self._waypoint_terms.push((term, weight));
+    }
+
+    // goal terms are only used when the goal is free (see set_goal_pull_weight)
+    pub fn add_goal_term(&mut self, term: IsolatedObjectiveTermBox, weight: f64) {
+        self._goal_terms.push((term, weight));
+    }
+
+    // None holds the goal fixed at the seed's last waypoint
+    pub fn set_goal_pull_weight(&mut self, goal_pull_weight: Option<f64>) {
+        self._goal_pull_weight = goal_pull_weight;
+    }
+
+    // resample the seed to this many waypoints (evenly spaced by arclength) before optimizing
+    pub fn set_num_waypoints(&mut self, num_waypoints: Option<usize>) {
+        self._num_waypoints = num_waypoints;
+    }
+
+    // per dof (lower, upper) bounds, applied to every free waypoint
+    pub fn set_bounds(&mut self, bounds: Option<Vec<(f64, f64)>>) {
+        self._bounds = bounds;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    /* the objective function and initial condition that optimize would use for this seed, e.g., to compare objective
+    values before and after optimizing */
+    pub fn get_objective_function_and_initial_condition(&self, seed: &LinearSplinePath) -> Result<(ObjectiveFunction, DVector<f64>), String> {
+        let resampled_seed = self._get_resampled_seed(seed)?;
+        return self._get_objective_function_and_initial_condition(&resampled_seed);
+    }
+
+    /* max_iter and max_evaluation_time are passed through to the engine.  The engine's bounds lynx vars are set for the
+    trajectory while it runs and put back afterwards. */
+    pub fn optimize(&self, seed: &LinearSplinePath, lynx_vars: &mut LynxVarsGeneric, max_iter: Option<usize>, max_evaluation_time: Option<f64>, recorder: &RecorderArcMutexOption, debug: bool) -> Result<LinearSplinePath, String> {
+        let resampled_seed = self._get_resampled_seed(seed)?;
+        let dim = resampled_seed.waypoints[0].len();
+        let start_state = resampled_seed.waypoints[0].clone();
+        let goal_state = resampled_seed.waypoints[resampled_seed.waypoints.len() - 1].clone();
+
+        let (objective_function, initial_condition) = self._get_objective_function_and_initial_condition(&resampled_seed)?;
+        if initial_condition.len() == 0 { return Ok(resampled_seed); }
+        let num_free_waypoints = initial_condition.len() / dim;
+
+        let (lower_bounds, upper_bounds) = self._get_tiled_bounds(dim, num_free_waypoints)?;
+        type f64VecOption = Option<Vec<f64>>;
+        let prev_lower_bounds = get_lynx_var_ref_generic!(lynx_vars, f64VecOption, "lower_bounds_option").ok().cloned();
+        let prev_upper_bounds = get_lynx_var_ref_generic!(lynx_vars, f64VecOption, "upper_bounds_option").ok().cloned();
+        set_or_add_lynx_var_generic!(lynx_vars, f64VecOption, "lower_bounds_option", lower_bounds)?;
+        set_or_add_lynx_var_generic!(lynx_vars, f64VecOption, "upper_bounds_option", upper_bounds)?;
+
+        let res = self._engine.optimize(&initial_condition, &objective_function, lynx_vars, max_iter, max_evaluation_time, recorder, debug);
+
+        set_or_add_lynx_var_generic!(lynx_vars, f64VecOption, "lower_bounds_option", prev_lower_bounds.unwrap_or(None))?;
+        set_or_add_lynx_var_generic!(lynx_vars, f64VecOption, "upper_bounds_option", prev_upper_bounds.unwrap_or(None))?;
+
+        let x_star = match res? {
+            NonlinearOptimizationResult::Success(o) => { o.get_x_star().clone() }
+            NonlinearOptimizationResult::Failure(_) => { None }
+        };
+        if x_star.is_none() { return Err("nonlinear optimization engine failed in TrajectoryOptimizer".to_string()); }
+
+        let free_waypoints = split_concatenated_optimization_vector_into_linear_spline_path(x_star.as_ref().unwrap(), dim)?;
+        let mut out_path = LinearSplinePath::new(vec![start_state]);
+        for w in &free_waypoints.waypoints { out_path.add_waypoint(w); }
+        if self._goal_pull_weight.is_none() { out_path.add_waypoint(&goal_state); }
+
+        return Ok(out_path);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    fn _get_objective_function_and_initial_condition(&self, seed: &LinearSplinePath) -> Result<(ObjectiveFunction, DVector<f64>), String> {
+        let dim = seed.waypoints[0].len();
+        for w in &seed.waypoints {
+            if w.len() != dim { return Err(format!("all waypoints in a trajectory optimization seed must have the same dimension ({:?} and {:?} found)", dim, w.len())); }
+        }
+
+        let start_state = &seed.waypoints[0];
+        let goal_state = &seed.waypoints[seed.waypoints.len() - 1];
+        let free_goal = self._goal_pull_weight.is_some();
+        let fixed_end_state = if free_goal { None } else { Some(goal_state) };
+
+        let mut objective_function = ObjectiveFunction::new(Vec::new(), None)?;
+        objective_function.add_isolated_objective_term(TrajectoryMinimizeJointVelocity::new(start_state, fixed_end_state).to_isolated_objective_term_box(), Some(self._velocity_weight));
+        objective_function.add_isolated_objective_term(TrajectoryMinimizeJointAcceleration::new(start_state, fixed_end_state).to_isolated_objective_term_box(), Some(self._acceleration_weight));
+        for (term, weight) in &self._waypoint_terms {
+            objective_function.add_isolated_objective_term(TrajectoryWaypointTerm::new(term.clone(), dim).to_isolated_objective_term_box(), Some(*weight));
+        }
+        if free_goal {
+            objective_function.add_isolated_objective_term(TrajectoryCSpaceGoal::new(goal_state).to_isolated_objective_term_box(), self._goal_pull_weight);
+            for (term, weight) in &self._goal_terms {
+                objective_function.add_isolated_objective_term(TrajectoryWaypointTerm::new_final_waypoint_only(term.clone(), dim).to_isolated_objective_term_box(), Some(*weight));
+            }
+        }
+
+        let free_waypoints = if free_goal { seed.waypoints[1..].to_vec() } else { seed.waypoints[1..seed.waypoints.len() - 1].to_vec() };
+        let initial_condition = glue_linear_spline_path_into_concatenated_optimization_vector(&LinearSplinePath::new(free_waypoints))?;
+
+        return Ok((objective_function, initial_condition));
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    fn _get_resampled_seed(&self, seed: &LinearSplinePath) -> Result<LinearSplinePath, String> {
+        if seed.waypoints.len() < 2 { return Err(format!("trajectory optimization seed must have at least 2 waypoints ({:?} given)", seed.waypoints.len())); }
+        if self._num_waypoints.is_none() { return Ok(seed.clone()); }
+
+        let num_waypoints = self._num_waypoints.unwrap();
+        if num_waypoints < 2 { return Err(format!("trajectory optimizer must resample to at least 2 waypoints ({:?} given)", num_waypoints)); }
+        return Ok(resample_linear_spline_path(seed, num_waypoints));
+    }
+
+    fn _get_tiled_bounds(&self, dim: usize, num_free_waypoints: usize) -> Result<(Option<Vec<f64>>, Option<Vec<f64>>), String> {
+        if self._bounds.is_none() { return Ok((None, None)); }
+
+        let bounds = self._bounds.as_ref().unwrap();
+        if bounds.len() != dim { return Err(format!("trajectory optimizer has {:?} bounds, but waypoints have dimension {:?}", bounds.len(), dim)); }
+        let mut lower_bounds = Vec::new();
+        let mut upper_bounds = Vec::new();
+        for _ in 0..num_free_waypoints {
+            for b in bounds {
+                lower_bounds.push(b.0);
+                upper_bounds.push(b.1);
+            }
+        }
+        return Ok((Some(lower_bounds), Some(upper_bounds)));
+    }
+}
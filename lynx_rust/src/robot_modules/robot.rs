@@ -36,9 +36,9 @@ impl Robot {
     pub fn new(robot_name: &str, configuration_name: Option<&str>) -> Result<Self, String> {
         if !check_if_robot_is_valid_choice(robot_name) { return Err(format!("Robot {} is not a valid choice as there is not a folder in the robots directory that has this name.", robot_name)) }
 
-        Robot::_create_link_triangle_meshes_if_need_be(&robot_name.to_string())?;
-
         let _robot_configuration_module = RobotConfigurationModule::new(robot_name, configuration_name)?;
+        Robot::_create_link_triangle_meshes_if_need_be(&_robot_configuration_module)?;
+
         let _robot_name = _robot_configuration_module.robot_model_module.robot_name.clone();
         let _robot_dof_module = RobotDOFModule::new(&_robot_configuration_module);
         let _robot_bounds_module = RobotBoundsModule::new(&_robot_configuration_module, &_robot_dof_module);
@@ -62,9 +62,9 @@ impl Robot {
     pub fn new_from_manual_inputs(robot_name: &str, configuration_name: &str, base_offset: ImplicitDualQuaternion, dead_end_link_names: Vec<String>, inactive_joint_names: Vec<String>, mobile_base_mode: String, mobile_base_bounds_filename: Option<&str>) -> Result<Self, String> {
         if !check_if_robot_is_valid_choice(robot_name) { return Err(format!("Robot {} is not a valid choice as there is not a folder in the robots directory that has this name.", robot_name)) }
 
-        Robot::_create_link_triangle_meshes_if_need_be(&robot_name.to_string())?;
-
         let _robot_configuration_module = RobotConfigurationModule::new_manual_inputs(robot_name, configuration_name, base_offset, dead_end_link_names, inactive_joint_names, mobile_base_mode, str_option_to_string_option(mobile_base_bounds_filename));
+        Robot::_create_link_triangle_meshes_if_need_be(&_robot_configuration_module)?;
+
         let _robot_name = _robot_configuration_module.robot_model_module.robot_name.clone();
         let _robot_dof_module = RobotDOFModule::new(&_robot_configuration_module);
         let _robot_bounds_module = RobotBoundsModule::new(&_robot_configuration_module, &_robot_dof_module);
@@ -86,7 +86,7 @@ impl Robot {
     }
 
     pub fn new_from_configuration_module(robot_configuration_module: &RobotConfigurationModule) -> Result<Self, String> {
-        Robot::_create_link_triangle_meshes_if_need_be(&robot_configuration_module.robot_model_module.robot_name)?;
+        Robot::_create_link_triangle_meshes_if_need_be(robot_configuration_module)?;
 
         let _robot_name = robot_configuration_module.robot_model_module.robot_name.clone();
         let _robot_dof_module = RobotDOFModule::new(robot_configuration_module);
@@ -163,6 +163,25 @@ impl Robot {
         }
     }
 
+    // updates the configuration module as well, so a triangle mesh collision module created later uses the same padding
+    pub fn set_link_padding(&mut self, link_name: &str, padding: f64) -> Result<(), String> {
+        self._robot_configuration_module.set_link_padding(link_name, padding)?;
+        let link_idx = self._robot_configuration_module.robot_model_module.get_link_idx_from_name(&link_name.to_string()).unwrap();
+        self._robot_core_collision_module.set_link_padding(link_idx, padding)?;
+        if self._robot_triangle_mesh_collision_module.is_some() {
+            self._robot_triangle_mesh_collision_module.as_mut().unwrap().set_link_padding(link_idx, padding)?;
+        }
+        Ok(())
+    }
+
+    pub fn get_link_padding(&self, link_name: &str) -> f64 {
+        return self._robot_configuration_module.get_link_padding(&link_name.to_string());
+    }
+
+    pub fn refit_link_spheres(&mut self, num_spheres_per_link: usize) -> Result<(), String> {
+        return self._robot_core_collision_module.refit_link_spheres(&self._robot_configuration_module, &self._robot_fk_module, &self._robot_bounds_module, num_spheres_per_link);
+    }
+
     /*
     pub fn get_mesh_info_module_ref(&self) -> &RobotMeshInfoModule {
         return &self._robot_mesh_info_module;
@@ -177,11 +196,12 @@ impl Robot {
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
-    fn _create_link_triangle_meshes_if_need_be(robot_name: &String) -> Result<(), String> {
+    fn _create_link_triangle_meshes_if_need_be(robot_configuration_module: &RobotConfigurationModule) -> Result<(), String> {
+        let robot_name = &robot_configuration_module.robot_model_module.robot_name;
         let exists1 = check_if_path_exists_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_triangle_meshes_visual".to_string());
         let exists2 = check_if_path_exists_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_triangle_meshes_collision".to_string());
         if !(exists1 || exists2) {
-            save_all_links_as_triangle_meshes(robot_name.clone())?;
+            save_all_links_as_triangle_meshes(robot_name.clone(), robot_configuration_module.get_mesh_repair_options_ref())?;
         }
         Ok(())
     }
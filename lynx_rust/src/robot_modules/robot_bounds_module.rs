@@ -262,8 +262,13 @@ impl RobotBoundsModule {
     }
 
     pub fn uniform_sample_from_bounds(&self) -> DVector<f64> {
-        let mut v =  DVector::from_element(self._num_dofs, 0.0);
         let mut rng = rand::thread_rng();
+        return self.uniform_sample_from_bounds_with_rng(&mut rng);
+    }
+
+    // same as uniform_sample_from_bounds, but draws from the given rng (e.g., a seeded StdRng for reproducible sampling)
+    pub fn uniform_sample_from_bounds_with_rng<R: Rng>(&self, rng: &mut R) -> DVector<f64> {
+        let mut v =  DVector::from_element(self._num_dofs, 0.0);
         for i in 0..self._num_dofs {
             v[i] = rng.gen_range(self._lower_bounds[i].max(-100.0), self._upper_bounds[i].min(100.0));
         }
@@ -311,6 +316,9 @@ impl LynxFloatVecSampler for RobotBoundsModule {
     fn lynx_float_vec_sampler_sample(&self, lynx_vars: &mut LynxVarsGeneric) -> Result<DVector<f64>, String> {
         return Ok(self.uniform_sample_from_bounds());
     }
+    fn lynx_float_vec_sampler_bounds(&self) -> Option<Vec<(f64, f64)>> {
+        return Some(self.get_bounds().clone());
+    }
 }
 impl LynxVarsUser for RobotBoundsModule { }
 
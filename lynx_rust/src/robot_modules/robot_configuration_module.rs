@@ -9,6 +9,8 @@ use crate::robot_modules::{link::Link, joint::Joint};
 use nalgebra::{Vector3, UnitQuaternion};
 use std::collections::HashMap;
 use yaml_rust::yaml::Yaml::Null;
+use yaml_rust::Yaml;
+use crate::utils::utils_preprocessing::mesh_preprocessing_utils::MeshRepairOptions;
 
 /*
 Notes:
@@ -25,6 +27,12 @@ Mobile base mode: string that specifies the mode of the base.  Options are:
     - planar_translation  (z assumed to be up)
     - planar_rotation    (z assumed to be up)
     - planar_translation_and_rotation    (z assumed to be up)
+Link padding: safety margin (in meters) that inflates a link's collision geometry in all collision checks.  Links that
+    are not listed in link_padding use default_link_padding.  Both are optional, and can be adjusted at runtime.
+Mesh repair: string that specifies how link meshes are checked when they are first preprocessed.  Options are:
+    - repair  (default, problems are reported and fixed)
+    - validate_only  (problems are reported, meshes are saved as they are)
+    - off
 */
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -36,7 +44,13 @@ pub struct  RobotConfigurationModule {
     pub inactive_joint_names: Vec<String>,
     pub mobile_base_mode: String, // static, floating, planar_translation, planar_rotation, planar_translation_and_rotation
     pub mobile_base_bounds_filename: Option<String>,
-    pub all_inactive_links: Vec<String>
+    pub all_inactive_links: Vec<String>,
+    #[serde(default)]
+    pub default_link_padding: f64,
+    #[serde(default)]
+    pub link_paddings: HashMap<String, f64>,
+    #[serde(default)]
+    pub mesh_repair_options: MeshRepairOptions
 }
 
 impl RobotConfigurationModule {
@@ -96,7 +110,8 @@ impl RobotConfigurationModule {
         let all_inactive_links = Vec::new();
 
         let mut out_self = Self { configuration_name: configuration_name.to_string(), robot_model_module, base_offset,
-            dead_end_link_names, inactive_joint_names, mobile_base_mode, all_inactive_links, mobile_base_bounds_filename };
+            dead_end_link_names, inactive_joint_names, mobile_base_mode, all_inactive_links, mobile_base_bounds_filename,
+            default_link_padding: 0.0, link_paddings: HashMap::new(), mesh_repair_options: MeshRepairOptions::new_default() };
 
         out_self._adjust_model_module_based_on_mobile_base_mode();
         out_self._set_inactive_links();
@@ -163,7 +178,37 @@ impl RobotConfigurationModule {
         base_offset.set_is_identity();
 
 
-        return Ok(Self::new_manual_inputs(robot_name, configuration_name, base_offset, dead_end_link_names, inactive_joint_names, mobile_base_mode, mobile_base_bounds_filename));
+        let mut out_self = Self::new_manual_inputs(robot_name, configuration_name, base_offset, dead_end_link_names, inactive_joint_names, mobile_base_mode, mobile_base_bounds_filename);
+
+        if y1["default_link_padding"] != Null {
+            let default_link_padding = Self::_get_yaml_padding_value(&y1["default_link_padding"], "default_link_padding")?;
+            out_self.set_default_link_padding(default_link_padding)?;
+        }
+
+        if y1["link_padding"] != Null {
+            let link_padding = y1["link_padding"].as_hash();
+            if link_padding.is_none() { return Err("link_padding must be a map from link names to paddings in robot configuration yaml.".to_string()); }
+            for (k, v) in link_padding.unwrap() {
+                let link_name = k.as_str();
+                if link_name.is_none() { return Err(format!("link_padding key {:?} must be a link name", k)); }
+                let padding = Self::_get_yaml_padding_value(v, link_name.unwrap())?;
+                out_self.set_link_padding(link_name.unwrap(), padding)?;
+            }
+        }
+
+        if y1["mesh_repair"] != Null {
+            let mesh_repair = y1["mesh_repair"].as_str();
+            if mesh_repair.is_none() { return Err(format!("mesh_repair ({:?}) must be a string in robot configuration yaml.", y1["mesh_repair"])); }
+            out_self.mesh_repair_options = MeshRepairOptions::new_from_mode(mesh_repair.unwrap())?;
+        }
+
+        return Ok(out_self);
+    }
+
+    fn _get_yaml_padding_value(y: &Yaml, name: &str) -> Result<f64, String> {
+        if y.as_f64().is_some() { return Ok(y.as_f64().unwrap()); }
+        if y.as_i64().is_some() { return Ok(y.as_i64().unwrap() as f64); }
+        return Err(format!("padding for {} must be a number in robot configuration yaml (got {:?})", name, y));
     }
 
     fn _create_configuration_directory_with_example_if_need_be(robot_name: String) {
@@ -180,7 +225,13 @@ impl RobotConfigurationModule {
         out_string += "# base_position_offset: [0., 0., 0.] \n";
         out_string += "#    ^^(represented in meters)\n";
         out_string += "# base_orientation_offset: [0., 0., 0.] \n";
-        out_string += "#    ^^(Euler angles, represented in radians)\n\n";
+        out_string += "#    ^^(Euler angles, represented in radians)\n";
+        out_string += "# default_link_padding: 0.0 \n";
+        out_string += "#    ^^(optional, safety margin in meters added around every link in collision checks)\n";
+        out_string += "# link_padding: {\"link_1\": 0.05} \n";
+        out_string += "#    ^^(optional, per-link safety margin in meters that overrides default_link_padding)\n";
+        out_string += "# mesh_repair: \"repair\" \n";
+        out_string += "#    ^^(optional, can be repair, validate_only, or off.  Only used when link meshes are first preprocessed)\n\n";
         out_string += "dead_end_links: [] \n";
         out_string += "inactive_joints: [] \n";
         out_string += "mobile_base_mode: \"static\" \n";
@@ -315,6 +366,64 @@ impl RobotConfigurationModule {
         }
     }
 
+    pub fn get_link_padding(&self, link_name: &String) -> f64 {
+        let padding = self.link_paddings.get(link_name);
+        if padding.is_some() { return *padding.unwrap(); }
+        return self.default_link_padding;
+    }
+
+    pub fn set_link_padding(&mut self, link_name: &str, padding: f64) -> Result<(), String> {
+        if padding < 0.0 { return Err(format!("padding for link {} must be non-negative (got {:?})", link_name, padding)); }
+        if self.robot_model_module.get_link_idx_from_name(&link_name.to_string()).is_none() {
+            return Err(format!("link {} not found in robot {}", link_name, self.robot_model_module.robot_name));
+        }
+        self.link_paddings.insert(link_name.to_string(), padding);
+        Ok(())
+    }
+
+    pub fn set_default_link_padding(&mut self, padding: f64) -> Result<(), String> {
+        if padding < 0.0 { return Err(format!("default link padding must be non-negative (got {:?})", padding)); }
+        self.default_link_padding = padding;
+        Ok(())
+    }
+
+    pub fn get_mesh_repair_options_ref(&self) -> &MeshRepairOptions {
+        return &self.mesh_repair_options;
+    }
+
+    // writes this configuration out as configurations/<configuration_name>.yaml, in the same format that is loaded above
+    pub fn save_robot_configuration_yaml(&self) {
+        let mut out_string = "".to_string();
+
+        let dead_end_links: Vec<String> = self.dead_end_link_names.iter().map(|s| format!("\"{}\"", s)).collect();
+        out_string += format!("dead_end_links: [{}] \n", dead_end_links.join(", ")).as_str();
+        let inactive_joints: Vec<String> = self.inactive_joint_names.iter().map(|s| format!("\"{}\"", s)).collect();
+        out_string += format!("inactive_joints: [{}] \n", inactive_joints.join(", ")).as_str();
+        out_string += format!("mobile_base_mode: \"{}\" \n", self.mobile_base_mode).as_str();
+        if self.mobile_base_bounds_filename.is_some() {
+            out_string += format!("mobile_base_bounds_filename: \"{}\" \n", self.mobile_base_bounds_filename.as_ref().unwrap()).as_str();
+        } else {
+            out_string += "mobile_base_bounds_filename: \n";
+        }
+        let t = &self.base_offset.translation;
+        out_string += format!("base_position_offset: [{:?}, {:?}, {:?}]\n", t[0], t[1], t[2]).as_str();
+        let (r, p, y) = self.base_offset.quat.euler_angles();
+        out_string += format!("base_orientation_offset: [{:?}, {:?}, {:?}]\n", r, p, y).as_str();
+        if self.default_link_padding > 0.0 {
+            out_string += format!("default_link_padding: {:?}\n", self.default_link_padding).as_str();
+        }
+        if !self.link_paddings.is_empty() {
+            let mut link_paddings: Vec<String> = self.link_paddings.iter().map(|(k, v)| format!("\"{}\": {:?}", k, v)).collect();
+            link_paddings.sort();
+            out_string += format!("link_padding: {{{}}}\n", link_paddings.join(", ")).as_str();
+        }
+        if self.mesh_repair_options.get_mode() != "repair" {
+            out_string += format!("mesh_repair: \"{}\"\n", self.mesh_repair_options.get_mode()).as_str();
+        }
+
+        write_string_to_file_relative_to_robot_directory(self.robot_model_module.robot_name.clone(), "configurations".to_string(), format!("{}.yaml", self.configuration_name.clone()), out_string, true);
+    }
+
     pub fn save_robot_configuration_module(&self) {
         let serialized = serde_json::to_string(&self).unwrap();
         write_string_to_file_relative_to_robot_directory( self.robot_model_module.robot_name.clone(), "autogenerated_metadata/configurations".to_string(), format!("{}.json", self.configuration_name.clone()).to_string(), serialized, true );
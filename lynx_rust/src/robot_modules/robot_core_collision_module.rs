@@ -5,6 +5,7 @@ use crate::utils::utils_files_and_strings::{file_utils::*, robot_folder_utils::*
 use crate::utils::utils_files_and_strings::string_utils::usize_to_string;
 use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
 use termion::{style, color};
+use nalgebra::Vector3;
 
 #[derive(Clone)]
 pub struct RobotCoreCollisionModule {
@@ -12,61 +13,76 @@ pub struct RobotCoreCollisionModule {
     _link_convex_shapes: Vec<Vec<CollisionObject>>,
     _link_obb_subcomponents: Vec<Vec<CollisionObject>>,
     _link_convex_shape_subcomponents: Vec<Vec<CollisionObject>>,
+    _link_spheres: Vec<Vec<CollisionObject>>,
     _link_obbs_skip_collision_check_tensor: BoolCollisionCheckTensor,
     _link_convex_shapes_skip_collision_check_tensor: BoolCollisionCheckTensor,
     _link_obb_subcomponents_skip_collision_check_tensor: BoolCollisionCheckTensor,
     _link_convex_shape_subcomponents_skip_collision_check_tensor: BoolCollisionCheckTensor,
+    _link_spheres_skip_collision_check_tensor: BoolCollisionCheckTensor,
     _link_obbs_average_distance_tensor: FloatCollisionCheckTensor,
     _link_convex_shapes_average_distance_tensor: FloatCollisionCheckTensor,
     _link_obb_subcomponents_average_distance_tensor: FloatCollisionCheckTensor,
     _link_convex_shape_subcomponents_average_distance_tensor: FloatCollisionCheckTensor,
+    _link_spheres_average_distance_tensor: FloatCollisionCheckTensor,
+    _link_paddings: Vec<f64>,
     _robot_name_copy: String
 }
 
 impl RobotCoreCollisionModule {
     pub fn new(robot_configuration_module: &RobotConfigurationModule, robot_fk_module: &RobotFKModule, robot_bounds_module: &RobotBoundsModule) -> Result<Self, String> {
+        return Self::new_with_tensor_generation_settings(robot_configuration_module, robot_fk_module, robot_bounds_module, &CollisionCheckTensorGenerationSettings::new_default());
+    }
+
+    // tensor_generation_settings are only used for skip and average distance tensors that are not already saved in the robot directory
+    pub fn new_with_tensor_generation_settings(robot_configuration_module: &RobotConfigurationModule, robot_fk_module: &RobotFKModule, robot_bounds_module: &RobotBoundsModule, tensor_generation_settings: &CollisionCheckTensorGenerationSettings) -> Result<Self, String> {
         let _link_obbs = Vec::new();
         let _link_convex_shapes = Vec::new();
         let _link_obb_subcomponents = Vec::new();
         let _link_convex_shape_subcomponents = Vec::new();
+        let _link_spheres = Vec::new();
 
         let _link_obbs_skip_collision_check_tensor = BoolCollisionCheckTensor::new_empty();
         let _link_convex_shapes_skip_collision_check_tensor = BoolCollisionCheckTensor::new_empty();
         let _link_obb_subcomponents_skip_collision_check_tensor = BoolCollisionCheckTensor::new_empty();
         let _link_convex_shape_subcomponents_skip_collision_check_tensor = BoolCollisionCheckTensor::new_empty();
+        let _link_spheres_skip_collision_check_tensor = BoolCollisionCheckTensor::new_empty();
 
         let _link_obbs_average_distance_tensor = FloatCollisionCheckTensor::new_empty();
         let _link_convex_shapes_average_distance_tensor = FloatCollisionCheckTensor::new_empty();
         let _link_obb_subcomponents_average_distance_tensor = FloatCollisionCheckTensor::new_empty();
         let _link_convex_shape_subcomponents_average_distance_tensor = FloatCollisionCheckTensor::new_empty();
+        let _link_spheres_average_distance_tensor = FloatCollisionCheckTensor::new_empty();
 
         let _robot_name_copy = robot_configuration_module.robot_model_module.robot_name.clone();
 
-        let mut out_self = Self { _link_obbs, _link_convex_shapes, _link_obb_subcomponents, _link_convex_shape_subcomponents,
+        let mut out_self = Self { _link_obbs, _link_convex_shapes, _link_obb_subcomponents, _link_convex_shape_subcomponents, _link_spheres,
         _link_obbs_skip_collision_check_tensor, _link_convex_shapes_skip_collision_check_tensor, _link_obb_subcomponents_skip_collision_check_tensor,
-            _link_convex_shape_subcomponents_skip_collision_check_tensor, _link_obbs_average_distance_tensor,
+            _link_convex_shape_subcomponents_skip_collision_check_tensor, _link_spheres_skip_collision_check_tensor, _link_obbs_average_distance_tensor,
             _link_convex_shapes_average_distance_tensor, _link_obb_subcomponents_average_distance_tensor,
-            _link_convex_shape_subcomponents_average_distance_tensor, _robot_name_copy };
+            _link_convex_shape_subcomponents_average_distance_tensor, _link_spheres_average_distance_tensor, _link_paddings: Vec::new(), _robot_name_copy };
 
         Self::_create_link_convex_shapes_if_need_be(robot_configuration_module)?;
-        Self::_create_link_convex_subcomponents_if_need_be(robot_configuration_module)?;
+        let changed_subcomponent_links = Self::_create_link_convex_subcomponents_if_need_be(robot_configuration_module)?;
+        Self::_create_link_spheres_if_need_be(robot_configuration_module)?;
 
         out_self._create_link_obbs(robot_configuration_module)?;
         out_self._create_link_convex_shapes(robot_configuration_module)?;
         out_self._create_link_obb_subcomponents(robot_configuration_module)?;
         out_self._create_link_convex_shape_subcomponents(robot_configuration_module)?;
+        out_self._create_link_spheres(robot_configuration_module)?;
 
-        let num_samples = 100_000;
-        out_self._load_or_create_link_skip_collision_check_tensor(&LinkGeometryType::OBBs, robot_fk_module, robot_bounds_module, num_samples, false);
-        out_self._load_or_create_link_skip_collision_check_tensor(&LinkGeometryType::ConvexShapes, robot_fk_module, robot_bounds_module, num_samples, false);
-        out_self._load_or_create_link_skip_collision_check_tensor(&LinkGeometryType::OBBSubcomponents, robot_fk_module, robot_bounds_module, num_samples, false);
-        out_self._load_or_create_link_skip_collision_check_tensor(&LinkGeometryType::ConvexShapeSubcomponents, robot_fk_module, robot_bounds_module, num_samples, false);
+        // paddings are set first so that skip and average distance tensors are generated with padded links
+        out_self._set_link_paddings_from_configuration_module(robot_configuration_module)?;
 
-        let num_samples = 1000;
-        out_self._load_or_create_link_average_distance_tensor(&LinkGeometryType::OBBs, robot_fk_module, robot_bounds_module, num_samples, false);
-        out_self._load_or_create_link_average_distance_tensor(&LinkGeometryType::ConvexShapes, robot_fk_module, robot_bounds_module, num_samples, false);
-        out_self._load_or_create_link_average_distance_tensor(&LinkGeometryType::OBBSubcomponents, robot_fk_module, robot_bounds_module, num_samples, false);
-        out_self._load_or_create_link_average_distance_tensor(&LinkGeometryType::ConvexShapeSubcomponents, robot_fk_module, robot_bounds_module, num_samples, false);
+        // saved subcomponent tensors (including the permanent skip tensors) are stale once any link is decomposed again,
+        // even if the number of subcomponents did not change
+        if !changed_subcomponent_links.is_empty() {
+            for lgt in &vec![ LinkGeometryType::OBBSubcomponents, LinkGeometryType::ConvexShapeSubcomponents ] {
+                out_self._load_or_create_link_skip_collision_check_tensor(lgt, robot_fk_module, robot_bounds_module, tensor_generation_settings, true)?;
+                out_self._load_or_create_link_average_distance_tensor(lgt, robot_fk_module, robot_bounds_module, tensor_generation_settings, true)?;
+            }
+        }
+        out_self._load_or_create_all_link_collision_check_tensors(robot_fk_module, robot_bounds_module, tensor_generation_settings, false)?;
 
         out_self._set_dead_link_collision_objects_as_inactive(robot_configuration_module);
 
@@ -109,32 +125,32 @@ impl RobotCoreCollisionModule {
 
     pub fn environment_intersect_check(&mut self, fk_res: &RobotFKResult, link_geometry_type: LinkGeometryType, collision_environment: &CollisionEnvironment, stop_at_first_detected: bool) -> Result<IntersectCheckMultipleResult, String> {
         self.set_poses_on_links(fk_res, &link_geometry_type);
-        return intersect_check_between_multiple_collision_objects(&collision_environment.environment_obbs, self.get_link_geometry_collision_objects_ref(&link_geometry_type), stop_at_first_detected, None);
+        return intersect_check_between_environment_and_multiple_collision_objects(collision_environment, self.get_link_geometry_collision_objects_ref(&link_geometry_type), stop_at_first_detected, None);
     }
 
     pub fn environment_distance_check(&mut self, fk_res: &RobotFKResult, link_geometry_type: LinkGeometryType, collision_environment: &CollisionEnvironment, stop_at_first_detected: bool) -> Result<DistanceCheckMultipleResult, String> {
         self.set_poses_on_links(fk_res, &link_geometry_type);
-        return distance_check_between_multiple_collision_objects(&collision_environment.environment_obbs, self.get_link_geometry_collision_objects_ref(&link_geometry_type), stop_at_first_detected, None, None);
+        return distance_check_between_environment_and_multiple_collision_objects(collision_environment, self.get_link_geometry_collision_objects_ref(&link_geometry_type), stop_at_first_detected, None, None);
     }
 
     pub fn environment_contact_check(&mut self, fk_res: &RobotFKResult, link_geometry_type: LinkGeometryType, collision_environment: &CollisionEnvironment, stop_at_first_detected: bool, margin: Option<f64>) -> Result<ContactCheckMultipleResult, String> {
         self.set_poses_on_links(fk_res, &link_geometry_type);
-        return contact_check_between_multiple_collision_objects(&collision_environment.environment_obbs, self.get_link_geometry_collision_objects_ref(&link_geometry_type), stop_at_first_detected, margin, None, None);
+        return contact_check_between_environment_and_multiple_collision_objects(collision_environment, self.get_link_geometry_collision_objects_ref(&link_geometry_type), stop_at_first_detected, margin, None, None);
     }
 
     pub fn environment_intersect_check_subset(&mut self, subset_check_idxs: &Vec<[[usize; 2]; 2]>, fk_res: &RobotFKResult, link_geometry_type: LinkGeometryType, collision_environment: &CollisionEnvironment, stop_at_first_detected: bool) -> Result<IntersectCheckMultipleResult, String> {
         self.set_poses_on_links(fk_res, &link_geometry_type);
-        return intersect_check_between_multiple_collision_objects_subset(subset_check_idxs, &collision_environment.environment_obbs, self.get_link_geometry_collision_objects_ref(&link_geometry_type), stop_at_first_detected, None);
+        return intersect_check_between_environment_and_multiple_collision_objects_subset(subset_check_idxs, collision_environment, self.get_link_geometry_collision_objects_ref(&link_geometry_type), stop_at_first_detected, None);
     }
 
     pub fn environment_distance_check_subset(&mut self, subset_check_idxs: &Vec<[[usize; 2]; 2]>, fk_res: &RobotFKResult, link_geometry_type: LinkGeometryType, collision_environment: &CollisionEnvironment, stop_at_first_detected: bool) -> Result<DistanceCheckMultipleResult, String> {
         self.set_poses_on_links(fk_res, &link_geometry_type);
-        return distance_check_between_multiple_collision_objects_subset(subset_check_idxs, &collision_environment.environment_obbs, self.get_link_geometry_collision_objects_ref(&link_geometry_type), stop_at_first_detected, None, None);
+        return distance_check_between_environment_and_multiple_collision_objects_subset(subset_check_idxs, collision_environment, self.get_link_geometry_collision_objects_ref(&link_geometry_type), stop_at_first_detected, None, None);
     }
 
     pub fn environment_contact_check_subset(&mut self, subset_check_idxs: &Vec<[[usize; 2]; 2]>, fk_res: &RobotFKResult, link_geometry_type: LinkGeometryType, collision_environment: &CollisionEnvironment, stop_at_first_detected: bool, margin: Option<f64>) -> Result<ContactCheckMultipleResult, String> {
         self.set_poses_on_links(fk_res, &link_geometry_type);
-        return contact_check_between_multiple_collision_objects_subset(subset_check_idxs, &collision_environment.environment_obbs, self.get_link_geometry_collision_objects_ref(&link_geometry_type), stop_at_first_detected, margin, None, None);
+        return contact_check_between_environment_and_multiple_collision_objects_subset(subset_check_idxs, collision_environment, self.get_link_geometry_collision_objects_ref(&link_geometry_type), stop_at_first_detected, margin, None, None);
     }
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
@@ -161,6 +177,9 @@ impl RobotCoreCollisionModule {
         let load_result4 = BoolCollisionCheckTensor::load_from_file_relative_to_robot_directory(self._robot_name_copy.clone(), "autogenerated_metadata/link_skip_collision_check_tensors".to_string(), "link_convex_shape_subcomponents_skip_collision_check_tensor_permanent.json".to_string());
         if load_result4.is_err() { return Err(format!("could not revert skip collison check tensors because link_convex_shape_subcomponents_skip_collision_check_tensor_permanent.json file is missing.  Delete the whole link_skip_collision_check_tensor folder and restart to let tensors recalculate from scratch.")) }
 
+        let load_result5 = BoolCollisionCheckTensor::load_from_file_relative_to_robot_directory(self._robot_name_copy.clone(), "autogenerated_metadata/link_skip_collision_check_tensors".to_string(), "link_spheres_skip_collision_check_tensor_permanent.json".to_string());
+        if load_result5.is_err() { return Err(format!("could not revert skip collison check tensors because link_spheres_skip_collision_check_tensor_permanent.json file is missing.  Delete the whole link_skip_collision_check_tensor folder and restart to let tensors recalculate from scratch.")) }
+
         self._link_obbs_skip_collision_check_tensor = load_result1.ok().unwrap();
         self._link_obbs_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), "autogenerated_metadata/link_skip_collision_check_tensors".to_string(), "link_obbs_skip_collision_check_tensor.json".to_string());
 
@@ -173,6 +192,9 @@ impl RobotCoreCollisionModule {
         self._link_convex_shape_subcomponents_skip_collision_check_tensor = load_result4.ok().unwrap();
         self._link_convex_shape_subcomponents_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), "autogenerated_metadata/link_skip_collision_check_tensors".to_string(), "link_convex_shape_subcomponents_skip_collision_check_tensor.json".to_string());
 
+        self._link_spheres_skip_collision_check_tensor = load_result5.ok().unwrap();
+        self._link_spheres_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), "autogenerated_metadata/link_skip_collision_check_tensors".to_string(), "link_spheres_skip_collision_check_tensor.json".to_string());
+
         Ok(())
     }
 
@@ -206,6 +228,13 @@ impl RobotCoreCollisionModule {
                 self._link_convex_shape_subcomponents_skip_collision_check_tensor = load_result4.ok().unwrap();
                 self._link_convex_shape_subcomponents_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), "autogenerated_metadata/link_skip_collision_check_tensors".to_string(), "link_convex_shape_subcomponents_skip_collision_check_tensor.json".to_string());
             }
+            LinkGeometryType::Spheres => {
+                let load_result5 = BoolCollisionCheckTensor::load_from_file_relative_to_robot_directory(self._robot_name_copy.clone(), "autogenerated_metadata/link_skip_collision_check_tensors".to_string(), "link_spheres_skip_collision_check_tensor_permanent.json".to_string());
+                if load_result5.is_err() { return Err(format!("could not revert skip collison check tensors because link_spheres_skip_collision_check_tensor_permanent.json file is missing.  Delete the whole link_skip_collision_check_tensor folder and restart to let tensors recalculate from scratch.")) }
+
+                self._link_spheres_skip_collision_check_tensor = load_result5.ok().unwrap();
+                self._link_spheres_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), "autogenerated_metadata/link_skip_collision_check_tensors".to_string(), "link_spheres_skip_collision_check_tensor.json".to_string());
+            }
         }
 
         Ok(())
@@ -214,7 +243,7 @@ impl RobotCoreCollisionModule {
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn add_not_in_collision_example(&mut self, fk_res: &RobotFKResult) -> Result<(), String> {
-        let link_geometry_types = vec![ LinkGeometryType::OBBs,  LinkGeometryType::ConvexShapes,  LinkGeometryType::OBBSubcomponents,  LinkGeometryType::ConvexShapeSubcomponents ];
+        let link_geometry_types = vec![ LinkGeometryType::OBBs,  LinkGeometryType::ConvexShapes,  LinkGeometryType::OBBSubcomponents,  LinkGeometryType::ConvexShapeSubcomponents,  LinkGeometryType::Spheres ];
 
         for lgt in link_geometry_types {
             let res = self.self_intersect_check(fk_res, lgt.clone(), false)?;
@@ -261,7 +290,7 @@ impl RobotCoreCollisionModule {
         let link_idx_2 = robot_configuration_module.robot_model_module.get_link_idx_from_name(&link_name_2);
         if link_idx_2.is_none() { return Err(format!("{:?} was not a valid link name.  ", link_name_2)); }
 
-        let link_geometry_types = vec![ LinkGeometryType::OBBs,  LinkGeometryType::ConvexShapes,  LinkGeometryType::OBBSubcomponents,  LinkGeometryType::ConvexShapeSubcomponents ];
+        let link_geometry_types = vec![ LinkGeometryType::OBBs,  LinkGeometryType::ConvexShapes,  LinkGeometryType::OBBSubcomponents,  LinkGeometryType::ConvexShapeSubcomponents,  LinkGeometryType::Spheres ];
 
         for lgt in link_geometry_types {
             let res = self._get_skip_collision_check_tensor_mut_ref(&lgt).add_skip_for_whole_vec(link_idx_1.unwrap(), link_idx_2.unwrap());
@@ -279,7 +308,7 @@ impl RobotCoreCollisionModule {
         let link_idx_2 = robot_configuration_module.robot_model_module.get_link_idx_from_name(&link_name_2);
         if link_idx_2.is_none() { return Err(format!("{:?} was not a valid link name.  ", link_name_2)); }
 
-        let link_geometry_types = vec![ LinkGeometryType::OBBs,  LinkGeometryType::ConvexShapes,  LinkGeometryType::OBBSubcomponents,  LinkGeometryType::ConvexShapeSubcomponents ];
+        let link_geometry_types = vec![ LinkGeometryType::OBBs,  LinkGeometryType::ConvexShapes,  LinkGeometryType::OBBSubcomponents,  LinkGeometryType::ConvexShapeSubcomponents,  LinkGeometryType::Spheres ];
 
         for lgt in link_geometry_types {
             let res = self._get_skip_collision_check_tensor_mut_ref(&lgt).remove_skip_for_whole_vec(link_idx_1.unwrap(), link_idx_2.unwrap());
@@ -290,6 +319,37 @@ impl RobotCoreCollisionModule {
         Ok(())
     }
 
+    // pairs of link names whose collision checks are skipped entirely (in both directions) for the given geometry type.
+    // Links without any geometry are not included.
+    pub fn get_skipped_link_pairs(&self, robot_configuration_module: &RobotConfigurationModule, link_geometry_type: LinkGeometryType) -> Result<Vec<[String; 2]>, String> {
+        let mut out_vec = Vec::new();
+
+        let collision_check_skip_tensor = self._get_skip_collision_check_tensor_ref(&link_geometry_type);
+        let link_geometry_vec = self.get_link_geometry_collision_objects_ref(&link_geometry_type);
+        let links = &robot_configuration_module.robot_model_module.links;
+
+        let l = link_geometry_vec.len();
+        for i in 0..l {
+            for k in (i+1)..l {
+                if link_geometry_vec[i].is_empty() || link_geometry_vec[k].is_empty() { continue; }
+
+                let mut all_skipped = true;
+                'outer: for j in 0..link_geometry_vec[i].len() {
+                    for m in 0..link_geometry_vec[k].len() {
+                        if !(collision_check_skip_tensor.get_is_skip([i, j], [k, m])? && collision_check_skip_tensor.get_is_skip([k, m], [i, j])?) {
+                            all_skipped = false;
+                            break 'outer;
+                        }
+                    }
+                }
+
+                if all_skipped { out_vec.push( [links[i].name.clone(), links[k].name.clone()] ); }
+            }
+        }
+
+        return Ok(out_vec);
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn accuracy_check(&mut self, robot_fk_module: &RobotFKModule, robot_bounds_module: &RobotBoundsModule, robot_triangle_mesh_collision_module: &mut RobotTriangleMeshCollisionModule, num_samples: usize) -> Result<(), String> {
@@ -397,81 +457,136 @@ impl RobotCoreCollisionModule {
         }
     }
 
-    fn _load_or_create_link_skip_collision_check_tensor(&mut self, link_geometry_type: &LinkGeometryType, robot_fk_module: &RobotFKModule, robot_bounds_module: &RobotBoundsModule, num_samples: usize, create_new_no_matter_what: bool) -> Result<(), String> {
+    fn _load_or_create_link_skip_collision_check_tensor(&mut self, link_geometry_type: &LinkGeometryType, robot_fk_module: &RobotFKModule, robot_bounds_module: &RobotBoundsModule, settings: &CollisionCheckTensorGenerationSettings, create_new_no_matter_what: bool) -> Result<(), String> {
         let load_result = BoolCollisionCheckTensor::load_from_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_filename(link_geometry_type));
         if load_result.is_ok() && !create_new_no_matter_what {
-            self._set_link_skip_tensor(load_result.ok().unwrap(), link_geometry_type);
-            return Ok(());
-        }
-
-        let mut count_collision_check_tensor = FloatCollisionCheckTensor::new(&self.get_link_geometry_collision_objects_ref(link_geometry_type), &self.get_link_geometry_collision_objects_ref(link_geometry_type));
-        let mut initial_skip_collision_check_tensor = BoolCollisionCheckTensor::new(&self.get_link_geometry_collision_objects_ref(link_geometry_type), &self.get_link_geometry_collision_objects_ref(link_geometry_type), self._get_skip_check_for_self_collision_mode(link_geometry_type));
-
-        for i in 0..num_samples {
-            if i % 100 == 0 {
-                println!("{}{}Calculating skip collision tensor for {:?}: sample {} of {} {}", color::Fg(color::Blue), style::Bold, link_geometry_type, i, num_samples, style::Reset);
-            }
-
-            let sample = robot_bounds_module.uniform_sample_from_bounds();
-            let fk_res = robot_fk_module.compute_fk(&sample)?;
-            self.set_poses_on_links(&fk_res, link_geometry_type);
-
-            let intersect_check_multiple_result = intersect_check_between_multiple_collision_objects(&self.get_link_geometry_collision_objects_ref(link_geometry_type), &self.get_link_geometry_collision_objects_ref(link_geometry_type), false, Some(&initial_skip_collision_check_tensor))?;
-            match intersect_check_multiple_result {
-                IntersectCheckMultipleResult::NoIntersectionsFound(i) => { count_collision_check_tensor.increment_count_at_given_idxs( &vec![ ] ); },
-                IntersectCheckMultipleResult::IntersectionFound(i) => { count_collision_check_tensor.increment_count_at_given_idxs( i.get_intersection_idxs() );  }
+            let loaded_tensor = load_result.ok().unwrap();
+            let collision_objects = self.get_link_geometry_collision_objects_ref(link_geometry_type);
+            if loaded_tensor.matches_collision_objects(collision_objects, collision_objects) {
+                self._set_link_skip_tensor(loaded_tensor, link_geometry_type);
+                return Ok(());
             }
+            println!("{}{}Saved {} does not match the current link geometry.  Recomputing it.{}", color::Fg(color::Blue), style::Bold, self._get_link_skip_tensor_filename(link_geometry_type), style::Reset);
         }
 
-        let new_tensor = BoolCollisionCheckTensor::new_for_always_and_never_in_collision_pairs(&count_collision_check_tensor, None, None, self._get_skip_check_for_self_collision_mode(link_geometry_type))?;
+        let (new_tensor, report) = self.compute_link_skip_collision_check_tensor(link_geometry_type, robot_fk_module, robot_bounds_module, settings)?;
         self._set_link_skip_tensor(new_tensor, link_geometry_type);
         self._save_link_skip_tensor_to_file(link_geometry_type);
         self._save_link_skip_tensor_to_permanent_file(link_geometry_type);
+        report.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_report_filename(link_geometry_type));
 
         Ok(())
     }
 
-    fn _load_or_create_link_average_distance_tensor(&mut self, link_geometry_type: &LinkGeometryType, robot_fk_module: &RobotFKModule, robot_bounds_module: &RobotBoundsModule, num_samples: usize, create_new_no_matter_what: bool) -> Result<(), String> {
+    fn _load_or_create_link_average_distance_tensor(&mut self, link_geometry_type: &LinkGeometryType, robot_fk_module: &RobotFKModule, robot_bounds_module: &RobotBoundsModule, settings: &CollisionCheckTensorGenerationSettings, create_new_no_matter_what: bool) -> Result<(), String> {
         let load_result = FloatCollisionCheckTensor::load_from_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_average_distance_tensors(), self._get_link_average_distance_filename(link_geometry_type));
         if load_result.is_ok() && !create_new_no_matter_what {
-            self._set_link_average_distance_tensor(load_result.ok().unwrap(), link_geometry_type);
-            return Ok(());
+            let loaded_tensor = load_result.ok().unwrap();
+            let collision_objects = self.get_link_geometry_collision_objects_ref(link_geometry_type);
+            if loaded_tensor.matches_collision_objects(collision_objects, collision_objects) {
+                self._set_link_average_distance_tensor(loaded_tensor, link_geometry_type);
+                return Ok(());
+            }
+            println!("{}{}Saved {} does not match the current link geometry.  Recomputing it.{}", color::Fg(color::Blue), style::Bold, self._get_link_average_distance_filename(link_geometry_type), style::Reset);
         }
 
+        let (average_distance_tensor, report) = self.compute_link_average_distance_tensor(link_geometry_type, robot_fk_module, robot_bounds_module, settings)?;
+        self._set_link_average_distance_tensor(average_distance_tensor, link_geometry_type);
+        self._save_link_average_distance_to_file(link_geometry_type);
+        report.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_average_distance_tensors(), self._get_link_average_distance_report_filename(link_geometry_type));
 
-        let mut average_distance_tensor = FloatCollisionCheckTensor::new(&self.get_link_geometry_collision_objects_ref(link_geometry_type), &self.get_link_geometry_collision_objects_ref(link_geometry_type));
+        Ok(())
+    }
 
-        for i in 0..num_samples {
-            if i % 50 == 0 {
-                println!("{}{}Calculating average distance tensor for {:?}: sample {} of {} {}", color::Fg(color::Blue), style::Bold, link_geometry_type, i, num_samples, style::Reset);
+    // samples are drawn from a seeded rng and spread across threads, so the output only depends on settings.seed and
+    // settings.num_skip_tensor_samples.  Nothing is saved or set on self.
+    pub fn compute_link_skip_collision_check_tensor(&self, link_geometry_type: &LinkGeometryType, robot_fk_module: &RobotFKModule, robot_bounds_module: &RobotBoundsModule, settings: &CollisionCheckTensorGenerationSettings) -> Result<(BoolCollisionCheckTensor, CollisionCheckTensorGenerationReport), String> {
+        let collision_objects = self.get_link_geometry_collision_objects_ref(link_geometry_type);
+        let initial_skip_collision_check_tensor = BoolCollisionCheckTensor::new(collision_objects, collision_objects, self._get_skip_check_for_self_collision_mode(link_geometry_type));
+
+        let label = format!("skip collision tensor for {:?}", link_geometry_type);
+        let chunk_count_tensors = run_seeded_sample_chunks_in_parallel(settings.num_skip_tensor_samples, settings.seed, settings.num_threads, label.as_str(), || self.clone(), |m, rng, num_samples_in_chunk| {
+            let mut count_collision_check_tensor = FloatCollisionCheckTensor::new(collision_objects, collision_objects);
+            for _ in 0..num_samples_in_chunk {
+                let sample = robot_bounds_module.uniform_sample_from_bounds_with_rng(rng);
+                let fk_res = robot_fk_module.compute_fk(&sample)?;
+                m.set_poses_on_links(&fk_res, link_geometry_type);
+
+                let intersect_check_multiple_result = intersect_check_between_multiple_collision_objects(m.get_link_geometry_collision_objects_ref(link_geometry_type), m.get_link_geometry_collision_objects_ref(link_geometry_type), false, Some(&initial_skip_collision_check_tensor))?;
+                match intersect_check_multiple_result {
+                    IntersectCheckMultipleResult::NoIntersectionsFound(_) => { count_collision_check_tensor.increment_count_at_given_idxs( &vec![ ] )?; },
+                    IntersectCheckMultipleResult::IntersectionFound(i) => { count_collision_check_tensor.increment_count_at_given_idxs( i.get_intersection_idxs() )?; }
+                }
             }
+            Ok(count_collision_check_tensor)
+        })?;
 
-            let sample = robot_bounds_module.uniform_sample_from_bounds();
-            let fk_res = robot_fk_module.compute_fk(&sample)?;
-            self.set_poses_on_links(&fk_res, link_geometry_type);
-
-            let distance_check_multiple_result = distance_check_between_multiple_collision_objects(&self.get_link_geometry_collision_objects_ref(link_geometry_type), &self.get_link_geometry_collision_objects_ref(link_geometry_type), false, None, None)?;
-            match distance_check_multiple_result {
-                DistanceCheckMultipleResult::NoIntersectionsFound(i) => {
-                    let l = i.get_distance_check_idxs().len();
-                    for j in 0..l {
-                        average_distance_tensor.increment_at_given_single_idxs_by_custom_value( &i.get_distance_check_idxs()[j], i.get_distance_check_distances()[j] );
-                    }
-                },
-                DistanceCheckMultipleResult::IntersectionFound(i) => {
-                    let l = i.get_distance_check_idxs().len();
-                    for j in 0..l {
-                        average_distance_tensor.increment_at_given_single_idxs_by_custom_value( &i.get_distance_check_idxs()[j], i.get_distance_check_distances()[j] );
-                    }
+        let mut count_collision_check_tensor = FloatCollisionCheckTensor::new(collision_objects, collision_objects);
+        for t in &chunk_count_tensors { count_collision_check_tensor.add_tensor(t)?; }
+
+        let new_tensor = BoolCollisionCheckTensor::new_for_always_and_never_in_collision_pairs(&count_collision_check_tensor, None, None, self._get_skip_check_for_self_collision_mode(link_geometry_type))?;
+        let report = CollisionCheckTensorGenerationReport::new_for_skip_tensor(self._get_link_skip_tensor_filename(link_geometry_type), settings.seed, &count_collision_check_tensor, &initial_skip_collision_check_tensor, &new_tensor, collision_objects)?;
+
+        return Ok((new_tensor, report));
+    }
+
+    pub fn compute_link_average_distance_tensor(&self, link_geometry_type: &LinkGeometryType, robot_fk_module: &RobotFKModule, robot_bounds_module: &RobotBoundsModule, settings: &CollisionCheckTensorGenerationSettings) -> Result<(FloatCollisionCheckTensor, CollisionCheckTensorGenerationReport), String> {
+        let collision_objects = self.get_link_geometry_collision_objects_ref(link_geometry_type);
+
+        let label = format!("average distance tensor for {:?}", link_geometry_type);
+        let chunk_tensors = run_seeded_sample_chunks_in_parallel(settings.num_average_distance_samples, settings.seed, settings.num_threads, label.as_str(), || self.clone(), |m, rng, num_samples_in_chunk| {
+            let mut distance_tensor = FloatCollisionCheckTensor::new(collision_objects, collision_objects);
+            let mut squared_distance_tensor = FloatCollisionCheckTensor::new(collision_objects, collision_objects);
+            for _ in 0..num_samples_in_chunk {
+                let sample = robot_bounds_module.uniform_sample_from_bounds_with_rng(rng);
+                let fk_res = robot_fk_module.compute_fk(&sample)?;
+                m.set_poses_on_links(&fk_res, link_geometry_type);
+
+                let distance_check_multiple_result = distance_check_between_multiple_collision_objects(m.get_link_geometry_collision_objects_ref(link_geometry_type), m.get_link_geometry_collision_objects_ref(link_geometry_type), false, None, None)?;
+                let i = match &distance_check_multiple_result {
+                    DistanceCheckMultipleResult::NoIntersectionsFound(i) => i,
+                    DistanceCheckMultipleResult::IntersectionFound(i) => i
+                };
+                let l = i.get_distance_check_idxs().len();
+                for j in 0..l {
+                    let d = i.get_distance_check_distances()[j];
+                    distance_tensor.increment_at_given_single_idxs_by_custom_value( &i.get_distance_check_idxs()[j], d )?;
+                    squared_distance_tensor.increment_at_given_single_idxs_by_custom_value( &i.get_distance_check_idxs()[j], d * d )?;
                 }
+                distance_tensor.increment_total_num_collision_checks();
+                squared_distance_tensor.increment_total_num_collision_checks();
             }
-            average_distance_tensor.increment_total_num_collision_checks();
+            Ok((distance_tensor, squared_distance_tensor))
+        })?;
+
+        let mut average_distance_tensor = FloatCollisionCheckTensor::new(collision_objects, collision_objects);
+        let mut squared_distance_tensor = FloatCollisionCheckTensor::new(collision_objects, collision_objects);
+        for t in &chunk_tensors {
+            average_distance_tensor.add_tensor(&t.0)?;
+            squared_distance_tensor.add_tensor(&t.1)?;
         }
 
-        self._set_link_average_distance_tensor(average_distance_tensor, link_geometry_type);
-        self._save_link_average_distance_to_file(link_geometry_type);
+        let initial_skip_collision_check_tensor = BoolCollisionCheckTensor::new(collision_objects, collision_objects, self._get_skip_check_for_self_collision_mode(link_geometry_type));
+        let report = CollisionCheckTensorGenerationReport::new_for_average_distance_tensor(self._get_link_average_distance_filename(link_geometry_type), settings.seed, &average_distance_tensor, &squared_distance_tensor, &initial_skip_collision_check_tensor, collision_objects)?;
 
-        Ok(())
+        return Ok((average_distance_tensor, report));
+    }
+
+    // recomputes (and saves) the skip and average distance tensors for all link geometry types with the given settings.
+    // Current link paddings and inactive links are taken into account.
+    pub fn regenerate_link_collision_check_tensors(&mut self, robot_fk_module: &RobotFKModule, robot_bounds_module: &RobotBoundsModule, settings: &CollisionCheckTensorGenerationSettings) -> Result<(), String> {
+        return self._load_or_create_all_link_collision_check_tensors(robot_fk_module, robot_bounds_module, settings, true);
+    }
+
+    fn _load_or_create_all_link_collision_check_tensors(&mut self, robot_fk_module: &RobotFKModule, robot_bounds_module: &RobotBoundsModule, settings: &CollisionCheckTensorGenerationSettings, create_new_no_matter_what: bool) -> Result<(), String> {
+        let link_geometry_types = vec![ LinkGeometryType::OBBs,  LinkGeometryType::ConvexShapes,  LinkGeometryType::OBBSubcomponents,  LinkGeometryType::ConvexShapeSubcomponents,  LinkGeometryType::Spheres ];
+        for lgt in &link_geometry_types {
+            self._load_or_create_link_skip_collision_check_tensor(lgt, robot_fk_module, robot_bounds_module, settings, create_new_no_matter_what)?;
+        }
+        for lgt in &link_geometry_types {
+            self._load_or_create_link_average_distance_tensor(lgt, robot_fk_module, robot_bounds_module, settings, create_new_no_matter_what)?;
+        }
+        return Ok(());
     }
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
@@ -485,6 +600,7 @@ impl RobotCoreCollisionModule {
                 self._link_convex_shapes[i].iter_mut().for_each(|x| x.active = false );
                 self._link_obb_subcomponents[i].iter_mut().for_each(|x| x.active = false );
                 self._link_convex_shape_subcomponents[i].iter_mut().for_each(|x| x.active = false );
+                self._link_spheres[i].iter_mut().for_each(|x| x.active = false );
 
             }
         }
@@ -492,24 +608,87 @@ impl RobotCoreCollisionModule {
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
+    // padding is applied to the link's collision objects in all link geometry types
+    pub fn set_link_padding(&mut self, link_idx: usize, padding: f64) -> Result<(), String> {
+        if link_idx >= self._link_paddings.len() {
+            return Err(format!("link idx {:?} is too high for number of links ({:?})", link_idx, self._link_paddings.len()));
+        }
+
+        for c in self._link_obbs[link_idx].iter_mut() { c.set_padding(padding)?; }
+        for c in self._link_convex_shapes[link_idx].iter_mut() { c.set_padding(padding)?; }
+        for c in self._link_obb_subcomponents[link_idx].iter_mut() { c.set_padding(padding)?; }
+        for c in self._link_convex_shape_subcomponents[link_idx].iter_mut() { c.set_padding(padding)?; }
+        for c in self._link_spheres[link_idx].iter_mut() { c.set_padding(padding)?; }
+        self._link_paddings[link_idx] = padding;
+
+        Ok(())
+    }
+
+    pub fn get_link_paddings_ref(&self) -> &Vec<f64> {
+        return &self._link_paddings;
+    }
+
+    fn _set_link_paddings_from_configuration_module(&mut self, robot_configuration_module: &RobotConfigurationModule) -> Result<(), String> {
+        let l = robot_configuration_module.robot_model_module.links.len();
+        self._link_paddings = vec![0.0; l];
+        for i in 0..l {
+            let padding = robot_configuration_module.get_link_padding(&robot_configuration_module.robot_model_module.links[i].name);
+            self.set_link_padding(i, padding)?;
+        }
+        Ok(())
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
     fn _create_link_convex_shapes_if_need_be(robot_configuration_module: &RobotConfigurationModule) -> Result<(), String> {
         let robot_name = robot_configuration_module.robot_model_module.robot_name.clone();
         let exists = check_if_path_exists_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_convex_shapes".to_string());
         if !exists {
-            convert_all_links_into_convex_shapes_and_save_mesh_files(robot_name.clone())?;
+            convert_all_links_into_convex_shapes_and_save_mesh_files(robot_name.clone(), robot_configuration_module.get_mesh_repair_options_ref())?;
         }
         Ok(())
     }
 
-    fn _create_link_convex_subcomponents_if_need_be(robot_configuration_module: &RobotConfigurationModule) -> Result<(), String> {
+    // returns the names of the links whose subcomponents were (re)computed
+    fn _create_link_convex_subcomponents_if_need_be(robot_configuration_module: &RobotConfigurationModule) -> Result<Vec<String>, String> {
         let robot_name = robot_configuration_module.robot_model_module.robot_name.clone();
         let exists = check_if_path_exists_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_convex_subcomponents".to_string());
         if !exists {
-            decompose_all_links_into_convex_subcomponents_and_save_mesh_files(robot_name.clone(), 8)?;
+            decompose_all_links_into_convex_subcomponents_and_save_mesh_files(robot_name.clone(), 8, robot_configuration_module.get_mesh_repair_options_ref())?;
+            return Ok(robot_configuration_module.robot_model_module.links.iter().map(|x| x.name.clone()).collect());
+        } else {
+            return update_link_convex_subcomponents_for_changed_overrides(robot_name.clone(), 8, robot_configuration_module.get_mesh_repair_options_ref());
+        }
+    }
+
+    fn _create_link_spheres_if_need_be(robot_configuration_module: &RobotConfigurationModule) -> Result<(), String> {
+        let robot_name = robot_configuration_module.robot_model_module.robot_name.clone();
+        let exists = check_if_path_exists_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_spheres".to_string());
+        if !exists {
+            fit_spheres_to_all_links_and_save_files(robot_name.clone(), 8)?;
         }
         Ok(())
     }
 
+    fn _get_link_spheres(robot_configuration_module: &RobotConfigurationModule) -> Result<Vec<Vec<(Vector3<f64>, f64)>>, String> {
+        let robot_name = robot_configuration_module.robot_model_module.robot_name.clone();
+
+        let mut out_vec = Vec::new();
+
+        let num_links = robot_configuration_module.robot_model_module.links.len();
+        for i in 0..num_links {
+            let partial_fp = "autogenerated_metadata/link_spheres/".to_string() + robot_configuration_module.robot_model_module.links[i].name.as_str() + ".json";
+            let json_string = read_file_contents_relative_to_robot_directory(robot_name.clone(), partial_fp.clone());
+            if json_string.is_none() { out_vec.push( Vec::new() ); continue; }
+
+            let spheres: Result<Vec<[f64; 4]>, _> = serde_json::from_str(&json_string.unwrap());
+            if spheres.is_err() { return Err(format!("link sphere file {:?} could not be parsed", partial_fp)); }
+            out_vec.push( spheres.unwrap().iter().map(|s| (Vector3::new(s[0], s[1], s[2]), s[3])).collect() );
+        }
+
+        return Ok(out_vec);
+    }
+
     fn _get_link_convex_shapes_trimesh_engines(robot_configuration_module: &RobotConfigurationModule) -> Result<Vec<Option<TriMeshEngine>>, String> {
         let robot_name= robot_configuration_module.robot_model_module.robot_name.clone();
 
@@ -652,6 +831,45 @@ impl RobotCoreCollisionModule {
         return Ok(());
     }
 
+    fn _create_link_spheres(&mut self, robot_configuration_module: &RobotConfigurationModule) -> Result<(), String> {
+        let link_spheres = Self::_get_link_spheres(robot_configuration_module)?;
+
+        self._link_spheres = Vec::new();
+
+        let l = link_spheres.len();
+        for i in 0..l {
+            let mut tmp = Vec::new();
+
+            let l2 = link_spheres[i].len();
+            for j in 0..l2 {
+                let collision_obj_name = robot_configuration_module.robot_model_module.links[i].name.clone() + "_" + usize_to_string(j).as_str();
+                let (center, radius) = link_spheres[i][j].clone();
+                let sphere = CollisionObject::new_ball( radius, Some(ImplicitDualQuaternion::new_from_euler_angles(0.,0.,0., center)), Some(collision_obj_name) );
+                tmp.push(sphere);
+            }
+
+            self._link_spheres.push(tmp);
+        }
+
+        return Ok(());
+    }
+
+    // re-fits the link spheres with the given number of spheres per link, then recomputes the sphere skip and average
+    // distance tensors.  Link paddings and dead-end links are re-applied to the new spheres.
+    pub fn refit_link_spheres(&mut self, robot_configuration_module: &RobotConfigurationModule, robot_fk_module: &RobotFKModule, robot_bounds_module: &RobotBoundsModule, num_spheres_per_link: usize) -> Result<(), String> {
+        fit_spheres_to_all_links_and_save_files(self._robot_name_copy.clone(), num_spheres_per_link)?;
+        self._create_link_spheres(robot_configuration_module)?;
+        self._set_link_paddings_from_configuration_module(robot_configuration_module)?;
+
+        let settings = CollisionCheckTensorGenerationSettings::new_default();
+        self._load_or_create_link_skip_collision_check_tensor(&LinkGeometryType::Spheres, robot_fk_module, robot_bounds_module, &settings, true)?;
+        self._load_or_create_link_average_distance_tensor(&LinkGeometryType::Spheres, robot_fk_module, robot_bounds_module, &settings, true)?;
+
+        self._set_dead_link_collision_objects_as_inactive(robot_configuration_module);
+
+        Ok(())
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn set_poses_on_links(&mut self, fk_res: &RobotFKResult, link_geometry_type: &LinkGeometryType) {
@@ -659,7 +877,8 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => self._set_poses_on_link_obbs(fk_res),
             LinkGeometryType::ConvexShapes => self._set_poses_on_link_convex_shapes(fk_res),
             LinkGeometryType::OBBSubcomponents => self._set_poses_on_link_obb_subcomponents(fk_res),
-            LinkGeometryType::ConvexShapeSubcomponents => self._set_poses_on_link_convex_shape_subcomponents(fk_res)
+            LinkGeometryType::ConvexShapeSubcomponents => self._set_poses_on_link_convex_shape_subcomponents(fk_res),
+            LinkGeometryType::Spheres => self._set_poses_on_link_spheres(fk_res)
         }
     }
 
@@ -713,6 +932,20 @@ impl RobotCoreCollisionModule {
         }
     }
 
+    fn _set_poses_on_link_spheres(&mut self, fk_res: &RobotFKResult) {
+        let link_frames = fk_res.get_link_frames_ref();
+        let l = link_frames.len();
+        for i in 0..l {
+            if link_frames[i].is_some() && !self._link_spheres[i].is_empty() {
+                let l2 = self._link_spheres[i].len();
+                for j in 0..l2 {
+                    self._link_spheres[i][j].set_curr_pose( &link_frames[i].as_ref().unwrap() );
+                    self._link_spheres[i][j].update_bounding_aabb();
+                }
+            }
+        }
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn get_link_geometry_collision_objects_ref(&self, link_geometry_type: &LinkGeometryType) -> &Vec<Vec<CollisionObject>> {
@@ -720,10 +953,31 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => return &self._link_obbs,
             LinkGeometryType::ConvexShapes => return &self._link_convex_shapes,
             LinkGeometryType::OBBSubcomponents => return &self._link_obb_subcomponents,
-            LinkGeometryType::ConvexShapeSubcomponents => return &self._link_convex_shape_subcomponents
+            LinkGeometryType::ConvexShapeSubcomponents => return &self._link_convex_shape_subcomponents,
+            LinkGeometryType::Spheres => return &self._link_spheres
         }
     }
 
+    // upper bound on the distance from each link frame to any point on that link's geometry.  Links without geometry get 0.0.
+    pub fn get_link_geometry_max_extents(&self, link_geometry_type: &LinkGeometryType) -> Vec<f64> {
+        let mut out_vec = Vec::new();
+
+        let collision_objects = self.get_link_geometry_collision_objects_ref(link_geometry_type);
+        let l = collision_objects.len();
+        for i in 0..l {
+            let mut max_extent = 0.0;
+            let l2 = collision_objects[i].len();
+            for j in 0..l2 {
+                let c = &collision_objects[i][j];
+                let extent = c.base_pose.translation.norm() + c.base_bounding_sphere.center().coords.norm() + c.base_bounding_sphere.radius() + c.padding;
+                if extent > max_extent { max_extent = extent; }
+            }
+            out_vec.push(max_extent);
+        }
+
+        return out_vec;
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     fn _get_skip_collision_check_tensor_ref(&self, link_geometry_type: &LinkGeometryType) -> &BoolCollisionCheckTensor {
@@ -731,7 +985,8 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => return &self._link_obbs_skip_collision_check_tensor,
             LinkGeometryType::ConvexShapes => return &self._link_convex_shapes_skip_collision_check_tensor,
             LinkGeometryType::OBBSubcomponents => return &self._link_obb_subcomponents_skip_collision_check_tensor,
-            LinkGeometryType::ConvexShapeSubcomponents => return &self._link_convex_shape_subcomponents_skip_collision_check_tensor
+            LinkGeometryType::ConvexShapeSubcomponents => return &self._link_convex_shape_subcomponents_skip_collision_check_tensor,
+            LinkGeometryType::Spheres => return &self._link_spheres_skip_collision_check_tensor
         }
     }
 
@@ -740,7 +995,8 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => return &mut self._link_obbs_skip_collision_check_tensor,
             LinkGeometryType::ConvexShapes => return &mut self._link_convex_shapes_skip_collision_check_tensor,
             LinkGeometryType::OBBSubcomponents => return &mut self._link_obb_subcomponents_skip_collision_check_tensor,
-            LinkGeometryType::ConvexShapeSubcomponents => return &mut self._link_convex_shape_subcomponents_skip_collision_check_tensor
+            LinkGeometryType::ConvexShapeSubcomponents => return &mut self._link_convex_shape_subcomponents_skip_collision_check_tensor,
+            LinkGeometryType::Spheres => return &mut self._link_spheres_skip_collision_check_tensor
         }
     }
 
@@ -749,7 +1005,8 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => return &self._link_obbs_average_distance_tensor,
             LinkGeometryType::ConvexShapes => return &self._link_convex_shapes_average_distance_tensor,
             LinkGeometryType::OBBSubcomponents => return &self._link_obb_subcomponents_average_distance_tensor,
-            LinkGeometryType::ConvexShapeSubcomponents => return &self._link_convex_shape_subcomponents_average_distance_tensor
+            LinkGeometryType::ConvexShapeSubcomponents => return &self._link_convex_shape_subcomponents_average_distance_tensor,
+            LinkGeometryType::Spheres => return &self._link_spheres_average_distance_tensor
         }
     }
 
@@ -758,7 +1015,8 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => return &mut self._link_obbs_average_distance_tensor,
             LinkGeometryType::ConvexShapes => return &mut self._link_convex_shapes_average_distance_tensor,
             LinkGeometryType::OBBSubcomponents => return &mut self._link_obb_subcomponents_average_distance_tensor,
-            LinkGeometryType::ConvexShapeSubcomponents => return &mut self._link_convex_shape_subcomponents_average_distance_tensor
+            LinkGeometryType::ConvexShapeSubcomponents => return &mut self._link_convex_shape_subcomponents_average_distance_tensor,
+            LinkGeometryType::Spheres => return &mut self._link_spheres_average_distance_tensor
         }
     }
 
@@ -771,7 +1029,8 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => return "link_obbs_skip_collision_check_tensor.json".to_string(),
             LinkGeometryType::ConvexShapes => return "link_convex_shapes_skip_collision_check_tensor.json".to_string(),
             LinkGeometryType::OBBSubcomponents => return "link_obb_subcomponents_skip_collision_check_tensor.json".to_string(),
-            LinkGeometryType::ConvexShapeSubcomponents => return "link_convex_shape_subcomponents_skip_collision_check_tensor.json".to_string()
+            LinkGeometryType::ConvexShapeSubcomponents => return "link_convex_shape_subcomponents_skip_collision_check_tensor.json".to_string(),
+            LinkGeometryType::Spheres => return "link_spheres_skip_collision_check_tensor.json".to_string()
         }
     }
 
@@ -780,10 +1039,15 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => return "link_obbs_skip_collision_check_tensor_permanent.json".to_string(),
             LinkGeometryType::ConvexShapes => return "link_convex_shapes_skip_collision_check_tensor_permanent.json".to_string(),
             LinkGeometryType::OBBSubcomponents => return "link_obb_subcomponents_skip_collision_check_tensor_permanent.json".to_string(),
-            LinkGeometryType::ConvexShapeSubcomponents => return "link_convex_shape_subcomponents_skip_collision_check_tensor_permanent.json".to_string()
+            LinkGeometryType::ConvexShapeSubcomponents => return "link_convex_shape_subcomponents_skip_collision_check_tensor_permanent.json".to_string(),
+            LinkGeometryType::Spheres => return "link_spheres_skip_collision_check_tensor_permanent.json".to_string()
         }
     }
 
+    fn _get_link_skip_tensor_report_filename(&self, link_geometry_type: &LinkGeometryType) -> String {
+        return self._get_link_skip_tensor_filename(link_geometry_type).replace(".json", "_report.json");
+    }
+
     fn _get_partial_fp_to_average_distance_tensors(&self) -> String {
         return "autogenerated_metadata/link_average_distance_tensors".to_string();
     }
@@ -793,16 +1057,22 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => return "link_obbs_average_distance_tensor.json".to_string(),
             LinkGeometryType::ConvexShapes => return "link_convex_shapes_average_distance_tensor.json".to_string(),
             LinkGeometryType::OBBSubcomponents => return "link_obb_subcomponents_average_distance_tensor.json".to_string(),
-            LinkGeometryType::ConvexShapeSubcomponents => return "link_convex_shape_subcomponents_average_distance_tensor.json".to_string()
+            LinkGeometryType::ConvexShapeSubcomponents => return "link_convex_shape_subcomponents_average_distance_tensor.json".to_string(),
+            LinkGeometryType::Spheres => return "link_spheres_average_distance_tensor.json".to_string()
         }
     }
 
+    fn _get_link_average_distance_report_filename(&self, link_geometry_type: &LinkGeometryType) -> String {
+        return self._get_link_average_distance_filename(link_geometry_type).replace(".json", "_report.json");
+    }
+
     fn _get_skip_check_for_self_collision_mode(&self, link_geometry_type: &LinkGeometryType) -> SkipCheckForSelfCollisionMode {
         match link_geometry_type {
             LinkGeometryType::OBBs => return SkipCheckForSelfCollisionMode::SameObjectOnly,
             LinkGeometryType::ConvexShapes => return SkipCheckForSelfCollisionMode::SameObjectOnly,
             LinkGeometryType::OBBSubcomponents => return SkipCheckForSelfCollisionMode::SameObjectOrSameVector,
-            LinkGeometryType::ConvexShapeSubcomponents => return SkipCheckForSelfCollisionMode::SameObjectOrSameVector
+            LinkGeometryType::ConvexShapeSubcomponents => return SkipCheckForSelfCollisionMode::SameObjectOrSameVector,
+            LinkGeometryType::Spheres => return SkipCheckForSelfCollisionMode::SameObjectOrSameVector
         }
     }
 
@@ -813,7 +1083,8 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => self._link_obbs_skip_collision_check_tensor = tensor,
             LinkGeometryType::ConvexShapes => self._link_convex_shapes_skip_collision_check_tensor = tensor,
             LinkGeometryType::OBBSubcomponents => self._link_obb_subcomponents_skip_collision_check_tensor = tensor,
-            LinkGeometryType::ConvexShapeSubcomponents => self._link_convex_shape_subcomponents_skip_collision_check_tensor = tensor
+            LinkGeometryType::ConvexShapeSubcomponents => self._link_convex_shape_subcomponents_skip_collision_check_tensor = tensor,
+            LinkGeometryType::Spheres => self._link_spheres_skip_collision_check_tensor = tensor
         }
     }
 
@@ -822,7 +1093,8 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => self._link_obbs_skip_collision_check_tensor.save_to_file_relative_to_robot_directory( self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_filename(link_geometry_type) ),
             LinkGeometryType::ConvexShapes => self._link_convex_shapes_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_filename(link_geometry_type)),
             LinkGeometryType::OBBSubcomponents => self._link_obb_subcomponents_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_filename(link_geometry_type)),
-            LinkGeometryType::ConvexShapeSubcomponents => self._link_convex_shape_subcomponents_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_filename(link_geometry_type))
+            LinkGeometryType::ConvexShapeSubcomponents => self._link_convex_shape_subcomponents_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_filename(link_geometry_type)),
+            LinkGeometryType::Spheres => self._link_spheres_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_filename(link_geometry_type))
         }
 
     }
@@ -832,7 +1104,8 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => self._link_obbs_skip_collision_check_tensor.save_to_file_relative_to_robot_directory( self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_permanent_filename(link_geometry_type) ),
             LinkGeometryType::ConvexShapes => self._link_convex_shapes_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_permanent_filename(link_geometry_type)),
             LinkGeometryType::OBBSubcomponents => self._link_obb_subcomponents_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_permanent_filename(link_geometry_type)),
-            LinkGeometryType::ConvexShapeSubcomponents => self._link_convex_shape_subcomponents_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_permanent_filename(link_geometry_type))
+            LinkGeometryType::ConvexShapeSubcomponents => self._link_convex_shape_subcomponents_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_permanent_filename(link_geometry_type)),
+            LinkGeometryType::Spheres => self._link_spheres_skip_collision_check_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_link_skip_tensors(), self._get_link_skip_tensor_permanent_filename(link_geometry_type))
         }
     }
 
@@ -843,7 +1116,8 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => self._link_obbs_average_distance_tensor = tensor,
             LinkGeometryType::ConvexShapes => self._link_convex_shapes_average_distance_tensor = tensor,
             LinkGeometryType::OBBSubcomponents => self._link_obb_subcomponents_average_distance_tensor = tensor,
-            LinkGeometryType::ConvexShapeSubcomponents => self._link_convex_shape_subcomponents_average_distance_tensor = tensor
+            LinkGeometryType::ConvexShapeSubcomponents => self._link_convex_shape_subcomponents_average_distance_tensor = tensor,
+            LinkGeometryType::Spheres => self._link_spheres_average_distance_tensor = tensor
         }
     }
 
@@ -852,7 +1126,8 @@ impl RobotCoreCollisionModule {
             LinkGeometryType::OBBs => self._link_obbs_average_distance_tensor.save_to_file_relative_to_robot_directory( self._robot_name_copy.clone(), self._get_partial_fp_to_average_distance_tensors(), self._get_link_average_distance_filename(link_geometry_type) ),
             LinkGeometryType::ConvexShapes => self._link_convex_shapes_average_distance_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_average_distance_tensors(), self._get_link_average_distance_filename(link_geometry_type)),
             LinkGeometryType::OBBSubcomponents => self._link_obb_subcomponents_average_distance_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_average_distance_tensors(), self._get_link_average_distance_filename(link_geometry_type)),
-            LinkGeometryType::ConvexShapeSubcomponents => self._link_convex_shape_subcomponents_average_distance_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_average_distance_tensors(), self._get_link_average_distance_filename(link_geometry_type))
+            LinkGeometryType::ConvexShapeSubcomponents => self._link_convex_shape_subcomponents_average_distance_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_average_distance_tensors(), self._get_link_average_distance_filename(link_geometry_type)),
+            LinkGeometryType::Spheres => self._link_spheres_average_distance_tensor.save_to_file_relative_to_robot_directory(self._robot_name_copy.clone(), self._get_partial_fp_to_average_distance_tensors(), self._get_link_average_distance_filename(link_geometry_type))
         }
 
     }
@@ -895,7 +1170,8 @@ pub enum LinkGeometryType {
     OBBs,
     ConvexShapes,
     OBBSubcomponents,
-    ConvexShapeSubcomponents
+    ConvexShapeSubcomponents,
+    Spheres
 }
 
 impl LinkGeometryType {
@@ -905,6 +1181,7 @@ impl LinkGeometryType {
             LinkGeometryType::ConvexShapes => { "ConvexShapes".to_string() }
             LinkGeometryType::OBBSubcomponents => { "OBBSubs".to_string() }
             LinkGeometryType::ConvexShapeSubcomponents => { "ConvexShapeSubs".to_string() }
+            LinkGeometryType::Spheres => { "Spheres".to_string() }
         }
     }
 }
\ No newline at end of file
@@ -3,7 +3,7 @@ use crate::robot_modules::robot_dof_module::RobotDOFModule;
 use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
 use crate::utils::utils_math::nalgebra_utils::*;
 use crate::robot_modules::{joint::Joint, link::Link};
-use nalgebra::{DVector, UnitQuaternion, Vector3, Unit};
+use nalgebra::{DVector, DMatrix, UnitQuaternion, Vector3, Unit};
 use termion::{color, style};
 
 #[derive(Clone)]
@@ -62,6 +62,15 @@ impl RobotFKModule {
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn compute_fk(&self, x: &DVector<f64> ) -> Result<RobotFKResult, String> {
+        return self._compute_fk(x, None);
+    }
+
+    // base_offset is applied relative to base_frame, e.g., the frame of a link on another robot that this robot is mounted on.
+    pub fn compute_fk_with_base_frame(&self, x: &DVector<f64>, base_frame: &ImplicitDualQuaternion) -> Result<RobotFKResult, String> {
+        return self._compute_fk(x, Some(base_frame));
+    }
+
+    fn _compute_fk(&self, x: &DVector<f64>, base_frame: Option<&ImplicitDualQuaternion>) -> Result<RobotFKResult, String> {
         if x.len() != self._num_dofs {
             println!("{}{}ERROR: robot state vector x does not have expected number of dofs ({} instead of {}).  Cannot compute FK. {}", color::Fg(color::Red), style::Bold, x.len(), self._num_dofs, style::Reset);
             return Err( format!("robot state vector x does not have expected number of dofs ({} instead of {}).  Cannot compute FK.", x.len(), self._num_dofs) );
@@ -69,6 +78,17 @@ impl RobotFKModule {
 
         let mut out_vec = self._default_starting_out_vec.clone();
 
+        let mut first_layer_pose = None;
+        if base_frame.is_some() {
+            let base_frame_u = base_frame.unwrap();
+            let world_idx = self._link_tree_traversal_layers_copy[0][0];
+            out_vec[world_idx] = Some(base_frame_u.clone());
+            if !self._base_offset.is_identity { first_layer_pose = Some(base_frame_u.multiply(&self._base_offset)); }
+            else { first_layer_pose = Some(base_frame_u.clone()); }
+        } else if !self._base_offset.is_identity {
+            first_layer_pose = Some(self._base_offset.clone());
+        }
+
         let num_layers = self._link_tree_traversal_layers_copy.len();
         if num_layers == 1 { return Ok( RobotFKResult::new(x, out_vec) ); }
 
@@ -79,7 +99,7 @@ impl RobotFKModule {
                 let curr_link_idx = self._link_tree_traversal_layers_copy[i][j];
                 let predecessor_link_idx = self._predecessor_link_idxs[curr_link_idx];
                 let predecessor_joint_idx = self._predecessor_joint_idxs[curr_link_idx];
-                self._compute_fk_on_single_link(x, curr_link_idx, predecessor_link_idx, predecessor_joint_idx, &mut out_vec, first_layer);
+                self._compute_fk_on_single_link(x, curr_link_idx, predecessor_link_idx, predecessor_joint_idx, &mut out_vec, if first_layer { first_layer_pose.as_ref() } else { None });
             }
         }
 
@@ -124,11 +144,153 @@ impl RobotFKModule {
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
-    fn _compute_fk_on_single_link(&self, x: &DVector<f64>, curr_link_idx: usize, predecessor_link_idx: usize, predecessor_joint_idx: usize, out_vec: &mut Vec<Option<ImplicitDualQuaternion>>, first_layer: bool) {
+    /* 3 x num_dofs jacobian of a world-space point rigidly attached to the given link.  The frame just
+    before each joint's dofs is recovered from the link frame in fk_res, so this also works for mounted robots */
+    pub fn compute_link_point_jacobian(&self, fk_res: &RobotFKResult, link_idx: usize, world_point: &Vector3<f64>) -> Result<DMatrix<f64>, String> {
+        if link_idx >= self._num_links {
+            return Err(format!("link idx {} is too high for robot with {} links", link_idx, self._num_links));
+        }
+        let x = fk_res.get_x_ref();
+        if x.len() != self._num_dofs {
+            return Err( format!("robot state vector x does not have expected number of dofs ({} instead of {}).  Cannot compute jacobian.", x.len(), self._num_dofs) );
+        }
+
+        let mut out_jacobian = DMatrix::from_element(3, self._num_dofs, 0.0);
+
+        let mut curr_link_idx = link_idx;
+        loop {
+            let predecessor_link_idx = self._predecessor_link_idxs[curr_link_idx];
+            let predecessor_joint_idx = self._predecessor_joint_idxs[curr_link_idx];
+            if predecessor_link_idx == usize::max_value() || predecessor_joint_idx == usize::max_value() { break; }
+
+            let joint = &self._joints_copy[predecessor_joint_idx];
+            if joint.active && joint.num_dofs > 0 {
+                let link_frame = fk_res.get_link_frames_ref()[curr_link_idx].as_ref();
+                if link_frame.is_none() {
+                    return Err(format!("link {} does not have a frame in the given fk result, so its jacobian could not be computed", curr_link_idx));
+                }
+
+                let x_dof_start_idx = self._get_input_x_starting_idx_from_joint_idx( predecessor_joint_idx );
+                let num_translations = joint.dof_translation_axes.len();
+                let num_rotations = joint.dof_rotation_axes.len();
+
+                let mut dof_transforms = Vec::new();
+                for i in 0..num_translations {
+                    let dof_translation = x[x_dof_start_idx + i] * &joint.dof_translation_axes[i];
+                    dof_transforms.push( ImplicitDualQuaternion::new_from_euler_angles(0., 0., 0., dof_translation) );
+                }
+                for i in 0..num_rotations {
+                    let dof_quat = UnitQuaternion::from_axis_angle( &joint.dof_rotation_axes_as_units[i], x[x_dof_start_idx + num_translations + i]);
+                    dof_transforms.push( ImplicitDualQuaternion::new( dof_quat, Vector3::zeros() ) );
+                }
+
+                let mut joint_motion = ImplicitDualQuaternion::new_identity();
+                for t in &dof_transforms { joint_motion = joint_motion.multiply(t); }
+
+                // pose of the joint frame after the origin offset, but before any dof motion
+                let mut curr_pose = link_frame.unwrap().multiply( &joint_motion.inverse() );
+
+                for i in 0..num_translations {
+                    let world_axis = curr_pose.quat * &joint.dof_translation_axes[i];
+                    out_jacobian.column_mut(x_dof_start_idx + i).copy_from(&world_axis);
+                    curr_pose = curr_pose.multiply(&dof_transforms[i]);
+                }
+                for i in 0..num_rotations {
+                    let world_axis = curr_pose.quat * joint.dof_rotation_axes_as_units[i].into_inner();
+                    let column = world_axis.cross( &(world_point - &curr_pose.translation) );
+                    out_jacobian.column_mut(x_dof_start_idx + num_translations + i).copy_from(&column);
+                    curr_pose = curr_pose.multiply(&dof_transforms[num_translations + i]);
+                }
+            }
+
+            curr_link_idx = predecessor_link_idx;
+        }
+
+        return Ok(out_jacobian);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn compute_link_motion_bounds(&self, x_a: &DVector<f64>, x_b: &DVector<f64>) -> Result<RobotLinkMotionBounds, String> {
+        return self._compute_link_motion_bounds(x_a, x_b, 0.0, 0.0);
+    }
+
+    // base frame bounds are the motion bounds on the frame this robot is mounted on, e.g., a link on another robot.
+    pub fn compute_link_motion_bounds_with_base_frame_motion_bounds(&self, x_a: &DVector<f64>, x_b: &DVector<f64>, base_frame_translation_bound: f64, base_frame_rotation_bound: f64) -> Result<RobotLinkMotionBounds, String> {
+        return self._compute_link_motion_bounds(x_a, x_b, base_frame_translation_bound, base_frame_rotation_bound);
+    }
+
+    fn _compute_link_motion_bounds(&self, x_a: &DVector<f64>, x_b: &DVector<f64>, base_frame_translation_bound: f64, base_frame_rotation_bound: f64) -> Result<RobotLinkMotionBounds, String> {
+        if x_a.len() != self._num_dofs || x_b.len() != self._num_dofs {
+            return Err( format!("robot state vectors do not have expected number of dofs ({} and {} instead of {}).  Cannot compute link motion bounds.", x_a.len(), x_b.len(), self._num_dofs) );
+        }
+
+        // translation bound: how far a link frame origin can move along the straight line from x_a to x_b.
+        // rotation bound: how far (in radians) a link frame can rotate along the same line.
+        // A point at distance r from a link frame can then move at most translation_bound + rotation_bound * r.
+        let mut translation_bounds = vec![ 0.0; self._num_links ];
+        let mut rotation_bounds = vec![ 0.0; self._num_links ];
+
+        let world_idx = self._link_tree_traversal_layers_copy[0][0];
+        translation_bounds[world_idx] = base_frame_translation_bound;
+        rotation_bounds[world_idx] = base_frame_rotation_bound;
+
+        let base_offset_norm = self._base_offset.translation.norm();
+
+        let num_layers = self._link_tree_traversal_layers_copy.len();
+        for i in 1..num_layers {
+            let l = self._link_tree_traversal_layers_copy[i].len();
+            for j in 0..l {
+                let curr_link_idx = self._link_tree_traversal_layers_copy[i][j];
+                if !self._links_copy[curr_link_idx].active { continue; }
+
+                let predecessor_link_idx = self._predecessor_link_idxs[curr_link_idx];
+                let predecessor_joint_idx = self._predecessor_joint_idxs[curr_link_idx];
+                let joint = &self._joints_copy[predecessor_joint_idx];
+
+                // upper bound on the distance between the predecessor link frame origin and the current link frame origin
+                let mut lever = 0.0;
+                if i == 1 { lever += base_offset_norm; }
+                if joint.has_origin_offset { lever += joint.origin_offset.translation.norm(); }
+
+                let mut joint_translation_bound = 0.0;
+                let mut joint_rotation_bound = 0.0;
+                if joint.active && joint.num_dofs > 0 {
+                    let x_dof_start_idx = self._get_input_x_starting_idx_from_joint_idx(predecessor_joint_idx);
+                    let mut count = 0 as usize;
+
+                    let l2 = joint.dof_translation_axes.len();
+                    for k in 0..l2 {
+                        let a = x_a[x_dof_start_idx + count];
+                        let b = x_b[x_dof_start_idx + count];
+                        let axis_norm = joint.dof_translation_axes[k].norm();
+                        lever += a.abs().max(b.abs()) * axis_norm;
+                        joint_translation_bound += (b - a).abs() * axis_norm;
+                        count += 1;
+                    }
+
+                    let l2 = joint.dof_rotation_axes.len();
+                    for _ in 0..l2 {
+                        joint_rotation_bound += (x_b[x_dof_start_idx + count] - x_a[x_dof_start_idx + count]).abs();
+                        count += 1;
+                    }
+                }
+
+                translation_bounds[curr_link_idx] = translation_bounds[predecessor_link_idx] + rotation_bounds[predecessor_link_idx] * lever + joint_translation_bound;
+                rotation_bounds[curr_link_idx] = rotation_bounds[predecessor_link_idx] + joint_rotation_bound;
+            }
+        }
+
+        return Ok( RobotLinkMotionBounds::new(translation_bounds, rotation_bounds) );
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    fn _compute_fk_on_single_link(&self, x: &DVector<f64>, curr_link_idx: usize, predecessor_link_idx: usize, predecessor_joint_idx: usize, out_vec: &mut Vec<Option<ImplicitDualQuaternion>>, first_layer_pose: Option<&ImplicitDualQuaternion>) {
         if !self._links_copy[curr_link_idx].active { return; }
 
         let mut out_pose = out_vec[predecessor_link_idx].as_ref().unwrap().clone();
-        if first_layer && !self._base_offset.is_identity { out_pose = self._base_offset.clone(); }
+        if first_layer_pose.is_some() { out_pose = first_layer_pose.unwrap().clone(); }
 
         if self._joints_copy[predecessor_joint_idx].has_origin_offset {
             // out_pose = out_pose.multiply_shortcircuit(  &self._joints_copy[predecessor_joint_idx].origin_offset  );
@@ -243,6 +405,30 @@ impl VecOfRobotFKResult {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct RobotLinkMotionBounds {
+    _translation_bounds: Vec<f64>,
+    _rotation_bounds: Vec<f64>
+}
+
+impl RobotLinkMotionBounds {
+    pub fn new(translation_bounds: Vec<f64>, rotation_bounds: Vec<f64>) -> Self {
+        return Self { _translation_bounds: translation_bounds, _rotation_bounds: rotation_bounds };
+    }
+
+    pub fn get_bound_on_point_motion(&self, link_idx: usize, distance_from_link_frame: f64) -> f64 {
+        return self._translation_bounds[link_idx] + self._rotation_bounds[link_idx] * distance_from_link_frame;
+    }
+
+    pub fn get_translation_bounds_ref(&self) -> &Vec<f64> {
+        return &self._translation_bounds;
+    }
+
+    pub fn get_rotation_bounds_ref(&self) -> &Vec<f64> {
+        return &self._rotation_bounds;
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct RobotFKGradientPerturbationsResult {
     _x: DVector<f64>,
@@ -41,6 +41,34 @@ impl RobotSalientLinksModule {
         return Ok( &self._salient_links[salient_link_idx] );
     }
 
+    // replaces any existing salient link on the same link with the same type
+    pub fn add_salient_link(&mut self, link_info: LinkInfo) {
+        let l = self._salient_links.len();
+        for i in 0..l {
+            if self._salient_links[i].link_name == link_info.link_name && self._salient_links[i].salient_link_type.to_string() == link_info.salient_link_type.to_string() {
+                self._salient_links[i] = link_info;
+                return;
+            }
+        }
+        self._salient_links.push(link_info);
+    }
+
+    pub fn save_to_file(&self) {
+        let mut out_string = "# this file is used to specify salient links for a particular robot.  See the lynx documentation for the format. \n\n".to_string();
+
+        for s in &self._salient_links {
+            out_string += format!("- link_name: \"{}\" \n", s.link_name).as_str();
+            out_string += format!("  salient_link_type: \"{}\" \n", s.salient_link_type.to_string()).as_str();
+            if s.link_local_forward_axis.is_some() { out_string += format!("  link_local_forward_axis: \"{}\" \n", s.link_local_forward_axis.as_ref().unwrap().to_string()).as_str(); }
+            if s.link_local_left_axis.is_some() { out_string += format!("  link_local_left_axis: \"{}\" \n", s.link_local_left_axis.as_ref().unwrap().to_string()).as_str(); }
+            if s.link_local_up_axis.is_some() { out_string += format!("  link_local_up_axis: \"{}\" \n", s.link_local_up_axis.as_ref().unwrap().to_string()).as_str(); }
+            out_string += "\n";
+        }
+
+        let partial_fp = "robot_salient_links".to_string();
+        write_string_to_file_relative_to_robot_directory(self._robot_name.clone(), partial_fp.clone(), "robot_salient_links.yaml".to_string(), out_string, true);
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn print_summary(&self) {
@@ -8,9 +8,10 @@ use crate::utils::utils_collisions::prelude::*;
 use crate::robot_modules::robot_fk_module::*;
 use crate::robot_modules::robot_core_collision_module::LinkGeometryType;
 use crate::robot_modules::robot_bounds_module::BoundsCheckResult;
-use nalgebra::DVector;
+use nalgebra::{DVector, DMatrix};
 use std::slice::{Iter, IterMut};
 use termion::{style, color};
+use yaml_rust::Yaml;
 use yaml_rust::yaml::Yaml::Null;
 use nalgebra::{UnitQuaternion, Vector3};
 use crate::utils::utils_vars::prelude::*;
@@ -25,6 +26,8 @@ pub struct RobotSet {
     _total_num_dofs: usize,
     _num_robots: usize,
     _robot_set_result_vector_idxs_to_robot_idxs: Vec<(usize, usize)>,
+    _robot_mounts: Vec<Option<RobotMount>>,
+    _robot_mount_skip_collision_check_tensors: Vec<Option<RobotMountSkipCollisionCheckTensors>>,
     pub robot_names: Option<Vec<String>>,
     pub configuration_names: Option<Vec<Option<String>>>,
     pub robot_set_name: Option<String>,
@@ -62,6 +65,8 @@ impl RobotSet {
             _total_num_dofs: 0,
             _num_robots: 0,
             _robot_set_result_vector_idxs_to_robot_idxs ,
+            _robot_mounts: Vec::new(),
+            _robot_mount_skip_collision_check_tensors: Vec::new(),
             robot_names: None,
             configuration_names: None,
             robot_set_name: None
@@ -81,25 +86,113 @@ impl RobotSet {
 
     pub fn add_robot(&mut self, robot_name: &str, configuration_name: Option<&str>) -> Result<(), String> {
         let robot_module_toolbox = Robot::new(robot_name, configuration_name)?;
-        self._add_robot(robot_module_toolbox);
+        self._add_robot(robot_module_toolbox)?;
 
         Ok(())
     }
 
     pub fn add_robot_from_robot_configuration_module(&mut self, robot_configuration_module: &RobotConfigurationModule) -> Result<(), String> {
         let robot_module_toolbox = Robot::new_from_configuration_module(robot_configuration_module)?;
-        self._add_robot(robot_module_toolbox);
+        self._add_robot(robot_module_toolbox)?;
 
         Ok(())
     }
 
-    fn _add_robot(&mut self, robot_module_toolbox: Robot) {
+    fn _add_robot(&mut self, robot_module_toolbox: Robot) -> Result<(), String> {
         let dofs = robot_module_toolbox.get_dof_module_ref().get_num_dofs();
         self._dofs_per_robot.push(dofs);
         self._total_num_dofs += dofs;
         self._robots.push(robot_module_toolbox);
+        self._robot_mounts.push(None);
         self._num_robots += 1;
         self._set_robot_set_result_vector_idxs_to_robot_idxs();
+        return self._set_robot_mount_skip_collision_check_tensors();
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn mount_robot_on_link(&mut self, child_robot_idx: usize, parent_robot_idx: usize, parent_link_name: &str) -> Result<(), String> {
+        if child_robot_idx >= self._num_robots {
+            return Err(format!("child robot idx {} is too high for robot set with {} robots", child_robot_idx, self._num_robots));
+        }
+        if parent_robot_idx >= child_robot_idx {
+            return Err(format!("parent robot idx ({}) must be less than child robot idx ({}) when mounting a robot", parent_robot_idx, child_robot_idx));
+        }
+
+        let parent_configuration_module = self._robots[parent_robot_idx].get_configuration_module_ref();
+        let parent_link_idx = parent_configuration_module.robot_model_module.get_link_idx_from_name(&parent_link_name.to_string());
+        if parent_link_idx.is_none() {
+            return Err(format!("{:?} is not a valid link name on parent robot {} ({:?})", parent_link_name, parent_robot_idx, parent_configuration_module.robot_model_module.robot_name));
+        }
+        if !parent_configuration_module.robot_model_module.links[parent_link_idx.unwrap()].active {
+            return Err(format!("cannot mount robot {} on link {:?} because it is not active on parent robot {}", child_robot_idx, parent_link_name, parent_robot_idx));
+        }
+
+        let previous_mount = self._robot_mounts[child_robot_idx].clone();
+        self._robot_mounts[child_robot_idx] = Some(RobotMount { parent_robot_idx, parent_link_idx: parent_link_idx.unwrap(), parent_link_name: parent_link_name.to_string() });
+        let res = self._set_robot_mount_skip_collision_check_tensors();
+        if res.is_err() {
+            self._robot_mounts[child_robot_idx] = previous_mount;
+            return res;
+        }
+
+        Ok(())
+    }
+
+    pub fn unmount_robot(&mut self, child_robot_idx: usize) -> Result<(), String> {
+        if child_robot_idx >= self._num_robots {
+            return Err(format!("child robot idx {} is too high for robot set with {} robots", child_robot_idx, self._num_robots));
+        }
+
+        self._robot_mounts[child_robot_idx] = None;
+        self._set_robot_mount_skip_collision_check_tensors()?;
+
+        Ok(())
+    }
+
+    fn _set_robot_mount_skip_collision_check_tensors(&mut self) -> Result<(), String> {
+        let mut out_vec = Vec::new();
+
+        let l = self._robot_set_result_vector_idxs_to_robot_idxs.len();
+        for i in 0..l {
+            let (parent_robot_idx, child_robot_idx) = self._robot_set_result_vector_idxs_to_robot_idxs[i];
+            let mount = &self._robot_mounts[child_robot_idx];
+            if mount.is_some() && mount.as_ref().unwrap().parent_robot_idx == parent_robot_idx {
+                let parent_link_idx = mount.as_ref().unwrap().parent_link_idx;
+                let child_base_link_idxs = Self::_get_link_idxs_rigidly_attached_to_base(&self._robots[child_robot_idx]);
+                out_vec.push( Some( RobotMountSkipCollisionCheckTensors::new(&self._robots[parent_robot_idx], &self._robots[child_robot_idx], parent_link_idx, &child_base_link_idxs)? ) );
+            } else {
+                out_vec.push(None);
+            }
+        }
+
+        self._robot_mount_skip_collision_check_tensors = out_vec;
+        Ok(())
+    }
+
+    fn _get_link_idxs_rigidly_attached_to_base(robot: &Robot) -> Vec<usize> {
+        let robot_model_module = &robot.get_configuration_module_ref().robot_model_module;
+
+        let mut out_vec = vec![ robot_model_module.world_link_idx ];
+        let mut i = 0;
+        while i < out_vec.len() {
+            let link_idx = out_vec[i];
+            for joint_idx in &robot_model_module.links[link_idx].children_joint_idxs {
+                let joint = &robot_model_module.joints[*joint_idx];
+                if (!joint.active || joint.num_dofs == 0) && robot_model_module.links[joint.child_link_idx].active {
+                    out_vec.push(joint.child_link_idx);
+                }
+            }
+            i += 1;
+        }
+
+        return out_vec;
+    }
+
+    fn _get_robot_mount_skip_collision_check_tensor_ref(&self, robot_set_result_vector_idx: usize, link_geometry_type: &LinkGeometryType) -> Option<&BoolCollisionCheckTensor> {
+        let tensors = &self._robot_mount_skip_collision_check_tensors[robot_set_result_vector_idx];
+        if tensors.is_none() { return None; }
+        return Some(tensors.as_ref().unwrap().get_skip_collision_check_tensor_ref(link_geometry_type));
     }
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
@@ -284,6 +377,8 @@ impl RobotSet {
     pub fn multi_robot_intersect_check(&mut self, fk_res: &VecOfRobotFKResult, link_geometry_type: LinkGeometryType, stop_at_first_detected: bool) -> Result<VecOfIntersectCheckMultipleResult, String> {
         let mut out_vec = VecOfIntersectCheckMultipleResult::new_empty();
 
+        let mut count = 0;
+
         let l = self.get_num_robots().clone();
         for i in 0..l {
             self.get_robots_mut_ref()[i].get_core_collision_module_mut_ref().set_poses_on_links(&fk_res.get_robot_fk_results_ref()[i], &link_geometry_type);
@@ -294,7 +389,8 @@ impl RobotSet {
                     let collision_objects1 = self.get_robots_ref()[i].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
                     let collision_objects2 = self.get_robots_ref()[j].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
 
-                    let res = intersect_check_between_multiple_collision_objects(collision_objects1, collision_objects2, stop_at_first_detected, None)?;
+                    let res = intersect_check_between_multiple_collision_objects(collision_objects1, collision_objects2, stop_at_first_detected, self._get_robot_mount_skip_collision_check_tensor_ref(count, &link_geometry_type))?;
+                    count += 1;
 
                     let label = format!("multi robot intersect check between robot {} ({:?}) and robot {} ({:?})", i, self.get_robots_ref()[i].get_configuration_module_ref().robot_model_module.robot_name.clone(),
                                         j, self.get_robots_ref()[j].get_configuration_module_ref().robot_model_module.robot_name.clone());
@@ -313,6 +409,8 @@ impl RobotSet {
     pub fn multi_robot_distance_check(&mut self, fk_res: &VecOfRobotFKResult, link_geometry_type: LinkGeometryType, stop_at_first_detected: bool) -> Result<VecOfDistanceCheckMultipleResult, String> {
         let mut out_vec = VecOfDistanceCheckMultipleResult::new_empty();
 
+        let mut count = 0;
+
         let l = self.get_num_robots().clone();
         for i in 0..l {
             self.get_robots_mut_ref()[i].get_core_collision_module_mut_ref().set_poses_on_links(&fk_res.get_robot_fk_results_ref()[i], &link_geometry_type);
@@ -323,7 +421,8 @@ impl RobotSet {
                     let collision_objects1 = self.get_robots_ref()[i].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
                     let collision_objects2 = self.get_robots_ref()[j].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
 
-                    let res = distance_check_between_multiple_collision_objects(collision_objects1, collision_objects2, stop_at_first_detected, None, None)?;
+                    let res = distance_check_between_multiple_collision_objects(collision_objects1, collision_objects2, stop_at_first_detected, self._get_robot_mount_skip_collision_check_tensor_ref(count, &link_geometry_type), None)?;
+                    count += 1;
 
                     let label = format!("multi robot distance check between robot {} ({:?}) and robot {} ({:?})", i, self.get_robots_ref()[i].get_configuration_module_ref().robot_model_module.robot_name.clone(),
                                         j, self.get_robots_ref()[j].get_configuration_module_ref().robot_model_module.robot_name.clone());
@@ -342,6 +441,8 @@ impl RobotSet {
     pub fn multi_robot_contact_check(&mut self, fk_res: &VecOfRobotFKResult, link_geometry_type: LinkGeometryType, stop_at_first_detected: bool, margin: Option<f64>) -> Result<VecOfContactCheckMultipleResult, String> {
         let mut out_vec = VecOfContactCheckMultipleResult::new_empty();
 
+        let mut count = 0;
+
         let l = self.get_num_robots().clone();
         for i in 0..l {
             self.get_robots_mut_ref()[i].get_core_collision_module_mut_ref().set_poses_on_links(&fk_res.get_robot_fk_results_ref()[i], &link_geometry_type);
@@ -352,7 +453,8 @@ impl RobotSet {
                     let collision_objects1 = self.get_robots_ref()[i].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
                     let collision_objects2 = self.get_robots_ref()[j].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
 
-                    let res = contact_check_between_multiple_collision_objects(collision_objects1, collision_objects2, stop_at_first_detected, margin, None, None)?;
+                    let res = contact_check_between_multiple_collision_objects(collision_objects1, collision_objects2, stop_at_first_detected, margin, self._get_robot_mount_skip_collision_check_tensor_ref(count, &link_geometry_type), None)?;
+                    count += 1;
 
 
                     let label = format!("multi robot contact check between robot {} ({:?}) and robot {} ({:?})", i, self.get_robots_ref()[i].get_configuration_module_ref().robot_model_module.robot_name.clone(),
@@ -388,7 +490,7 @@ impl RobotSet {
                     let collision_objects1 = self.get_robots_ref()[i].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
                     let collision_objects2 = self.get_robots_ref()[j].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
 
-                    let res = intersect_check_between_multiple_collision_objects_subset(&subset_check_idxs[count], collision_objects1, collision_objects2, stop_at_first_detected, None)?;
+                    let res = intersect_check_between_multiple_collision_objects_subset(&subset_check_idxs[count], collision_objects1, collision_objects2, stop_at_first_detected, self._get_robot_mount_skip_collision_check_tensor_ref(count, &link_geometry_type))?;
                     count += 1;
 
                     let label = format!("multi robot intersect subset check between robot {} ({:?}) and robot {} ({:?})", i, self.get_robots_ref()[i].get_configuration_module_ref().robot_model_module.robot_name.clone(),
@@ -424,7 +526,7 @@ impl RobotSet {
                     let collision_objects1 = self.get_robots_ref()[i].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
                     let collision_objects2 = self.get_robots_ref()[j].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
 
-                    let res = distance_check_between_multiple_collision_objects_subset(&subset_check_idxs[count], collision_objects1, collision_objects2, stop_at_first_detected, None, None)?;
+                    let res = distance_check_between_multiple_collision_objects_subset(&subset_check_idxs[count], collision_objects1, collision_objects2, stop_at_first_detected, self._get_robot_mount_skip_collision_check_tensor_ref(count, &link_geometry_type), None)?;
                     count += 1;
 
                     let label = format!("multi robot distance check subset between robot {} ({:?}) and robot {} ({:?})", i, self.get_robots_ref()[i].get_configuration_module_ref().robot_model_module.robot_name.clone(),
@@ -460,7 +562,7 @@ impl RobotSet {
                     let collision_objects1 = self.get_robots_ref()[i].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
                     let collision_objects2 = self.get_robots_ref()[j].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
 
-                    let res = contact_check_between_multiple_collision_objects_subset(&subset_check_idxs[count], collision_objects1, collision_objects2, stop_at_first_detected, margin, None, None)?;
+                    let res = contact_check_between_multiple_collision_objects_subset(&subset_check_idxs[count], collision_objects1, collision_objects2, stop_at_first_detected, margin, self._get_robot_mount_skip_collision_check_tensor_ref(count, &link_geometry_type), None)?;
                     count += 1;
 
                     let label = format!("multi robot contact subset check between robot {} ({:?}) and robot {} ({:?})", i, self.get_robots_ref()[i].get_configuration_module_ref().robot_model_module.robot_name.clone(),
@@ -484,7 +586,62 @@ impl RobotSet {
 
         let robot_state_vecs = self.split_full_state_vector_into_robot_state_vectors(full_state_vec)?;
         for i in 0..self._num_robots {
-            out_vec.add_robot_fk_result( self._robots[i].get_fk_module_ref().compute_fk(&robot_state_vecs[i])? );
+            if self._robot_mounts[i].is_none() {
+                out_vec.add_robot_fk_result( self._robots[i].get_fk_module_ref().compute_fk(&robot_state_vecs[i])? );
+            } else {
+                let mount = self._robot_mounts[i].as_ref().unwrap();
+                let parent_link_frame = out_vec.get_robot_fk_results_ref()[mount.parent_robot_idx].get_link_frames_ref()[mount.parent_link_idx].clone();
+                if parent_link_frame.is_none() {
+                    return Err(format!("link {:?} on robot {} did not have a frame, so robot {} could not be mounted on it", mount.parent_link_name, mount.parent_robot_idx, i));
+                }
+                out_vec.add_robot_fk_result( self._robots[i].get_fk_module_ref().compute_fk_with_base_frame(&robot_state_vecs[i], parent_link_frame.as_ref().unwrap())? );
+            }
+        }
+
+        return Ok(out_vec);
+    }
+
+    // jacobian (3 x total number of dofs) of a world point rigidly attached to the given link on the given robot.  If the
+    // robot is mounted, the point also moves with the parent robot's dofs, so the parent link's point jacobian is chained in.
+    pub fn compute_link_point_jacobian(&self, fk_res: &VecOfRobotFKResult, robot_idx: usize, link_idx: usize, world_point: &Vector3<f64>) -> Result<DMatrix<f64>, String> {
+        if robot_idx >= self._num_robots {
+            return Err(format!("robot idx {} is too high for number of robots ({})", robot_idx, self._num_robots));
+        }
+
+        let mut out_jacobian = DMatrix::from_element(3, self._total_num_dofs, 0.0);
+
+        let mut curr_robot_idx = robot_idx;
+        let mut curr_link_idx = link_idx;
+        loop {
+            let robot_jacobian = self._robots[curr_robot_idx].get_fk_module_ref().compute_link_point_jacobian(&fk_res.get_robot_fk_results_ref()[curr_robot_idx], curr_link_idx, world_point)?;
+            let dof_start_idx: usize = self._dofs_per_robot[0..curr_robot_idx].iter().sum();
+            for k in 0..self._dofs_per_robot[curr_robot_idx] {
+                out_jacobian.column_mut(dof_start_idx + k).copy_from(&robot_jacobian.column(k));
+            }
+
+            if self._robot_mounts[curr_robot_idx].is_none() { break; }
+            let mount = self._robot_mounts[curr_robot_idx].as_ref().unwrap();
+            curr_robot_idx = mount.parent_robot_idx;
+            curr_link_idx = mount.parent_link_idx;
+        }
+
+        return Ok(out_jacobian);
+    }
+
+    pub fn compute_link_motion_bounds(&self, full_state_vec_a: &DVector<f64>, full_state_vec_b: &DVector<f64>) -> Result<Vec<RobotLinkMotionBounds>, String> {
+        let mut out_vec: Vec<RobotLinkMotionBounds> = Vec::new();
+
+        let robot_state_vecs_a = self.split_full_state_vector_into_robot_state_vectors(full_state_vec_a)?;
+        let robot_state_vecs_b = self.split_full_state_vector_into_robot_state_vectors(full_state_vec_b)?;
+        for i in 0..self._num_robots {
+            if self._robot_mounts[i].is_none() {
+                out_vec.push( self._robots[i].get_fk_module_ref().compute_link_motion_bounds(&robot_state_vecs_a[i], &robot_state_vecs_b[i])? );
+            } else {
+                let mount = self._robot_mounts[i].as_ref().unwrap();
+                let parent_translation_bound = out_vec[mount.parent_robot_idx].get_translation_bounds_ref()[mount.parent_link_idx];
+                let parent_rotation_bound = out_vec[mount.parent_robot_idx].get_rotation_bounds_ref()[mount.parent_link_idx];
+                out_vec.push( self._robots[i].get_fk_module_ref().compute_link_motion_bounds_with_base_frame_motion_bounds(&robot_state_vecs_a[i], &robot_state_vecs_b[i], parent_translation_bound, parent_rotation_bound)? );
+            }
         }
 
         return Ok(out_vec);
@@ -546,21 +703,31 @@ impl RobotSet {
 
         let y = get_yaml_obj(fp.clone())?;
 
+        let mut mounts = Vec::new();
+
         let robots_by_filenames_vec = if y[0]["robots_by_filenames"] == Null { vec![ ] } else { y[0]["robots_by_filenames"].as_vec().expect("robots_by_filename must be included in robot_sets yaml").to_vec() };
 
         let l = robots_by_filenames_vec.len();
         for i in 0..l {
+            if robots_by_filenames_vec[i]["mount"] != Null {
+                mounts.push( (self._num_robots, Self::_get_mount_from_yaml(&robots_by_filenames_vec[i]["mount"])?) );
+            }
+
             let robot_name = robots_by_filenames_vec[i]["robot_name"].as_str().expect("robot_name must be included in robots_by_filename in robots_sets yaml");
             let configuration_name = if robots_by_filenames_vec[i]["configuration_name"] == Null { None } else { Some( robots_by_filenames_vec[i]["configuration_name"].as_str().expect("configuration_name must be included in robots_by_filename in robots_sets yaml") ) };
 
             let robot_configuration = RobotConfigurationModule::new(robot_name, configuration_name)?;
 
-            self.add_robot_from_robot_configuration_module(&robot_configuration);
+            self.add_robot_from_robot_configuration_module(&robot_configuration)?;
         }
 
         let robots_by_filenames_vec = if y[0]["robots_by_manual_inputs"] == Null { vec![ ] } else { y[0]["robots_by_manual_inputs"].as_vec().expect("robots_by_manual_inputs must be included in robot_sets yaml").to_vec() };
         let l = robots_by_filenames_vec.len();
         for i in 0..l {
+            if robots_by_filenames_vec[i]["mount"] != Null {
+                mounts.push( (self._num_robots, Self::_get_mount_from_yaml(&robots_by_filenames_vec[i]["mount"])?) );
+            }
+
             let robot_name = robots_by_filenames_vec[i]["robot_name"].as_str().expect("robot_name must be included in robots_by_filename in robots_sets yaml");
 
             let mut dead_end_link_names = Vec::new();
@@ -610,12 +777,30 @@ impl RobotSet {
 
             let robot_configuration = RobotConfigurationModule::new_manual_inputs(robot_name, "manual", base_offset, dead_end_link_names, inactive_joint_names, mobile_base_mode, mobile_base_bounds_filename);
 
-            self.add_robot_from_robot_configuration_module(&robot_configuration);
+            self.add_robot_from_robot_configuration_module(&robot_configuration)?;
+        }
+
+        for (child_robot_idx, (parent_robot_idx, parent_link_name)) in mounts {
+            self.mount_robot_on_link(child_robot_idx, parent_robot_idx, parent_link_name.as_str())?;
         }
 
         Ok(())
     }
 
+    fn _get_mount_from_yaml(mount_yaml: &Yaml) -> Result<(usize, String), String> {
+        let parent_robot_idx = mount_yaml["parent_robot_idx"].as_i64();
+        if parent_robot_idx.is_none() || parent_robot_idx.unwrap() < 0 {
+            return Err(format!("mount must include a non-negative integer parent_robot_idx in robot_sets yaml ({:?})", mount_yaml));
+        }
+
+        let parent_link_name = mount_yaml["parent_link_name"].as_str();
+        if parent_link_name.is_none() {
+            return Err(format!("mount must include a parent_link_name in robot_sets yaml ({:?})", mount_yaml));
+        }
+
+        return Ok( (parent_robot_idx.unwrap() as usize, parent_link_name.unwrap().to_string()) );
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     fn _set_robot_set_result_vector_idxs_to_robot_idxs(&mut self) {
@@ -637,6 +822,19 @@ impl RobotSet {
         return &self._robot_set_result_vector_idxs_to_robot_idxs;
     }
 
+    pub fn get_robot_mounts_ref(&self) -> &Vec<Option<RobotMount>> {
+        return &self._robot_mounts;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn set_link_padding(&mut self, robot_idx: usize, link_name: &str, padding: f64) -> Result<(), String> {
+        if robot_idx >= self._num_robots {
+            return Err(format!("robot idx {:?} is too high for number of robots ({:?})", robot_idx, self._num_robots));
+        }
+        return self._robots[robot_idx].set_link_padding(link_name, padding);
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn get_iter(&self) -> Iter<Robot> {
@@ -677,7 +875,73 @@ impl LynxFloatVecSampler for RobotSet {
     fn lynx_float_vec_sampler_sample(&self, lynx_vars: &mut LynxVarsGeneric) -> Result<DVector<f64>, String> {
         return self.float_vec_sampler_sample();
     }
+    fn lynx_float_vec_sampler_bounds(&self) -> Option<Vec<(f64, f64)>> {
+        let mut out_vec = Vec::new();
+        for r in &self._robots { out_vec.extend(r.get_bounds_module_ref().get_bounds().iter().cloned()); }
+        return Some(out_vec);
+    }
 }
 impl LynxMultiFloatVecSampler for RobotSet { }
 impl MultiFloatVecSampler for RobotSet { }
 impl LynxVarsUser for RobotSet { }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug)]
+pub struct RobotMount {
+    pub parent_robot_idx: usize,
+    pub parent_link_idx: usize,
+    pub parent_link_name: String
+}
+
+#[derive(Clone, Debug)]
+pub struct RobotMountSkipCollisionCheckTensors {
+    _link_obbs_skip_collision_check_tensor: BoolCollisionCheckTensor,
+    _link_convex_shapes_skip_collision_check_tensor: BoolCollisionCheckTensor,
+    _link_obb_subcomponents_skip_collision_check_tensor: BoolCollisionCheckTensor,
+    _link_convex_shape_subcomponents_skip_collision_check_tensor: BoolCollisionCheckTensor,
+    _link_spheres_skip_collision_check_tensor: BoolCollisionCheckTensor
+}
+
+impl RobotMountSkipCollisionCheckTensors {
+    pub fn new(parent_robot: &Robot, child_robot: &Robot, parent_link_idx: usize, child_base_link_idxs: &Vec<usize>) -> Result<Self, String> {
+        let _link_obbs_skip_collision_check_tensor = Self::_get_skip_collision_check_tensor(parent_robot, child_robot, parent_link_idx, child_base_link_idxs, &LinkGeometryType::OBBs)?;
+        let _link_convex_shapes_skip_collision_check_tensor = Self::_get_skip_collision_check_tensor(parent_robot, child_robot, parent_link_idx, child_base_link_idxs, &LinkGeometryType::ConvexShapes)?;
+        let _link_obb_subcomponents_skip_collision_check_tensor = Self::_get_skip_collision_check_tensor(parent_robot, child_robot, parent_link_idx, child_base_link_idxs, &LinkGeometryType::OBBSubcomponents)?;
+        let _link_convex_shape_subcomponents_skip_collision_check_tensor = Self::_get_skip_collision_check_tensor(parent_robot, child_robot, parent_link_idx, child_base_link_idxs, &LinkGeometryType::ConvexShapeSubcomponents)?;
+        let _link_spheres_skip_collision_check_tensor = Self::_get_skip_collision_check_tensor(parent_robot, child_robot, parent_link_idx, child_base_link_idxs, &LinkGeometryType::Spheres)?;
+
+        return Ok(Self { _link_obbs_skip_collision_check_tensor, _link_convex_shapes_skip_collision_check_tensor,
+            _link_obb_subcomponents_skip_collision_check_tensor, _link_convex_shape_subcomponents_skip_collision_check_tensor,
+            _link_spheres_skip_collision_check_tensor });
+    }
+
+    fn _get_skip_collision_check_tensor(parent_robot: &Robot, child_robot: &Robot, parent_link_idx: usize, child_base_link_idxs: &Vec<usize>, link_geometry_type: &LinkGeometryType) -> Result<BoolCollisionCheckTensor, String> {
+        let collision_objects1 = parent_robot.get_core_collision_module_ref().get_link_geometry_collision_objects_ref(link_geometry_type);
+        let collision_objects2 = child_robot.get_core_collision_module_ref().get_link_geometry_collision_objects_ref(link_geometry_type);
+
+        let mut out_tensor = BoolCollisionCheckTensor::new(collision_objects1, collision_objects2, SkipCheckForSelfCollisionMode::NoSelfCollisions);
+        for child_link_idx in child_base_link_idxs {
+            let res = out_tensor.add_skip_for_whole_vec(parent_link_idx, *child_link_idx);
+            if res.is_err() {
+                let parent_links = &parent_robot.get_configuration_module_ref().robot_model_module.links;
+                let child_links = &child_robot.get_configuration_module_ref().robot_model_module.links;
+                let parent_link_name = if parent_link_idx < parent_links.len() { parent_links[parent_link_idx].name.clone() } else { format!("<invalid link idx {}>", parent_link_idx) };
+                let child_link_name = if *child_link_idx < child_links.len() { child_links[*child_link_idx].name.clone() } else { format!("<invalid link idx {}>", child_link_idx) };
+                return Err(format!("could not skip {:?} collision checks between parent link {:?} and child link {:?} of mounted robot: {}", link_geometry_type, parent_link_name, child_link_name, res.err().unwrap()));
+            }
+        }
+
+        return Ok(out_tensor);
+    }
+
+    pub fn get_skip_collision_check_tensor_ref(&self, link_geometry_type: &LinkGeometryType) -> &BoolCollisionCheckTensor {
+        match link_geometry_type {
+            LinkGeometryType::OBBs => return &self._link_obbs_skip_collision_check_tensor,
+            LinkGeometryType::ConvexShapes => return &self._link_convex_shapes_skip_collision_check_tensor,
+            LinkGeometryType::OBBSubcomponents => return &self._link_obb_subcomponents_skip_collision_check_tensor,
+            LinkGeometryType::ConvexShapeSubcomponents => return &self._link_convex_shape_subcomponents_skip_collision_check_tensor,
+            LinkGeometryType::Spheres => return &self._link_spheres_skip_collision_check_tensor
+        }
+    }
+}
@@ -28,9 +28,24 @@ impl RobotTriangleMeshCollisionModule {
         out_self._create_link_triangle_meshes(robot_configuration_module);
         out_self._load_or_create_link_skip_collision_check_tensor(robot_fk_module, robot_bounds_module, 5, false);
 
+        let l = out_self._link_triangle_meshes.len();
+        for i in 0..l {
+            let padding = robot_configuration_module.get_link_padding(&robot_configuration_module.robot_model_module.links[i].name);
+            out_self.set_link_padding(i, padding)?;
+        }
+
         return Ok(out_self);
     }
 
+    pub fn set_link_padding(&mut self, link_idx: usize, padding: f64) -> Result<(), String> {
+        if link_idx >= self._link_triangle_meshes.len() {
+            return Err(format!("link idx {:?} is too high for number of links ({:?})", link_idx, self._link_triangle_meshes.len()));
+        }
+
+        for c in self._link_triangle_meshes[link_idx].iter_mut() { c.set_padding(padding)?; }
+        Ok(())
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn self_intersect_check(&mut self, fk_res: &RobotFKResult, stop_at_first_detected: bool) -> Result<IntersectCheckMultipleResult, String> {
@@ -52,17 +67,17 @@ impl RobotTriangleMeshCollisionModule {
 
     pub fn environment_intersect_check(&mut self, fk_res: &RobotFKResult, collision_environment: &CollisionEnvironment, stop_at_first_detected: bool) -> Result<IntersectCheckMultipleResult, String> {
         self._set_poses_on_link_triangle_meshes(fk_res);
-        return intersect_check_between_multiple_collision_objects( &collision_environment.environment_obbs, &self._link_triangle_meshes, stop_at_first_detected, Some(&self._link_triangle_meshes_skip_collision_check_tensor));
+        return intersect_check_between_environment_and_multiple_collision_objects( collision_environment, &self._link_triangle_meshes, stop_at_first_detected, Some(&self._link_triangle_meshes_skip_collision_check_tensor));
     }
 
     pub fn environment_distance_check(&mut self, fk_res: &RobotFKResult, collision_environment: &CollisionEnvironment, stop_at_first_detected_intersection: bool) -> Result<DistanceCheckMultipleResult, String> {
         self._set_poses_on_link_triangle_meshes(fk_res);
-        return distance_check_between_multiple_collision_objects( &collision_environment.environment_obbs, &self._link_triangle_meshes, stop_at_first_detected_intersection, Some(&self._link_triangle_meshes_skip_collision_check_tensor), None);
+        return distance_check_between_environment_and_multiple_collision_objects( collision_environment, &self._link_triangle_meshes, stop_at_first_detected_intersection, Some(&self._link_triangle_meshes_skip_collision_check_tensor), None);
     }
 
     pub fn environment_contact_check(&mut self, fk_res: &RobotFKResult, collision_environment: &CollisionEnvironment, stop_at_first_detected_intersection: bool, margin: Option<f64>) -> Result<ContactCheckMultipleResult, String> {
         self._set_poses_on_link_triangle_meshes(fk_res);
-        return contact_check_between_multiple_collision_objects( &collision_environment.environment_obbs, &self._link_triangle_meshes, stop_at_first_detected_intersection, margin, Some(&self._link_triangle_meshes_skip_collision_check_tensor), None);
+        return contact_check_between_environment_and_multiple_collision_objects( collision_environment, &self._link_triangle_meshes, stop_at_first_detected_intersection, margin, Some(&self._link_triangle_meshes_skip_collision_check_tensor), None);
     }
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
@@ -72,7 +87,7 @@ impl RobotTriangleMeshCollisionModule {
         let exists1 = check_if_path_exists_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_triangle_meshes_visual".to_string());
         let exists2 = check_if_path_exists_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_triangle_meshes_collision".to_string());
         if !(exists1 || exists2) {
-            save_all_links_as_triangle_meshes(robot_name.clone())?;
+            save_all_links_as_triangle_meshes(robot_name.clone(), robot_configuration_module.get_mesh_repair_options_ref())?;
         }
         Ok(())
     }
@@ -2,16 +2,33 @@ use crate::robot_modules::robot_set::*;
 use crate::utils::utils_collisions::prelude::*;
 use crate::robot_modules::robot_fk_module::*;
 use crate::robot_modules::robot_core_collision_module::LinkGeometryType;
+use crate::robot_modules::robot_bounds_module::BoundsCheckResult;
 use crate::utils::utils_files_and_strings::prelude::*;
+use crate::utils::utils_pointclouds::pointcloud::PointCloud;
+use crate::utils::utils_paths::{linear_spline_path::LinearSplinePath, path_validation_report::*};
 use termion::{style, color};
+use nalgebra::{DVector, Vector3, Point3};
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::HashSet;
+
+const OCCUPANCY_GRID_OBJECT_NAME: &str = "occupancy_grid";
+
+/* environment versions are drawn from one global counter, so clones of a robot world (e.g., across LynxVarsParallel)
+share a version until one of them changes, and worlds that change independently never end up with the same version */
+static NEXT_ENVIRONMENT_VERSION: AtomicU64 = AtomicU64::new(0);
+fn new_environment_version() -> u64 { return NEXT_ENVIRONMENT_VERSION.fetch_add(1, Ordering::SeqCst); }
 
 #[derive(Clone, Debug)]
 pub struct RobotWorld {
     _robot_set: RobotSet,
     _collision_environment: Option<CollisionEnvironment>,
+    _occupancy_grid: Option<VoxelOccupancyGrid>,
+    _occupancy_grid_voxel_keys: Option<Vec<[i64; 3]>>, // voxel of each component of the occupancy grid object, None if it has to be rebuilt
     _robot_names: Option<Vec<String>>,
     _configuration_names: Option<Vec<Option<String>>>,
     _robot_set_name: Option<String>,
+    _environment_version: u64
 }
 
 impl RobotWorld {
@@ -25,9 +42,12 @@ impl RobotWorld {
         let mut out_self = Self {
             _robot_set,
             _collision_environment,
+            _occupancy_grid: None,
+            _occupancy_grid_voxel_keys: None,
             _robot_names: None,
             _configuration_names: None,
-            _robot_set_name: Some(robot_set_name.to_string())
+            _robot_set_name: Some(robot_set_name.to_string()),
+            _environment_version: new_environment_version()
         };
 
         return Ok(out_self);
@@ -52,9 +72,12 @@ impl RobotWorld {
         let mut out_self = Self {
             _robot_set,
             _collision_environment,
+            _occupancy_grid: None,
+            _occupancy_grid_voxel_keys: None,
             _robot_names: Some(_robot_names),
             _configuration_names: Some(_configuration_names),
             _robot_set_name: None,
+            _environment_version: new_environment_version()
         };
 
         return Ok(out_self);
@@ -96,9 +119,12 @@ impl RobotWorld {
             Ok(Self {
                 _robot_set: robot_set,
                 _collision_environment: collision_environment,
+                _occupancy_grid: None,
+                _occupancy_grid_voxel_keys: None,
                 _robot_names: Some(robot_names_.clone()),
                 _configuration_names: Some(configuration_names_.clone()),
-                _robot_set_name: None
+                _robot_set_name: None,
+                _environment_version: new_environment_version()
             })
         } else {
             let robot_set_name_ = robot_set_name.as_ref().unwrap();
@@ -107,15 +133,39 @@ impl RobotWorld {
             Ok(Self {
                 _robot_set: robot_set,
                 _collision_environment: collision_environment,
+                _occupancy_grid: None,
+                _occupancy_grid_voxel_keys: None,
                 _robot_names: None,
                 _configuration_names: None,
-                _robot_set_name: Some(robot_set_name_.clone())
+                _robot_set_name: Some(robot_set_name_.clone()),
+                _environment_version: new_environment_version()
             })
         };
     }
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
+    /* self and multi robot checks only set link poses on the robot set, so unlike get_robot_set_mut_ref, they do not change
+    the environment version */
+    pub fn self_intersect_check(&mut self, fk_res: &VecOfRobotFKResult, link_geometry_type: LinkGeometryType, stop_at_first_detected: bool) -> Result<VecOfIntersectCheckMultipleResult, String> {
+        return self._robot_set.self_intersect_check(fk_res, link_geometry_type, stop_at_first_detected);
+    }
+
+    pub fn self_contact_check(&mut self, fk_res: &VecOfRobotFKResult, link_geometry_type: LinkGeometryType, stop_at_first_detected: bool, margin: Option<f64>) -> Result<VecOfContactCheckMultipleResult, String> {
+        return self._robot_set.self_contact_check(fk_res, link_geometry_type, stop_at_first_detected, margin);
+    }
+
+    pub fn multi_robot_intersect_check(&mut self, fk_res: &VecOfRobotFKResult, link_geometry_type: LinkGeometryType, stop_at_first_detected: bool) -> Result<VecOfIntersectCheckMultipleResult, String> {
+        return self._robot_set.multi_robot_intersect_check(fk_res, link_geometry_type, stop_at_first_detected);
+    }
+
+    pub fn set_poses_on_links(&mut self, fk_res: &VecOfRobotFKResult, link_geometry_type: &LinkGeometryType) {
+        let l = self._robot_set.get_num_robots();
+        for i in 0..l {
+            self._robot_set.get_robots_mut_ref()[i].get_core_collision_module_mut_ref().set_poses_on_links(&fk_res.get_robot_fk_results_ref()[i], link_geometry_type);
+        }
+    }
+
     pub fn environment_intersect_check(&mut self, fk_res: &VecOfRobotFKResult, link_geometry_type: LinkGeometryType, stop_at_first_detected: bool) -> Result<VecOfIntersectCheckMultipleResult, String> {
         if self._collision_environment.is_none() {
             return Ok(VecOfIntersectCheckMultipleResult::new_no_intersections_found(self._robot_set.get_num_robots(), stop_at_first_detected));
@@ -301,7 +351,229 @@ impl RobotWorld {
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
+    // continuous check along the straight joint space segment from q_a to q_b using conservative advancement.  At each
+    // state, no point on a link can move farther than its motion bound (scaled by the fraction of the segment remaining),
+    // so the state can safely advance by closest distance / motion bound without tunneling through thin obstacles.
+    // Distances smaller than tolerance are reported as collisions.
+    pub fn segment_intersect_check(&mut self, q_a: &DVector<f64>, q_b: &DVector<f64>, link_geometry_type: LinkGeometryType, tolerance: f64) -> Result<CollisionCheckResult, String> {
+        let endpoints = [q_a, q_b];
+        for q in endpoints.iter() {
+            let bounds_check = self._robot_set.check_if_state_is_within_bounds(q)?;
+            match bounds_check {
+                BoundsCheckResult::InBounds => {}
+                BoundsCheckResult::OutOfBounds(s) => { return Ok( CollisionCheckResult::InCollision(s) ) }
+                BoundsCheckResult::Error(s) => { return Err(s) }
+            }
+        }
+
+        let motion_bounds = self._robot_set.compute_link_motion_bounds(q_a, q_b)?;
+        let mut link_geometry_max_extents = Vec::new();
+        for robot in self._robot_set.get_robots_ref() {
+            link_geometry_max_extents.push( robot.get_core_collision_module_ref().get_link_geometry_max_extents(&link_geometry_type) );
+        }
+        let robot_pair_idxs = self._robot_set.get_robot_set_result_vector_idxs_to_robot_idxs_ref().clone();
+        let mut distance_check_cutoff = None;
+        if self._collision_environment.is_some() { distance_check_cutoff = self._collision_environment.as_ref().unwrap().broad_phase.get_distance_check_cutoff(); }
+
+        let dir = q_b - q_a;
+        let mut t = 0.0;
+        loop {
+            let q = q_a + t * &dir;
+            let fk_res = self._robot_set.compute_fk(&q)?;
+
+            let mut min_distance = std::f64::INFINITY;
+            let mut step = std::f64::INFINITY;
+
+            let self_distance_check = self._robot_set.self_distance_check(&fk_res, link_geometry_type.clone(), true)?;
+            if self_distance_check.in_collision() { return Ok(CollisionCheckResult::InCollision(format!("self collision at t = {:?} along segment", t))) }
+            let results = self_distance_check.get_distance_check_multiple_results_ref();
+            let l = results.len();
+            for i in 0..l {
+                let info = results[i].get_distance_check_multiple_info_ref();
+                let idxs = info.get_distance_check_idxs();
+                let distances = info.get_distance_check_distances();
+                let l2 = idxs.len();
+                for j in 0..l2 {
+                    let link_idx1 = idxs[j][0][0];
+                    let link_idx2 = idxs[j][1][0];
+                    let motion_bound = motion_bounds[i].get_bound_on_point_motion(link_idx1, link_geometry_max_extents[i][link_idx1]) + motion_bounds[i].get_bound_on_point_motion(link_idx2, link_geometry_max_extents[i][link_idx2]);
+                    min_distance = min_distance.min(distances[j]);
+                    step = step.min(Self::_get_conservative_advancement_step(distances[j], motion_bound));
+                }
+            }
+
+            let environment_distance_check = self.environment_distance_check(&fk_res, link_geometry_type.clone(), true)?;
+            if environment_distance_check.in_collision() { return Ok(CollisionCheckResult::InCollision(format!("environment collision at t = {:?} along segment", t))) }
+            let results = environment_distance_check.get_distance_check_multiple_results_ref();
+            let l = results.len();
+            for i in 0..l {
+                let info = results[i].get_distance_check_multiple_info_ref();
+                let idxs = info.get_distance_check_idxs();
+                let distances = info.get_distance_check_distances();
+                let l2 = idxs.len();
+                for j in 0..l2 {
+                    let link_idx = idxs[j][1][0];
+                    let motion_bound = motion_bounds[i].get_bound_on_point_motion(link_idx, link_geometry_max_extents[i][link_idx]);
+                    min_distance = min_distance.min(distances[j]);
+                    step = step.min(Self::_get_conservative_advancement_step(distances[j], motion_bound));
+                }
+            }
+            // with a broad phase distance cutoff, pairs left out of the results are only known to be at least the cutoff apart
+            if distance_check_cutoff.is_some() {
+                let l = motion_bounds.len();
+                for i in 0..l {
+                    let l2 = link_geometry_max_extents[i].len();
+                    for link_idx in 0..l2 {
+                        let motion_bound = motion_bounds[i].get_bound_on_point_motion(link_idx, link_geometry_max_extents[i][link_idx]);
+                        step = step.min(Self::_get_conservative_advancement_step(distance_check_cutoff.unwrap(), motion_bound));
+                    }
+                }
+            }
+
+            let multi_robot_distance_check = self._robot_set.multi_robot_distance_check(&fk_res, link_geometry_type.clone(), true)?;
+            if multi_robot_distance_check.in_collision() { return Ok(CollisionCheckResult::InCollision(format!("multi robot collision at t = {:?} along segment", t))) }
+            let results = multi_robot_distance_check.get_distance_check_multiple_results_ref();
+            let l = results.len();
+            for i in 0..l {
+                let (robot_idx1, robot_idx2) = robot_pair_idxs[i];
+                let info = results[i].get_distance_check_multiple_info_ref();
+                let idxs = info.get_distance_check_idxs();
+                let distances = info.get_distance_check_distances();
+                let l2 = idxs.len();
+                for j in 0..l2 {
+                    let link_idx1 = idxs[j][0][0];
+                    let link_idx2 = idxs[j][1][0];
+                    let motion_bound = motion_bounds[robot_idx1].get_bound_on_point_motion(link_idx1, link_geometry_max_extents[robot_idx1][link_idx1]) + motion_bounds[robot_idx2].get_bound_on_point_motion(link_idx2, link_geometry_max_extents[robot_idx2][link_idx2]);
+                    min_distance = min_distance.min(distances[j]);
+                    step = step.min(Self::_get_conservative_advancement_step(distances[j], motion_bound));
+                }
+            }
+
+            if min_distance < tolerance { return Ok(CollisionCheckResult::InCollision(format!("closest distance {:?} is within tolerance {:?} at t = {:?} along segment", min_distance, tolerance, t))) }
+
+            if t >= 1.0 { return Ok(CollisionCheckResult::NotInCollision); }
+            t = (t + step).min(1.0);
+        }
+    }
+
+    fn _get_conservative_advancement_step(distance: f64, motion_bound: f64) -> f64 {
+        if motion_bound <= 0.0 { return std::f64::INFINITY; }
+        return distance / motion_bound;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // discrete check of every segment of the path, with states spaced so that no joint moves more than resolution between
+    // consecutive checked states.  States are checked in parallel, each thread on its own clone of the robot world, and
+    // the report only depends on the path and resolution (not on the number of threads).
+    pub fn validate_path(&self, path: &LinearSplinePath, link_geometry_type: LinkGeometryType, resolution: f64, num_threads: Option<usize>) -> Result<PathValidationReport, String> {
+        if !(resolution > 0.0) { return Err(format!("resolution must be positive in validate_path (got {:?})", resolution)); }
+        let num_waypoints = path.get_num_waypoints();
+        if num_waypoints == 0 { return Err("cannot validate a path with no waypoints".to_string()); }
+
+        // (segment idx, parameter, state) for every state to check.  A single waypoint path is one segment that starts and ends at that waypoint
+        let num_segments = (num_waypoints - 1).max(1);
+        let mut states_to_check = Vec::new();
+        for i in 0..num_segments {
+            let q_a = &path.waypoints[i];
+            let q_b = &path.waypoints[(i + 1).min(num_waypoints - 1)];
+            let dir = q_b - q_a;
+            let num_steps = (dir.amax() / resolution).ceil().max(1.0) as usize;
+            for k in 0..num_steps + 1 {
+                let t = k as f64 / num_steps as f64;
+                states_to_check.push((i, t, q_a + t * &dir));
+            }
+        }
+
+        let mut num_threads_ = num_cpus::get(); if num_threads.is_some() { num_threads_ = num_threads.unwrap(); }
+        if num_threads_ == 0 { return Err("num threads of 0 is invalid in validate_path".to_string()); }
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(num_threads_).build();
+        if pool.is_err() { return Err(format!("could not build thread pool for path validation: {:?}", pool.err().unwrap().to_string())); }
+        let pool = pool.ok().unwrap();
+
+        let state_results: Vec<Result<(Vec<[String; 2]>, Option<f64>, Option<String>), String>> = pool.install(|| {
+            states_to_check.par_iter().map_init(|| self.clone(), |robot_world, (_, _, q)| {
+                robot_world._validate_path_state(q, &link_geometry_type)
+            }).collect()
+        });
+
+        let mut segments: Vec<PathSegmentValidationResult> = (0..num_segments).map(|i| PathSegmentValidationResult {
+            segment_idx: i,
+            num_states_checked: 0,
+            first_colliding_parameter: None,
+            colliding_pairs: Vec::new(),
+            min_clearance: None,
+            out_of_bounds_states: Vec::new()
+        }).collect();
+
+        let l = states_to_check.len();
+        for j in 0..l {
+            let (i, t, q) = &states_to_check[j];
+            let (colliding_pairs, clearance, out_of_bounds_message) = state_results[j].clone()?;
+            let segment = &mut segments[*i];
+
+            segment.num_states_checked += 1;
+            if !colliding_pairs.is_empty() && segment.first_colliding_parameter.is_none() { segment.first_colliding_parameter = Some(*t); }
+            for p in colliding_pairs {
+                if !segment.colliding_pairs.contains(&p) { segment.colliding_pairs.push(p); }
+            }
+            if clearance.is_some() && (segment.min_clearance.is_none() || clearance.unwrap() < segment.min_clearance.unwrap()) {
+                segment.min_clearance = clearance;
+            }
+            if out_of_bounds_message.is_some() {
+                segment.out_of_bounds_states.push(PathOutOfBoundsState { parameter: *t, state: q.iter().cloned().collect(), message: out_of_bounds_message.unwrap() });
+            }
+        }
+
+        return Ok(PathValidationReport { link_geometry_type: format!("{:?}", link_geometry_type), resolution, num_waypoints, segments });
+    }
+
+    // (colliding pair names, clearance, out of bounds message) for a single state.  Clearance is the closest distance
+    // reported by the self, environment, and multi robot distance checks (None if no pair is within the broad phase cutoff),
+    // or 0.0 if the state is in collision
+    fn _validate_path_state(&mut self, q: &DVector<f64>, link_geometry_type: &LinkGeometryType) -> Result<(Vec<[String; 2]>, Option<f64>, Option<String>), String> {
+        let mut out_of_bounds_message = None;
+        match self._robot_set.check_if_state_is_within_bounds(q)? {
+            BoundsCheckResult::InBounds => {}
+            BoundsCheckResult::OutOfBounds(s) => { out_of_bounds_message = Some(s); }
+            BoundsCheckResult::Error(s) => { return Err(s); }
+        }
+
+        let fk_res = self._robot_set.compute_fk(q)?;
+
+        let mut colliding_pairs = Vec::new();
+        let intersect_checks = vec![ self._robot_set.self_intersect_check(&fk_res, link_geometry_type.clone(), false)?,
+                                     self.environment_intersect_check(&fk_res, link_geometry_type.clone(), false)?,
+                                     self._robot_set.multi_robot_intersect_check(&fk_res, link_geometry_type.clone(), false)? ];
+        for check in &intersect_checks {
+            for res in check.get_intersect_check_multiple_results_ref() {
+                for p in res.get_intersect_check_multiple_info_ref().get_intersection_names() {
+                    if !colliding_pairs.contains(p) { colliding_pairs.push(p.clone()); }
+                }
+            }
+        }
+
+        if !colliding_pairs.is_empty() { return Ok((colliding_pairs, Some(0.0), out_of_bounds_message)); }
+
+        let mut clearance: Option<f64> = None;
+        let distance_checks = vec![ self._robot_set.self_distance_check(&fk_res, link_geometry_type.clone(), false)?,
+                                    self.environment_distance_check(&fk_res, link_geometry_type.clone(), false)?,
+                                    self._robot_set.multi_robot_distance_check(&fk_res, link_geometry_type.clone(), false)? ];
+        for check in &distance_checks {
+            for res in check.get_distance_check_multiple_results_ref() {
+                for d in res.get_distance_check_multiple_info_ref().get_distance_check_distances() {
+                    if clearance.is_none() || *d < clearance.unwrap() { clearance = Some(*d); }
+                }
+            }
+        }
+
+        return Ok((colliding_pairs, clearance, out_of_bounds_message));
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
     pub fn update_robot_set(&mut self, robot_names: Vec<&str>, configuration_names: Vec<Option<&str>>) -> Result<(), String> {
+        self._environment_version = new_environment_version();
         let robot_set = RobotSet::new(robot_names.clone(), configuration_names.clone())?;
         self._robot_set = robot_set;
         self._robot_names = Some(str_vec_to_string_vec(&robot_names));
@@ -311,6 +583,7 @@ impl RobotWorld {
     }
 
     pub fn update_robot_set_from_set_name(&mut self, robot_set_name: &str) -> Result<(), String> {
+        self._environment_version = new_environment_version();
         let robot_set = RobotSet::new_from_set_name(robot_set_name)?;
         self._robot_set = robot_set;
         self._robot_names = None;
@@ -320,6 +593,7 @@ impl RobotWorld {
     }
 
     pub fn update_robot_set_with_given_set(&mut self, robot_set: RobotSet) {
+        self._environment_version = new_environment_version();
         self._robot_names = robot_set.robot_names.clone();
         self._configuration_names = robot_set.configuration_names.clone();
         self._robot_set_name = robot_set.robot_set_name.clone();
@@ -327,37 +601,287 @@ impl RobotWorld {
     }
 
     pub fn update_collision_environment(&mut self, environment_name: Option<&str>) -> Result<(), String> {
+        self._environment_version = new_environment_version();
         self._collision_environment = None;
+        self._occupancy_grid_voxel_keys = None;
         if environment_name.is_some() {
             self._collision_environment = Some(CollisionEnvironment::new_with_environment_name(environment_name.unwrap())?);
             self._collision_environment.as_mut().unwrap().update_bounding_volumes_on_all_environment_obbs();
         }
-        Ok(())
+        self._sync_occupancy_grid_with_collision_environment()
     }
 
-    pub fn update_collision_environment_with_given_collision_environment(&mut self, collision_environment: CollisionEnvironment) {
+    pub fn update_collision_environment_with_given_collision_environment(&mut self, collision_environment: CollisionEnvironment) -> Result<(), String> {
+        self._environment_version = new_environment_version();
         self._collision_environment = Some(collision_environment);
         self._collision_environment.as_mut().unwrap().update_bounding_volumes_on_all_environment_obbs();
+        self._occupancy_grid_voxel_keys = None;
+        self._sync_occupancy_grid_with_collision_environment()
     }
 
     pub fn set_robot_set(&mut self, robot_set: RobotSet) {
+        self._environment_version = new_environment_version();
         self._robot_set = robot_set;
     }
 
-    pub fn set_collision_environment(&mut self, collision_environment: CollisionEnvironment) {
+    pub fn set_collision_environment(&mut self, collision_environment: CollisionEnvironment) -> Result<(), String> {
+        self._environment_version = new_environment_version();
         self._collision_environment = Some(collision_environment);
+        self._occupancy_grid_voxel_keys = None;
+        self._sync_occupancy_grid_with_collision_environment()
     }
 
-    pub fn absorb_collision_environment(&mut self, collision_environment: CollisionEnvironment) {
+    pub fn absorb_collision_environment(&mut self, collision_environment: CollisionEnvironment) -> Result<(), String> {
+        self._environment_version = new_environment_version();
         if self._collision_environment.is_some() {
             self._collision_environment.as_mut().unwrap().absorb(&collision_environment);
+            self._occupancy_grid_voxel_keys = None;
+            self._sync_occupancy_grid_with_collision_environment()
         } else {
-            self.set_collision_environment(collision_environment);
+            self.set_collision_environment(collision_environment)
         }
     }
 
-    pub fn remove_collision_environment(&mut self) {
+    pub fn remove_collision_environment(&mut self) -> Result<(), String> {
+        self._environment_version = new_environment_version();
         self._collision_environment = None;
+        self._occupancy_grid_voxel_keys = None;
+        self._sync_occupancy_grid_with_collision_environment()
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // the occupied voxels of the occupancy grid are kept as the components of a single object named "occupancy_grid" in
+    // the collision environment, so all environment checks (intersect, distance, contact, segment) include them.
+    pub fn set_occupancy_grid(&mut self, occupancy_grid: VoxelOccupancyGrid) -> Result<(), String> {
+        self._environment_version = new_environment_version();
+        self._occupancy_grid = Some(occupancy_grid);
+        self._occupancy_grid_voxel_keys = None;
+        return self._sync_occupancy_grid_with_collision_environment();
+    }
+
+    pub fn insert_point_cloud_into_occupancy_grid(&mut self, point_cloud: &PointCloud, sensor_origin: &Vector3<f64>, max_range: Option<f64>) -> Result<(), String> {
+        self._environment_version = new_environment_version();
+        if self._occupancy_grid.is_none() {
+            return Err("occupancy grid must be set (using set_occupancy_grid) before inserting a point cloud".to_string());
+        }
+        self._occupancy_grid.as_mut().unwrap().insert_point_cloud(point_cloud, sensor_origin, max_range);
+        return self._sync_occupancy_grid_with_collision_environment();
+    }
+
+    pub fn remove_occupancy_grid(&mut self) -> Result<(), String> {
+        self._environment_version = new_environment_version();
+        self._occupancy_grid = None;
+        self._occupancy_grid_voxel_keys = None;
+        if self._collision_environment.is_some() && self._collision_environment.as_ref().unwrap().object_names.contains(&OCCUPANCY_GRID_OBJECT_NAME.to_string()) {
+            self._collision_environment.as_mut().unwrap().delete_object_by_name(OCCUPANCY_GRID_OBJECT_NAME)?;
+        }
+        Ok(())
+    }
+
+    fn _sync_occupancy_grid_with_collision_environment(&mut self) -> Result<(), String> {
+        if self._occupancy_grid.is_none() { return Ok(()); }
+
+        if self._collision_environment.is_none() {
+            self._collision_environment = Some(CollisionEnvironment::new_empty(OCCUPANCY_GRID_OBJECT_NAME));
+        }
+
+        let occupancy_grid = self._occupancy_grid.as_ref().unwrap();
+        let collision_environment = self._collision_environment.as_mut().unwrap();
+
+        // the occupancy grid object is rebuilt from scratch unless its components are known to match the tracked voxels
+        let object_idx = collision_environment.object_names.iter().position(|n| n.as_str() == OCCUPANCY_GRID_OBJECT_NAME);
+        if self._occupancy_grid_voxel_keys.is_none() || object_idx.is_none() || collision_environment.environment_obbs[object_idx.unwrap()].len() != self._occupancy_grid_voxel_keys.as_ref().unwrap().len() {
+            let occupied_keys = occupancy_grid.get_occupied_keys();
+            let (components, trimesh_engines) = occupancy_grid.get_voxel_collision_objects(OCCUPANCY_GRID_OBJECT_NAME, &occupied_keys);
+            collision_environment.set_object_components_by_name(OCCUPANCY_GRID_OBJECT_NAME, components, trimesh_engines)?;
+            self._occupancy_grid_voxel_keys = Some(occupied_keys);
+            return Ok(());
+        }
+
+        // otherwise, only voxels whose occupancy changed are removed or added.  Removing from the back keeps the
+        // component idxs still to be visited valid, since swap_remove only moves the last component.
+        let voxel_keys = self._occupancy_grid_voxel_keys.as_mut().unwrap();
+        let occupied_keys: HashSet<[i64; 3]> = occupancy_grid.get_occupied_keys().into_iter().collect();
+        let mut i = voxel_keys.len();
+        while i > 0 {
+            i -= 1;
+            if !occupied_keys.contains(&voxel_keys[i]) {
+                collision_environment.swap_remove_object_component_by_name(OCCUPANCY_GRID_OBJECT_NAME, i)?;
+                voxel_keys.swap_remove(i);
+            }
+        }
+
+        let curr_keys: HashSet<[i64; 3]> = voxel_keys.iter().cloned().collect();
+        let mut new_keys: Vec<[i64; 3]> = occupied_keys.into_iter().filter(|k| !curr_keys.contains(k)).collect();
+        new_keys.sort();
+        let (components, trimesh_engines) = occupancy_grid.get_voxel_collision_objects(OCCUPANCY_GRID_OBJECT_NAME, &new_keys);
+        collision_environment.add_object_components_by_name(OCCUPANCY_GRID_OBJECT_NAME, components, trimesh_engines)?;
+        voxel_keys.extend(new_keys);
+
+        Ok(())
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // a target is visible from a link if the line of sight from the link frame's origin to the target does not pass through
+    // any robot link or environment object.  The from link, links in ignore_links ([robot idx, link idx]), and objects that
+    // contain the target itself (e.g., the object the target point sits on) are never counted as occluders.
+    pub fn visibility_check(&mut self, fk_res: &VecOfRobotFKResult, robot_idx: usize, from_link_idx: usize, target_point: &Vector3<f64>, link_geometry_type: LinkGeometryType, ignore_links: &Vec<[usize; 2]>) -> Result<VisibilityCheckResult, String> {
+        let mut res = self.visibility_check_multiple(fk_res, robot_idx, from_link_idx, &vec![target_point.clone()], link_geometry_type, ignore_links)?;
+        return Ok(res.remove(0));
+    }
+
+    // same as visibility_check for many targets, but link poses are only set once
+    pub fn visibility_check_multiple(&mut self, fk_res: &VecOfRobotFKResult, robot_idx: usize, from_link_idx: usize, target_points: &Vec<Vector3<f64>>, link_geometry_type: LinkGeometryType, ignore_links: &Vec<[usize; 2]>) -> Result<Vec<VisibilityCheckResult>, String> {
+        let fk_results = fk_res.get_robot_fk_results_ref();
+        if robot_idx >= fk_results.len() { return Err(format!("robot idx {:?} is too high for number of robots ({:?})", robot_idx, fk_results.len())); }
+        let link_frames = fk_results[robot_idx].get_link_frames_ref();
+        if from_link_idx >= link_frames.len() || link_frames[from_link_idx].is_none() {
+            return Err(format!("link idx {:?} on robot {:?} does not have a frame in visibility_check", from_link_idx, robot_idx));
+        }
+        let viewpoint = link_frames[from_link_idx].as_ref().unwrap().translation.clone();
+
+        self.set_poses_on_links(fk_res, &link_geometry_type);
+
+        let l = self._robot_set.get_num_robots();
+
+        // subsets of robot link collision objects that can occlude, per robot.  Links that contain the viewpoint (e.g., the
+        // link that a camera is mounted to) are left out, just like objects that contain the target
+        let viewpoint_point = Point3::from(viewpoint.clone());
+        let mut subset_check_idxs = Vec::new();
+        for i in 0..l {
+            let mut subset = Vec::new();
+            let group = self._robot_set.get_robots_ref()[i].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
+            let contains_res = contains_point_check_between_multiple_collision_objects(group, &viewpoint_point, false)?;
+            let viewpoint_container_idxs = match &contains_res {
+                ContainsPointCheckMultipleResult::PointContainerFound(c) => c.get_container_idxs().clone(),
+                ContainsPointCheckMultipleResult::NoPointContainerFound(_) => Vec::new()
+            };
+            for j in 0..group.len() {
+                if i == robot_idx && j == from_link_idx { continue; }
+                if ignore_links.contains(&[i, j]) { continue; }
+                if viewpoint_container_idxs.iter().any(|x| x[0] == j) { continue; }
+                for k in 0..group[j].len() { subset.push([j, k]); }
+            }
+            subset_check_idxs.push(subset);
+        }
+
+        let mut out_vec = Vec::new();
+        for target_point in target_points {
+            let mut occluders = Vec::new();
+
+            for i in 0..l {
+                let group = self._robot_set.get_robots_ref()[i].get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&link_geometry_type);
+                let link_names = &self._robot_set.get_robots_ref()[i].get_configuration_module_ref().robot_model_module.links;
+                for (idxs, entry_fraction, exit_fraction) in Self::_get_occluders_in_group(group, &subset_check_idxs[i], &viewpoint, target_point)? {
+                    occluders.push(VisibilityOccluder { name: link_names[idxs[0]].name.clone(), robot_idx: Some(i), idxs, entry_fraction, exit_fraction });
+                }
+            }
+
+            if self._collision_environment.is_some() {
+                let e = self._collision_environment.as_ref().unwrap();
+                let mut subset = Vec::new();
+                for j in 0..e.environment_obbs.len() {
+                    for k in 0..e.environment_obbs[j].len() { subset.push([j, k]); }
+                }
+                for (idxs, entry_fraction, exit_fraction) in Self::_get_occluders_in_group(&e.environment_obbs, &subset, &viewpoint, target_point)? {
+                    occluders.push(VisibilityOccluder { name: e.object_names[idxs[0]].clone(), robot_idx: None, idxs, entry_fraction, exit_fraction });
+                }
+            }
+
+            out_vec.push(VisibilityCheckResult::new(viewpoint.clone(), target_point.clone(), occluders));
+        }
+
+        return Ok(out_vec);
+    }
+
+    pub fn visibility_check_from_link_name(&mut self, fk_res: &VecOfRobotFKResult, robot_idx: usize, from_link_name: &str, target_point: &Vector3<f64>, link_geometry_type: LinkGeometryType, ignore_link_names: &Vec<(usize, String)>) -> Result<VisibilityCheckResult, String> {
+        let from_link_idx = self._get_link_idx_from_name(robot_idx, from_link_name)?;
+        let mut ignore_links = Vec::new();
+        for (r, name) in ignore_link_names {
+            ignore_links.push([*r, self._get_link_idx_from_name(*r, name)?]);
+        }
+        return self.visibility_check(fk_res, robot_idx, from_link_idx, target_point, link_geometry_type, &ignore_links);
+    }
+
+    // ([i, j] idxs, entry fraction, exit fraction) for each object in the subset that intersects the segment from viewpoint
+    // to target without containing the target
+    fn _get_occluders_in_group(group: &Vec<Vec<CollisionObject>>, subset_check_idxs: &Vec<[usize; 2]>, viewpoint: &Vector3<f64>, target_point: &Vector3<f64>) -> Result<Vec<([usize; 2], f64, f64)>, String> {
+        let mut out_vec = Vec::new();
+        if subset_check_idxs.is_empty() { return Ok(out_vec); }
+
+        let origin = Point3::from(viewpoint.clone());
+        let target = Point3::from(target_point.clone());
+        let direction = target_point - viewpoint;
+
+        // rays are cast with a max toi of 1.0, i.e., only along the segment from viewpoint to target
+        let ray_res = ray_intersect_check_between_multiple_collision_objects_subset(subset_check_idxs.clone(), group, &origin, &direction, false)?;
+        let hit_idxs = match &ray_res {
+            RayIntersectCheckMultipleResult::IntersectionFound(i) => i.get_ray_intersection_idxs().clone(),
+            RayIntersectCheckMultipleResult::NoIntersectionFound(_) => { return Ok(out_vec); }
+        };
+
+        let contains_res = contains_point_check_between_multiple_collision_objects(group, &target, false)?;
+        let container_idxs = match &contains_res {
+            ContainsPointCheckMultipleResult::PointContainerFound(i) => i.get_container_idxs().clone(),
+            ContainsPointCheckMultipleResult::NoPointContainerFound(_) => Vec::new()
+        };
+
+        for idxs in hit_idxs {
+            if container_idxs.contains(&idxs) { continue; }
+            let object = &group[idxs[0]][idxs[1]];
+            let entry = object.cast_ray(&origin, &direction, 1.0);
+            let exit = object.cast_ray(&target, &(-direction), 1.0);
+            let entry_fraction = if entry.is_some() { entry.unwrap().toi } else { 0.0 };
+            let exit_fraction = if exit.is_some() { 1.0 - exit.unwrap().toi } else { 1.0 };
+            out_vec.push((idxs, entry_fraction, exit_fraction));
+        }
+
+        return Ok(out_vec);
+    }
+
+    fn _get_link_idx_from_name(&self, robot_idx: usize, link_name: &str) -> Result<usize, String> {
+        let robots = self._robot_set.get_robots_ref();
+        if robot_idx >= robots.len() { return Err(format!("robot idx {:?} is too high for number of robots ({:?})", robot_idx, robots.len())); }
+        let link_idx = robots[robot_idx].get_configuration_module_ref().robot_model_module.get_link_idx_from_name(&link_name.to_string());
+        if link_idx.is_none() { return Err(format!("link {:?} not found on robot {:?}", link_name, robot_idx)); }
+        return Ok(link_idx.unwrap());
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // paddings can be changed at any time, and are used by all subsequent intersect, distance, contact, and segment checks
+    pub fn set_link_padding(&mut self, robot_idx: usize, link_name: &str, padding: f64) -> Result<(), String> {
+        self._environment_version = new_environment_version();
+        return self._robot_set.set_link_padding(robot_idx, link_name, padding);
+    }
+
+    pub fn set_environment_object_padding(&mut self, object_name: &str, padding: f64) -> Result<(), String> {
+        self._environment_version = new_environment_version();
+        if self._collision_environment.is_none() {
+            return Err("collision environment must be set before setting an environment object padding".to_string());
+        }
+        return self._collision_environment.as_mut().unwrap().set_object_padding_by_name(object_name, padding);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // moves environment objects that have a trajectory to their pose at the given time.  Does nothing without an environment.
+    pub fn set_environment_time(&mut self, time: f64) -> Result<(), String> {
+        if self._collision_environment.is_none() { return Ok(()); }
+        // nothing moves (and cached collision checks stay valid) if no object has a trajectory
+        if self._collision_environment.as_ref().unwrap().object_trajectories.iter().all(|t| t.is_none()) { return Ok(()); }
+        self._environment_version = new_environment_version();
+        return self._collision_environment.as_mut().unwrap().set_time(time);
+    }
+
+    pub fn set_environment_object_trajectory(&mut self, object_name: &str, trajectory: Option<ObjectPoseTrajectory>) -> Result<(), String> {
+        self._environment_version = new_environment_version();
+        if self._collision_environment.is_none() {
+            return Err("collision environment must be set before setting an environment object trajectory".to_string());
+        }
+        return self._collision_environment.as_mut().unwrap().set_object_trajectory_by_name(object_name, trajectory);
     }
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
@@ -375,7 +899,9 @@ impl RobotWorld {
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
+    // anything can be changed through the returned reference, so the environment version is changed
     pub fn get_robot_set_mut_ref(&mut self) -> &mut RobotSet {
+        self._environment_version = new_environment_version();
         return &mut self._robot_set;
     }
 
@@ -384,10 +910,48 @@ impl RobotWorld {
     }
 
     pub fn get_collision_environment_option_mut_ref(&mut self) -> &mut Option<CollisionEnvironment> {
+        self._environment_version = new_environment_version();
         return &mut self._collision_environment;
     }
 
     pub fn get_collision_environment_option_ref(&self) -> &Option<CollisionEnvironment> {
         return &self._collision_environment;
     }
+
+    pub fn get_occupancy_grid_option_ref(&self) -> &Option<VoxelOccupancyGrid> {
+        return &self._occupancy_grid;
+    }
+
+    // e.g., "ur5" or "ur5[config_name],ur5" for worlds made from robot names, or the set name for worlds made from a robot set
+    pub fn get_robot_world_name(&self) -> String {
+        if self._robot_set_name.is_some() { return self._robot_set_name.as_ref().unwrap().clone(); }
+        if self._robot_names.is_none() { return "".to_string(); }
+
+        let robot_names = self._robot_names.as_ref().unwrap();
+        let l = robot_names.len();
+        let mut names = Vec::new();
+        for i in 0..l {
+            let mut name = robot_names[i].clone();
+            if self._configuration_names.is_some() && self._configuration_names.as_ref().unwrap()[i].is_some() {
+                name += format!("[{}]", self._configuration_names.as_ref().unwrap()[i].as_ref().unwrap()).as_str();
+            }
+            names.push(name);
+        }
+        return names.join(",");
+    }
+
+    pub fn get_environment_name(&self) -> Option<String> {
+        if self._collision_environment.is_none() { return None; }
+        return Some(self._collision_environment.as_ref().unwrap().environment_name.clone());
+    }
+
+    // changes whenever the robot set or environment is changed through the functions above, including whenever
+    // get_robot_set_mut_ref or get_collision_environment_option_mut_ref is called.
+    pub fn get_environment_version(&self) -> u64 {
+        return self._environment_version;
+    }
+
+    pub fn mark_environment_changed(&mut self) {
+        self._environment_version = new_environment_version();
+    }
 }
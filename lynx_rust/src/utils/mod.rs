@@ -15,3 +15,4 @@ pub mod utils_robot_objective_specification;
 pub mod utils_paths;
 pub mod utils_nearest_neighbor;
 pub mod utils_runtime_management;
+pub mod utils_sensors;
\ No newline at end of file
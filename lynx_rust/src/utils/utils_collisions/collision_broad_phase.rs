@@ -0,0 +1,341 @@
+use crate::utils::utils_collisions::collision_object::CollisionObject;
+use ncollide3d::bounding_volume::{BoundingVolume, AABB};
+use std::collections::HashMap;
+
+
+/* bounding volume hierarchy (AABB tree) over the components of a collision environment.  Leaves are individual
+components, indexed the same way as environment_obbs ([object idx, component idx]).  When objects move, their leaves are
+refit in place (the tree topology is only rebuilt when objects are added or removed).  Single components can also be
+inserted and removed without a rebuild, e.g., for voxels of an occupancy grid that change between point clouds. */
+#[derive(Clone, Debug)]
+pub struct EnvironmentBroadPhase {
+    _nodes: Vec<BroadPhaseNode>,
+    _root: Option<usize>,
+    _leaf_node_idxs: HashMap<[usize; 2], usize>,
+    _num_components: usize,
+    _free_node_idxs: Vec<usize>,
+    _distance_check_cutoff: Option<f64>
+}
+
+impl EnvironmentBroadPhase {
+    pub fn new(environment_obbs: &Vec<Vec<CollisionObject>>) -> Self {
+        let mut out_self = Self { _nodes: Vec::new(), _root: None, _leaf_node_idxs: HashMap::new(), _num_components: 0, _free_node_idxs: Vec::new(), _distance_check_cutoff: None };
+        out_self.rebuild(environment_obbs);
+        return out_self;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn rebuild(&mut self, environment_obbs: &Vec<Vec<CollisionObject>>) {
+        self._nodes = Vec::new();
+        self._root = None;
+        self._leaf_node_idxs = HashMap::new();
+        self._free_node_idxs = Vec::new();
+
+        let mut items = Vec::new();
+        let l1 = environment_obbs.len();
+        for i in 0..l1 {
+            let l2 = environment_obbs[i].len();
+            for j in 0..l2 {
+                items.push( ([i, j], Self::_get_world_aabb(&environment_obbs[i][j])) );
+            }
+        }
+        self._num_components = items.len();
+
+        if !items.is_empty() {
+            self._root = Some( self._build(&mut items, None) );
+        }
+    }
+
+    pub fn refit_object(&mut self, environment_obbs: &Vec<Vec<CollisionObject>>, object_idx: usize) {
+        if object_idx >= environment_obbs.len() { return; }
+
+        let l = environment_obbs[object_idx].len();
+        for j in 0..l {
+            let node_idx = self._leaf_node_idxs.get(&[object_idx, j]);
+            if node_idx.is_none() { continue; }
+            let mut curr_idx = *node_idx.unwrap();
+            self._nodes[curr_idx].aabb = Self::_get_world_aabb(&environment_obbs[object_idx][j]);
+
+            while self._nodes[curr_idx].parent.is_some() {
+                curr_idx = self._nodes[curr_idx].parent.unwrap();
+                self._refit_internal_node(curr_idx);
+            }
+        }
+    }
+
+    pub fn refit_all(&mut self, environment_obbs: &Vec<Vec<CollisionObject>>) {
+        let leaves: Vec<([usize; 2], usize)> = self._leaf_node_idxs.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        for (idxs, node_idx) in leaves {
+            if idxs[0] < environment_obbs.len() && idxs[1] < environment_obbs[idxs[0]].len() {
+                self._nodes[node_idx].aabb = Self::_get_world_aabb(&environment_obbs[idxs[0]][idxs[1]]);
+            }
+        }
+
+        // parents come before their children in a pre-order traversal, so a reverse sweep visits children first
+        if self._root.is_none() { return; }
+        let mut pre_order = Vec::new();
+        let mut stack = vec![ self._root.unwrap() ];
+        while !stack.is_empty() {
+            let node_idx = stack.pop().unwrap();
+            pre_order.push(node_idx);
+            if self._nodes[node_idx].children.is_some() {
+                let children = self._nodes[node_idx].children.unwrap();
+                stack.push(children[0]);
+                stack.push(children[1]);
+            }
+        }
+        for node_idx in pre_order.iter().rev() {
+            if self._nodes[*node_idx].children.is_some() { self._refit_internal_node(*node_idx); }
+        }
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // adds a leaf for environment_obbs[idxs[0]][idxs[1]] next to the existing leaf whose merged aabb grows the least
+    pub fn insert_component(&mut self, environment_obbs: &Vec<Vec<CollisionObject>>, idxs: [usize; 2]) -> Result<(), String> {
+        if idxs[0] >= environment_obbs.len() || idxs[1] >= environment_obbs[idxs[0]].len() {
+            return Err(format!("component idxs {:?} are not in environment in broad phase insert_component", idxs));
+        }
+        if self._leaf_node_idxs.contains_key(&idxs) {
+            return Err(format!("component idxs {:?} are already in broad phase", idxs));
+        }
+
+        let aabb = Self::_get_world_aabb(&environment_obbs[idxs[0]][idxs[1]]);
+        let leaf_idx = self._add_node( BroadPhaseNode { aabb: aabb.clone(), parent: None, children: None, component_idxs: Some(idxs) } );
+        self._leaf_node_idxs.insert(idxs, leaf_idx);
+        self._num_components += 1;
+
+        if self._root.is_none() {
+            self._root = Some(leaf_idx);
+            return Ok(());
+        }
+
+        let mut sibling_idx = self._root.unwrap();
+        while self._nodes[sibling_idx].children.is_some() {
+            let children = self._nodes[sibling_idx].children.unwrap();
+            let growth0 = Self::_get_volume(&self._nodes[children[0]].aabb.merged(&aabb)) - Self::_get_volume(&self._nodes[children[0]].aabb);
+            let growth1 = Self::_get_volume(&self._nodes[children[1]].aabb.merged(&aabb)) - Self::_get_volume(&self._nodes[children[1]].aabb);
+            sibling_idx = if growth0 <= growth1 { children[0] } else { children[1] };
+        }
+
+        let parent = self._nodes[sibling_idx].parent;
+        let new_parent_idx = self._add_node( BroadPhaseNode { aabb: aabb.clone(), parent, children: Some([sibling_idx, leaf_idx]), component_idxs: None } );
+        self._nodes[sibling_idx].parent = Some(new_parent_idx);
+        self._nodes[leaf_idx].parent = Some(new_parent_idx);
+        if parent.is_none() {
+            self._root = Some(new_parent_idx);
+        } else {
+            self._replace_child(parent.unwrap(), sibling_idx, new_parent_idx);
+        }
+        self._refit_ancestors(leaf_idx);
+
+        Ok(())
+    }
+
+    // removes the leaf for the given component, putting its sibling in place of their parent
+    pub fn remove_component(&mut self, idxs: [usize; 2]) -> Result<(), String> {
+        let leaf_idx = self._leaf_node_idxs.remove(&idxs);
+        if leaf_idx.is_none() {
+            return Err(format!("component idxs {:?} are not in broad phase", idxs));
+        }
+        let leaf_idx = leaf_idx.unwrap();
+        self._num_components -= 1;
+        self._free_node_idxs.push(leaf_idx);
+
+        let parent = self._nodes[leaf_idx].parent;
+        if parent.is_none() {
+            self._root = None;
+            return Ok(());
+        }
+
+        let parent_idx = parent.unwrap();
+        let children = self._nodes[parent_idx].children.unwrap();
+        let sibling_idx = if children[0] == leaf_idx { children[1] } else { children[0] };
+        let grandparent = self._nodes[parent_idx].parent;
+        self._nodes[sibling_idx].parent = grandparent;
+        self._free_node_idxs.push(parent_idx);
+        if grandparent.is_none() {
+            self._root = Some(sibling_idx);
+        } else {
+            self._replace_child(grandparent.unwrap(), parent_idx, sibling_idx);
+            self._refit_ancestors(sibling_idx);
+        }
+
+        Ok(())
+    }
+
+    // the component at from_idxs is now stored at to_idxs in environment_obbs (e.g., after a swap_remove)
+    pub fn reindex_component(&mut self, from_idxs: [usize; 2], to_idxs: [usize; 2]) -> Result<(), String> {
+        if self._leaf_node_idxs.contains_key(&to_idxs) {
+            return Err(format!("component idxs {:?} are already in broad phase", to_idxs));
+        }
+        let leaf_idx = self._leaf_node_idxs.remove(&from_idxs);
+        if leaf_idx.is_none() {
+            return Err(format!("component idxs {:?} are not in broad phase", from_idxs));
+        }
+        self._nodes[leaf_idx.unwrap()].component_idxs = Some(to_idxs);
+        self._leaf_node_idxs.insert(to_idxs, leaf_idx.unwrap());
+        Ok(())
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // all environment components whose aabb overlaps the given aabb
+    pub fn query_aabb(&self, aabb: &AABB<f64>) -> Vec<[usize; 2]> {
+        let mut out_vec = Vec::new();
+        if self._root.is_none() { return out_vec; }
+
+        let mut stack = vec![ self._root.unwrap() ];
+        while !stack.is_empty() {
+            let node = &self._nodes[stack.pop().unwrap()];
+            if !node.aabb.intersects(aabb) { continue; }
+            if node.component_idxs.is_some() {
+                out_vec.push( node.component_idxs.unwrap() );
+            } else {
+                let children = node.children.unwrap();
+                stack.push(children[0]);
+                stack.push(children[1]);
+            }
+        }
+
+        return out_vec;
+    }
+
+    // candidate [[environment object idx, component idx], [group object idx, component idx]] pairs, sorted in the same
+    // order that the exhaustive group queries would visit them.  If margin is None, all pairs are returned.
+    pub fn get_candidate_pairs(&self, group: &Vec<Vec<CollisionObject>>, margin: Option<f64>) -> Vec<[[usize; 2]; 2]> {
+        let mut out_vec = Vec::new();
+
+        let l1 = group.len();
+        for k in 0..l1 {
+            let l2 = group[k].len();
+            for l in 0..l2 {
+                if margin.is_none() {
+                    for env_idxs in self._leaf_node_idxs.keys() { out_vec.push( [env_idxs.clone(), [k, l]] ); }
+                } else {
+                    let query = Self::_get_world_aabb(&group[k][l]).loosened(margin.unwrap().max(0.0));
+                    let env_idxs = self.query_aabb(&query);
+                    for e in env_idxs { out_vec.push( [e, [k, l]] ); }
+                }
+            }
+        }
+
+        out_vec.sort();
+        return out_vec;
+    }
+
+    pub fn filter_subset(&self, subset_check_idxs: &Vec<[[usize; 2]; 2]>, group: &Vec<Vec<CollisionObject>>, margin: Option<f64>) -> Vec<[[usize; 2]; 2]> {
+        if margin.is_none() { return subset_check_idxs.clone(); }
+
+        let mut out_vec = Vec::new();
+        for c in subset_check_idxs {
+            let node_idx = self._leaf_node_idxs.get(&c[0]);
+            if node_idx.is_none() { continue; }
+            let query = Self::_get_world_aabb(&group[c[1][0]][c[1][1]]).loosened(margin.unwrap().max(0.0));
+            if self._nodes[*node_idx.unwrap()].aabb.intersects(&query) { out_vec.push(c.clone()); }
+        }
+        return out_vec;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // when Some, distance checks skip pairs whose aabbs are farther apart than the cutoff.  Any pair missing from a
+    // distance check result is then guaranteed to be at least the cutoff apart.
+    pub fn set_distance_check_cutoff(&mut self, distance_check_cutoff: Option<f64>) {
+        self._distance_check_cutoff = distance_check_cutoff;
+    }
+
+    pub fn get_distance_check_cutoff(&self) -> Option<f64> {
+        return self._distance_check_cutoff;
+    }
+
+    pub fn get_num_components(&self) -> usize {
+        return self._num_components;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    fn _build(&mut self, items: &mut [([usize; 2], AABB<f64>)], parent: Option<usize>) -> usize {
+        let node_idx = self._nodes.len();
+
+        if items.len() == 1 {
+            self._nodes.push( BroadPhaseNode { aabb: items[0].1.clone(), parent, children: None, component_idxs: Some(items[0].0) } );
+            self._leaf_node_idxs.insert(items[0].0, node_idx);
+            return node_idx;
+        }
+
+        self._nodes.push( BroadPhaseNode { aabb: items[0].1.clone(), parent, children: None, component_idxs: None } );
+
+        // median split along the axis with the largest spread of aabb centers
+        let mut mins = items[0].1.center();
+        let mut maxs = items[0].1.center();
+        for (_, aabb) in items.iter() {
+            let c = aabb.center();
+            for k in 0..3 {
+                if c[k] < mins[k] { mins[k] = c[k]; }
+                if c[k] > maxs[k] { maxs[k] = c[k]; }
+            }
+        }
+        let spread = maxs - mins;
+        let mut axis = 0;
+        if spread[1] > spread[axis] { axis = 1; }
+        if spread[2] > spread[axis] { axis = 2; }
+        items.sort_by(|a, b| a.1.center()[axis].partial_cmp(&b.1.center()[axis]).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mid = items.len() / 2;
+        let (left, right) = items.split_at_mut(mid);
+        let child1 = self._build(left, Some(node_idx));
+        let child2 = self._build(right, Some(node_idx));
+        self._nodes[node_idx].children = Some([child1, child2]);
+        self._refit_internal_node(node_idx);
+
+        return node_idx;
+    }
+
+    fn _add_node(&mut self, node: BroadPhaseNode) -> usize {
+        if self._free_node_idxs.is_empty() {
+            self._nodes.push(node);
+            return self._nodes.len() - 1;
+        }
+        let node_idx = self._free_node_idxs.pop().unwrap();
+        self._nodes[node_idx] = node;
+        return node_idx;
+    }
+
+    fn _replace_child(&mut self, node_idx: usize, old_child_idx: usize, new_child_idx: usize) {
+        let mut children = self._nodes[node_idx].children.unwrap();
+        if children[0] == old_child_idx { children[0] = new_child_idx; } else { children[1] = new_child_idx; }
+        self._nodes[node_idx].children = Some(children);
+    }
+
+    fn _refit_ancestors(&mut self, node_idx: usize) {
+        let mut curr_idx = node_idx;
+        while self._nodes[curr_idx].parent.is_some() {
+            curr_idx = self._nodes[curr_idx].parent.unwrap();
+            self._refit_internal_node(curr_idx);
+        }
+    }
+
+    fn _get_volume(aabb: &AABB<f64>) -> f64 {
+        let extents = aabb.maxs() - aabb.mins();
+        return extents[0] * extents[1] * extents[2];
+    }
+
+    fn _refit_internal_node(&mut self, node_idx: usize) {
+        let children = self._nodes[node_idx].children.unwrap();
+        self._nodes[node_idx].aabb = self._nodes[children[0]].aabb.merged(&self._nodes[children[1]].aabb);
+    }
+
+    fn _get_world_aabb(c: &CollisionObject) -> AABB<f64> {
+        return c.base_bounding_aabb.transform_by(&c.curr_isometry).loosened(c.padding);
+    }
+}
+
+#[derive(Clone, Debug)]
+struct BroadPhaseNode {
+    aabb: AABB<f64>,
+    parent: Option<usize>,
+    children: Option<[usize; 2]>,
+    component_idxs: Option<[usize; 2]>
+}
@@ -0,0 +1,171 @@
+use crate::utils::utils_collisions::{collision_checker::*, collision_check_result_enum::CollisionCheckResult};
+use crate::utils::utils_vars::lynx_vars_generic::LynxVarsGeneric;
+use crate::utils::utils_vars::lynx_vars_user::*;
+use nalgebra::DVector;
+use std::collections::{HashMap, BTreeMap};
+use std::sync::{Arc, Mutex};
+
+/* memoizes the results of another collision checker.  States are quantized to a grid with the given resolution, so any
+two states in the same grid cell share a result, and results are keyed on the wrapped checker's environment version so they
+are not reused after the environment changes.  Clones share one cache (and its statistics), so a single CachedCollisionChecker
+can be handed to planners that run on LynxVarsParallel.  Errors are never cached. */
+#[derive(Clone)]
+pub struct CachedCollisionChecker {
+    _collision_checker: CollisionCheckerBox,
+    _quantization_resolution: f64,
+    _cache: Arc<Mutex<CollisionCheckCache>>
+}
+impl CachedCollisionChecker {
+    pub fn new(collision_checker: CollisionCheckerBox, quantization_resolution: f64, capacity: usize) -> Result<Self, String> {
+        if !(quantization_resolution > 0.0) {
+            return Err(format!("quantization resolution must be positive in CachedCollisionChecker (got {:?})", quantization_resolution));
+        }
+        if capacity == 0 { return Err("capacity of CachedCollisionChecker must be at least 1".to_string()); }
+        return Ok(Self { _collision_checker: collision_checker, _quantization_resolution: quantization_resolution, _cache: Arc::new(Mutex::new(CollisionCheckCache::new(capacity))) });
+    }
+
+    pub fn get_cache_statistics(&self) -> CollisionCheckCacheStatistics {
+        return self._cache.lock().unwrap().get_statistics();
+    }
+
+    pub fn clear_cache(&self) {
+        self._cache.lock().unwrap().clear();
+    }
+
+    pub fn get_collision_checker_ref(&self) -> &CollisionCheckerBox {
+        return &self._collision_checker;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    fn _get_key(&self, states: Vec<&DVector<f64>>, is_segment: bool, lynx_vars: &LynxVarsGeneric) -> Result<CollisionCheckCacheKey, String> {
+        let mut quantized_states = Vec::new();
+        for s in states {
+            for x in s.iter() { quantized_states.push((x / self._quantization_resolution).round() as i64); }
+        }
+        let environment_version = self._collision_checker.get_collision_environment_version(lynx_vars)?;
+        return Ok(CollisionCheckCacheKey { _quantized_states: quantized_states, _is_segment: is_segment, _environment_version: environment_version });
+    }
+
+    fn _get_or_compute<F>(&self, key: CollisionCheckCacheKey, f: F) -> Result<CollisionCheckResult, String>
+        where F: FnOnce() -> Result<CollisionCheckResult, String> {
+        // the lock is not held while the wrapped checker runs, so threads only wait on each other for lookups
+        let cached = self._cache.lock().unwrap().get(&key);
+        if cached.is_some() { return Ok(cached.unwrap()); }
+
+        let res = f()?;
+        match &res {
+            CollisionCheckResult::Error(_) => {}
+            _ => { self._cache.lock().unwrap().insert(key, res.clone()); }
+        }
+        return Ok(res);
+    }
+}
+impl CollisionChecker for CachedCollisionChecker {
+    fn in_collision(&self, state: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
+        let key = self._get_key(vec![state], false, lynx_vars)?;
+        return self._get_or_compute(key, || self._collision_checker.in_collision(state, lynx_vars));
+    }
+    fn segment_in_collision(&self, q_a: &DVector<f64>, q_b: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
+        let key = self._get_key(vec![q_a, q_b], true, lynx_vars)?;
+        return self._get_or_compute(key, || self._collision_checker.segment_in_collision(q_a, q_b, lynx_vars));
+    }
+    fn get_collision_environment_name(&self, lynx_vars: &LynxVarsGeneric) -> Result<String, String> {
+        return self._collision_checker.get_collision_environment_name(lynx_vars);
+    }
+    fn get_collision_environment_version(&self, lynx_vars: &LynxVarsGeneric) -> Result<u64, String> {
+        return self._collision_checker.get_collision_environment_version(lynx_vars);
+    }
+}
+impl LynxVarsUser for CachedCollisionChecker {
+    fn get_lynx_vars_types(&self) -> Vec<(&'static str, &'static str)> {
+        return self._collision_checker.get_lynx_vars_types();
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollisionCheckCacheStatistics {
+    pub num_hits: usize,
+    pub num_misses: usize,
+    pub num_evictions: usize,
+    pub num_entries: usize
+}
+impl CollisionCheckCacheStatistics {
+    pub fn get_hit_ratio(&self) -> f64 {
+        let total = self.num_hits + self.num_misses;
+        if total == 0 { return 0.0; }
+        return self.num_hits as f64 / total as f64;
+    }
+
+    pub fn print_summary(&self) {
+        println!("collision check cache: {:?} hits, {:?} misses (hit ratio {:?}), {:?} evictions, {:?} entries", self.num_hits, self.num_misses, self.get_hit_ratio(), self.num_evictions, self.num_entries);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct CollisionCheckCacheKey {
+    _quantized_states: Vec<i64>,
+    _is_segment: bool,
+    _environment_version: u64
+}
+
+/* bounded least recently used map.  Each entry stores the tick of its last use, and _ticks_to_keys orders entries by
+that tick so the least recently used entry can be found and evicted in log time */
+#[derive(Clone, Debug)]
+struct CollisionCheckCache {
+    _entries: HashMap<CollisionCheckCacheKey, (CollisionCheckResult, u64)>,
+    _ticks_to_keys: BTreeMap<u64, CollisionCheckCacheKey>,
+    _tick: u64,
+    _capacity: usize,
+    _num_hits: usize,
+    _num_misses: usize,
+    _num_evictions: usize
+}
+impl CollisionCheckCache {
+    pub fn new(capacity: usize) -> Self {
+        return Self { _entries: HashMap::new(), _ticks_to_keys: BTreeMap::new(), _tick: 0, _capacity: capacity, _num_hits: 0, _num_misses: 0, _num_evictions: 0 };
+    }
+
+    pub fn get(&mut self, key: &CollisionCheckCacheKey) -> Option<CollisionCheckResult> {
+        self._tick += 1;
+        let tick = self._tick;
+        let entry = self._entries.get_mut(key);
+        if entry.is_none() {
+            self._num_misses += 1;
+            return None;
+        }
+
+        let entry = entry.unwrap();
+        self._ticks_to_keys.remove(&entry.1);
+        self._ticks_to_keys.insert(tick, key.clone());
+        entry.1 = tick;
+        self._num_hits += 1;
+        return Some(entry.0.clone());
+    }
+
+    pub fn insert(&mut self, key: CollisionCheckCacheKey, result: CollisionCheckResult) {
+        self._tick += 1;
+        let tick = self._tick;
+        let old_entry = self._entries.insert(key.clone(), (result, tick));
+        if old_entry.is_some() { self._ticks_to_keys.remove(&old_entry.unwrap().1); }
+        self._ticks_to_keys.insert(tick, key);
+
+        while self._entries.len() > self._capacity {
+            let oldest_tick = *self._ticks_to_keys.keys().next().unwrap();
+            let oldest_key = self._ticks_to_keys.remove(&oldest_tick).unwrap();
+            self._entries.remove(&oldest_key);
+            self._num_evictions += 1;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self._entries.clear();
+        self._ticks_to_keys.clear();
+    }
+
+    pub fn get_statistics(&self) -> CollisionCheckCacheStatistics {
+        return CollisionCheckCacheStatistics { num_hits: self._num_hits, num_misses: self._num_misses, num_evictions: self._num_evictions, num_entries: self._entries.len() };
+    }
+}
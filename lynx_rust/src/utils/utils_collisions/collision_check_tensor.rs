@@ -3,7 +3,7 @@ use crate::utils::utils_files_and_strings::{file_utils::*, robot_folder_utils::*
 use serde::{Serialize, Deserialize};
 
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct BoolCollisionCheckTensor {
     _tensor: Vec<Vec<Vec<Vec<bool>>>>,
     _dim1: usize,
@@ -117,6 +117,11 @@ impl BoolCollisionCheckTensor {
         return Ok(out_self);
     }
 
+    // false if the collision objects have changed shape since this tensor was made, e.g., a link was decomposed again
+    pub fn matches_collision_objects(&self, collision_objects_group_1: &Vec<Vec<CollisionObject>>, collision_objects_group_2: &Vec<Vec<CollisionObject>>) -> bool {
+        return _get_dims_for_collision_objects(collision_objects_group_1, collision_objects_group_2) == [self._dim1, self._dim2, self._dim3, self._dim4];
+    }
+
     pub fn combine(&self, other: &BoolCollisionCheckTensor) -> Result<Self, String> {
         if !(self._dim1 == other._dim1) { return Err(format!("SkipCollisionCheck combine failed because _dim1 did not match ({:?} and {:?})", self._dim1, other._dim1)); }
         if !(self._dim2 == other._dim2) { return Err(format!("SkipCollisionCheck combine failed because _dim2 did not match ({:?} and {:?})", self._dim2, other._dim2)); }
@@ -346,6 +351,34 @@ impl FloatCollisionCheckTensor {
         return Ok(out_self);
     }
 
+    // sums in the values and number of collision checks from another tensor of the same dimensions
+    pub fn matches_collision_objects(&self, collision_objects_group_1: &Vec<Vec<CollisionObject>>, collision_objects_group_2: &Vec<Vec<CollisionObject>>) -> bool {
+        return _get_dims_for_collision_objects(collision_objects_group_1, collision_objects_group_2) == [self._dim1, self._dim2, self._dim3, self._dim4];
+    }
+
+    pub fn add_tensor(&mut self, other: &FloatCollisionCheckTensor) -> Result<(), String> {
+        if !(self._dim1 == other._dim1 && self._dim2 == other._dim2 && self._dim3 == other._dim3 && self._dim4 == other._dim4) {
+            return Err(format!("FloatCollisionCheckTensor add_tensor failed because dimensions did not match ({:?} and {:?})", [self._dim1, self._dim2, self._dim3, self._dim4], [other._dim1, other._dim2, other._dim3, other._dim4]));
+        }
+
+        for i in 0..self._dim1 {
+            for j in 0..self._dim2 {
+                for k in 0..self._dim3 {
+                    for l in 0..self._dim4 {
+                        self._tensor[i][j][k][l] += other._tensor[i][j][k][l];
+                    }
+                }
+            }
+        }
+        self._total_num_collision_checks += other._total_num_collision_checks;
+
+        return Ok(());
+    }
+
+    pub fn get_total_num_collision_checks(&self) -> f64 {
+        return self._total_num_collision_checks;
+    }
+
     pub fn increment_total_num_collision_checks(&mut self) {
         self._total_num_collision_checks += 1.0;
     }
@@ -441,8 +474,14 @@ impl FloatCollisionCheckTensor {
 }
 
 
+fn _get_dims_for_collision_objects(collision_objects_group_1: &Vec<Vec<CollisionObject>>, collision_objects_group_2: &Vec<Vec<CollisionObject>>) -> [usize; 4] {
+    let dim2 = collision_objects_group_1.iter().map(|x| x.len()).max().unwrap_or(0);
+    let dim4 = collision_objects_group_2.iter().map(|x| x.len()).max().unwrap_or(0);
+    return [collision_objects_group_1.len(), dim2, collision_objects_group_2.len(), dim4];
+}
+
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub enum SkipCheckForSelfCollisionMode {
     SameObjectOnly,
     SameObjectOrSameVector,
@@ -0,0 +1,193 @@
+use crate::utils::utils_collisions::collision_object::CollisionObject;
+use crate::utils::utils_collisions::collision_check_tensor::*;
+use crate::utils::utils_files_and_strings::robot_folder_utils::*;
+use serde::{Serialize, Deserialize};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use termion::{color, style};
+
+/* samples are split into fixed size chunks, and each chunk draws from its own rng seeded from the master seed.  Chunk
+results are combined in chunk order, so the generated tensors only depend on the seed and number of samples, not on the
+number of threads. */
+const SAMPLE_CHUNK_SIZE: usize = 250;
+const Z_95: f64 = 1.96;
+
+#[derive(Clone, Debug)]
+pub struct CollisionCheckTensorGenerationSettings {
+    pub num_skip_tensor_samples: usize,
+    pub num_average_distance_samples: usize,
+    pub seed: u64,
+    pub num_threads: Option<usize> // None uses all cpus
+}
+
+impl CollisionCheckTensorGenerationSettings {
+    pub fn new(num_skip_tensor_samples: usize, num_average_distance_samples: usize, seed: u64, num_threads: Option<usize>) -> Self {
+        return Self { num_skip_tensor_samples, num_average_distance_samples, seed, num_threads };
+    }
+
+    pub fn new_default() -> Self {
+        return Self::new(100_000, 1000, 0, None);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// runs f once per chunk of samples, in parallel.  f gets per-thread state (created by init), the chunk's rng, and the
+// number of samples in the chunk.  Outputs are returned in chunk order.
+pub fn run_seeded_sample_chunks_in_parallel<S, T, I, F>(num_samples: usize, seed: u64, num_threads: Option<usize>, label: &str, init: I, f: F) -> Result<Vec<T>, String>
+    where I: Fn() -> S + Sync + Send,
+          F: Fn(&mut S, &mut StdRng, usize) -> Result<T, String> + Sync + Send,
+          T: Send {
+    let mut num_threads_ = num_cpus::get(); if num_threads.is_some() { num_threads_ = num_threads.unwrap(); }
+    if num_threads_ == 0 { return Err("num threads of 0 is invalid when generating collision check tensors".to_string()); }
+
+    let num_chunks = (num_samples + SAMPLE_CHUNK_SIZE - 1) / SAMPLE_CHUNK_SIZE;
+    let mut master_rng = StdRng::seed_from_u64(seed);
+    let chunk_seeds: Vec<u64> = (0..num_chunks).map(|_| master_rng.gen::<u64>()).collect();
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(num_threads_).build();
+    if pool.is_err() { return Err(format!("could not build thread pool for collision check tensor generation: {:?}", pool.err().unwrap().to_string())); }
+    let pool = pool.ok().unwrap();
+
+    let num_chunks_completed = AtomicUsize::new(0);
+    let results: Vec<Result<T, String>> = pool.install(|| {
+        (0..num_chunks).into_par_iter().map_init(|| init(), |state, c| {
+            let num_samples_in_chunk = SAMPLE_CHUNK_SIZE.min(num_samples - c * SAMPLE_CHUNK_SIZE);
+            let mut rng = StdRng::seed_from_u64(chunk_seeds[c]);
+            let out = f(state, &mut rng, num_samples_in_chunk);
+
+            let completed = num_chunks_completed.fetch_add(1, Ordering::SeqCst) + 1;
+            if completed % 20 == 0 || completed == num_chunks {
+                println!("{}{}Calculating {}: {} of {} samples {}", color::Fg(color::Blue), style::Bold, label, (completed * SAMPLE_CHUNK_SIZE).min(num_samples), num_samples, style::Reset);
+            }
+
+            out
+        }).collect()
+    });
+
+    let mut out_vec = Vec::new();
+    for r in results { out_vec.push(r?); }
+    return Ok(out_vec);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LinkPairSampleStatistics {
+    pub names: [String; 2],
+    pub idxs: [[usize; 2]; 2],
+    pub mean: f64, // collision ratio for skip tensors, mean distance for average distance tensors
+    pub confidence_interval_95: [f64; 2],
+    pub skip_collision_check: Option<bool>
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CollisionCheckTensorGenerationReport {
+    pub tensor_name: String,
+    pub seed: u64,
+    pub num_samples: usize,
+    pub link_pairs: Vec<LinkPairSampleStatistics>
+}
+
+impl CollisionCheckTensorGenerationReport {
+    // one entry per unordered pair of active collision objects that was actually sampled (i.e., not skipped by initial_skip_tensor)
+    pub fn new_for_skip_tensor(tensor_name: String, seed: u64, count_tensor: &FloatCollisionCheckTensor, initial_skip_tensor: &BoolCollisionCheckTensor, skip_tensor: &BoolCollisionCheckTensor, collision_objects: &Vec<Vec<CollisionObject>>) -> Result<Self, String> {
+        let n = count_tensor.get_total_num_collision_checks();
+        let mut link_pairs = Vec::new();
+
+        for idxs in Self::_get_sampled_pair_idxs(initial_skip_tensor, collision_objects)? {
+            let ratio = count_tensor.get_mean_at_given_idxs(idxs[0], idxs[1])?;
+            link_pairs.push(LinkPairSampleStatistics {
+                names: Self::_get_names(&idxs, collision_objects),
+                idxs,
+                mean: ratio,
+                confidence_interval_95: _wilson_score_interval(ratio, n),
+                skip_collision_check: Some(skip_tensor.get_is_skip(idxs[0], idxs[1])?)
+            });
+        }
+
+        return Ok(Self { tensor_name, seed, num_samples: n as usize, link_pairs });
+    }
+
+    // squared_distance_tensor holds the sums of squared distances, used for the standard error of the mean distance
+    pub fn new_for_average_distance_tensor(tensor_name: String, seed: u64, distance_tensor: &FloatCollisionCheckTensor, squared_distance_tensor: &FloatCollisionCheckTensor, initial_skip_tensor: &BoolCollisionCheckTensor, collision_objects: &Vec<Vec<CollisionObject>>) -> Result<Self, String> {
+        let n = distance_tensor.get_total_num_collision_checks();
+        let mut link_pairs = Vec::new();
+
+        for idxs in Self::_get_sampled_pair_idxs(initial_skip_tensor, collision_objects)? {
+            let mean = distance_tensor.get_mean_at_given_idxs(idxs[0], idxs[1])?;
+            let mean_of_squares = squared_distance_tensor.get_mean_at_given_idxs(idxs[0], idxs[1])?;
+            let mut half_width = 0.0;
+            if n > 1.0 {
+                let variance = ((mean_of_squares - mean * mean) * n / (n - 1.0)).max(0.0);
+                half_width = Z_95 * (variance / n).sqrt();
+            }
+            link_pairs.push(LinkPairSampleStatistics {
+                names: Self::_get_names(&idxs, collision_objects),
+                idxs,
+                mean,
+                confidence_interval_95: [mean - half_width, mean + half_width],
+                skip_collision_check: None
+            });
+        }
+
+        return Ok(Self { tensor_name, seed, num_samples: n as usize, link_pairs });
+    }
+
+    pub fn load_from_file_relative_to_robot_directory(robot_name: String, partial_fp_to_dir: String, file_name: String) -> Result<Self, String> {
+        let json_string = read_file_contents_relative_to_robot_directory(robot_name, partial_fp_to_dir.clone() + "/" + file_name.as_str());
+        if json_string.is_none() {
+            return Err(format!("no file {:?} found when trying to load a CollisionCheckTensorGenerationReport", partial_fp_to_dir.clone() + "/" + file_name.as_str()));
+        }
+
+        let out_self = serde_json::from_str(&json_string.unwrap());
+        if out_self.is_err() {
+            return Err(format!("file {:?} could not be parsed as a CollisionCheckTensorGenerationReport", partial_fp_to_dir + "/" + file_name.as_str()));
+        }
+        return Ok(out_self.unwrap());
+    }
+
+    pub fn save_to_file_relative_to_robot_directory(&self, robot_name: String, partial_fp_to_dir: String, file_name: String) {
+        let serialized = serde_json::to_string_pretty(&self).unwrap();
+        write_string_to_file_relative_to_robot_directory(robot_name, partial_fp_to_dir, file_name, serialized, true);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    fn _get_sampled_pair_idxs(initial_skip_tensor: &BoolCollisionCheckTensor, collision_objects: &Vec<Vec<CollisionObject>>) -> Result<Vec<[[usize; 2]; 2]>, String> {
+        let mut out_vec = Vec::new();
+
+        let l1 = collision_objects.len();
+        for i in 0..l1 {
+            let l2 = collision_objects[i].len();
+            for j in 0..l2 {
+                for k in i..l1 {
+                    let l4 = collision_objects[k].len();
+                    for l in 0..l4 {
+                        if k == i && l <= j { continue; }
+                        if !collision_objects[i][j].active || !collision_objects[k][l].active { continue; }
+                        if initial_skip_tensor.get_is_skip([i, j], [k, l])? { continue; }
+                        out_vec.push([[i, j], [k, l]]);
+                    }
+                }
+            }
+        }
+
+        return Ok(out_vec);
+    }
+
+    fn _get_names(idxs: &[[usize; 2]; 2], collision_objects: &Vec<Vec<CollisionObject>>) -> [String; 2] {
+        return [collision_objects[idxs[0][0]][idxs[0][1]].name.clone(), collision_objects[idxs[1][0]][idxs[1][1]].name.clone()];
+    }
+}
+
+fn _wilson_score_interval(ratio: f64, n: f64) -> [f64; 2] {
+    if n <= 0.0 { return [0.0, 1.0]; }
+    let z2 = Z_95 * Z_95;
+    let denom = 1.0 + z2 / n;
+    let center = (ratio + z2 / (2.0 * n)) / denom;
+    let half_width = Z_95 * (ratio * (1.0 - ratio) / n + z2 / (4.0 * n * n)).sqrt() / denom;
+    return [(center - half_width).max(0.0), (center + half_width).min(1.0)];
+}
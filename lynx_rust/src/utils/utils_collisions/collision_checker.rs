@@ -2,16 +2,39 @@ use crate::robot_modules::{robot_core_collision_module::RobotCoreCollisionModule
 use crate::robot_modules::robot_core_collision_module::LinkGeometryType;
 use crate::robot_modules::robot::Robot;
 use crate::robot_modules::robot_world::RobotWorld;
-use crate::utils::utils_collisions::{collision_check_result_enum::*, collision_multiple_results::*};
+use crate::robot_modules::robot_set::RobotSet;
+use crate::utils::utils_collisions::{collision_check_result_enum::*, collision_multiple_results::*, signed_distance_field::*};
 use crate::utils::utils_math::nalgebra_utils::vec_to_dvec;
 use crate::utils::utils_vars::lynx_vars_generic::LynxVarsGeneric;
 use crate::utils::utils_vars::lynx_vars_user::*;
 use crate::utils::utils_image_environments::image_environment::ImageEnvironment;
+use crate::utils::utils_paths::timed_path::TimedPath;
 use nalgebra::{DVector};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+const SEGMENT_SAMPLING_RESOLUTION: f64 = 0.01;
+const SEGMENT_CONSERVATIVE_ADVANCEMENT_TOLERANCE: f64 = 0.001;
 
 pub trait CollisionChecker: Send + Sync + LynxVarsUser + AsLynxVarsUser + CollisionCheckerClone {
     fn in_collision(&self, state: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String>;
+    fn segment_in_collision(&self, q_a: &DVector<f64>, q_b: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
+        // checkers without a continuous check fall back on dense sampling along the segment.
+        let num_steps = ((q_b - q_a).norm() / SEGMENT_SAMPLING_RESOLUTION).ceil() as usize;
+        for i in 0..num_steps + 1 {
+            let t = if num_steps == 0 { 1.0 } else { i as f64 / num_steps as f64 };
+            let res = self.in_collision(&(q_a + t * (q_b - q_a)), lynx_vars)?;
+            match res {
+                CollisionCheckResult::NotInCollision => {}
+                _ => { return Ok(res); }
+            }
+        }
+        return Ok(CollisionCheckResult::NotInCollision);
+    }
     fn get_collision_environment_name(&self, lynx_vars: &LynxVarsGeneric) -> Result<String, String> { Ok("".to_string()) }
+    // changes whenever results of in_collision or segment_in_collision may have changed for the same states (used to
+    // invalidate CachedCollisionChecker results).  Checkers against a fixed environment can keep the default.
+    fn get_collision_environment_version(&self, lynx_vars: &LynxVarsGeneric) -> Result<u64, String> { Ok(0) }
     fn to_collision_checker_box(&self) -> CollisionCheckerBox {
         return CollisionCheckerBox(self.clone_box());
     }
@@ -36,9 +59,21 @@ impl CollisionCheckerBox {
         return self.0.in_collision(state, lynx_vars);
     }
 
+    pub fn segment_in_collision(&self, q_a: &DVector<f64>, q_b: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
+        return self.0.segment_in_collision(q_a, q_b, lynx_vars);
+    }
+
     pub fn get_collision_environment_name(&self, lynx_vars: &LynxVarsGeneric) -> Result<String, String> {
         return self.0.get_collision_environment_name(lynx_vars);
     }
+
+    pub fn get_collision_environment_version(&self, lynx_vars: &LynxVarsGeneric) -> Result<u64, String> {
+        return self.0.get_collision_environment_version(lynx_vars);
+    }
+
+    pub fn get_lynx_vars_types(&self) -> Vec<(&'static str, &'static str)> {
+        return self.0.get_lynx_vars_types();
+    }
 }
 impl Clone for CollisionCheckerBox {
     fn clone(&self) -> Self {
@@ -63,6 +98,7 @@ unsafe impl Sync for CollisionCheckerBox { }
 pub struct NullCollisionChecker;
 impl CollisionChecker for NullCollisionChecker {
     fn in_collision(&self, state: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) ->  Result<CollisionCheckResult, String> { return Ok(CollisionCheckResult::NotInCollision) }
+    fn segment_in_collision(&self, q_a: &DVector<f64>, q_b: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> { return Ok(CollisionCheckResult::NotInCollision) }
 }
 impl LynxVarsUser for NullCollisionChecker { }
 
@@ -70,6 +106,7 @@ impl LynxVarsUser for NullCollisionChecker { }
 pub struct AlwaysCollision;
 impl CollisionChecker for AlwaysCollision {
     fn in_collision(&self, state: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String>{ return Ok(CollisionCheckResult::InCollision("always collision".to_string())); }
+    fn segment_in_collision(&self, q_a: &DVector<f64>, q_b: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> { return Ok(CollisionCheckResult::InCollision("always collision".to_string())); }
 }
 impl LynxVarsUser for AlwaysCollision { }
 
@@ -92,6 +129,13 @@ impl CollisionChecker for SphereCollisionChecker {
             return Ok(CollisionCheckResult::NotInCollision);
         }
     }
+    fn segment_in_collision(&self, q_a: &DVector<f64>, q_b: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
+        let dir = q_b - q_a;
+        let dir_norm_squared = dir.norm_squared();
+        let mut t = 0.0;
+        if dir_norm_squared > 0.0 { t = ((&self.center - q_a).dot(&dir) / dir_norm_squared).max(0.0).min(1.0); }
+        return self.in_collision(&(q_a + t * dir), lynx_vars);
+    }
 }
 impl LynxVarsUser for SphereCollisionChecker { }
 
@@ -101,26 +145,34 @@ impl CollisionChecker for RobotWorldCollisionChecker {
     fn in_collision(&self, state: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
         let mut robot_world = get_lynx_var_mut_ref_generic!(lynx_vars, RobotWorld, "robot_world")?;
 
-        let bounds_check = robot_world.get_robot_set_mut_ref().check_if_state_is_within_bounds(state)?;
+        let bounds_check = robot_world.get_robot_set_ref().check_if_state_is_within_bounds(state)?;
         match bounds_check {
             BoundsCheckResult::InBounds => {}
             BoundsCheckResult::OutOfBounds(s) => { return Ok( CollisionCheckResult::InCollision(s) ) }
             BoundsCheckResult::Error(s) => { return Err(s) }
         }
 
-        let fk_res = robot_world.get_robot_set_mut_ref().compute_fk(state)?;
+        let fk_res = robot_world.get_robot_set_ref().compute_fk(state)?;
 
-        let self_collision_check = robot_world.get_robot_set_mut_ref().self_intersect_check(&fk_res, LinkGeometryType::OBBs, true)?;
+        let self_collision_check = robot_world.self_intersect_check(&fk_res, LinkGeometryType::OBBs, true)?;
         if self_collision_check.in_collision() { return Ok(CollisionCheckResult::InCollision("self collision".to_string())) }
 
         let environment_collision_check = robot_world.environment_intersect_check(&fk_res, LinkGeometryType::OBBs, true)?;
         if environment_collision_check.in_collision() { return Ok(CollisionCheckResult::InCollision("environment collision".to_string())) }
 
-        let multi_robot_collision_check = robot_world.get_robot_set_mut_ref().multi_robot_intersect_check(&fk_res, LinkGeometryType::OBBs, true)?;
+        let multi_robot_collision_check = robot_world.multi_robot_intersect_check(&fk_res, LinkGeometryType::OBBs, true)?;
         if multi_robot_collision_check.in_collision() { return Ok(CollisionCheckResult::InCollision("multi robot collision".to_string())) }
 
         return Ok(CollisionCheckResult::NotInCollision);
     }
+    fn segment_in_collision(&self, q_a: &DVector<f64>, q_b: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
+        let mut robot_world = get_lynx_var_mut_ref_generic!(lynx_vars, RobotWorld, "robot_world")?;
+        return robot_world.segment_intersect_check(q_a, q_b, LinkGeometryType::OBBs, SEGMENT_CONSERVATIVE_ADVANCEMENT_TOLERANCE);
+    }
+    fn get_collision_environment_version(&self, lynx_vars: &LynxVarsGeneric) -> Result<u64, String> {
+        let robot_world = get_lynx_var_ref_generic!(lynx_vars, RobotWorld, "robot_world")?;
+        return Ok(robot_world.get_environment_version());
+    }
 }
 impl LynxVarsUser for RobotWorldCollisionChecker {
     fn get_lynx_vars_types(&self) -> Vec<(&'static str, &'static str)> {
@@ -128,6 +180,148 @@ impl LynxVarsUser for RobotWorldCollisionChecker {
     }
 }
 
+/* self and multi-robot collisions are checked with the given link geometry, but environment collisions are checked
+with sphere approximations of the links against the signed distance field, rather than the robot world's collision environment. */
+#[derive(Clone)]
+pub struct SignedDistanceFieldCollisionChecker {
+    _link_geometry_type: LinkGeometryType,
+    _link_sphere_approximations: Vec<LinkSphereApproximation>
+}
+impl SignedDistanceFieldCollisionChecker {
+    pub fn new(robot_set: &RobotSet, link_geometry_type: LinkGeometryType) -> Self {
+        let mut _link_sphere_approximations = Vec::new();
+        for r in robot_set.get_robots_ref() {
+            _link_sphere_approximations.push( LinkSphereApproximation::new(r.get_core_collision_module_ref(), &link_geometry_type) );
+        }
+        return Self { _link_geometry_type: link_geometry_type, _link_sphere_approximations };
+    }
+}
+impl CollisionChecker for SignedDistanceFieldCollisionChecker {
+    fn in_collision(&self, state: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
+        let mut robot_world = get_lynx_var_mut_ref_generic!(lynx_vars, RobotWorld, "robot_world")?;
+
+        let bounds_check = robot_world.get_robot_set_ref().check_if_state_is_within_bounds(state)?;
+        match bounds_check {
+            BoundsCheckResult::InBounds => {}
+            BoundsCheckResult::OutOfBounds(s) => { return Ok( CollisionCheckResult::InCollision(s) ) }
+            BoundsCheckResult::Error(s) => { return Err(s) }
+        }
+
+        let fk_res = robot_world.get_robot_set_ref().compute_fk(state)?;
+
+        let self_collision_check = robot_world.self_intersect_check(&fk_res, self._link_geometry_type.clone(), true)?;
+        if self_collision_check.in_collision() { return Ok(CollisionCheckResult::InCollision("self collision".to_string())) }
+
+        let multi_robot_collision_check = robot_world.multi_robot_intersect_check(&fk_res, self._link_geometry_type.clone(), true)?;
+        if multi_robot_collision_check.in_collision() { return Ok(CollisionCheckResult::InCollision("multi robot collision".to_string())) }
+
+        let link_paddings: Vec<Vec<f64>> = robot_world.get_robot_set_ref().get_robots_ref().iter().map(|r| r.get_core_collision_module_ref().get_link_paddings_ref().clone()).collect();
+        let signed_distance_field = get_lynx_var_ref_generic!(lynx_vars, SignedDistanceField, "signed_distance_field")?;
+
+        let fk_results = fk_res.get_robot_fk_results_ref();
+        let l = fk_results.len().min(self._link_sphere_approximations.len());
+        for i in 0..l {
+            let spheres = self._link_sphere_approximations[i].get_spheres_in_world_frame(&fk_results[i], &link_paddings[i]);
+            for (link_idx, center, radius) in spheres {
+                if signed_distance_field.distance(&center) < radius {
+                    return Ok(CollisionCheckResult::InCollision(format!("environment collision with signed distance field on link {:?} of robot {:?}", link_idx, i)));
+                }
+            }
+        }
+
+        return Ok(CollisionCheckResult::NotInCollision);
+    }
+    // changes whenever the robot world changes or the signed distance field is replaced
+    fn get_collision_environment_version(&self, lynx_vars: &LynxVarsGeneric) -> Result<u64, String> {
+        let robot_world_version = RobotWorldCollisionChecker.get_collision_environment_version(lynx_vars)?;
+        let signed_distance_field = get_lynx_var_ref_generic!(lynx_vars, SignedDistanceField, "signed_distance_field")?;
+
+        let mut hasher = DefaultHasher::new();
+        (robot_world_version, signed_distance_field.get_version()).hash(&mut hasher);
+        return Ok(hasher.finish());
+    }
+}
+impl LynxVarsUser for SignedDistanceFieldCollisionChecker {
+    fn get_lynx_vars_types(&self) -> Vec<(&'static str, &'static str)> {
+        return vec![ ("RobotWorld", "robot_world"), ("SignedDistanceField", "signed_distance_field") ];
+    }
+}
+
+/* checks robot states against a time-varying collision environment.  in_collision and segment_in_collision check against the
+environment at its current time (i.e., they behave like RobotWorldCollisionChecker), while the timed functions first move
+environment objects with trajectories to the given time.  Note that the timed functions leave the environment at the last checked time. */
+#[derive(Clone)]
+pub struct TimeVaryingRobotWorldCollisionChecker {
+    _time_resolution: f64
+}
+impl TimeVaryingRobotWorldCollisionChecker {
+    // time_resolution is the largest time step between samples on timed segments
+    pub fn new(time_resolution: f64) -> Result<Self, String> {
+        if !(time_resolution > 0.0) {
+            return Err(format!("time resolution must be positive (got {:?})", time_resolution));
+        }
+        return Ok(Self { _time_resolution: time_resolution });
+    }
+
+    pub fn in_collision_at_time(&self, state: &DVector<f64>, time: f64, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
+        let mut robot_world = get_lynx_var_mut_ref_generic!(lynx_vars, RobotWorld, "robot_world")?;
+        robot_world.set_environment_time(time)?;
+        return RobotWorldCollisionChecker.in_collision(state, lynx_vars);
+    }
+
+    pub fn timed_segment_in_collision(&self, q_a: &DVector<f64>, time_a: f64, q_b: &DVector<f64>, time_b: f64, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
+        if time_b < time_a {
+            return Err(format!("timed segment must not go backwards in time ({:?} to {:?})", time_a, time_b));
+        }
+
+        let num_state_steps = ((q_b - q_a).norm() / SEGMENT_SAMPLING_RESOLUTION).ceil() as usize;
+        let num_time_steps = ((time_b - time_a) / self._time_resolution).ceil() as usize;
+        let num_steps = num_state_steps.max(num_time_steps);
+        for i in 0..num_steps + 1 {
+            let u = if num_steps == 0 { 1.0 } else { i as f64 / num_steps as f64 };
+            let time = time_a + u * (time_b - time_a);
+            let res = self.in_collision_at_time(&(q_a + u * (q_b - q_a)), time, lynx_vars)?;
+            match res {
+                CollisionCheckResult::NotInCollision => {}
+                CollisionCheckResult::InCollision(s) => { return Ok(CollisionCheckResult::InCollision(format!("{} at time {:?}", s, time))); }
+                _ => { return Ok(res); }
+            }
+        }
+        return Ok(CollisionCheckResult::NotInCollision);
+    }
+
+    pub fn timed_path_in_collision(&self, timed_path: &TimedPath, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
+        let l = timed_path.get_num_waypoints();
+        if l == 0 { return Ok(CollisionCheckResult::NotInCollision); }
+        if l == 1 { return self.in_collision_at_time(&timed_path.waypoints[0], timed_path.times[0], lynx_vars); }
+
+        for i in 0..l-1 {
+            let res = self.timed_segment_in_collision(&timed_path.waypoints[i], timed_path.times[i], &timed_path.waypoints[i+1], timed_path.times[i+1], lynx_vars)?;
+            match res {
+                CollisionCheckResult::NotInCollision => {}
+                _ => { return Ok(res); }
+            }
+        }
+        return Ok(CollisionCheckResult::NotInCollision);
+    }
+}
+impl CollisionChecker for TimeVaryingRobotWorldCollisionChecker {
+    fn in_collision(&self, state: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
+        return RobotWorldCollisionChecker.in_collision(state, lynx_vars);
+    }
+    fn segment_in_collision(&self, q_a: &DVector<f64>, q_b: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<CollisionCheckResult, String> {
+        return RobotWorldCollisionChecker.segment_in_collision(q_a, q_b, lynx_vars);
+    }
+    fn get_collision_environment_version(&self, lynx_vars: &LynxVarsGeneric) -> Result<u64, String> {
+        return RobotWorldCollisionChecker.get_collision_environment_version(lynx_vars);
+    }
+}
+impl LynxVarsUser for TimeVaryingRobotWorldCollisionChecker {
+    fn get_lynx_vars_types(&self) -> Vec<(&'static str, &'static str)> {
+        return vec![ ("RobotWorld", "robot_world") ];
+    }
+}
+
 #[derive(Clone)]
 pub struct ImageEnvironmentCollisionChecker {
     _image_environment: ImageEnvironment
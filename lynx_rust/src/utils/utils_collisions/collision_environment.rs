@@ -1,5 +1,7 @@
-use crate::utils::utils_collisions::{oriented_bounding_box::OBB, collision_object::CollisionObject, triangle_mesh_engine::TriMeshEngine};
+use crate::utils::utils_collisions::{oriented_bounding_box::OBB, collision_object::CollisionObject, triangle_mesh_engine::TriMeshEngine, collision_broad_phase::EnvironmentBroadPhase, object_pose_trajectory::ObjectPoseTrajectory};
 use crate::utils::utils_files_and_strings::{file_utils::*, string_utils::*};
+use crate::utils::utils_parsing::yaml_parsing_utils::get_yaml_obj;
+use crate::utils::utils_parsing::environment_scene_parsing_utils::{read_environment_scene_file, write_environment_scene_file, environment_scene_to_collision_environment, collision_environment_to_environment_scene};
 use crate::utils::utils_se3::prelude::ImplicitDualQuaternion;
 use termion::{color, style};
 use ncollide3d::math::Point;
@@ -13,7 +15,11 @@ pub struct CollisionEnvironment {
     pub object_names: Vec<String>,
     pub trimesh_engines: Vec<Vec<TriMeshEngine>>,
     pub original_file_directories: Vec<String>,
-    pub transforms: Vec<ImplicitDualQuaternion>
+    pub transforms: Vec<ImplicitDualQuaternion>,
+    pub object_paddings: Vec<f64>, // safety margin around each object, applied to all of its components
+    pub object_trajectories: Vec<Option<ObjectPoseTrajectory>>, // objects with a trajectory are moved by set_time
+    pub curr_time: f64,
+    pub broad_phase: EnvironmentBroadPhase // kept in sync with environment_obbs by all functions below that modify objects
 }
 
 impl CollisionEnvironment {
@@ -30,10 +36,18 @@ impl CollisionEnvironment {
             object_names,
             trimesh_engines,
             original_file_directories,
-            transforms
+            transforms,
+            object_paddings: Vec::new(),
+            object_trajectories: Vec::new(),
+            curr_time: 0.0,
+            broad_phase: EnvironmentBroadPhase::new(&Vec::new())
         };
         out_self._load_environment_obbs_with_environment_name(&environment_name.to_string())?;
         out_self._set_bounding_volumes();
+        out_self.object_paddings = vec![0.0; out_self.environment_obbs.len()];
+        out_self._load_object_paddings_from_yaml(&environment_name.to_string())?;
+        out_self.rebuild_broad_phase();
+        out_self.set_time(0.0)?;
 
         return Ok(out_self);
     }
@@ -51,10 +65,17 @@ impl CollisionEnvironment {
             object_names,
             trimesh_engines,
             original_file_directories,
-            transforms
+            transforms,
+            object_paddings: Vec::new(),
+            object_trajectories: Vec::new(),
+            curr_time: 0.0,
+            broad_phase: EnvironmentBroadPhase::new(&Vec::new())
         };
 
         out_self._load_environment_from_json_string(json_string);
+        out_self.object_paddings = vec![0.0; out_self.environment_obbs.len()];
+        out_self.rebuild_broad_phase();
+        out_self.set_time(0.0)?;
 
         return Ok(out_self);
     }
@@ -72,14 +93,28 @@ impl CollisionEnvironment {
             object_names,
             trimesh_engines,
             original_file_directories,
-            transforms
+            transforms,
+            object_paddings: Vec::new(),
+            object_trajectories: Vec::new(),
+            curr_time: 0.0,
+            broad_phase: EnvironmentBroadPhase::new(&Vec::new())
         };
 
         out_self._load_environment_from_metadata_fp(metadata_fp);
+        out_self.object_paddings = vec![0.0; out_self.environment_obbs.len()];
+        out_self.rebuild_broad_phase();
+        out_self.set_time(0.0)?;
 
         return Ok(out_self);
     }
 
+    // see environment_scene_parsing_utils for the scene format.  Relative mesh paths are resolved against the scene file's directory
+    pub fn new_from_scene_file(fp: String) -> Result<Self, String> {
+        let scene = read_environment_scene_file(fp.clone())?;
+        let mesh_directory = std::path::Path::new(&fp).parent().map(|p| p.to_str().unwrap().to_string());
+        return environment_scene_to_collision_environment(&scene, mesh_directory);
+    }
+
     pub fn spawn(&self) -> Self {
         let mut environment_obbs = Vec::new();
         let l1 = self.environment_obbs.len();
@@ -107,7 +142,11 @@ impl CollisionEnvironment {
             environment_obbs, object_names,
             trimesh_engines: _trimesh_engines,
             original_file_directories: self.original_file_directories.clone(),
-            transforms: self.transforms.clone() };
+            transforms: self.transforms.clone(),
+            object_paddings: self.object_paddings.clone(),
+            object_trajectories: self.object_trajectories.clone(),
+            curr_time: self.curr_time,
+            broad_phase: self.broad_phase.clone() };
     }
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
@@ -124,7 +163,7 @@ impl CollisionEnvironment {
             }
         }
 
-        let serialized = serde_json::to_string( &(vertices, indices, self.object_names.clone(), self.original_file_directories.clone(), self.transforms.clone()) ).ok().unwrap();
+        let serialized = serde_json::to_string( &(vertices, indices, self.object_names.clone(), self.original_file_directories.clone(), self.transforms.clone(), self.object_trajectories.clone()) ).ok().unwrap();
         return serialized;
     }
 
@@ -140,7 +179,7 @@ impl CollisionEnvironment {
             }
         }
 
-        let serialized = serde_json::to_string( &(vertices, indices, self.object_names.clone(), self.original_file_directories.clone(), self.transforms.clone()) ).ok().unwrap();
+        let serialized = serde_json::to_string( &(vertices, indices, self.object_names.clone(), self.original_file_directories.clone(), self.transforms.clone(), self.object_trajectories.clone()) ).ok().unwrap();
         write_string_to_file(fp, "environment_metadata.json".to_string(), serialized, true );
     }
 
@@ -149,16 +188,32 @@ impl CollisionEnvironment {
         self.save_environment_obbs_metadata_with_fp(fp);
     }
 
+    pub fn save_to_scene_file(&self, fp: String) -> Result<(), String> {
+        let scene = collision_environment_to_environment_scene(self)?;
+        return write_environment_scene_file(fp, &scene);
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
+    /* metadata is a json tuple of (vertices, indices, object names, original file directories, transforms, object trajectories).
+    The object trajectories entry was added later, so metadata without it is still accepted */
     fn _load_environment_from_json_string(&mut self, json_string: String) {
-        let metadata: (Vec<Vec<Vec<Point<f64>>>>,Vec<Vec<Vec<Point<usize>>>>, Vec<String>, Vec<String>, Vec<ImplicitDualQuaternion>) = serde_json::from_str(&json_string).unwrap();
+        let metadata_with_trajectories: Result<(Vec<Vec<Vec<Point<f64>>>>,Vec<Vec<Vec<Point<usize>>>>, Vec<String>, Vec<String>, Vec<ImplicitDualQuaternion>, Vec<Option<ObjectPoseTrajectory>>), _> = serde_json::from_str(&json_string);
+        let metadata = match metadata_with_trajectories {
+            Ok(m) => m,
+            Err(_) => {
+                let m: (Vec<Vec<Vec<Point<f64>>>>,Vec<Vec<Vec<Point<usize>>>>, Vec<String>, Vec<String>, Vec<ImplicitDualQuaternion>) = serde_json::from_str(&json_string).unwrap();
+                let num_objects = m.2.len();
+                (m.0, m.1, m.2, m.3, m.4, vec![None; num_objects])
+            }
+        };
 
-        let (vertices, indices, object_names, original_file_directories, transforms) = &metadata;
+        let (vertices, indices, object_names, original_file_directories, transforms, object_trajectories) = &metadata;
 
         self.object_names = object_names.clone();
         self.original_file_directories = original_file_directories.clone();
         self.transforms = transforms.clone();
+        self.object_trajectories = object_trajectories.clone();
 
         let l = vertices.len();
         for i in 0..l {
@@ -202,7 +257,7 @@ impl CollisionEnvironment {
         let l = mesh_filenames.len();
         for i in 0..l {
             let ext = get_filename_extension(mesh_filenames[i].clone());
-            if !(mesh_filenames[i].clone() == "environment_metadata.json".to_string()) && !(ext == "glb") {
+            if !(mesh_filenames[i].clone() == "environment_metadata.json".to_string()) && !(ext == "glb") && !(ext == "yaml") {
                 self.object_names.push( get_filename_without_extension(mesh_filenames[i].clone()) );
             }
         }
@@ -223,13 +278,16 @@ impl CollisionEnvironment {
             }
         }
 
+        // trajectories only live in the metadata file, so carry them over by object name when the metadata is regenerated
+        let previous_object_trajectories = if metadata_exists { self._read_object_trajectories_from_metadata_fp(metadata_fp.clone()) } else { Vec::new() };
+
         let mut count = 0 as usize;
         if !loaded_from_metadata {
             let l = mesh_filenames.len();
             for i in 0..l {
                 if mesh_filenames[i] == "environment_metadata.json".to_string() { continue; }
                 let ext = get_filename_extension(mesh_filenames[i].clone());
-                if ext == "glb" {
+                if ext == "glb" || ext == "yaml" {
                     continue
                 };
 
@@ -248,6 +306,8 @@ impl CollisionEnvironment {
 
                 self.original_file_directories.push(environment_name.to_string());
                 self.transforms.push(ImplicitDualQuaternion::new_identity());
+                let previous_object_trajectory = previous_object_trajectories.iter().find(|(n, _)| n == &self.object_names[count]);
+                self.object_trajectories.push( previous_object_trajectory.map(|(_, t)| t.clone()).unwrap_or(None) );
 
                 count += 1;
             }
@@ -257,6 +317,15 @@ impl CollisionEnvironment {
         Ok(())
     }
 
+    fn _read_object_trajectories_from_metadata_fp(&self, metadata_fp: String) -> Vec<(String, Option<ObjectPoseTrajectory>)> {
+        let json_string = read_file_contents(metadata_fp);
+        if json_string.is_none() { return Vec::new(); }
+
+        let mut previous = Self::new_empty("");
+        previous._load_environment_from_json_string(json_string.unwrap());
+        return previous.object_names.into_iter().zip(previous.object_trajectories.into_iter()).collect();
+    }
+
     fn _load_environment_from_metadata_with_environment_name(&mut self, environment_name: String) {
         let metadata_fp = get_path_to_src() + "assets/mesh_environments/" + environment_name.as_str() + "/environment_metadata.json";
 
@@ -278,6 +347,21 @@ impl CollisionEnvironment {
         return get_all_files_in_directory(fp);
     }
 
+    pub fn new_empty(environment_name: &str) -> Self {
+        return Self {
+            environment_name: environment_name.to_string(),
+            environment_obbs: Vec::new(),
+            object_names: Vec::new(),
+            trimesh_engines: Vec::new(),
+            original_file_directories: Vec::new(),
+            transforms: Vec::new(),
+            object_paddings: Vec::new(),
+            object_trajectories: Vec::new(),
+            curr_time: 0.0,
+            broad_phase: EnvironmentBroadPhase::new(&Vec::new())
+        };
+    }
+
     fn _get_all_mesh_files_in_particular_environment_directory(&self, environment_name: &String) -> Vec<String> {
         let fp = get_path_to_src() + "assets/mesh_environments/" + environment_name.as_str();
         return get_all_files_in_directory(fp);
@@ -296,6 +380,7 @@ impl CollisionEnvironment {
             self.environment_obbs[idx][i].set_curr_pose(new_transform);
             self.environment_obbs[idx][i].update_all_bounding_volumes();
         }
+        self.broad_phase.refit_object(&self.environment_obbs, idx);
 
         Ok(())
     }
@@ -316,6 +401,12 @@ impl CollisionEnvironment {
                 self.environment_obbs[i][j].update_all_bounding_volumes();
             }
         }
+        self.broad_phase.refit_all(&self.environment_obbs);
+    }
+
+    // only needs to be called manually if environment_obbs is modified directly
+    pub fn rebuild_broad_phase(&mut self) {
+        self.broad_phase.rebuild(&self.environment_obbs);
     }
 
     fn _get_object_idx_from_object_name(&self, name: &str) -> Option<usize> {
@@ -328,6 +419,195 @@ impl CollisionEnvironment {
         return None;
     }
 
+    // replaces all components of the given object, adding the object (with identity transform) if it does not exist yet
+    pub fn set_object_components_by_name(&mut self, name: &str, components: Vec<CollisionObject>, trimesh_engines: Vec<TriMeshEngine>) -> Result<(), String> {
+        if components.len() != trimesh_engines.len() {
+            return Err(format!("number of components ({:?}) must match number of trimesh engines ({:?})", components.len(), trimesh_engines.len()));
+        }
+
+        let idx = self._get_object_idx_from_object_name(name);
+        if idx.is_none() {
+            self.object_names.push(name.to_string());
+            self.transforms.push(ImplicitDualQuaternion::new_identity());
+            self.original_file_directories.push("".to_string());
+            self.object_paddings.push(0.0);
+            self.object_trajectories.push(None);
+            self.environment_obbs.push(components);
+            self.trimesh_engines.push(trimesh_engines);
+            let i = self.environment_obbs.len() - 1;
+            for c in self.environment_obbs[i].iter_mut() { c.padding = self.object_paddings[i]; }
+            let transform = self.transforms[i].clone();
+            self.update_object_transform_by_idx(i, &transform)?;
+            self.rebuild_broad_phase();
+            return Ok(());
+        }
+
+        let i = idx.unwrap();
+        self.environment_obbs[i] = components;
+        self.trimesh_engines[i] = trimesh_engines;
+        for c in self.environment_obbs[i].iter_mut() { c.padding = self.object_paddings[i]; }
+        let transform = self.transforms[i].clone();
+        self.update_object_transform_by_idx(i, &transform)?;
+        self.rebuild_broad_phase();
+
+        Ok(())
+    }
+
+    // appends components to an existing object, only inserting the new components into the broad phase
+    pub fn add_object_components_by_name(&mut self, name: &str, components: Vec<CollisionObject>, trimesh_engines: Vec<TriMeshEngine>) -> Result<(), String> {
+        if components.len() != trimesh_engines.len() {
+            return Err(format!("number of components ({:?}) must match number of trimesh engines ({:?})", components.len(), trimesh_engines.len()));
+        }
+        let idx = self._get_object_idx_from_object_name(name);
+        if idx.is_none() {
+            return Err(format!("object name {} was not found in collision environment", name));
+        }
+
+        let i = idx.unwrap();
+        for (mut c, t) in components.into_iter().zip(trimesh_engines.into_iter()) {
+            c.padding = self.object_paddings[i];
+            c.set_curr_pose(&self.transforms[i]);
+            c.update_all_bounding_volumes();
+            self.environment_obbs[i].push(c);
+            self.trimesh_engines[i].push(t);
+            let j = self.environment_obbs[i].len() - 1;
+            self.broad_phase.insert_component(&self.environment_obbs, [i, j])?;
+        }
+
+        Ok(())
+    }
+
+    // removes a single component of an object.  The object's last component takes its place (as in Vec::swap_remove).
+    pub fn swap_remove_object_component_by_name(&mut self, name: &str, component_idx: usize) -> Result<(), String> {
+        let idx = self._get_object_idx_from_object_name(name);
+        if idx.is_none() {
+            return Err(format!("object name {} was not found in collision environment", name));
+        }
+
+        let i = idx.unwrap();
+        let l = self.environment_obbs[i].len();
+        if component_idx >= l {
+            return Err(format!("component idx {:?} is too high for number of components ({:?}) on object {}", component_idx, l, name));
+        }
+
+        self.broad_phase.remove_component([i, component_idx])?;
+        if component_idx != l - 1 {
+            self.broad_phase.reindex_component([i, l - 1], [i, component_idx])?;
+        }
+        self.environment_obbs[i].swap_remove(component_idx);
+        self.trimesh_engines[i].swap_remove(component_idx);
+
+        Ok(())
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn set_object_padding_by_idx(&mut self, idx: usize, padding: f64) -> Result<(), String> {
+        if idx >= self.environment_obbs.len() {
+            return Err(format!("idx {:?} is too high for number of environment objects ({:?})", idx, self.environment_obbs.len()));
+        }
+
+        for c in self.environment_obbs[idx].iter_mut() { c.set_padding(padding)?; }
+        self.object_paddings[idx] = padding;
+        self.broad_phase.refit_object(&self.environment_obbs, idx);
+
+        Ok(())
+    }
+
+    pub fn set_object_padding_by_name(&mut self, name: &str, padding: f64) -> Result<(), String> {
+        let idx = self._get_object_idx_from_object_name(name);
+        if idx.is_none() {
+            return Err(format!("object name {} was not found in collision environment", name));
+        }
+        return self.set_object_padding_by_idx(idx.unwrap(), padding);
+    }
+
+    /* optional environment_padding.yaml file in the environment directory, with the following format:
+    default_object_padding: 0.0
+    object_padding: {"object_name": 0.05}
+    */
+    fn _load_object_paddings_from_yaml(&mut self, environment_name: &String) -> Result<(), String> {
+        let fp = get_path_to_src() + "assets/mesh_environments/" + environment_name.as_str() + "/environment_padding.yaml";
+        let y = get_yaml_obj(fp)?;
+        if y.len() == 0 { return Ok(()); }
+        let y1 = &y[0];
+
+        let default_object_padding = &y1["default_object_padding"];
+        if !default_object_padding.is_badvalue() && !default_object_padding.is_null() {
+            let padding = default_object_padding.as_f64().or(default_object_padding.as_i64().map(|x| x as f64));
+            if padding.is_none() { return Err(format!("default_object_padding must be a number in environment {} (got {:?})", environment_name, default_object_padding)); }
+            let l = self.environment_obbs.len();
+            for i in 0..l { self.set_object_padding_by_idx(i, padding.unwrap())?; }
+        }
+
+        let object_padding = y1["object_padding"].as_hash();
+        if object_padding.is_some() {
+            for (k, v) in object_padding.unwrap() {
+                let name = k.as_str();
+                let padding = v.as_f64().or(v.as_i64().map(|x| x as f64));
+                if name.is_none() || padding.is_none() { return Err(format!("object_padding entries must map object names to numbers in environment {} (got {:?}: {:?})", environment_name, k, v)); }
+                self.set_object_padding_by_name(name.unwrap(), padding.unwrap())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // note that objects with a trajectory are moved back onto their trajectory on the next call to set_time,
+    // overriding any transform set manually with update_object_transform_by_idx
+    pub fn set_object_trajectory_by_idx(&mut self, idx: usize, trajectory: Option<ObjectPoseTrajectory>) -> Result<(), String> {
+        if idx >= self.environment_obbs.len() {
+            return Err(format!("idx {:?} is too high for number of environment objects ({:?})", idx, self.environment_obbs.len()));
+        }
+
+        self.object_trajectories[idx] = trajectory;
+        if self.object_trajectories[idx].is_some() {
+            let pose = self.object_trajectories[idx].as_ref().unwrap().get_pose_at_time(self.curr_time)?;
+            self.update_object_transform_by_idx(idx, &pose)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn set_object_trajectory_by_name(&mut self, name: &str, trajectory: Option<ObjectPoseTrajectory>) -> Result<(), String> {
+        let idx = self._get_object_idx_from_object_name(name);
+        if idx.is_none() {
+            return Err(format!("object name {} was not found in collision environment", name));
+        }
+        return self.set_object_trajectory_by_idx(idx.unwrap(), trajectory);
+    }
+
+    // moves all objects that have a trajectory to their pose at the given time.  Static objects are left untouched.
+    pub fn set_time(&mut self, time: f64) -> Result<(), String> {
+        self.curr_time = time;
+        let l = self.object_trajectories.len();
+        for i in 0..l {
+            if self.object_trajectories[i].is_none() { continue; }
+            let pose = self.object_trajectories[i].as_ref().unwrap().get_pose_at_time(time)?;
+            self.update_object_transform_by_idx(i, &pose)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn has_moving_objects(&self) -> bool {
+        return self.object_trajectories.iter().any(|t| t.is_some());
+    }
+
+    // latest end time over all object trajectories, or None if the environment is static
+    pub fn get_trajectories_end_time(&self) -> Option<f64> {
+        let mut out_time = None;
+        for t in &self.object_trajectories {
+            if t.is_some() {
+                let end_time = t.as_ref().unwrap().get_end_time();
+                if out_time.is_none() || end_time > out_time.unwrap() { out_time = Some(end_time); }
+            }
+        }
+        return out_time;
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn delete_object_by_idx(&mut self, idx: usize) -> Result<(), String> {
@@ -340,6 +620,9 @@ impl CollisionEnvironment {
         self.original_file_directories.remove(idx);
         self.environment_obbs.remove(idx);
         self.trimesh_engines.remove(idx);
+        self.object_paddings.remove(idx);
+        self.object_trajectories.remove(idx);
+        self.rebuild_broad_phase();
 
         Ok(())
     }
@@ -362,6 +645,9 @@ impl CollisionEnvironment {
         self.original_file_directories.push(self.original_file_directories[idx].clone());
         self.environment_obbs.push(self.environment_obbs[idx].clone());
         self.trimesh_engines.push(self.trimesh_engines[idx].clone());
+        self.object_paddings.push(self.object_paddings[idx]);
+        self.object_trajectories.push(self.object_trajectories[idx].clone());
+        self.rebuild_broad_phase();
 
         Ok(())
     }
@@ -385,8 +671,10 @@ impl CollisionEnvironment {
             self.trimesh_engines.push(other.trimesh_engines[i].clone());
             self.transforms.push(other.transforms[i].clone());
             self.original_file_directories.push(other.original_file_directories[i].clone());
+            self.object_paddings.push(other.object_paddings[i]);
+            self.object_trajectories.push(other.object_trajectories[i].clone());
         }
-
+        self.rebuild_broad_phase();
     }
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
@@ -405,6 +693,8 @@ impl CollisionEnvironment {
             println!("          name: {:?}", self.object_names[i]);
             println!("          num components: {:?}", self.environment_obbs[i].len());
             println!("          transform: {:?}", self.transforms[i]);
+            println!("          padding: {:?}", self.object_paddings[i]);
+            println!("          has trajectory: {:?}", self.object_trajectories[i].is_some());
             println!("          original directory: {:?}", self.original_file_directories[i]);
         }
         println!();
@@ -1,6 +1,7 @@
 use crate::utils::utils_recorders::stopwatch::Stopwatch;
 use crate::utils::utils_collisions::collision_check_tensor::*;
 use ncollide3d::query::{Proximity, PointQuery, Contact, Ray, RayCast, PointProjection};
+use nalgebra::Vector3;
 
 
 #[derive(Debug, Clone)]
@@ -177,7 +178,7 @@ impl DistanceCheckMultipleInfo {
                 self._distance_check_distance_ratio_with_respect_to_average.insert(i, distance_ratio_with_respect_to_average);
             }
         }
-        if distance == 0.0 { self._num_intersections += 1 }
+        if distance <= 0.0 { self._num_intersections += 1 }
 
         Ok(())
     }
@@ -488,4 +489,63 @@ impl ContainsPointCheckMultipleInfo {
     pub fn get_stopwatch_mut_ref(&mut self) -> &mut Stopwatch {
         return &mut self._stopwatch;
     }
-}
\ No newline at end of file
+}
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug)]
+pub struct VisibilityOccluder {
+    pub name: String,
+    pub robot_idx: Option<usize>, // None for collision environment objects
+    pub idxs: [usize; 2],
+    pub entry_fraction: f64, // where the line of sight enters and exits the occluder, as fractions of the way from viewpoint to target
+    pub exit_fraction: f64
+}
+
+#[derive(Clone, Debug)]
+pub struct VisibilityCheckResult {
+    _viewpoint: Vector3<f64>,
+    _target_point: Vector3<f64>,
+    _occluders: Vec<VisibilityOccluder> // sorted by entry_fraction
+}
+
+impl VisibilityCheckResult {
+    pub fn new(viewpoint: Vector3<f64>, target_point: Vector3<f64>, mut occluders: Vec<VisibilityOccluder>) -> Self {
+        occluders.sort_by(|a, b| a.entry_fraction.partial_cmp(&b.entry_fraction).unwrap());
+        return Self { _viewpoint: viewpoint, _target_point: target_point, _occluders: occluders };
+    }
+
+    pub fn print_summary(&self) {
+        if self.is_visible() {
+            println!("Target {:?} is visible from {:?}", self._target_point, self._viewpoint);
+        } else {
+            println!("Target {:?} is occluded from {:?}", self._target_point, self._viewpoint);
+            for o in &self._occluders {
+                println!("occluder {:?} (robot idx {:?}) {:?} ---> from {:?} to {:?} of the way to the target", o.name, o.robot_idx, o.idxs, o.entry_fraction, o.exit_fraction);
+            }
+        }
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn is_visible(&self) -> bool {
+        return self._occluders.is_empty();
+    }
+
+    // total length of the line of sight that passes through occluders (overlapping occluders are counted more than once)
+    pub fn get_occluded_length(&self) -> f64 {
+        let sight_line_length = (self._target_point - self._viewpoint).norm();
+        return self._occluders.iter().map(|o| (o.exit_fraction - o.entry_fraction).max(0.0) * sight_line_length).sum();
+    }
+
+    pub fn get_occluders_ref(&self) -> &Vec<VisibilityOccluder> {
+        return &self._occluders;
+    }
+
+    pub fn get_viewpoint_ref(&self) -> &Vector3<f64> {
+        return &self._viewpoint;
+    }
+
+    pub fn get_target_point_ref(&self) -> &Vector3<f64> {
+        return &self._target_point;
+    }
+}
@@ -1,5 +1,5 @@
 use ncollide3d::query;
-use ncollide3d::query::{Proximity, PointQuery, Contact, Ray, RayCast, PointProjection};
+use ncollide3d::query::{Proximity, PointQuery, Contact, Ray, RayCast, RayIntersection, PointProjection};
 use ncollide3d::shape::FeatureId;
 use ncollide3d::shape::{Ball, Cuboid, Cylinder, Capsule, Cone, ConvexHull, Shape, TriMesh, CompositeShape, Compound, ShapeHandle};
 use ncollide3d::bounding_volume::{self, BoundingVolume, BoundingSphere, AABB};
@@ -38,7 +38,8 @@ pub struct CollisionObject {
     pub has_base_pose: bool,
     pub curr_isometry: Isometry3<f64>,
     pub bounding_sphere_outdated: bool,
-    pub bounding_aabb_outdated: bool
+    pub bounding_aabb_outdated: bool,
+    pub padding: f64 // safety margin that inflates the object in all queries below (intersect, distance, contact, bounding volumes)
 }
 
 impl CollisionObject {
@@ -69,7 +70,7 @@ impl CollisionObject {
         let mut name_ = u64_to_string(id);
         if name.is_some() { name_ = name.unwrap(); }
 
-        Self {name: name_, id, active: true, shape, bounding_sphere, base_bounding_sphere, manual_bounding_sphere, bounding_aabb, base_bounding_aabb, curr_pose, base_pose: base_pose_local, curr_quaternion_global_delta, curr_translation_global_delta, base_rot_mat, has_base_pose, curr_isometry, bounding_sphere_outdated: false, bounding_aabb_outdated: false}
+        Self {name: name_, id, active: true, shape, bounding_sphere, base_bounding_sphere, manual_bounding_sphere, bounding_aabb, base_bounding_aabb, curr_pose, base_pose: base_pose_local, curr_quaternion_global_delta, curr_translation_global_delta, base_rot_mat, has_base_pose, curr_isometry, bounding_sphere_outdated: false, bounding_aabb_outdated: false, padding: 0.0}
     }
 
     pub fn new_capsule(half_height: f64, radius: f64, base_pose: Option<ImplicitDualQuaternion>, name: Option<String>) -> Self {
@@ -148,9 +149,10 @@ impl CollisionObject {
         let curr_isometry = self.curr_isometry.clone();
         let bounding_sphere_outdated = self.bounding_sphere_outdated;
         let bounding_aabb_outdated = self.bounding_aabb_outdated;
+        let padding = self.padding;
         return Self {name, id, active, shape, bounding_sphere, base_bounding_sphere, manual_bounding_sphere, bounding_aabb,
             base_bounding_aabb, curr_pose, base_pose, curr_quaternion_global_delta, curr_translation_global_delta,
-            base_rot_mat, has_base_pose, curr_isometry, bounding_sphere_outdated, bounding_aabb_outdated };
+            base_rot_mat, has_base_pose, curr_isometry, bounding_sphere_outdated, bounding_aabb_outdated, padding };
     }
 
     ////////////////////////////////////////////////////////////////////////////////////////////////
@@ -160,6 +162,12 @@ impl CollisionObject {
     }
 
     pub fn intersect_check(&self, other: &CollisionObject) -> bool {
+        let padding = self.padding + other.padding;
+        if padding > 0.0 {
+            let p = query::proximity(&self.curr_isometry, &(**self.shape), &other.curr_isometry, &(**other.shape), padding);
+            return p != Proximity::Disjoint;
+        }
+
         let p = self.proximity_check(other);
         if p == Proximity::Intersecting {
             return true;
@@ -193,8 +201,9 @@ impl CollisionObject {
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn distance_check(&self, other: &CollisionObject) -> f64 {
+        // with padding, the distance is between the inflated objects, so it can be negative
         let dis = query::distance( &self.curr_isometry, &(**self.shape), &other.curr_isometry, &(**other.shape) );
-        return dis;
+        return dis - self.padding - other.padding;
     }
 
     pub fn distance_check_bounding_sphere(&self, other: &CollisionObject) -> f64 {
@@ -205,7 +214,15 @@ impl CollisionObject {
 
     pub fn contact_check(&self, other: &CollisionObject, margin: Option<f64>) -> Option<Contact<f64>> {
         let mut prediction = 0.2; if margin.is_some() { prediction = margin.unwrap(); }
-        return query::contact( &self.curr_isometry, &(**self.shape), &other.curr_isometry, &(**other.shape), prediction  );
+        let padding = self.padding + other.padding;
+        let contact = query::contact( &self.curr_isometry, &(**self.shape), &other.curr_isometry, &(**other.shape), prediction + padding  );
+        if contact.is_none() || padding == 0.0 { return contact; }
+
+        // contact points are moved onto the surfaces of the inflated objects
+        let c = contact.unwrap();
+        let world1 = c.world1 + c.normal.into_inner() * self.padding;
+        let world2 = c.world2 - c.normal.into_inner() * other.padding;
+        return Some( Contact::new(world1, world2, c.normal, c.depth + padding) );
     }
 
     pub fn contact_check_bounding_sphere(&self, other: &CollisionObject, margin: Option<f64>) -> Option<Contact<f64>> {
@@ -225,6 +242,12 @@ impl CollisionObject {
         return self.manual_bounding_sphere.intersects_ray(&self.curr_isometry, &ray, 1.0);
     }
 
+    // first hit along the ray within max_toi.  The toi is in units of ray_direction's length, and the normal is in the world frame
+    pub fn cast_ray(&self, ray_origin: &Point3<f64>, ray_direction: &Vector3<f64>, max_toi: f64) -> Option<RayIntersection<f64>> {
+        let ray = Ray::new(ray_origin.clone(), ray_direction.clone());
+        return self.shape.toi_and_normal_with_ray(&self.curr_isometry, &ray, max_toi, true);
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn contains_point(&self, point: &Point3<f64>) -> bool {
@@ -313,12 +336,12 @@ impl CollisionObject {
     }
 
     pub fn update_bounding_sphere(&mut self) {
-        self.bounding_sphere = self.base_bounding_sphere.transform_by(&self.curr_isometry);
+        self.bounding_sphere = self.base_bounding_sphere.transform_by(&self.curr_isometry).loosened(self.padding);
         self.bounding_sphere_outdated = false;
     }
 
     pub fn update_bounding_aabb(&mut self) {
-        self.bounding_aabb = self.base_bounding_aabb.transform_by(&self.curr_isometry);
+        self.bounding_aabb = self.base_bounding_aabb.transform_by(&self.curr_isometry).loosened(self.padding);
         self.bounding_aabb_outdated = false;
     }
 
@@ -327,6 +350,13 @@ impl CollisionObject {
         self.update_bounding_aabb();
     }
 
+    pub fn set_padding(&mut self, padding: f64) -> Result<(), String> {
+        if padding < 0.0 { return Err(format!("padding must be non-negative (got {:?})", padding)); }
+        self.padding = padding;
+        self.update_all_bounding_volumes();
+        Ok(())
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     fn _get_id() -> u64 {
@@ -1,9 +1,10 @@
 use crate::utils::utils_collisions::collision_object::*;
 use crate::utils::utils_collisions::collision_check_tensor::*;
 use crate::utils::utils_collisions::collision_multiple_results::*;
+use crate::utils::utils_collisions::collision_environment::CollisionEnvironment;
 use crate::utils::utils_recorders::stopwatch::Stopwatch;
-use nalgebra::{Point3, Vector3};
-use ncollide3d::query::{Proximity, PointQuery, Contact, Ray, RayCast, PointProjection};
+use nalgebra::{Point3, Vector3, Isometry3};
+use ncollide3d::query::{Proximity, PointQuery, Contact, Ray, RayCast, RayIntersection, PointProjection};
 use std::time::{Instant, Duration};
 use std::fmt;
 
@@ -105,7 +106,7 @@ pub fn distance_check_between_multiple_collision_objects(group1: &Vec<Vec<Collis
                         if group1[i][j].active && group2[k][l].active {
                             let distance = group1[i][j].distance_check(&group2[k][l]);
                             distance_check_multiple_info.add_distance(group1[i][j].name.clone(), [i, j], group2[k][l].name.clone(), [k, l], distance, average_distance_tensor);
-                            if distance == 0.0 && stop_at_first_detected_intersection {
+                            if distance <= 0.0 && stop_at_first_detected_intersection {
                                 distance_check_multiple_info.get_stopwatch_mut_ref().add_time_marker(None);
                                 return Ok(DistanceCheckMultipleResult::IntersectionFound(distance_check_multiple_info));
                             }
@@ -133,7 +134,7 @@ pub fn distance_check_between_multiple_collision_objects_subset(subset_check_idx
             if group1[c[0][0]][c[0][1]].active && group2[c[1][0]][c[1][1]].active {
                 let distance = group1[c[0][0]][c[0][1]].distance_check(&group2[c[1][0]][c[1][1]]);
                 distance_check_multiple_info.add_distance(group1[c[0][0]][c[0][1]].name.clone(), c[0], group2[c[1][0]][c[1][1]].name.clone(), c[1], distance, average_distance_tensor);
-                if distance == 0.0 && stop_at_first_detected_intersection {
+                if distance <= 0.0 && stop_at_first_detected_intersection {
                     distance_check_multiple_info.get_stopwatch_mut_ref().add_time_marker(None);
                     return Ok(DistanceCheckMultipleResult::IntersectionFound(distance_check_multiple_info));
                 }
@@ -220,6 +221,42 @@ pub fn contact_check_between_multiple_collision_objects_subset(subset_check_idxs
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+/* environment queries.  These return the same results as calling the group queries above with
+collision_environment.environment_obbs as group1, but only visit the pairs returned by the environment's broad phase.
+For distance checks, pairs are only pruned if a distance check cutoff is set on the broad phase. */
+
+pub fn intersect_check_between_environment_and_multiple_collision_objects(collision_environment: &CollisionEnvironment, group: &Vec<Vec<CollisionObject>>, stop_at_first_detected: bool, skip_collision_check_tensor: Option<&BoolCollisionCheckTensor>) -> Result<IntersectCheckMultipleResult, String> {
+    let subset_check_idxs = collision_environment.broad_phase.get_candidate_pairs(group, Some(0.0));
+    return intersect_check_between_multiple_collision_objects_subset(&subset_check_idxs, &collision_environment.environment_obbs, group, stop_at_first_detected, skip_collision_check_tensor);
+}
+
+pub fn intersect_check_between_environment_and_multiple_collision_objects_subset(subset_check_idxs: &Vec<  [ [usize; 2]; 2 ]  >, collision_environment: &CollisionEnvironment, group: &Vec<Vec<CollisionObject>>, stop_at_first_detected: bool, skip_collision_check_tensor: Option<&BoolCollisionCheckTensor>) -> Result<IntersectCheckMultipleResult, String> {
+    let filtered_check_idxs = collision_environment.broad_phase.filter_subset(subset_check_idxs, group, Some(0.0));
+    return intersect_check_between_multiple_collision_objects_subset(&filtered_check_idxs, &collision_environment.environment_obbs, group, stop_at_first_detected, skip_collision_check_tensor);
+}
+
+pub fn distance_check_between_environment_and_multiple_collision_objects(collision_environment: &CollisionEnvironment, group: &Vec<Vec<CollisionObject>>, stop_at_first_detected_intersection: bool, skip_collision_check_tensor: Option<&BoolCollisionCheckTensor>, average_distance_tensor: Option<&FloatCollisionCheckTensor>) -> Result<DistanceCheckMultipleResult, String> {
+    let subset_check_idxs = collision_environment.broad_phase.get_candidate_pairs(group, collision_environment.broad_phase.get_distance_check_cutoff());
+    return distance_check_between_multiple_collision_objects_subset(&subset_check_idxs, &collision_environment.environment_obbs, group, stop_at_first_detected_intersection, skip_collision_check_tensor, average_distance_tensor);
+}
+
+pub fn distance_check_between_environment_and_multiple_collision_objects_subset(subset_check_idxs: &Vec<  [ [usize; 2]; 2 ]  >, collision_environment: &CollisionEnvironment, group: &Vec<Vec<CollisionObject>>, stop_at_first_detected_intersection: bool, skip_collision_check_tensor: Option<&BoolCollisionCheckTensor>, average_distance_tensor: Option<&FloatCollisionCheckTensor>) -> Result<DistanceCheckMultipleResult, String> {
+    let filtered_check_idxs = collision_environment.broad_phase.filter_subset(subset_check_idxs, group, collision_environment.broad_phase.get_distance_check_cutoff());
+    return distance_check_between_multiple_collision_objects_subset(&filtered_check_idxs, &collision_environment.environment_obbs, group, stop_at_first_detected_intersection, skip_collision_check_tensor, average_distance_tensor);
+}
+
+pub fn contact_check_between_environment_and_multiple_collision_objects(collision_environment: &CollisionEnvironment, group: &Vec<Vec<CollisionObject>>, stop_at_first_detected_intersection: bool, margin: Option<f64>, skip_collision_check_tensor: Option<&BoolCollisionCheckTensor>, average_distance_tensor: Option<&FloatCollisionCheckTensor>) -> Result<ContactCheckMultipleResult, String> {
+    let subset_check_idxs = collision_environment.broad_phase.get_candidate_pairs(group, Some(margin.unwrap_or(0.0)));
+    return contact_check_between_multiple_collision_objects_subset(&subset_check_idxs, &collision_environment.environment_obbs, group, stop_at_first_detected_intersection, margin, skip_collision_check_tensor, average_distance_tensor);
+}
+
+pub fn contact_check_between_environment_and_multiple_collision_objects_subset(subset_check_idxs: &Vec<  [ [usize; 2]; 2 ]  >, collision_environment: &CollisionEnvironment, group: &Vec<Vec<CollisionObject>>, stop_at_first_detected_intersection: bool, margin: Option<f64>, skip_collision_check_tensor: Option<&BoolCollisionCheckTensor>, average_distance_tensor: Option<&FloatCollisionCheckTensor>) -> Result<ContactCheckMultipleResult, String> {
+    let filtered_check_idxs = collision_environment.broad_phase.filter_subset(subset_check_idxs, group, Some(margin.unwrap_or(0.0)));
+    return contact_check_between_multiple_collision_objects_subset(&filtered_check_idxs, &collision_environment.environment_obbs, group, stop_at_first_detected_intersection, margin, skip_collision_check_tensor, average_distance_tensor);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
 pub fn ray_intersect_check_between_multiple_collision_objects(group: &Vec<Vec<CollisionObject>>, ray_origin: &Point3<f64>, ray_direction: &Vector3<f64>, stop_at_first_detected: bool) -> Result<RayIntersectCheckMultipleResult, String> {
     let mut ray_intersect_check_multiple_info = RayIntersectCheckMultipleInfo::new(stop_at_first_detected);
 
@@ -272,6 +309,31 @@ pub fn ray_intersect_check_between_multiple_collision_objects_subset(subset_chec
     }
 }
 
+// closest hit along the ray over all active objects in the group, as ([i, j] idxs of the object hit, ray intersection)
+pub fn ray_cast_between_multiple_collision_objects(group: &Vec<Vec<CollisionObject>>, ray_origin: &Point3<f64>, ray_direction: &Vector3<f64>, max_toi: f64) -> Option<([usize; 2], RayIntersection<f64>)> {
+    let ray = Ray::new(ray_origin.clone(), ray_direction.clone());
+    let identity = Isometry3::identity();
+    let mut out: Option<([usize; 2], RayIntersection<f64>)> = None;
+    let mut best_toi = max_toi;
+
+    let l = group.len();
+    for i in 0..l {
+        let l2 = group[i].len();
+        for j in 0..l2 {
+            if !group[i][j].active { continue; }
+            if !group[i][j].bounding_aabb_outdated && !group[i][j].bounding_aabb.intersects_ray(&identity, &ray, best_toi) { continue; }
+
+            let hit = group[i][j].cast_ray(ray_origin, ray_direction, best_toi);
+            if hit.is_some() && hit.as_ref().unwrap().toi < best_toi {
+                best_toi = hit.as_ref().unwrap().toi;
+                out = Some(([i, j], hit.unwrap()));
+            }
+        }
+    }
+
+    return out;
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 pub fn contains_point_check_between_multiple_collision_objects(group: &Vec<Vec<CollisionObject>>, point: &Point3<f64>, stop_at_first_detected: bool) -> Result<ContainsPointCheckMultipleResult, String> {
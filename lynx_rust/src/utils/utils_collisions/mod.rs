@@ -6,7 +6,13 @@ pub mod collision_check_result_enum;
 pub mod oriented_bounding_box_utils;
 pub mod collision_object_group_queries;
 pub mod collision_check_tensor;
+pub mod collision_check_tensor_generation;
 pub mod collision_checker;
+pub mod collision_check_cache;
 pub mod collision_multiple_results;
 pub mod vec_of_collision_multiple_results;
+pub mod signed_distance_field;
+pub mod voxel_occupancy_grid;
+pub mod collision_broad_phase;
+pub mod object_pose_trajectory;
 pub mod prelude;
@@ -0,0 +1,93 @@
+use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
+use serde::{Serialize, Deserialize};
+
+/* timed sequence of poses for a single environment object, e.g., a box on a conveyor or a moving fixture.  Poses between
+waypoints are interpolated (lerp on translation, slerp on rotation).  Before the first waypoint and after the last one,
+the pose is held constant, unless looping is true, in which case the trajectory repeats with period equal to its duration. */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ObjectPoseTrajectory {
+    _waypoints: Vec<(f64, ImplicitDualQuaternion)>,
+    _looping: bool
+}
+
+impl ObjectPoseTrajectory {
+    pub fn new(waypoints: Vec<(f64, ImplicitDualQuaternion)>, looping: bool) -> Result<Self, String> {
+        if waypoints.is_empty() {
+            return Err("object pose trajectory must have at least one waypoint".to_string());
+        }
+        let l = waypoints.len();
+        for i in 1..l {
+            if !(waypoints[i].0 > waypoints[i-1].0) {
+                return Err(format!("object pose trajectory waypoint times must be strictly increasing (got {:?} after {:?})", waypoints[i].0, waypoints[i-1].0));
+            }
+        }
+        return Ok(Self { _waypoints: waypoints, _looping: looping });
+    }
+
+    pub fn new_empty(looping: bool) -> Self {
+        return Self { _waypoints: Vec::new(), _looping: looping };
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn add_waypoint(&mut self, time: f64, pose: &ImplicitDualQuaternion) -> Result<(), String> {
+        if !self._waypoints.is_empty() && !(time > self._waypoints[self._waypoints.len()-1].0) {
+            return Err(format!("waypoint time {:?} must be greater than the last waypoint time {:?}", time, self._waypoints[self._waypoints.len()-1].0));
+        }
+        self._waypoints.push( (time, pose.clone()) );
+        Ok(())
+    }
+
+    pub fn get_pose_at_time(&self, time: f64) -> Result<ImplicitDualQuaternion, String> {
+        if self._waypoints.is_empty() {
+            return Err("cannot get pose on object pose trajectory with no waypoints".to_string());
+        }
+
+        let l = self._waypoints.len();
+        let start_time = self._waypoints[0].0;
+        let end_time = self._waypoints[l-1].0;
+
+        let mut t = time;
+        if self._looping && end_time > start_time {
+            t = start_time + (time - start_time).rem_euclid(end_time - start_time);
+        }
+
+        if t <= start_time { return Ok(self._waypoints[0].1.clone()); }
+        if t >= end_time { return Ok(self._waypoints[l-1].1.clone()); }
+
+        // first waypoint with time greater than t
+        let idx = self._waypoints.iter().position(|w| w.0 > t).unwrap();
+        let (t0, pose0) = &self._waypoints[idx-1];
+        let (t1, pose1) = &self._waypoints[idx];
+        let u = (t - t0) / (t1 - t0);
+
+        // slerp is undefined for rotations 180 degrees apart, so fall back on nlerp there
+        let quat = pose0.quat.try_slerp(&pose1.quat, u, 0.000001).unwrap_or(pose0.quat.nlerp(&pose1.quat, u));
+        let translation = pose0.translation + u * (pose1.translation - pose0.translation);
+        return Ok(ImplicitDualQuaternion::new(quat, translation));
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn get_waypoints_ref(&self) -> &Vec<(f64, ImplicitDualQuaternion)> {
+        return &self._waypoints;
+    }
+
+    pub fn get_looping(&self) -> bool {
+        return self._looping;
+    }
+
+    pub fn get_start_time(&self) -> f64 {
+        if self._waypoints.is_empty() { return 0.0; }
+        return self._waypoints[0].0;
+    }
+
+    pub fn get_end_time(&self) -> f64 {
+        if self._waypoints.is_empty() { return 0.0; }
+        return self._waypoints[self._waypoints.len()-1].0;
+    }
+
+    pub fn get_duration(&self) -> f64 {
+        return self.get_end_time() - self.get_start_time();
+    }
+}
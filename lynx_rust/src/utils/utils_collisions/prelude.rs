@@ -1,6 +1,8 @@
 pub use crate::utils::utils_collisions::{collision_check_result_enum::*,
                                          collision_check_tensor::*,
+                                         collision_check_tensor_generation::*,
                                          collision_checker::*,
+                                         collision_check_cache::*,
                                          collision_environment::*,
                                          collision_object::*,
                                          collision_object_group_queries::*,
@@ -8,4 +10,8 @@ pub use crate::utils::utils_collisions::{collision_check_result_enum::*,
                                          oriented_bounding_box_utils::*,
                                          triangle_mesh_engine::*,
                                          collision_multiple_results::*,
-                                         vec_of_collision_multiple_results::*};
\ No newline at end of file
+                                         vec_of_collision_multiple_results::*,
+                                         signed_distance_field::*,
+                                         voxel_occupancy_grid::*,
+                                         collision_broad_phase::*,
+                                         object_pose_trajectory::*};
\ No newline at end of file
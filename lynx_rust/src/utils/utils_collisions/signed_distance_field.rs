@@ -0,0 +1,290 @@
+use crate::utils::utils_collisions::{collision_environment::CollisionEnvironment, collision_object::CollisionObject};
+use crate::utils::utils_files_and_strings::file_utils::*;
+use crate::robot_modules::robot_core_collision_module::{RobotCoreCollisionModule, LinkGeometryType};
+use crate::robot_modules::robot_fk_module::RobotFKResult;
+use ncollide3d::bounding_volume::BoundingVolume;
+use ncollide3d::shape::Ball;
+use nalgebra::{Vector3, Point3};
+use serde::{Serialize, Deserialize};
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/* versions are drawn from one global counter, the same way robot world environment versions are, so every field that is
+built or loaded gets its own version, while clones share it.  Used to invalidate cached collision checks against a field */
+static NEXT_SIGNED_DISTANCE_FIELD_VERSION: AtomicU64 = AtomicU64::new(0);
+fn new_signed_distance_field_version() -> u64 { return NEXT_SIGNED_DISTANCE_FIELD_VERSION.fetch_add(1, Ordering::SeqCst); }
+
+/* voxel grid of signed distances.  Values are stored at grid nodes and queried with trilinear interpolation.
+Negative values are inside geometry. */
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SignedDistanceField {
+    _origin: Vector3<f64>, // world position of grid node (0,0,0)
+    _resolution: f64,
+    _dims: [usize; 3],
+    _values: Vec<f64>,
+    #[serde(skip, default = "new_signed_distance_field_version")]
+    _version: u64
+}
+
+impl SignedDistanceField {
+    pub fn new_from_collision_environment(collision_environment: &CollisionEnvironment, resolution: f64, padding: f64) -> Result<Self, String> {
+        if resolution <= 0.0 { return Err(format!("resolution must be positive in signed distance field (got {:?})", resolution)); }
+
+        let environment_obbs = &collision_environment.environment_obbs;
+
+        let mut mins = Vector3::new(std::f64::INFINITY, std::f64::INFINITY, std::f64::INFINITY);
+        let mut maxs = Vector3::new(-std::f64::INFINITY, -std::f64::INFINITY, -std::f64::INFINITY);
+        let mut num_active_objects = 0;
+        for o in environment_obbs {
+            for c in o {
+                if !c.active { continue; }
+                let aabb = c.base_bounding_aabb.transform_by(&c.curr_isometry);
+                for k in 0..3 {
+                    if aabb.mins()[k] < mins[k] { mins[k] = aabb.mins()[k]; }
+                    if aabb.maxs()[k] > maxs[k] { maxs[k] = aabb.maxs()[k]; }
+                }
+                num_active_objects += 1;
+            }
+        }
+        if num_active_objects == 0 {
+            return Err(format!("collision environment {:?} has no active objects to build a signed distance field from", collision_environment.environment_name));
+        }
+
+        let padding_vec = Vector3::new(padding, padding, padding);
+        let _origin = mins - padding_vec;
+        let extents = (maxs + padding_vec) - _origin;
+
+        let mut _dims = [0 as usize; 3];
+        for k in 0..3 {
+            _dims[k] = ((extents[k] / resolution).ceil() as usize + 1).max(2);
+        }
+
+        let num_nodes = _dims[0] * _dims[1] * _dims[2];
+        let _values: Vec<f64> = (0..num_nodes).into_par_iter().map(|idx| {
+            let i = idx % _dims[0];
+            let j = (idx / _dims[0]) % _dims[1];
+            let k = idx / (_dims[0] * _dims[1]);
+            let point = _origin + Vector3::new(i as f64, j as f64, k as f64) * resolution;
+            Self::_signed_distance_to_environment(environment_obbs, &point)
+        }).collect();
+
+        return Ok(Self { _origin, _resolution: resolution, _dims, _values, _version: new_signed_distance_field_version() });
+    }
+
+    pub fn load_from_file(fp_to_dir: String, file_name: String) -> Result<Self, String> {
+        let json_string = read_file_contents( fp_to_dir.clone() + "/" + file_name.as_str() );
+        if json_string.is_none() {
+            return Err(format!("no file {:?} found when trying to load a SignedDistanceField", fp_to_dir + "/" + file_name.as_str()));
+        }
+
+        let out_self = serde_json::from_str(&json_string.unwrap());
+        if out_self.is_err() {
+            return Err(format!("file {:?} could not be parsed as a SignedDistanceField", fp_to_dir + "/" + file_name.as_str()));
+        }
+        return Ok(out_self.unwrap());
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn save_to_file(&self, fp_to_dir: String, file_name: String) {
+        let serialized = serde_json::to_string(&self).unwrap();
+        write_string_to_file(fp_to_dir, file_name, serialized, true);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn distance(&self, point: &Vector3<f64>) -> f64 {
+        return self.distance_and_gradient(point).0;
+    }
+
+    pub fn gradient(&self, point: &Vector3<f64>) -> Vector3<f64> {
+        return self.distance_and_gradient(point).1;
+    }
+
+    // points outside of the grid are clamped to the grid boundary, and the distance from the point to the boundary
+    // is added on.  This keeps the field (an upper bound on the true distance) and its gradient well defined everywhere.
+    pub fn distance_and_gradient(&self, point: &Vector3<f64>) -> (f64, Vector3<f64>) {
+        let mut idxs = [0 as usize; 3];
+        let mut fracs = [0.0; 3];
+        let mut clamped = [false; 3];
+        let mut outside_vec = Vector3::zeros();
+
+        for k in 0..3 {
+            let max_coord = (self._dims[k] - 1) as f64;
+            let mut coord = (point[k] - self._origin[k]) / self._resolution;
+            if coord < 0.0 {
+                outside_vec[k] = coord * self._resolution;
+                coord = 0.0;
+                clamped[k] = true;
+            } else if coord > max_coord {
+                outside_vec[k] = (coord - max_coord) * self._resolution;
+                coord = max_coord;
+                clamped[k] = true;
+            }
+
+            let idx = (coord.floor() as usize).min(self._dims[k] - 2);
+            idxs[k] = idx;
+            fracs[k] = coord - idx as f64;
+        }
+
+        let c000 = self._get_value(idxs[0], idxs[1], idxs[2]);
+        let c100 = self._get_value(idxs[0] + 1, idxs[1], idxs[2]);
+        let c010 = self._get_value(idxs[0], idxs[1] + 1, idxs[2]);
+        let c110 = self._get_value(idxs[0] + 1, idxs[1] + 1, idxs[2]);
+        let c001 = self._get_value(idxs[0], idxs[1], idxs[2] + 1);
+        let c101 = self._get_value(idxs[0] + 1, idxs[1], idxs[2] + 1);
+        let c011 = self._get_value(idxs[0], idxs[1] + 1, idxs[2] + 1);
+        let c111 = self._get_value(idxs[0] + 1, idxs[1] + 1, idxs[2] + 1);
+
+        let (fx, fy, fz) = (fracs[0], fracs[1], fracs[2]);
+
+        let c00 = c000 * (1.0 - fx) + c100 * fx;
+        let c10 = c010 * (1.0 - fx) + c110 * fx;
+        let c01 = c001 * (1.0 - fx) + c101 * fx;
+        let c11 = c011 * (1.0 - fx) + c111 * fx;
+        let c0 = c00 * (1.0 - fy) + c10 * fy;
+        let c1 = c01 * (1.0 - fy) + c11 * fy;
+        let interpolated = c0 * (1.0 - fz) + c1 * fz;
+
+        let mut gradient = Vector3::zeros();
+        gradient[0] = (1.0 - fy) * (1.0 - fz) * (c100 - c000) + fy * (1.0 - fz) * (c110 - c010) + (1.0 - fy) * fz * (c101 - c001) + fy * fz * (c111 - c011);
+        gradient[1] = (1.0 - fx) * (1.0 - fz) * (c010 - c000) + fx * (1.0 - fz) * (c110 - c100) + (1.0 - fx) * fz * (c011 - c001) + fx * fz * (c111 - c101);
+        gradient[2] = (1.0 - fx) * (1.0 - fy) * (c001 - c000) + fx * (1.0 - fy) * (c101 - c100) + (1.0 - fx) * fy * (c011 - c010) + fx * fy * (c111 - c110);
+        gradient /= self._resolution;
+        for k in 0..3 { if clamped[k] { gradient[k] = 0.0; } }
+
+        let outside_distance = outside_vec.norm();
+        if outside_distance > 0.0 {
+            gradient += outside_vec / outside_distance;
+        }
+
+        return (interpolated + outside_distance, gradient);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn get_origin_ref(&self) -> &Vector3<f64> { return &self._origin; }
+
+    pub fn get_resolution(&self) -> f64 { return self._resolution; }
+
+    pub fn get_dims(&self) -> [usize; 3] { return self._dims; }
+
+    pub fn get_values_ref(&self) -> &Vec<f64> { return &self._values; }
+
+    pub fn get_version(&self) -> u64 { return self._version; }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    fn _get_value(&self, i: usize, j: usize, k: usize) -> f64 {
+        return self._values[i + self._dims[0] * (j + self._dims[1] * k)];
+    }
+
+    fn _signed_distance_to_environment(environment_obbs: &Vec<Vec<CollisionObject>>, point: &Vector3<f64>) -> f64 {
+        let p = Point3::from(point.clone());
+
+        let mut min_signed_distance = std::f64::INFINITY;
+        for o in environment_obbs {
+            for c in o {
+                if !c.active { continue; }
+                let projection = c.project_point(&p, false);
+                let mut signed_distance = (p - projection.point).norm();
+                if projection.is_inside { signed_distance *= -1.0; }
+                if signed_distance < min_signed_distance { min_signed_distance = signed_distance; }
+            }
+        }
+
+        return min_signed_distance;
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/* conservative sphere covering of each link's collision geometry, used for queries against a SignedDistanceField.
+Each collision object's local bounding box is split along its longest axis into roughly cube-shaped pieces, and
+each piece is covered by its circumscribing sphere.  Ball collision objects (e.g., LinkGeometryType::Spheres) are used
+as they are.  Spheres are stored without link padding, which is added when spheres are queried, since paddings can
+change after the approximation is built. */
+#[derive(Clone, Debug)]
+pub struct LinkSphereApproximation {
+    _link_spheres: Vec<Vec<(Vector3<f64>, f64)>> // per link, (center in link frame, unpadded radius)
+}
+
+impl LinkSphereApproximation {
+    pub fn new(robot_core_collision_module: &RobotCoreCollisionModule, link_geometry_type: &LinkGeometryType) -> Self {
+        let mut _link_spheres = Vec::new();
+
+        let collision_objects = robot_core_collision_module.get_link_geometry_collision_objects_ref(link_geometry_type);
+        for link_collision_objects in collision_objects {
+            let mut spheres = Vec::new();
+            for c in link_collision_objects {
+                if !c.active { continue; }
+                spheres.append(&mut Self::_get_spheres_covering_collision_object(c));
+            }
+            _link_spheres.push(spheres);
+        }
+
+        return Self { _link_spheres };
+    }
+
+    pub fn get_link_spheres_ref(&self) -> &Vec<Vec<(Vector3<f64>, f64)>> {
+        return &self._link_spheres;
+    }
+
+    // outputs (link idx, sphere center in world frame, radius) for all links that have a frame in fk_res.  Each radius is
+    // inflated by its link's current padding (e.g., from RobotCoreCollisionModule::get_link_paddings_ref).
+    pub fn get_spheres_in_world_frame(&self, fk_res: &RobotFKResult, link_paddings: &Vec<f64>) -> Vec<(usize, Vector3<f64>, f64)> {
+        let mut out_vec = Vec::new();
+
+        let link_frames = fk_res.get_link_frames_ref();
+        let l = link_frames.len().min(self._link_spheres.len());
+        for i in 0..l {
+            if link_frames[i].is_none() { continue; }
+            let link_frame = link_frames[i].as_ref().unwrap();
+            let padding = if i < link_paddings.len() { link_paddings[i] } else { 0.0 };
+            for (center, radius) in &self._link_spheres[i] {
+                out_vec.push( (i, link_frame.multiply_by_vector3(center), *radius + padding) );
+            }
+        }
+
+        return out_vec;
+    }
+
+    pub fn get_num_spheres(&self) -> usize {
+        return self._link_spheres.iter().map(|x| x.len()).sum();
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    fn _get_spheres_covering_collision_object(c: &CollisionObject) -> Vec<(Vector3<f64>, f64)> {
+        let mut out_vec = Vec::new();
+
+        let ball = c.shape.as_shape::<Ball<f64>>();
+        if ball.is_some() {
+            out_vec.push( (c.base_pose.translation.clone(), ball.unwrap().radius()) );
+            return out_vec;
+        }
+
+        let aabb = &c.base_bounding_aabb;
+        let center = aabb.center().coords;
+        let half_extents = aabb.half_extents();
+
+        let mut long_axis = 0;
+        for k in 1..3 { if half_extents[k] > half_extents[long_axis] { long_axis = k; } }
+        let mut short_axes = Vec::new();
+        for k in 0..3 { if k != long_axis { short_axes.push(k); } }
+
+        let a = half_extents[long_axis];
+        let b = half_extents[short_axes[0]].max(half_extents[short_axes[1]]);
+        let num_spheres = if b <= 0.0 { 1 } else { ((a / b).ceil() as usize).max(1) };
+        let segment_half_length = a / num_spheres as f64;
+        let radius = (segment_half_length.powi(2) + half_extents[short_axes[0]].powi(2) + half_extents[short_axes[1]].powi(2)).sqrt();
+
+        for m in 0..num_spheres {
+            let mut local_center = center.clone();
+            local_center[long_axis] += -a + (2 * m + 1) as f64 * segment_half_length;
+            out_vec.push( (c.base_pose.multiply_by_vector3(&local_center), radius) );
+        }
+
+        return out_vec;
+    }
+}
@@ -10,11 +10,13 @@ use stl_io;
 use collada::document::ColladaDocument;
 use collada::{*};
 use std::fs;
+use std::collections::{HashMap, HashSet};
 use ncollide3d::procedural;
 use nalgebra::geometry::Point3;
 use ncollide3d::transformation;
 // use meshopt;
 use crate::utils::utils_pointclouds::pointcloud_data_utils::*;
+use crate::utils::utils_files_and_strings::{ply_utils::*, gltf_utils::*};
 use crate::utils::utils_math::geometry_utils::*;
 use crate::utils::utils_sampling::prelude::*;
 use crate::utils::utils_math::geometry_utils::*;
@@ -22,7 +24,7 @@ use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
 
 
 /*
-This struct parses .dae, .obj, and .stl files and converts them into tri meshes
+This struct parses .dae, .obj, .stl, .ply, .gltf, and .glb files and converts them into tri meshes
 */
 #[derive(Clone, Debug)]
 pub struct TriMeshEngine {
@@ -51,6 +53,10 @@ impl TriMeshEngine {
             return Self::new_from_dae_path(fp);
         } else if (ext_unwrap == "stl") || (ext_unwrap == "STL") {
             return Self::new_from_stl_path(fp);
+        } else if ext_unwrap == "ply" || ext_unwrap == "PLY" {
+            return Self::new_from_ply_path(fp);
+        } else if ext_unwrap == "gltf" || ext_unwrap == "glb" || ext_unwrap == "GLTF" || ext_unwrap == "GLB" {
+            return Self::new_from_gltf_path(fp);
         } else {
             println!("{}{}WARNING: {:?} invalid file type in TriMeshParser{}", color::Fg(color::Yellow), style::Bold, fp, style::Reset);
             return Err(format!("{:?} invalid file type in TriMeshParser", fp));
@@ -244,6 +250,65 @@ impl TriMeshEngine {
         return Ok(Self {vertices, indices})
     }
 
+    // polygonal faces are triangulated as fans around their first vertex
+    pub fn new_from_ply_path(fp: String) -> Result<Self, String> {
+        let ply_data = read_ply_file(fp.clone())?;
+
+        let vertex_element = ply_data.get_element_ref("vertex");
+        if vertex_element.is_none() { return Err(format!("no vertex element in ply file {:?}", fp)); }
+        let vertex_element = vertex_element.unwrap();
+        let x_idx = vertex_element.get_property_idx("x");
+        let y_idx = vertex_element.get_property_idx("y");
+        let z_idx = vertex_element.get_property_idx("z");
+        if x_idx.is_none() || y_idx.is_none() || z_idx.is_none() { return Err(format!("vertex element in ply file {:?} must have x, y, and z properties", fp)); }
+
+        let mut vertices = Vec::new();
+        for row in &vertex_element.rows {
+            vertices.push( Point::new(row[x_idx.unwrap()][0], row[y_idx.unwrap()][0], row[z_idx.unwrap()][0]) );
+        }
+
+        let mut indices = Vec::new();
+        let face_element = ply_data.get_element_ref("face");
+        if face_element.is_some() {
+            let face_element = face_element.unwrap();
+            let mut list_idx = face_element.get_property_idx("vertex_indices");
+            if list_idx.is_none() { list_idx = face_element.get_property_idx("vertex_index"); }
+            if list_idx.is_none() { return Err(format!("face element in ply file {:?} must have a vertex_indices property", fp)); }
+
+            for row in &face_element.rows {
+                let face = &row[list_idx.unwrap()];
+                let l = face.len();
+                for j in 1..l.max(2)-1 {
+                    let idx = Point::new( face[0] as usize, face[j] as usize, face[j+1] as usize );
+                    if idx[0] >= vertices.len() || idx[1] >= vertices.len() || idx[2] >= vertices.len() {
+                        return Err(format!("face index out of range in ply file {:?}", fp));
+                    }
+                    indices.push(idx);
+                }
+            }
+        }
+
+        if vertices.len() == 0 {
+            return Err(format!("zero vertices parsed from {:?} .ply file in TriMeshEngine parser", fp));
+        }
+
+        return Ok(Self {vertices, indices})
+    }
+
+    // node transforms are applied, and all triangle primitives in the default scene are merged into one mesh
+    pub fn new_from_gltf_path(fp: String) -> Result<Self, String> {
+        let (_vertices, _indices) = read_gltf_triangles(fp.clone())?;
+
+        let vertices: Vec<Point<f64>> = _vertices.iter().map(|v| Point::new(v[0], v[1], v[2])).collect();
+        let indices: Vec<Point<usize>> = _indices.iter().map(|t| Point::new(t[0], t[1], t[2])).collect();
+
+        if vertices.len() == 0 {
+            return Err(format!("zero vertices parsed from {:?} gltf file in TriMeshEngine parser", fp));
+        }
+
+        return Ok(Self {vertices, indices})
+    }
+
     pub fn new_from_procedural_trimesh(procedural_trimesh: &procedural::TriMesh<f64>) -> Self {
         let mut vertices = Vec::new();
         let mut indices = Vec::new();
@@ -363,6 +428,30 @@ impl TriMeshEngine {
         }
     }
 
+    pub fn output_to_ply(&self, out_fp: String, binary: bool) -> Result<(), String> {
+        let mut vertex_element = PlyElement::new("vertex", vec![PlyProperty::new_scalar("x", "float"), PlyProperty::new_scalar("y", "float"), PlyProperty::new_scalar("z", "float")]);
+        for v in &self.vertices {
+            vertex_element.rows.push( vec![vec![v[0]], vec![v[1]], vec![v[2]]] );
+        }
+
+        let mut face_element = PlyElement::new("face", vec![PlyProperty::new_list("vertex_indices", "uchar", "int")]);
+        for t in &self.indices {
+            face_element.rows.push( vec![vec![t[0] as f64, t[1] as f64, t[2] as f64]] );
+        }
+
+        let mut ply_data = PlyData::new_empty();
+        ply_data.elements.push(vertex_element);
+        ply_data.elements.push(face_element);
+        return write_ply_file(out_fp, &ply_data, binary);
+    }
+
+    // writes a .glb file if out_fp ends in .glb, and a .gltf file with an embedded buffer otherwise
+    pub fn output_to_gltf(&self, out_fp: String) -> Result<(), String> {
+        let vertices = self.vertices.iter().map(|v| [v[0], v[1], v[2]]).collect();
+        let indices = self.indices.iter().map(|t| [t[0], t[1], t[2]]).collect();
+        return write_gltf_file(out_fp, &vertices, &indices);
+    }
+
     pub fn to_procedural_trimesh(&self) -> procedural::TriMesh<f64> {
         let mut coords = self.vertices.clone();
         let mut index_buffer: Vec<Point3<u32>> = Vec::new();
@@ -395,6 +484,62 @@ impl TriMeshEngine {
         return TriMeshEngine::new_from_procedural_trimesh(&ch);
     }
 
+    /* vertex clustering: vertices are snapped to a uniform grid (all vertices in a cell are merged into their average), and
+    triangles that collapse are dropped.  The cell size is found by bisection, so the output has at most target_num_triangles
+    triangles while keeping as much detail as possible. */
+    pub fn simplify_to_num_triangles(&self, target_num_triangles: usize) -> TriMeshEngine {
+        if self.indices.len() <= target_num_triangles || self.vertices.is_empty() { return self.clone(); }
+
+        let mut mins = Vector3::new(std::f64::INFINITY, std::f64::INFINITY, std::f64::INFINITY);
+        let mut maxs = -mins.clone();
+        for v in &self.vertices {
+            mins = mins.inf(&v.coords);
+            maxs = maxs.sup(&v.coords);
+        }
+        let diagonal = (maxs - mins).norm();
+        if diagonal == 0.0 { return self.clone(); }
+
+        let mut lower = 0.0;
+        let mut upper = diagonal;
+        let mut out_trimesh = self._cluster_vertices(upper);
+        for _ in 0..30 {
+            let mid = (lower + upper) / 2.0;
+            let t = self._cluster_vertices(mid);
+            if t.indices.len() <= target_num_triangles { out_trimesh = t; upper = mid; }
+            else { lower = mid; }
+        }
+
+        return out_trimesh;
+    }
+
+    fn _cluster_vertices(&self, cell_size: f64) -> TriMeshEngine {
+        let mut cell_to_idx: HashMap<(i64, i64, i64), usize> = HashMap::new();
+        let mut sums: Vec<(Vector3<f64>, f64)> = Vec::new();
+        let mut vertex_map = Vec::new();
+        for v in &self.vertices {
+            let cell = ((v[0] / cell_size).floor() as i64, (v[1] / cell_size).floor() as i64, (v[2] / cell_size).floor() as i64);
+            let idx = *cell_to_idx.entry(cell).or_insert(sums.len());
+            if idx == sums.len() { sums.push( (Vector3::zeros(), 0.0) ); }
+            sums[idx].0 += v.coords;
+            sums[idx].1 += 1.0;
+            vertex_map.push(idx);
+        }
+
+        let vertices = sums.iter().map(|(sum, count)| Point::from(sum / *count)).collect();
+        let mut indices = Vec::new();
+        let mut seen = HashSet::new();
+        for t in &self.indices {
+            let (a, b, c) = (vertex_map[t[0]], vertex_map[t[1]], vertex_map[t[2]]);
+            if a == b || b == c || a == c { continue; }
+            let mut key = [a, b, c];
+            key.sort();
+            if !seen.insert(key) { continue; }
+            indices.push( Point::new(a, b, c) );
+        }
+
+        return TriMeshEngine::new(vertices, indices);
+    }
+
     pub fn compute_center(&self) -> Vector3<f64> {
         let mut center = Vector3::zeros();
         let mut count = 0.0;
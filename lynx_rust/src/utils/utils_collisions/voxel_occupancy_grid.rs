@@ -0,0 +1,244 @@
+use crate::utils::utils_collisions::{collision_object::CollisionObject, triangle_mesh_engine::TriMeshEngine};
+use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
+use crate::utils::utils_pointclouds::pointcloud::PointCloud;
+use ncollide3d::math::Point;
+use nalgebra::Vector3;
+use std::collections::{HashMap, HashSet};
+
+
+/* hashed voxel grid that stores occupancy as log-odds.  Point clouds are integrated with a sensor origin: the voxel
+containing each point is marked as a hit, and all voxels along the ray from the sensor origin to the point are
+marked as misses (free space ray clearing).  Voxels that have never been observed are unknown and treated as free. */
+#[derive(Clone, Debug)]
+pub struct VoxelOccupancyGrid {
+    _resolution: f64,
+    _log_odds: HashMap<[i64; 3], f64>,
+    _log_odds_hit: f64,
+    _log_odds_miss: f64,
+    _log_odds_min: f64,
+    _log_odds_max: f64,
+    _log_odds_occupied_threshold: f64
+}
+
+impl VoxelOccupancyGrid {
+    pub fn new(resolution: f64) -> Result<Self, String> {
+        return Self::new_with_sensor_model(resolution, 0.7, 0.4, 0.5);
+    }
+
+    pub fn new_with_sensor_model(resolution: f64, probability_hit: f64, probability_miss: f64, occupied_probability_threshold: f64) -> Result<Self, String> {
+        if resolution <= 0.0 { return Err(format!("resolution must be positive in voxel occupancy grid (got {:?})", resolution)); }
+        if !(probability_hit > 0.5 && probability_hit < 1.0) { return Err(format!("probability_hit must be in (0.5, 1.0) (got {:?})", probability_hit)); }
+        if !(probability_miss > 0.0 && probability_miss < 0.5) { return Err(format!("probability_miss must be in (0.0, 0.5) (got {:?})", probability_miss)); }
+        if !(occupied_probability_threshold > 0.0 && occupied_probability_threshold < 1.0) { return Err(format!("occupied_probability_threshold must be in (0.0, 1.0) (got {:?})", occupied_probability_threshold)); }
+
+        return Ok(Self {
+            _resolution: resolution,
+            _log_odds: HashMap::new(),
+            _log_odds_hit: Self::_probability_to_log_odds(probability_hit),
+            _log_odds_miss: Self::_probability_to_log_odds(probability_miss),
+            _log_odds_min: Self::_probability_to_log_odds(0.12),
+            _log_odds_max: Self::_probability_to_log_odds(0.97),
+            _log_odds_occupied_threshold: Self::_probability_to_log_odds(occupied_probability_threshold)
+        });
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // points farther than max_range from the sensor origin only clear free space up to max_range and are not marked as hits.
+    // Each voxel is updated at most once per point cloud, with hits taking precedence over misses.
+    pub fn insert_point_cloud(&mut self, point_cloud: &PointCloud, sensor_origin: &Vector3<f64>, max_range: Option<f64>) {
+        let mut hit_keys = HashSet::new();
+        let mut miss_keys = HashSet::new();
+
+        for p in point_cloud.get_points_ref() {
+            let mut end_point = p.clone();
+            let mut is_hit = true;
+            if max_range.is_some() {
+                let dir = p - sensor_origin;
+                let dis = dir.norm();
+                if dis > max_range.unwrap() {
+                    end_point = sensor_origin + dir * (max_range.unwrap() / dis);
+                    is_hit = false;
+                }
+            }
+
+            let ray_keys = self._get_ray_keys(sensor_origin, &end_point);
+            for k in ray_keys { miss_keys.insert(k); }
+
+            let end_key = self.get_key(&end_point);
+            if is_hit { hit_keys.insert(end_key); } else { miss_keys.insert(end_key); }
+        }
+
+        for k in &hit_keys {
+            self._update_log_odds(k, self._log_odds_hit);
+        }
+        for k in &miss_keys {
+            if hit_keys.contains(k) { continue; }
+            self._update_log_odds(k, self._log_odds_miss);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self._log_odds.clear();
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn get_key(&self, point: &Vector3<f64>) -> [i64; 3] {
+        return [ (point[0] / self._resolution).floor() as i64,
+                 (point[1] / self._resolution).floor() as i64,
+                 (point[2] / self._resolution).floor() as i64 ];
+    }
+
+    pub fn get_voxel_center(&self, key: &[i64; 3]) -> Vector3<f64> {
+        return Vector3::new( (key[0] as f64 + 0.5) * self._resolution,
+                             (key[1] as f64 + 0.5) * self._resolution,
+                             (key[2] as f64 + 0.5) * self._resolution );
+    }
+
+    // None if the voxel containing the point has never been observed
+    pub fn get_occupancy_probability(&self, point: &Vector3<f64>) -> Option<f64> {
+        let log_odds = self._log_odds.get(&self.get_key(point));
+        if log_odds.is_none() { return None; }
+        return Some( 1.0 - 1.0 / (1.0 + log_odds.unwrap().exp()) );
+    }
+
+    pub fn is_occupied(&self, point: &Vector3<f64>) -> bool {
+        return self._is_key_occupied(&self.get_key(point));
+    }
+
+    pub fn is_known(&self, point: &Vector3<f64>) -> bool {
+        return self._log_odds.contains_key(&self.get_key(point));
+    }
+
+    pub fn get_occupied_keys(&self) -> Vec<[i64; 3]> {
+        let mut out_vec: Vec<[i64; 3]> = self._log_odds.keys().filter(|k| self._is_key_occupied(k)).map(|k| k.clone()).collect();
+        out_vec.sort();
+        return out_vec;
+    }
+
+    pub fn get_occupied_voxel_centers(&self) -> Vec<Vector3<f64>> {
+        return self.get_occupied_keys().iter().map(|k| self.get_voxel_center(k)).collect();
+    }
+
+    pub fn get_num_occupied_voxels(&self) -> usize {
+        return self._log_odds.keys().filter(|k| self._is_key_occupied(k)).count();
+    }
+
+    pub fn get_num_known_voxels(&self) -> usize {
+        return self._log_odds.len();
+    }
+
+    pub fn get_resolution(&self) -> f64 {
+        return self._resolution;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // one cuboid collision object (and matching box triangle mesh) per occupied voxel, so the grid can be
+    // represented as an object in a CollisionEnvironment
+    pub fn get_occupied_voxel_collision_objects(&self, name: &str) -> (Vec<CollisionObject>, Vec<TriMeshEngine>) {
+        return self.get_voxel_collision_objects(name, &self.get_occupied_keys());
+    }
+
+    // same as above for the given voxels, whether or not they are occupied.  Components are named by voxel key.
+    pub fn get_voxel_collision_objects(&self, name: &str, keys: &Vec<[i64; 3]>) -> (Vec<CollisionObject>, Vec<TriMeshEngine>) {
+        let mut collision_objects = Vec::new();
+        let mut trimesh_engines = Vec::new();
+
+        let h = self._resolution / 2.0;
+        for k in keys {
+            let center = self.get_voxel_center(k);
+            let voxel_name = format!("{}_{:?}_{:?}_{:?}", name, k[0], k[1], k[2]);
+            collision_objects.push( CollisionObject::new_cuboid(h, h, h, Some(ImplicitDualQuaternion::new_from_euler_angles(0., 0., 0., center.clone())), Some(voxel_name)) );
+            trimesh_engines.push( Self::_get_voxel_trimesh_engine(&center, h) );
+        }
+
+        return (collision_objects, trimesh_engines);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    fn _is_key_occupied(&self, key: &[i64; 3]) -> bool {
+        let log_odds = self._log_odds.get(key);
+        if log_odds.is_none() { return false; }
+        return *log_odds.unwrap() > self._log_odds_occupied_threshold;
+    }
+
+    fn _update_log_odds(&mut self, key: &[i64; 3], delta: f64) {
+        let (log_odds_min, log_odds_max) = (self._log_odds_min, self._log_odds_max);
+        let entry = self._log_odds.entry(key.clone()).or_insert(0.0);
+        *entry = (*entry + delta).max(log_odds_min).min(log_odds_max);
+    }
+
+    // 3D digital differential analyzer (Amanatides & Woo).  Returns all voxels the segment passes through, excluding the
+    // voxel containing end.
+    fn _get_ray_keys(&self, start: &Vector3<f64>, end: &Vector3<f64>) -> Vec<[i64; 3]> {
+        let mut out_vec = Vec::new();
+
+        let mut curr_key = self.get_key(start);
+        let end_key = self.get_key(end);
+        if curr_key == end_key { return out_vec; }
+
+        let dir = end - start;
+        let length = dir.norm();
+        let dir_unit = dir / length;
+
+        let mut step = [0 as i64; 3];
+        let mut t_max = [std::f64::INFINITY; 3];
+        let mut t_delta = [std::f64::INFINITY; 3];
+        for k in 0..3 {
+            if dir_unit[k] > 0.0 {
+                step[k] = 1;
+                let boundary = (curr_key[k] + 1) as f64 * self._resolution;
+                t_max[k] = (boundary - start[k]) / dir_unit[k];
+                t_delta[k] = self._resolution / dir_unit[k];
+            } else if dir_unit[k] < 0.0 {
+                step[k] = -1;
+                let boundary = curr_key[k] as f64 * self._resolution;
+                t_max[k] = (boundary - start[k]) / dir_unit[k];
+                t_delta[k] = -self._resolution / dir_unit[k];
+            }
+        }
+
+        loop {
+            out_vec.push(curr_key.clone());
+
+            let mut axis = 0;
+            if t_max[1] < t_max[axis] { axis = 1; }
+            if t_max[2] < t_max[axis] { axis = 2; }
+
+            if t_max[axis] > length { break; }
+
+            curr_key[axis] += step[axis];
+            t_max[axis] += t_delta[axis];
+
+            if curr_key == end_key { break; }
+        }
+
+        return out_vec;
+    }
+
+    fn _get_voxel_trimesh_engine(center: &Vector3<f64>, h: f64) -> TriMeshEngine {
+        let mut vertices = Vec::new();
+        for i in 0..8 {
+            let x = if i & 1 == 0 { -h } else { h };
+            let y = if i & 2 == 0 { -h } else { h };
+            let z = if i & 4 == 0 { -h } else { h };
+            vertices.push( Point::new(center[0] + x, center[1] + y, center[2] + z) );
+        }
+
+        let indices = vec![ Point::new(0, 2, 1), Point::new(1, 2, 3),
+                            Point::new(4, 5, 6), Point::new(5, 7, 6),
+                            Point::new(0, 1, 4), Point::new(1, 5, 4),
+                            Point::new(2, 6, 3), Point::new(3, 6, 7),
+                            Point::new(0, 4, 2), Point::new(2, 4, 6),
+                            Point::new(1, 3, 5), Point::new(3, 7, 5) ];
+
+        return TriMeshEngine::new(vertices, indices);
+    }
+
+    fn _probability_to_log_odds(p: f64) -> f64 {
+        return (p / (1.0 - p)).ln();
+    }
+}
@@ -0,0 +1,341 @@
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use nalgebra::{Matrix4, Vector3, Vector4, Quaternion, UnitQuaternion, U3};
+use crate::utils::utils_files_and_strings::ply_utils::read_binary_value;
+
+
+/* minimal reader and writer for triangle geometry in .gltf (with embedded or external buffers) and .glb files.  Only
+positions and triangle indices are read; materials, normals, textures, skins, and animations are ignored.  All triangle
+primitives in the default scene are merged into a single mesh, with each node's transform (matrix or translation / rotation /
+scale, accumulated down the node hierarchy) applied to its vertices. */
+
+const GLB_MAGIC: &[u8] = b"glTF";
+const GLB_JSON_CHUNK_TYPE: u32 = 0x4E4F534A;
+const GLB_BIN_CHUNK_TYPE: u32 = 0x004E4942;
+
+const COMPONENT_TYPE_UNSIGNED_BYTE: u64 = 5121;
+const COMPONENT_TYPE_UNSIGNED_SHORT: u64 = 5123;
+const COMPONENT_TYPE_UNSIGNED_INT: u64 = 5125;
+const COMPONENT_TYPE_FLOAT: u64 = 5126;
+const PRIMITIVE_MODE_TRIANGLES: u64 = 4;
+
+pub fn read_gltf_triangles(fp: String) -> Result<(Vec<[f64; 3]>, Vec<[usize; 3]>), String> {
+    let bytes = fs::read(fp.clone());
+    if bytes.is_err() { return Err(format!("could not open gltf file {:?}", fp)); }
+    let bytes = bytes.unwrap();
+
+    let (json_string, glb_bin_chunk) = if bytes.len() >= 4 && &bytes[0..4] == GLB_MAGIC { _split_glb(&bytes, &fp)? } else { (String::from_utf8_lossy(&bytes).to_string(), None) };
+
+    let gltf: Result<serde_json::Value, _> = serde_json::from_str(&json_string);
+    if gltf.is_err() { return Err(format!("could not parse gltf json in file {:?}: {:?}", fp, gltf.err().unwrap().to_string())); }
+    let gltf = gltf.unwrap();
+
+    let directory = Path::new(&fp).parent().map(|p| p.to_path_buf()).unwrap_or(Path::new(".").to_path_buf());
+    let mut buffers = Vec::new();
+    for (i, b) in _get_array(&gltf, "buffers").iter().enumerate() {
+        match b.get("uri").and_then(|u| u.as_str()) {
+            Some(uri) => {
+                if uri.starts_with("data:") {
+                    let comma = uri.find(',');
+                    if comma.is_none() || !uri[0..comma.unwrap()].ends_with(";base64") { return Err(format!("buffer {:?} in gltf file {:?} has an unsupported data uri", i, fp)); }
+                    buffers.push( base64_decode(&uri[comma.unwrap() + 1..])? );
+                } else {
+                    let buffer_fp = directory.join(uri);
+                    let buffer = fs::read(&buffer_fp);
+                    if buffer.is_err() { return Err(format!("could not open buffer {:?} referenced by gltf file {:?}", buffer_fp, fp)); }
+                    buffers.push(buffer.unwrap());
+                }
+            }
+            None => {
+                if glb_bin_chunk.is_none() { return Err(format!("buffer {:?} in gltf file {:?} has no uri and there is no binary chunk", i, fp)); }
+                buffers.push(glb_bin_chunk.clone().unwrap());
+            }
+        }
+    }
+
+    // nodes of the default scene, or all root nodes if the file has no scenes
+    let nodes = _get_array(&gltf, "nodes");
+    let mut root_nodes = Vec::new();
+    let scenes = _get_array(&gltf, "scenes");
+    if !scenes.is_empty() {
+        let scene_idx = gltf.get("scene").and_then(|s| s.as_u64()).unwrap_or(0) as usize;
+        if scene_idx >= scenes.len() { return Err(format!("default scene {:?} does not exist in gltf file {:?}", scene_idx, fp)); }
+        for n in _get_array(&scenes[scene_idx], "nodes") { root_nodes.push(_as_idx(n, &fp)?); }
+    } else {
+        let mut is_child = vec![false; nodes.len()];
+        for n in nodes { for c in _get_array(n, "children") { let c = _as_idx(c, &fp)?; if c < is_child.len() { is_child[c] = true; } } }
+        for i in 0..nodes.len() { if !is_child[i] { root_nodes.push(i); } }
+    }
+
+    let mut out_vertices = Vec::new();
+    let mut out_indices = Vec::new();
+    let mut stack: Vec<(usize, Matrix4<f64>, usize)> = root_nodes.iter().map(|n| (*n, Matrix4::identity(), 0)).collect();
+    while !stack.is_empty() {
+        let (node_idx, parent_transform, depth) = stack.pop().unwrap();
+        if node_idx >= nodes.len() { return Err(format!("node {:?} does not exist in gltf file {:?}", node_idx, fp)); }
+        if depth > nodes.len() { return Err(format!("node hierarchy in gltf file {:?} has a cycle", fp)); }
+        let node = &nodes[node_idx];
+        let transform = parent_transform * _get_node_local_transform(node, &fp)?;
+
+        if let Some(mesh_idx) = node.get("mesh") {
+            let mesh_idx = _as_idx(mesh_idx, &fp)?;
+            let meshes = _get_array(&gltf, "meshes");
+            if mesh_idx >= meshes.len() { return Err(format!("mesh {:?} does not exist in gltf file {:?}", mesh_idx, fp)); }
+
+            for primitive in _get_array(&meshes[mesh_idx], "primitives") {
+                let mode = primitive.get("mode").and_then(|m| m.as_u64()).unwrap_or(PRIMITIVE_MODE_TRIANGLES);
+                if mode != PRIMITIVE_MODE_TRIANGLES { continue; }
+
+                let position_accessor = primitive.get("attributes").and_then(|a| a.get("POSITION"));
+                if position_accessor.is_none() { continue; }
+                let positions = _read_accessor(&gltf, &buffers, _as_idx(position_accessor.unwrap(), &fp)?, &fp)?;
+
+                let offset = out_vertices.len();
+                for p in &positions {
+                    if p.len() != 3 { return Err(format!("POSITION accessor in gltf file {:?} must be VEC3", fp)); }
+                    let v = transform * Vector4::new(p[0], p[1], p[2], 1.0);
+                    out_vertices.push([v[0], v[1], v[2]]);
+                }
+
+                let indices: Vec<usize> = match primitive.get("indices") {
+                    Some(a) => { _read_accessor(&gltf, &buffers, _as_idx(a, &fp)?, &fp)?.iter().map(|x| x[0] as usize).collect() }
+                    None => { (0..positions.len()).collect() }
+                };
+                // mirrored transforms flip triangle winding, so flip it back
+                let flip = transform.fixed_slice::<U3, U3>(0, 0).determinant() < 0.0;
+                for t in indices.chunks(3) {
+                    if t.len() < 3 { break; }
+                    if t.iter().any(|x| *x >= positions.len()) { return Err(format!("triangle index out of range in gltf file {:?}", fp)); }
+                    if flip { out_indices.push([offset + t[0], offset + t[2], offset + t[1]]); }
+                    else { out_indices.push([offset + t[0], offset + t[1], offset + t[2]]); }
+                }
+            }
+        }
+
+        for c in _get_array(node, "children") { stack.push( (_as_idx(c, &fp)?, transform.clone(), depth + 1) ); }
+    }
+
+    return Ok((out_vertices, out_indices));
+}
+
+// writes a single mesh with a single node.  .glb files get a binary chunk, and .gltf files get the buffer embedded as a base64 data uri
+pub fn write_gltf_file(fp: String, vertices: &Vec<[f64; 3]>, indices: &Vec<[usize; 3]>) -> Result<(), String> {
+    let ext = Path::new(&fp).extension().map(|e| e.to_str().unwrap().to_lowercase());
+    let binary = match ext.as_ref().map(|e| e.as_str()) {
+        Some("glb") => true,
+        Some("gltf") => false,
+        _ => { return Err(format!("gltf file {:?} must have a .gltf or .glb extension", fp)); }
+    };
+
+    let mut buffer = Vec::new();
+    let mut min = [std::f64::INFINITY; 3];
+    let mut max = [-std::f64::INFINITY; 3];
+    for v in vertices {
+        for k in 0..3 {
+            buffer.extend_from_slice( &(v[k] as f32).to_le_bytes() );
+            min[k] = min[k].min(v[k] as f32 as f64);
+            max[k] = max[k].max(v[k] as f32 as f64);
+        }
+    }
+    if vertices.is_empty() { min = [0.0; 3]; max = [0.0; 3]; }
+    let positions_length = buffer.len();
+    for t in indices {
+        for k in 0..3 {
+            if t[k] >= vertices.len() { return Err(format!("triangle index {:?} out of range for {:?} vertices", t[k], vertices.len())); }
+            buffer.extend_from_slice( &(t[k] as u32).to_le_bytes() );
+        }
+    }
+    let indices_length = buffer.len() - positions_length;
+
+    let mut buffer_json = serde_json::json!({ "byteLength": buffer.len() });
+    if !binary { buffer_json["uri"] = serde_json::Value::String( "data:application/octet-stream;base64,".to_string() + base64_encode(&buffer).as_str() ); }
+
+    let gltf = serde_json::json!({
+        "asset": { "version": "2.0", "generator": "lynx" },
+        "scene": 0,
+        "scenes": [ { "nodes": [0] } ],
+        "nodes": [ { "mesh": 0 } ],
+        "meshes": [ { "primitives": [ { "attributes": { "POSITION": 0 }, "indices": 1, "mode": PRIMITIVE_MODE_TRIANGLES } ] } ],
+        "accessors": [
+            { "bufferView": 0, "componentType": COMPONENT_TYPE_FLOAT, "count": vertices.len(), "type": "VEC3", "min": min.to_vec(), "max": max.to_vec() },
+            { "bufferView": 1, "componentType": COMPONENT_TYPE_UNSIGNED_INT, "count": 3 * indices.len(), "type": "SCALAR" }
+        ],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": positions_length, "target": 34962 },
+            { "buffer": 0, "byteOffset": positions_length, "byteLength": indices_length, "target": 34963 }
+        ],
+        "buffers": [ buffer_json ]
+    });
+
+    let mut out_bytes = Vec::new();
+    if binary {
+        // chunks must be 4 byte aligned; json is padded with spaces and binary data with zeros
+        let mut json_bytes = serde_json::to_string(&gltf).unwrap().into_bytes();
+        while json_bytes.len() % 4 != 0 { json_bytes.push(b' '); }
+        while buffer.len() % 4 != 0 { buffer.push(0); }
+
+        let total_length = 12 + 8 + json_bytes.len() + 8 + buffer.len();
+        out_bytes.extend_from_slice(GLB_MAGIC);
+        out_bytes.extend_from_slice(&(2 as u32).to_le_bytes());
+        out_bytes.extend_from_slice(&(total_length as u32).to_le_bytes());
+        out_bytes.extend_from_slice(&(json_bytes.len() as u32).to_le_bytes());
+        out_bytes.extend_from_slice(&GLB_JSON_CHUNK_TYPE.to_le_bytes());
+        out_bytes.append(&mut json_bytes);
+        out_bytes.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+        out_bytes.extend_from_slice(&GLB_BIN_CHUNK_TYPE.to_le_bytes());
+        out_bytes.append(&mut buffer);
+    } else {
+        out_bytes = serde_json::to_string_pretty(&gltf).unwrap().into_bytes();
+    }
+
+    let file = File::create(fp.clone());
+    if file.is_err() { return Err(format!("could not create gltf file {:?}", fp)); }
+    let res = file.unwrap().write_all(&out_bytes);
+    if res.is_err() { return Err(format!("could not write gltf file {:?}", fp)); }
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn base64_encode(bytes: &[u8]) -> String {
+    let mut out_string = String::new();
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | (b[2] as u32);
+        for k in 0..4 {
+            if k <= chunk.len() { out_string.push( BASE64_ALPHABET[((n >> (18 - 6 * k)) & 63) as usize] as char ); }
+            else { out_string.push('='); }
+        }
+    }
+    return out_string;
+}
+
+pub fn base64_decode(s: &str) -> Result<Vec<u8>, String> {
+    let mut out_bytes = Vec::new();
+    let mut n = 0 as u32;
+    let mut num_bits = 0;
+    for c in s.bytes() {
+        if c == b'=' { break; }
+        if c.is_ascii_whitespace() { continue; }
+        let v = BASE64_ALPHABET.iter().position(|x| *x == c);
+        if v.is_none() { return Err(format!("invalid character {:?} in base64 data", c as char)); }
+        n = (n << 6) | v.unwrap() as u32;
+        num_bits += 6;
+        if num_bits >= 8 {
+            num_bits -= 8;
+            out_bytes.push( ((n >> num_bits) & 255) as u8 );
+        }
+    }
+    return Ok(out_bytes);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn _split_glb(bytes: &Vec<u8>, fp: &String) -> Result<(String, Option<Vec<u8>>), String> {
+    let read_u32 = |i: usize| -> u32 { u32::from_le_bytes([bytes[i], bytes[i+1], bytes[i+2], bytes[i+3]]) };
+    if bytes.len() < 20 { return Err(format!("glb file {:?} is too short", fp)); }
+
+    let mut json_string = None;
+    let mut bin_chunk = None;
+    let mut cursor = 12;
+    while cursor + 8 <= bytes.len() {
+        let chunk_length = read_u32(cursor) as usize;
+        let chunk_type = read_u32(cursor + 4);
+        let start = cursor + 8;
+        if start + chunk_length > bytes.len() { return Err(format!("chunk in glb file {:?} runs past the end of the file", fp)); }
+        if chunk_type == GLB_JSON_CHUNK_TYPE && json_string.is_none() { json_string = Some(String::from_utf8_lossy(&bytes[start..start + chunk_length]).to_string()); }
+        else if chunk_type == GLB_BIN_CHUNK_TYPE && bin_chunk.is_none() { bin_chunk = Some(bytes[start..start + chunk_length].to_vec()); }
+        cursor = start + chunk_length;
+    }
+
+    if json_string.is_none() { return Err(format!("glb file {:?} has no json chunk", fp)); }
+    return Ok((json_string.unwrap(), bin_chunk));
+}
+
+fn _get_array<'a>(v: &'a serde_json::Value, key: &str) -> &'a [serde_json::Value] {
+    return v.get(key).and_then(|a| a.as_array()).map(|a| a.as_slice()).unwrap_or(&[]);
+}
+
+fn _as_idx(v: &serde_json::Value, fp: &String) -> Result<usize, String> {
+    let idx = v.as_u64();
+    if idx.is_none() { return Err(format!("expected an index, but got {:?} in gltf file {:?}", v, fp)); }
+    return Ok(idx.unwrap() as usize);
+}
+
+fn _get_f64s(v: &serde_json::Value, key: &str, expected_len: usize, fp: &String) -> Result<Option<Vec<f64>>, String> {
+    let a = v.get(key);
+    if a.is_none() { return Ok(None); }
+    let values: Vec<Option<f64>> = a.unwrap().as_array().map(|a| a.iter().map(|x| x.as_f64()).collect()).unwrap_or(Vec::new());
+    if values.len() != expected_len || values.iter().any(|x| x.is_none()) { return Err(format!("node {:?} in gltf file {:?} must have {:?} numbers", key, fp, expected_len)); }
+    return Ok(Some(values.iter().map(|x| x.unwrap()).collect()));
+}
+
+fn _get_node_local_transform(node: &serde_json::Value, fp: &String) -> Result<Matrix4<f64>, String> {
+    if let Some(m) = _get_f64s(node, "matrix", 16, fp)? {
+        return Ok(Matrix4::from_column_slice(&m));
+    }
+
+    let t = _get_f64s(node, "translation", 3, fp)?.unwrap_or(vec![0., 0., 0.]);
+    let r = _get_f64s(node, "rotation", 4, fp)?.unwrap_or(vec![0., 0., 0., 1.]);
+    let s = _get_f64s(node, "scale", 3, fp)?.unwrap_or(vec![1., 1., 1.]);
+
+    // gltf quaternions are stored as [x, y, z, w]
+    let rotation = UnitQuaternion::from_quaternion(Quaternion::new(r[3], r[0], r[1], r[2]));
+    let mut out_mat = Matrix4::new_nonuniform_scaling(&Vector3::new(s[0], s[1], s[2]));
+    out_mat = rotation.to_homogeneous() * out_mat;
+    out_mat = Matrix4::new_translation(&Vector3::new(t[0], t[1], t[2])) * out_mat;
+    return Ok(out_mat);
+}
+
+fn _read_accessor(gltf: &serde_json::Value, buffers: &Vec<Vec<u8>>, accessor_idx: usize, fp: &String) -> Result<Vec<Vec<f64>>, String> {
+    let accessors = _get_array(gltf, "accessors");
+    if accessor_idx >= accessors.len() { return Err(format!("accessor {:?} does not exist in gltf file {:?}", accessor_idx, fp)); }
+    let accessor = &accessors[accessor_idx];
+    if accessor.get("sparse").is_some() { return Err(format!("sparse accessors are not supported (accessor {:?} in gltf file {:?})", accessor_idx, fp)); }
+
+    let count = accessor.get("count").and_then(|c| c.as_u64()).unwrap_or(0) as usize;
+    let num_components = match accessor.get("type").and_then(|t| t.as_str()) {
+        Some("SCALAR") => 1, Some("VEC2") => 2, Some("VEC3") => 3, Some("VEC4") => 4,
+        t => { return Err(format!("unsupported accessor type {:?} in gltf file {:?}", t, fp)); }
+    };
+    let component_type = accessor.get("componentType").and_then(|c| c.as_u64()).unwrap_or(0);
+    let (component_size, data_type) = match component_type {
+        COMPONENT_TYPE_UNSIGNED_BYTE => (1, "uchar"),
+        COMPONENT_TYPE_UNSIGNED_SHORT => (2, "ushort"),
+        COMPONENT_TYPE_UNSIGNED_INT => (4, "uint"),
+        COMPONENT_TYPE_FLOAT => (4, "float"),
+        _ => { return Err(format!("unsupported accessor component type {:?} in gltf file {:?}", component_type, fp)); }
+    };
+
+    // accessors without a buffer view are all zeros
+    let buffer_view_idx = accessor.get("bufferView");
+    if buffer_view_idx.is_none() { return Ok(vec![vec![0.0; num_components]; count]); }
+    let buffer_views = _get_array(gltf, "bufferViews");
+    let buffer_view_idx = _as_idx(buffer_view_idx.unwrap(), fp)?;
+    if buffer_view_idx >= buffer_views.len() { return Err(format!("buffer view {:?} does not exist in gltf file {:?}", buffer_view_idx, fp)); }
+    let buffer_view = &buffer_views[buffer_view_idx];
+
+    let buffer_idx = _as_idx(buffer_view.get("buffer").unwrap_or(&serde_json::Value::Null), fp)?;
+    if buffer_idx >= buffers.len() { return Err(format!("buffer {:?} does not exist in gltf file {:?}", buffer_idx, fp)); }
+    let buffer = &buffers[buffer_idx];
+
+    let start = buffer_view.get("byteOffset").and_then(|o| o.as_u64()).unwrap_or(0) as usize + accessor.get("byteOffset").and_then(|o| o.as_u64()).unwrap_or(0) as usize;
+    let stride = buffer_view.get("byteStride").and_then(|s| s.as_u64()).unwrap_or((component_size * num_components) as u64) as usize;
+
+    let mut out_vec = Vec::new();
+    for i in 0..count {
+        let mut cursor = start + i * stride;
+        let mut element = Vec::new();
+        for _ in 0..num_components {
+            element.push( read_binary_value(buffer, &mut cursor, data_type, true)? );
+        }
+        out_vec.push(element);
+    }
+
+    return Ok(out_vec);
+}
@@ -2,4 +2,6 @@ pub mod file_utils;
 pub mod string_utils;
 pub mod robot_folder_utils;
 pub mod fileIO_directory_utils;
+pub mod ply_utils;
+pub mod gltf_utils;
 pub mod prelude;
\ No newline at end of file
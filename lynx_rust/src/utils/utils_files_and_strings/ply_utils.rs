@@ -0,0 +1,293 @@
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+
+
+/* minimal reader and writer for .ply files (ascii, binary_little_endian, and binary_big_endian).  All values are
+stored as f64, regardless of the property type in the file.  Scalar properties hold a single value per row, and
+list properties hold all values in the list. */
+#[derive(Clone, Debug)]
+pub struct PlyData {
+    pub elements: Vec<PlyElement>
+}
+
+impl PlyData {
+    pub fn new_empty() -> Self {
+        return Self { elements: Vec::new() };
+    }
+
+    pub fn get_element_ref(&self, name: &str) -> Option<&PlyElement> {
+        for e in &self.elements {
+            if e.name.as_str() == name { return Some(e); }
+        }
+        return None;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PlyElement {
+    pub name: String,
+    pub properties: Vec<PlyProperty>,
+    pub rows: Vec<Vec<Vec<f64>>> // rows[row idx][property idx]
+}
+
+impl PlyElement {
+    pub fn new(name: &str, properties: Vec<PlyProperty>) -> Self {
+        return Self { name: name.to_string(), properties, rows: Vec::new() };
+    }
+
+    pub fn get_property_idx(&self, name: &str) -> Option<usize> {
+        let l = self.properties.len();
+        for i in 0..l {
+            if self.properties[i].name.as_str() == name { return Some(i); }
+        }
+        return None;
+    }
+
+    pub fn get_num_rows(&self) -> usize {
+        return self.rows.len();
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PlyProperty {
+    pub name: String,
+    pub data_type: String,
+    pub list_count_type: Option<String> // Some for list properties
+}
+
+impl PlyProperty {
+    pub fn new_scalar(name: &str, data_type: &str) -> Self {
+        return Self { name: name.to_string(), data_type: data_type.to_string(), list_count_type: None };
+    }
+
+    pub fn new_list(name: &str, list_count_type: &str, data_type: &str) -> Self {
+        return Self { name: name.to_string(), data_type: data_type.to_string(), list_count_type: Some(list_count_type.to_string()) };
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn read_ply_file(fp: String) -> Result<PlyData, String> {
+    let bytes = fs::read(fp.clone());
+    if bytes.is_err() { return Err(format!("could not open ply file {:?}", fp)); }
+    let bytes = bytes.unwrap();
+
+    // header is always ascii, and ends with an "end_header" line
+    let end_header_tag = b"end_header";
+    let mut header_end = None;
+    let l = bytes.len();
+    if l >= end_header_tag.len() {
+        for i in 0..(l - end_header_tag.len() + 1) {
+            if &bytes[i..i + end_header_tag.len()] == end_header_tag {
+                let mut j = i + end_header_tag.len();
+                while j < l && bytes[j] != b'\n' { j += 1; }
+                header_end = Some((i, (j + 1).min(l)));
+                break;
+            }
+        }
+    }
+    if header_end.is_none() { return Err(format!("no end_header found in ply file {:?}", fp)); }
+    let (header_text_end, body_start) = header_end.unwrap();
+
+    let header = String::from_utf8_lossy(&bytes[0..header_text_end]).to_string();
+    let mut lines = header.lines();
+    if lines.next().map(|x| x.trim()) != Some("ply") { return Err(format!("file {:?} does not start with ply magic number", fp)); }
+
+    let mut format = "".to_string();
+    let mut elements = Vec::new();
+    let mut counts = Vec::new();
+    for line in lines {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() { continue; }
+        match tokens[0] {
+            "format" => {
+                if tokens.len() < 2 { return Err(format!("malformed format line in ply file {:?}", fp)); }
+                format = tokens[1].to_string();
+            }
+            "element" => {
+                if tokens.len() < 3 { return Err(format!("malformed element line in ply file {:?}", fp)); }
+                let count = tokens[2].parse::<usize>();
+                if count.is_err() { return Err(format!("malformed element count in ply file {:?}", fp)); }
+                elements.push(PlyElement::new(tokens[1], Vec::new()));
+                counts.push(count.unwrap());
+            }
+            "property" => {
+                if elements.is_empty() { return Err(format!("property declared before any element in ply file {:?}", fp)); }
+                let e = elements.last_mut().unwrap();
+                if tokens.len() >= 5 && tokens[1] == "list" {
+                    e.properties.push(PlyProperty::new_list(tokens[4], tokens[2], tokens[3]));
+                } else if tokens.len() >= 3 {
+                    e.properties.push(PlyProperty::new_scalar(tokens[2], tokens[1]));
+                } else {
+                    return Err(format!("malformed property line in ply file {:?}", fp));
+                }
+            }
+            _ => { }
+        }
+    }
+
+    let body = &bytes[body_start..];
+    match format.as_str() {
+        "ascii" => {
+            let body_string = String::from_utf8_lossy(body).to_string();
+            let mut tokens = body_string.split_whitespace();
+            let num_elements = elements.len();
+            for i in 0..num_elements {
+                for _ in 0..counts[i] {
+                    let mut row = Vec::new();
+                    for p in &elements[i].properties {
+                        let mut num_values = 1;
+                        if p.list_count_type.is_some() {
+                            num_values = _parse_ascii_token(tokens.next(), &fp)? as usize;
+                        }
+                        let mut values = Vec::new();
+                        for _ in 0..num_values { values.push(_parse_ascii_token(tokens.next(), &fp)?); }
+                        row.push(values);
+                    }
+                    elements[i].rows.push(row);
+                }
+            }
+        }
+        "binary_little_endian" | "binary_big_endian" => {
+            let little_endian = format.as_str() == "binary_little_endian";
+            let mut cursor = 0 as usize;
+            let num_elements = elements.len();
+            for i in 0..num_elements {
+                for _ in 0..counts[i] {
+                    let mut row = Vec::new();
+                    for p in &elements[i].properties {
+                        let mut num_values = 1;
+                        if p.list_count_type.is_some() {
+                            num_values = read_binary_value(body, &mut cursor, p.list_count_type.as_ref().unwrap(), little_endian)? as usize;
+                        }
+                        let mut values = Vec::new();
+                        for _ in 0..num_values { values.push(read_binary_value(body, &mut cursor, &p.data_type, little_endian)?); }
+                        row.push(values);
+                    }
+                    elements[i].rows.push(row);
+                }
+            }
+        }
+        _ => { return Err(format!("unsupported ply format {:?} in file {:?}", format, fp)); }
+    }
+
+    return Ok(PlyData { elements });
+}
+
+pub fn write_ply_file(fp: String, ply_data: &PlyData, binary: bool) -> Result<(), String> {
+    let mut header = "ply\n".to_string();
+    if binary { header += "format binary_little_endian 1.0\n"; } else { header += "format ascii 1.0\n"; }
+    for e in &ply_data.elements {
+        header += format!("element {} {}\n", e.name, e.rows.len()).as_str();
+        for p in &e.properties {
+            if p.list_count_type.is_some() {
+                header += format!("property list {} {} {}\n", p.list_count_type.as_ref().unwrap(), p.data_type, p.name).as_str();
+            } else {
+                header += format!("property {} {}\n", p.data_type, p.name).as_str();
+            }
+        }
+    }
+    header += "end_header\n";
+
+    let mut out_bytes = header.into_bytes();
+    for e in &ply_data.elements {
+        for row in &e.rows {
+            if row.len() != e.properties.len() { return Err(format!("row in ply element {:?} has {:?} properties, but {:?} were declared", e.name, row.len(), e.properties.len())); }
+            let mut ascii_tokens = Vec::new();
+            let l = row.len();
+            for i in 0..l {
+                let p = &e.properties[i];
+                if p.list_count_type.is_some() {
+                    if binary { write_binary_value(&mut out_bytes, row[i].len() as f64, p.list_count_type.as_ref().unwrap(), true)?; }
+                    else { ascii_tokens.push(format!("{}", row[i].len())); }
+                } else if row[i].len() != 1 {
+                    return Err(format!("scalar property {:?} in ply element {:?} must have exactly one value", p.name, e.name));
+                }
+                for v in &row[i] {
+                    if binary { write_binary_value(&mut out_bytes, *v, &p.data_type, true)?; }
+                    else { ascii_tokens.push(_format_ascii_value(*v, &p.data_type)); }
+                }
+            }
+            if !binary {
+                out_bytes.extend_from_slice(ascii_tokens.join(" ").as_bytes());
+                out_bytes.push(b'\n');
+            }
+        }
+    }
+
+    let file = File::create(fp.clone());
+    if file.is_err() { return Err(format!("could not create ply file {:?}", fp)); }
+    let res = file.unwrap().write_all(&out_bytes);
+    if res.is_err() { return Err(format!("could not write ply file {:?}", fp)); }
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// type names follow the ply spec, and also accept the single letter + size names used by .pcd files (e.g., F4, U1)
+pub fn read_binary_value(bytes: &[u8], cursor: &mut usize, data_type: &str, little_endian: bool) -> Result<f64, String> {
+    let size = get_binary_type_size(data_type)?;
+    if *cursor + size > bytes.len() { return Err(format!("unexpected end of binary data while reading {:?}", data_type)); }
+
+    let mut b = [0 as u8; 8];
+    b[0..size].copy_from_slice(&bytes[*cursor..*cursor + size]);
+    if !little_endian { b[0..size].reverse(); }
+    *cursor += size;
+
+    let out_val = match data_type {
+        "char" | "int8" | "I1" => (b[0] as i8) as f64,
+        "uchar" | "uint8" | "U1" => b[0] as f64,
+        "short" | "int16" | "I2" => i16::from_le_bytes([b[0], b[1]]) as f64,
+        "ushort" | "uint16" | "U2" => u16::from_le_bytes([b[0], b[1]]) as f64,
+        "int" | "int32" | "I4" => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+        "uint" | "uint32" | "U4" => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+        "float" | "float32" | "F4" => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+        "double" | "float64" | "F8" => f64::from_le_bytes(b),
+        _ => { return Err(format!("unsupported binary data type {:?}", data_type)); }
+    };
+
+    return Ok(out_val);
+}
+
+pub fn write_binary_value(out_bytes: &mut Vec<u8>, value: f64, data_type: &str, little_endian: bool) -> Result<(), String> {
+    let mut b = match data_type {
+        "char" | "int8" | "I1" => (value as i8).to_le_bytes().to_vec(),
+        "uchar" | "uint8" | "U1" => (value as u8).to_le_bytes().to_vec(),
+        "short" | "int16" | "I2" => (value as i16).to_le_bytes().to_vec(),
+        "ushort" | "uint16" | "U2" => (value as u16).to_le_bytes().to_vec(),
+        "int" | "int32" | "I4" => (value as i32).to_le_bytes().to_vec(),
+        "uint" | "uint32" | "U4" => (value as u32).to_le_bytes().to_vec(),
+        "float" | "float32" | "F4" => (value as f32).to_le_bytes().to_vec(),
+        "double" | "float64" | "F8" => value.to_le_bytes().to_vec(),
+        _ => { return Err(format!("unsupported binary data type {:?}", data_type)); }
+    };
+    if !little_endian { b.reverse(); }
+    out_bytes.append(&mut b);
+    Ok(())
+}
+
+pub fn get_binary_type_size(data_type: &str) -> Result<usize, String> {
+    return match data_type {
+        "char" | "int8" | "I1" | "uchar" | "uint8" | "U1" => Ok(1),
+        "short" | "int16" | "I2" | "ushort" | "uint16" | "U2" => Ok(2),
+        "int" | "int32" | "I4" | "uint" | "uint32" | "U4" | "float" | "float32" | "F4" => Ok(4),
+        "double" | "float64" | "F8" => Ok(8),
+        _ => Err(format!("unsupported binary data type {:?}", data_type))
+    }
+}
+
+fn _parse_ascii_token(token: Option<&str>, fp: &String) -> Result<f64, String> {
+    if token.is_none() { return Err(format!("unexpected end of ascii data in ply file {:?}", fp)); }
+    let val = token.unwrap().parse::<f64>();
+    if val.is_err() { return Err(format!("could not parse {:?} as a number in ply file {:?}", token.unwrap(), fp)); }
+    return Ok(val.unwrap());
+}
+
+fn _format_ascii_value(value: f64, data_type: &str) -> String {
+    return match data_type {
+        "float" | "float32" | "double" | "float64" => format!("{}", value),
+        _ => format!("{}", value.round() as i64)
+    }
+}
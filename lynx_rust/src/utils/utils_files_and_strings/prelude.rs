@@ -1 +1 @@
-pub use crate::utils::utils_files_and_strings::{file_utils::*, fileIO_directory_utils::*, robot_folder_utils::*, string_utils::*};
\ No newline at end of file
+pub use crate::utils::utils_files_and_strings::{file_utils::*, fileIO_directory_utils::*, robot_folder_utils::*, string_utils::*, ply_utils::*, gltf_utils::*};
\ No newline at end of file
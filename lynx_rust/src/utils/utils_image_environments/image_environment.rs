@@ -202,6 +202,9 @@ impl LynxFloatVecSampler for ImageEnvironment {
         let range_sampler = RectangleFloatVecSampler::new(vec![ (0.0, self.world_height), (0.0, self.world_width) ]);
         return range_sampler.float_vec_sampler_sample();
     }
+    fn lynx_float_vec_sampler_bounds(&self) -> Option<Vec<(f64, f64)>> {
+        return Some(vec![ (0.0, self.world_height), (0.0, self.world_width) ]);
+    }
 }
 impl LynxVarsUser for ImageEnvironment{ }
 unsafe impl Send for ImageEnvironment { }
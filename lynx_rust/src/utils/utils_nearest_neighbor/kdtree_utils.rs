@@ -2,6 +2,7 @@ use crate::utils::utils_sampling::prelude::*;
 use nalgebra::DVector;
 use termion::{style, color};
 use crate::utils::utils_sampling::float_vec_sampler_traits::FloatVecSampler;
+use crate::utils::utils_pointclouds::pointcloud::PointCloud;
 
 #[derive(Debug, Clone)]
 struct KDTreeNode {
@@ -102,6 +103,10 @@ impl KDTree {
         Ok(out_self)
     }
 
+    pub fn new_balanced_from_point_cloud(point_cloud: &PointCloud) -> Result<Self, String> {
+        return Self::new_balanced( &point_cloud.get_points_as_dvecs() );
+    }
+
     pub fn new_random_balanced(dim: usize, num_points: usize) -> Result<Self, String> {
         let mut points = Vec::new();
         let mut sampler = RangeFloatVecSampler::new(-1.0, 1.0, dim);
@@ -1,6 +1,7 @@
 
 use vpsearch::{*};
 use nalgebra::{DVector, Vector3, Vector};
+use crate::utils::utils_pointclouds::pointcloud::PointCloud;
 // use crate::lib::utils_mist::nalgebra_utils::vec_to_dvec;
 // use crate::lib::utils_rust::file_utils::{*};
 
@@ -25,6 +26,10 @@ impl VPSearch3D {
         Self{tree, points: points_vec}
     }
 
+    pub fn new_from_point_cloud(point_cloud: &PointCloud) -> Self {
+        return Self::new( point_cloud.get_points_ref() );
+    }
+
     pub fn new_vec(v: &Vec<Vec<f64>>) -> Self {
         let mut vec3s = Vec::new();
         let l = v.len();
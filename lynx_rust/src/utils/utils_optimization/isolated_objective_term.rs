@@ -7,6 +7,10 @@ use crate::utils::utils_se3::transformation_utils::*;
 use crate::utils::utils_collisions::collision_object_group_queries::*;
 use crate::robot_modules::robot_core_collision_module::LinkGeometryType;
 use crate::utils::utils_collisions::collision_environment::CollisionEnvironment;
+use crate::utils::utils_collisions::signed_distance_field::*;
+use crate::utils::utils_collisions::vec_of_collision_multiple_results::VecOfContactCheckMultipleResult;
+use crate::robot_modules::robot_set::RobotSet;
+use crate::robot_modules::robot_world::RobotWorld;
 use crate::utils::utils_robot_objective_specification::link_info::*;
 use crate::utils::utils_math::geometry_utils::*;
 use crate::utils::utils_recorders::prelude::*;
@@ -43,6 +47,9 @@ pub trait IsolatedObjectiveTerm : LynxVarsUser + AsLynxVarsUser + IsolatedObject
         return Ok(out_gradient);
     }
     fn get_default_loss_function(&self) -> Box<dyn LossFunction> { return Box::new( IdentityLoss ) }
+    fn to_isolated_objective_term_box(&self) -> IsolatedObjectiveTermBox {
+        return IsolatedObjectiveTermBox(self.clone_box());
+    }
     fn print_diagnostics_information(&self, lynx_vars: &mut LynxVarsGeneric, x: &Option<Vec<f64>>) {
         let recorder_none = &RecorderArcMutexOption::new_none();
 
@@ -161,6 +168,9 @@ impl<T> IsolatedObjectiveTermClone for T where T: 'static + IsolatedObjectiveTer
 
 pub struct IsolatedObjectiveTermBox(Box<dyn IsolatedObjectiveTerm>);
 impl IsolatedObjectiveTermBox {
+    pub fn new(isolated_objective_term: &dyn IsolatedObjectiveTerm) -> Self {
+        return Self( isolated_objective_term.clone_box() );
+    }
     pub fn name(&self) -> String { return self.0.name(); }
     pub fn call(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<f64, String> {
         return self.0.call(x, lynx_vars, recorder);
@@ -185,6 +195,223 @@ impl Clone for IsolatedObjectiveTermBox {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/* penalizes link spheres that come within margin of the environment encoded in the signed distance field.  Holding the
+sphere radius fixed, d(penetration)/dq = -grad_sdf(center)^T J_center, where J_center is the jacobian of the sphere center on
+its link, so the gradient only needs one fk call and one field lookup per sphere */
+#[derive(Clone)]
+pub struct RobotSignedDistanceFieldCollisionAvoidance {
+    _link_sphere_approximations: Vec<LinkSphereApproximation>,
+    _margin: f64
+}
+impl RobotSignedDistanceFieldCollisionAvoidance {
+    pub fn new(robot_set: &RobotSet, link_geometry_type: LinkGeometryType, margin: f64) -> Self {
+        let mut _link_sphere_approximations = Vec::new();
+        for r in robot_set.get_robots_ref() {
+            _link_sphere_approximations.push( LinkSphereApproximation::new(r.get_core_collision_module_ref(), &link_geometry_type) );
+        }
+        return Self { _link_sphere_approximations, _margin: margin };
+    }
+
+    fn _cost_and_gradient(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, compute_gradient: bool) -> Result<(f64, DVector<f64>), String> {
+        let robot_world = get_lynx_var_ref_generic!(lynx_vars, RobotWorld, "robot_world")?;
+        let robot_set = robot_world.get_robot_set_ref();
+        let fk_res = robot_set.compute_fk(x)?;
+        let link_paddings: Vec<Vec<f64>> = robot_set.get_robots_ref().iter().map(|r| r.get_core_collision_module_ref().get_link_paddings_ref().clone()).collect();
+
+        let signed_distance_field = get_lynx_var_ref_generic!(lynx_vars, SignedDistanceField, "signed_distance_field")?;
+
+        let mut out_val = 0.0;
+        let mut out_gradient = DVector::from_element(robot_set.get_total_num_dofs(), 0.0);
+        let fk_results = fk_res.get_robot_fk_results_ref();
+        let l = fk_results.len().min(self._link_sphere_approximations.len());
+        for i in 0..l {
+            let spheres = self._link_sphere_approximations[i].get_spheres_in_world_frame(&fk_results[i], &link_paddings[i]);
+            for (link_idx, center, radius) in spheres {
+                let (distance, sdf_gradient) = signed_distance_field.distance_and_gradient(&center);
+                let penetration = self._margin - (distance - radius);
+                if penetration <= 0.0 { continue; }
+                out_val += penetration * penetration;
+
+                if !compute_gradient { continue; }
+                let jacobian = robot_set.compute_link_point_jacobian(&fk_res, i, link_idx, &center)?;
+                out_gradient -= 2.0 * penetration * (jacobian.transpose() * &sdf_gradient);
+            }
+        }
+
+        return Ok((out_val, out_gradient));
+    }
+}
+impl IsolatedObjectiveTerm for RobotSignedDistanceFieldCollisionAvoidance {
+    fn name(&self) -> String { return "robot_signed_distance_field_collision_avoidance".to_string() }
+    fn call(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<f64, String> {
+        let (out_val, _) = self._cost_and_gradient(x, lynx_vars, false)?;
+        return Ok(out_val);
+    }
+    fn gradient(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<DVector<f64>, String> {
+        let (_, out_gradient) = self._cost_and_gradient(x, lynx_vars, true)?;
+        return Ok(out_gradient);
+    }
+}
+impl LynxVarsUser for RobotSignedDistanceFieldCollisionAvoidance {
+    fn get_lynx_vars_types(&self) -> Vec<(&'static str, &'static str)> {
+        return vec![ ("RobotWorld", "robot_world"), ("SignedDistanceField", "signed_distance_field") ];
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/* penalizes robot links that come within margin of each other.  The gradient is analytic, built from the witness points
+and normals of a single contact query and the link point jacobians, rather than from repeated fk and contact queries */
+#[derive(Clone)]
+pub struct RobotSelfCollisionAvoidance {
+    _link_geometry_type: LinkGeometryType,
+    _margin: f64
+}
+impl RobotSelfCollisionAvoidance {
+    pub fn new(link_geometry_type: LinkGeometryType, margin: f64) -> Self {
+        return Self { _link_geometry_type: link_geometry_type, _margin: margin };
+    }
+}
+impl IsolatedObjectiveTerm for RobotSelfCollisionAvoidance {
+    fn name(&self) -> String { return "robot_self_collision_avoidance".to_string() }
+    fn call(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<f64, String> {
+        let robot_world = get_lynx_var_mut_ref_generic!(lynx_vars, RobotWorld, "robot_world")?;
+        let fk_res = robot_world.get_robot_set_ref().compute_fk(x)?;
+        let contact_res = robot_world.self_contact_check(&fk_res, self._link_geometry_type.clone(), false, Some(self._margin))?;
+
+        let (out_val, _) = collision_avoidance_cost_and_gradient(robot_world.get_robot_set_ref(), &fk_res, &contact_res, self._margin, [true, true], false)?;
+        return Ok(out_val);
+    }
+    fn gradient(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<DVector<f64>, String> {
+        let robot_world = get_lynx_var_mut_ref_generic!(lynx_vars, RobotWorld, "robot_world")?;
+        let fk_res = robot_world.get_robot_set_ref().compute_fk(x)?;
+        let contact_res = robot_world.self_contact_check(&fk_res, self._link_geometry_type.clone(), false, Some(self._margin))?;
+
+        let (_, out_gradient) = collision_avoidance_cost_and_gradient(robot_world.get_robot_set_ref(), &fk_res, &contact_res, self._margin, [true, true], true)?;
+        return Ok(out_gradient);
+    }
+}
+impl LynxVarsUser for RobotSelfCollisionAvoidance {
+    fn get_lynx_vars_types(&self) -> Vec<(&'static str, &'static str)> {
+        return vec![ ("RobotWorld", "robot_world") ];
+    }
+}
+
+/* penalizes robot links that come within margin of the robot world's collision environment, with the same analytic
+gradient as RobotSelfCollisionAvoidance.  Environment objects are static, so only the robot side of each contact moves */
+#[derive(Clone)]
+pub struct RobotEnvironmentCollisionAvoidance {
+    _link_geometry_type: LinkGeometryType,
+    _margin: f64
+}
+impl RobotEnvironmentCollisionAvoidance {
+    pub fn new(link_geometry_type: LinkGeometryType, margin: f64) -> Self {
+        return Self { _link_geometry_type: link_geometry_type, _margin: margin };
+    }
+}
+impl IsolatedObjectiveTerm for RobotEnvironmentCollisionAvoidance {
+    fn name(&self) -> String { return "robot_environment_collision_avoidance".to_string() }
+    fn call(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<f64, String> {
+        let robot_world = get_lynx_var_mut_ref_generic!(lynx_vars, RobotWorld, "robot_world")?;
+        let fk_res = robot_world.get_robot_set_ref().compute_fk(x)?;
+        let contact_res = robot_world.environment_contact_check(&fk_res, self._link_geometry_type.clone(), false, Some(self._margin))?;
+
+        let (out_val, _) = collision_avoidance_cost_and_gradient(robot_world.get_robot_set_ref(), &fk_res, &contact_res, self._margin, [false, true], false)?;
+        return Ok(out_val);
+    }
+    fn gradient(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<DVector<f64>, String> {
+        let robot_world = get_lynx_var_mut_ref_generic!(lynx_vars, RobotWorld, "robot_world")?;
+        let fk_res = robot_world.get_robot_set_ref().compute_fk(x)?;
+        let contact_res = robot_world.environment_contact_check(&fk_res, self._link_geometry_type.clone(), false, Some(self._margin))?;
+
+        let (_, out_gradient) = collision_avoidance_cost_and_gradient(robot_world.get_robot_set_ref(), &fk_res, &contact_res, self._margin, [false, true], true)?;
+        return Ok(out_gradient);
+    }
+}
+impl LynxVarsUser for RobotEnvironmentCollisionAvoidance {
+    fn get_lynx_vars_types(&self) -> Vec<(&'static str, &'static str)> {
+        return vec![ ("RobotWorld", "robot_world") ];
+    }
+}
+
+/* penalizes occlusion of target points as seen from a link (e.g., a camera link), using RobotWorld::visibility_check_multiple.
+The cost is the total length of the lines of sight that pass through occluders, which, unlike a visible/occluded flag,
+shrinks smoothly as an occluder moves out of the way */
+#[derive(Clone)]
+pub struct RobotLinkOcclusionAvoidance {
+    _robot_idx: usize,
+    _from_link_idx: usize,
+    _target_points: Vec<Vector3<f64>>,
+    _link_geometry_type: LinkGeometryType,
+    _ignore_links: Vec<[usize; 2]>
+}
+impl RobotLinkOcclusionAvoidance {
+    pub fn new(robot_idx: usize, from_link_idx: usize, target_points: Vec<Vector3<f64>>, link_geometry_type: LinkGeometryType, ignore_links: Vec<[usize; 2]>) -> Self {
+        return Self { _robot_idx: robot_idx, _from_link_idx: from_link_idx, _target_points: target_points, _link_geometry_type: link_geometry_type, _ignore_links: ignore_links };
+    }
+}
+impl IsolatedObjectiveTerm for RobotLinkOcclusionAvoidance {
+    fn name(&self) -> String { return "robot_link_occlusion_avoidance".to_string() }
+    fn call(&self, x: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric, recorder: &RecorderArcMutexOption) -> Result<f64, String> {
+        let robot_world = get_lynx_var_mut_ref_generic!(lynx_vars, RobotWorld, "robot_world")?;
+        let fk_res = robot_world.get_robot_set_ref().compute_fk(x)?;
+        let visibility_results = robot_world.visibility_check_multiple(&fk_res, self._robot_idx, self._from_link_idx, &self._target_points, self._link_geometry_type.clone(), &self._ignore_links)?;
+
+        let mut out_val = 0.0;
+        for r in &visibility_results { out_val += r.get_occluded_length(); }
+        return Ok(out_val);
+    }
+}
+impl LynxVarsUser for RobotLinkOcclusionAvoidance {
+    fn get_lynx_vars_types(&self) -> Vec<(&'static str, &'static str)> {
+        return vec![ ("RobotWorld", "robot_world") ];
+    }
+}
+
+/* cost is the sum of squared penetrations past margin over all contacts, where the signed distance of a contact is
+-depth = n . (world2 - world1).  Holding the normal fixed, d(signed distance)/dq = n^T (J2 - J1), where Ji is the jacobian
+of witness point i on its link.  robot_sides says which sides of each contact are robot links (as opposed to environment objects).
+Contact results are expected to be ordered by robot, as returned by the robot set and robot world contact checks.
+Links on mounted robots also contribute to the dofs of their parent robots. */
+fn collision_avoidance_cost_and_gradient(robot_set: &RobotSet, fk_res: &VecOfRobotFKResult, contact_res: &VecOfContactCheckMultipleResult, margin: f64, robot_sides: [bool; 2], compute_gradient: bool) -> Result<(f64, DVector<f64>), String> {
+    let mut out_val = 0.0;
+    let mut out_gradient = DVector::from_element(robot_set.get_total_num_dofs(), 0.0);
+
+    let contact_results = contact_res.get_contact_check_multiple_results_ref();
+    let fk_results = fk_res.get_robot_fk_results_ref();
+
+    let l = contact_results.len().min(fk_results.len());
+    for i in 0..l {
+        let info = contact_results[i].get_contact_check_multiple_info_ref();
+        let contacts = info.get_contact_check_contacts();
+        let idxs = info.get_contact_check_idxs();
+
+        let num_contacts = contacts.len();
+        for j in 0..num_contacts {
+            let penetration = margin + contacts[j].depth;
+            if penetration <= 0.0 { continue; }
+            out_val += penetration * penetration;
+
+            if !compute_gradient { continue; }
+
+            // jacobians span all dofs in the robot set, since links on mounted robots also move with their parent robots
+            let normal = contacts[j].normal.into_inner();
+            let witness_points = [ contacts[j].world1.coords, contacts[j].world2.coords ];
+            for side in 0..2 {
+                if !robot_sides[side] { continue; }
+                let jacobian = robot_set.compute_link_point_jacobian(fk_res, i, idxs[j][side][0], &witness_points[side])?;
+                let sign = if side == 0 { 1.0 } else { -1.0 };
+                let d_penetration = jacobian.transpose() * &normal;
+                out_gradient += 2.0 * penetration * sign * d_penetration;
+            }
+        }
+    }
+
+    return Ok((out_val, out_gradient));
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,331 @@
+use crate::utils::utils_files_and_strings::file_utils::*;
+use crate::utils::utils_collisions::{collision_environment::CollisionEnvironment, collision_object::CollisionObject, triangle_mesh_engine::TriMeshEngine};
+use crate::utils::utils_se3::prelude::ImplicitDualQuaternion;
+use ncollide3d::shape::{Ball, Cuboid, Cylinder, Capsule};
+use ncollide3d::procedural;
+use ncollide3d::math::Point;
+use nalgebra::Vector3;
+use serde::{Serialize, Deserialize};
+use yaml_rust::{Yaml, YamlLoader, YamlEmitter};
+use std::sync::Arc;
+
+/* human-writable scene format for collision environments, as an alternative to mesh directories and their autogenerated
+environment_metadata.json.  A scene lists named objects, each with a single shape, a pose, and an optional padding.  Example (yaml):
+
+name: table_scene
+objects:
+  - name: table
+    shape: {type: box, half_extents: [0.5, 0.8, 0.02]}
+    translation: [0.6, 0.0, 0.7]
+  - name: can
+    shape: {type: cylinder, radius: 0.04, length: 0.12}
+    translation: [0.5, 0.1, 0.78]
+    rotation: [0.0, 0.0, 1.57]
+    padding: 0.01
+  - name: bracket
+    shape: {type: mesh, path: meshes/bracket.stl}
+
+rotation holds euler angles (rx, ry, rz), as in ImplicitDualQuaternion::new_from_euler_angles.  Cylinders and capsules are
+aligned with their local z axis (as in urdf), and length is the length of the straight section, so a capsule extends
+length / 2 + radius from its center.  Planes are finite, with normal along their local z axis, and size is the full extent
+in x and y.  Relative mesh paths are relative to the scene file; meshes are split into convex components, the same way
+meshes in environment directories are. */
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SceneShape {
+    Box { half_extents: [f64; 3] },
+    Sphere { radius: f64 },
+    Cylinder { radius: f64, length: f64 },
+    Capsule { radius: f64, length: f64 },
+    Plane { size: [f64; 2] },
+    Mesh { path: String }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SceneObject {
+    pub name: String,
+    pub shape: SceneShape,
+    #[serde(default)]
+    pub translation: [f64; 3],
+    #[serde(default)]
+    pub rotation: [f64; 3],
+    #[serde(default)]
+    pub padding: f64
+}
+
+impl SceneObject {
+    pub fn get_pose(&self) -> ImplicitDualQuaternion {
+        return ImplicitDualQuaternion::new_from_euler_angles(self.rotation[0], self.rotation[1], self.rotation[2],
+                                                             Vector3::new(self.translation[0], self.translation[1], self.translation[2]));
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnvironmentScene {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub objects: Vec<SceneObject>
+}
+
+impl EnvironmentScene {
+    pub fn new_empty(name: &str) -> Self {
+        return Self { name: name.to_string(), objects: Vec::new() };
+    }
+}
+
+// planes are represented as boxes this thin, which is also how they are recognized again on export
+const PLANE_HALF_THICKNESS: f64 = 0.0005;
+const SPHERE_MESH_SUBDIVISIONS: u32 = 16;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn read_environment_scene_file(fp: String) -> Result<EnvironmentScene, String> {
+    let contents = read_file_contents(fp.clone());
+    if contents.is_none() { return Err(format!("environment scene file {:?} could not be read", fp)); }
+
+    let ext = get_filename_extension(fp.clone());
+    if ext == "json" { return parse_environment_scene_json_string(&contents.unwrap()); }
+    else if ext == "yaml" || ext == "yml" { return parse_environment_scene_yaml_string(&contents.unwrap()); }
+    return Err(format!("environment scene file {:?} must be a json or yaml file", fp));
+}
+
+pub fn parse_environment_scene_json_string(s: &String) -> Result<EnvironmentScene, String> {
+    let res: Result<EnvironmentScene, _> = serde_json::from_str(s);
+    if res.is_err() { return Err(format!("environment scene could not be parsed: {:?}", res.err().unwrap().to_string())); }
+    return Ok(res.ok().unwrap());
+}
+
+pub fn parse_environment_scene_yaml_string(s: &String) -> Result<EnvironmentScene, String> {
+    let docs = YamlLoader::load_from_str(s.as_str());
+    if docs.is_err() { return Err(format!("environment scene yaml could not be parsed: {:?}", docs.err().unwrap().to_string())); }
+    let docs = docs.ok().unwrap();
+    if docs.is_empty() { return Err("environment scene yaml was empty".to_string()); }
+
+    let res: Result<EnvironmentScene, _> = serde_json::from_value(_yaml_to_json_value(&docs[0]));
+    if res.is_err() { return Err(format!("environment scene could not be parsed: {:?}", res.err().unwrap().to_string())); }
+    return Ok(res.ok().unwrap());
+}
+
+pub fn write_environment_scene_file(fp: String, environment_scene: &EnvironmentScene) -> Result<(), String> {
+    let path = std::path::Path::new(&fp);
+    let fp_to_dir = path.parent();
+    let file_name = path.file_name();
+    if fp_to_dir.is_none() || file_name.is_none() { return Err(format!("{:?} is not a valid environment scene file path", fp)); }
+
+    let ext = get_filename_extension(fp.clone());
+    let out_string = if ext == "json" { environment_scene_to_json_string(environment_scene) }
+        else if ext == "yaml" || ext == "yml" { environment_scene_to_yaml_string(environment_scene)? }
+        else { return Err(format!("environment scene file {:?} must be a json or yaml file", fp)); };
+
+    write_string_to_file(fp_to_dir.unwrap().to_str().unwrap().to_string(), file_name.unwrap().to_str().unwrap().to_string(), out_string, true);
+    Ok(())
+}
+
+pub fn environment_scene_to_json_string(environment_scene: &EnvironmentScene) -> String {
+    return serde_json::to_string_pretty(environment_scene).unwrap();
+}
+
+pub fn environment_scene_to_yaml_string(environment_scene: &EnvironmentScene) -> Result<String, String> {
+    let value = serde_json::to_value(environment_scene).unwrap();
+    let mut out_string = String::new();
+    let res = YamlEmitter::new(&mut out_string).dump(&_json_value_to_yaml(&value));
+    if res.is_err() { return Err(format!("environment scene could not be written as yaml: {:?}", res.err().unwrap())); }
+    return Ok(out_string);
+}
+
+fn _yaml_to_json_value(y: &Yaml) -> serde_json::Value {
+    return match y {
+        Yaml::Real(s) => { s.parse::<f64>().ok().and_then(|f| serde_json::Number::from_f64(f)).map(|n| serde_json::Value::Number(n)).unwrap_or(serde_json::Value::Null) }
+        Yaml::Integer(i) => { serde_json::Value::from(*i) }
+        Yaml::String(s) => { serde_json::Value::String(s.clone()) }
+        Yaml::Boolean(b) => { serde_json::Value::Bool(*b) }
+        Yaml::Array(a) => { serde_json::Value::Array(a.iter().map(|x| _yaml_to_json_value(x)).collect()) }
+        Yaml::Hash(h) => {
+            let mut m = serde_json::Map::new();
+            for (k, v) in h {
+                let key = match k { Yaml::String(s) => s.clone(), Yaml::Integer(i) => i.to_string(), Yaml::Real(s) => s.clone(), _ => continue };
+                m.insert(key, _yaml_to_json_value(v));
+            }
+            serde_json::Value::Object(m)
+        }
+        _ => { serde_json::Value::Null }
+    };
+}
+
+fn _json_value_to_yaml(v: &serde_json::Value) -> Yaml {
+    return match v {
+        serde_json::Value::Null => { Yaml::Null }
+        serde_json::Value::Bool(b) => { Yaml::Boolean(*b) }
+        serde_json::Value::Number(n) => {
+            if n.is_i64() { Yaml::Integer(n.as_i64().unwrap()) }
+            else { Yaml::Real(format!("{:?}", n.as_f64().unwrap())) }
+        }
+        serde_json::Value::String(s) => { Yaml::String(s.clone()) }
+        serde_json::Value::Array(a) => { Yaml::Array(a.iter().map(|x| _json_value_to_yaml(x)).collect()) }
+        serde_json::Value::Object(m) => {
+            let mut h = yaml_rust::yaml::Hash::new();
+            for (k, x) in m { h.insert(Yaml::String(k.clone()), _json_value_to_yaml(x)); }
+            Yaml::Hash(h)
+        }
+    };
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/* mesh_directory is used to resolve relative mesh paths.  The mesh path given in the scene is kept as the object's entry
+in original_file_directories, so the object is exported as a mesh reference again. */
+pub fn environment_scene_to_collision_environment(environment_scene: &EnvironmentScene, mesh_directory: Option<String>) -> Result<CollisionEnvironment, String> {
+    let mut collision_environment = CollisionEnvironment::new_empty(environment_scene.name.as_str());
+
+    for o in &environment_scene.objects {
+        if collision_environment.object_names.contains(&o.name) {
+            return Err(format!("object name {:?} appears more than once in environment scene {:?}", o.name, environment_scene.name));
+        }
+
+        let (components, trimesh_engines) = _get_scene_shape_components(&o.shape, &o.name, &mesh_directory)?;
+        collision_environment.set_object_components_by_name(o.name.as_str(), components, trimesh_engines)?;
+        collision_environment.update_object_transform_by_name(o.name.as_str(), &o.get_pose())?;
+        collision_environment.set_object_padding_by_name(o.name.as_str(), o.padding)?;
+
+        match &o.shape {
+            SceneShape::Mesh { path } => {
+                let idx = collision_environment.get_num_objects() - 1;
+                collision_environment.original_file_directories[idx] = path.clone();
+            }
+            _ => { }
+        }
+    }
+
+    return Ok(collision_environment);
+}
+
+/* objects that came from a scene mesh reference are exported as that mesh reference.  Every other component is exported as
+its own primitive, named after its object (with the component index appended if the object has more than one component).
+Components whose shape is not a primitive (e.g., convex hulls or triangle meshes) cannot be exported. */
+pub fn collision_environment_to_environment_scene(collision_environment: &CollisionEnvironment) -> Result<EnvironmentScene, String> {
+    let mut out_scene = EnvironmentScene::new_empty(collision_environment.environment_name.as_str());
+
+    let l = collision_environment.get_num_objects();
+    for i in 0..l {
+        let name = collision_environment.object_names[i].clone();
+        let transform = &collision_environment.transforms[i];
+        let padding = collision_environment.object_paddings[i];
+
+        let original_file = collision_environment.original_file_directories[i].clone();
+        let ext = get_filename_extension(original_file.clone());
+        if ext == "stl" || ext == "obj" || ext == "dae" || ext == "ply" || ext == "gltf" || ext == "glb" {
+            out_scene.objects.push( _get_scene_object(name, SceneShape::Mesh { path: original_file }, transform, padding) );
+            continue;
+        }
+
+        let components = &collision_environment.environment_obbs[i];
+        let num_components = components.len();
+        for j in 0..num_components {
+            let c = &components[j];
+            let component_name = if num_components == 1 { name.clone() } else { format!("{}_{:?}", name, j) };
+
+            let shape;
+            let mut local_pose = c.base_pose.clone();
+            if let Some(b) = c.shape.as_shape::<Cuboid<f64>>() {
+                let h = b.half_extents();
+                if (h[2] - PLANE_HALF_THICKNESS).abs() < 0.000000001 { shape = SceneShape::Plane { size: [2.0 * h[0], 2.0 * h[1]] }; }
+                else { shape = SceneShape::Box { half_extents: [h[0], h[1], h[2]] }; }
+            } else if let Some(b) = c.shape.as_shape::<Ball<f64>>() {
+                shape = SceneShape::Sphere { radius: b.radius() };
+            } else if let Some(b) = c.shape.as_shape::<Cylinder<f64>>() {
+                shape = SceneShape::Cylinder { radius: b.radius(), length: 2.0 * b.half_height() };
+                local_pose = local_pose.multiply(&_get_z_axis_to_y_axis_rotation());
+            } else if let Some(b) = c.shape.as_shape::<Capsule<f64>>() {
+                shape = SceneShape::Capsule { radius: b.radius(), length: 2.0 * b.half_height() };
+                local_pose = local_pose.multiply(&_get_z_axis_to_y_axis_rotation());
+            } else {
+                return Err(format!("component {:?} of object {:?} does not have a primitive shape, so it cannot be exported to an environment scene", j, name));
+            }
+
+            out_scene.objects.push( _get_scene_object(component_name, shape, &transform.multiply(&local_pose), padding) );
+        }
+    }
+
+    return Ok(out_scene);
+}
+
+fn _get_scene_object(name: String, shape: SceneShape, pose: &ImplicitDualQuaternion, padding: f64) -> SceneObject {
+    let (rx, ry, rz) = pose.quat.euler_angles();
+    return SceneObject { name, shape, translation: [pose.translation[0], pose.translation[1], pose.translation[2]], rotation: [rx, ry, rz], padding };
+}
+
+// ncollide cylinders and capsules are aligned with their local y axis, while scene cylinders and capsules are aligned with z
+fn _get_y_axis_to_z_axis_rotation() -> ImplicitDualQuaternion {
+    return ImplicitDualQuaternion::new_from_euler_angles(std::f64::consts::FRAC_PI_2, 0., 0., Vector3::zeros());
+}
+
+fn _get_z_axis_to_y_axis_rotation() -> ImplicitDualQuaternion {
+    return _get_y_axis_to_z_axis_rotation().inverse();
+}
+
+fn _get_scene_shape_components(shape: &SceneShape, name: &String, mesh_directory: &Option<String>) -> Result<(Vec<CollisionObject>, Vec<TriMeshEngine>), String> {
+    let component_name = Some(name.clone() + "_0");
+    match shape {
+        SceneShape::Box { half_extents } => {
+            if half_extents.iter().any(|h| !(*h > 0.0)) { return Err(format!("box half extents of object {:?} must be positive", name)); }
+            let c = CollisionObject::new_cuboid(half_extents[0], half_extents[1], half_extents[2], None, component_name);
+            return Ok( (vec![c], vec![_get_box_trimesh_engine(half_extents)]) );
+        }
+        SceneShape::Plane { size } => {
+            if size.iter().any(|s| !(*s > 0.0)) { return Err(format!("plane size of object {:?} must be positive", name)); }
+            let half_extents = [size[0] / 2.0, size[1] / 2.0, PLANE_HALF_THICKNESS];
+            let c = CollisionObject::new_cuboid(half_extents[0], half_extents[1], half_extents[2], None, component_name);
+            return Ok( (vec![c], vec![_get_box_trimesh_engine(&half_extents)]) );
+        }
+        SceneShape::Sphere { radius } => {
+            if !(*radius > 0.0) { return Err(format!("sphere radius of object {:?} must be positive", name)); }
+            let c = CollisionObject::new_ball(*radius, None, component_name);
+            let mut t = procedural::sphere(2.0 * radius, SPHERE_MESH_SUBDIVISIONS, SPHERE_MESH_SUBDIVISIONS, false);
+            t.unify_index_buffer();
+            return Ok( (vec![c], vec![TriMeshEngine::new_from_procedural_trimesh(&t)]) );
+        }
+        SceneShape::Cylinder { radius, length } => {
+            if !(*radius > 0.0) || !(*length > 0.0) { return Err(format!("cylinder radius and length of object {:?} must be positive", name)); }
+            let local_pose = _get_y_axis_to_z_axis_rotation();
+            let c = CollisionObject::new( Box::new(Arc::new(Cylinder::new(length / 2.0, *radius))), Some(local_pose.clone()), component_name);
+            let mut t = procedural::cylinder(2.0 * radius, *length, SPHERE_MESH_SUBDIVISIONS);
+            t.unify_index_buffer();
+            let mut trimesh_engine = TriMeshEngine::new_from_procedural_trimesh(&t);
+            trimesh_engine.transform_vertices(&local_pose);
+            return Ok( (vec![c], vec![trimesh_engine]) );
+        }
+        SceneShape::Capsule { radius, length } => {
+            if !(*radius > 0.0) || !(*length > 0.0) { return Err(format!("capsule radius and length of object {:?} must be positive", name)); }
+            let local_pose = _get_y_axis_to_z_axis_rotation();
+            let c = CollisionObject::new_capsule(length / 2.0, *radius, Some(local_pose.clone()), component_name);
+            let mut t = procedural::capsule(&(2.0 * radius), length, SPHERE_MESH_SUBDIVISIONS, SPHERE_MESH_SUBDIVISIONS);
+            t.unify_index_buffer();
+            let mut trimesh_engine = TriMeshEngine::new_from_procedural_trimesh(&t);
+            trimesh_engine.transform_vertices(&local_pose);
+            return Ok( (vec![c], vec![trimesh_engine]) );
+        }
+        SceneShape::Mesh { path } => {
+            let mut fp = path.clone();
+            if !std::path::Path::new(path).is_absolute() && mesh_directory.is_some() {
+                fp = mesh_directory.as_ref().unwrap().clone() + "/" + path.as_str();
+            }
+            let t = TriMeshEngine::new_from_path(fp)?;
+            let ts = t.split_into_convex_components(0.08, 0);
+            let mut components = Vec::new();
+            let l = ts.len();
+            for j in 0..l {
+                components.push( CollisionObject::new_cuboid_from_trimesh_engine(&ts[j], Some(name.clone() + format!("_{:?}", j).as_str())) );
+            }
+            return Ok( (components, ts) );
+        }
+    }
+}
+
+fn _get_box_trimesh_engine(half_extents: &[f64; 3]) -> TriMeshEngine {
+    let mut vertices = Vec::new();
+    for x in &[-half_extents[0], half_extents[0]] { for y in &[-half_extents[1], half_extents[1]] { for z in &[-half_extents[2], half_extents[2]] { vertices.push(Point::new(*x, *y, *z)); } } }
+    let indices = vec![ [0,1,3], [0,3,2], [4,6,7], [4,7,5], [0,4,5], [0,5,1], [2,3,7], [2,7,6], [0,2,6], [0,6,4], [1,5,7], [1,7,3] ];
+    return TriMeshEngine::new(vertices, indices.iter().map(|t| Point::new(t[0], t[1], t[2])).collect());
+}
@@ -2,4 +2,6 @@ pub mod urdf_parsing_utils;
 pub mod urdf_joint;
 pub mod urdf_link;
 pub mod yaml_parsing_utils;
-pub mod mesh_visual_offset_and_scaling_parsing;
\ No newline at end of file
+pub mod mesh_visual_offset_and_scaling_parsing;
+pub mod srdf_parsing_utils;
+pub mod environment_scene_parsing_utils;
@@ -0,0 +1,482 @@
+use crate::utils::utils_files_and_strings::file_utils::*;
+use crate::utils::utils_files_and_strings::robot_folder_utils::*;
+use crate::utils::utils_robot_objective_specification::link_info::*;
+use crate::utils::utils_se3::prelude::ImplicitDualQuaternion;
+use crate::robot_modules::robot_configuration_module::RobotConfigurationModule;
+use crate::robot_modules::robot_model_module::RobotModelModule;
+use crate::robot_modules::robot_dof_module::RobotDOFModule;
+use crate::robot_modules::robot_saved_joint_states_module::RobotSavedJointStatesModule;
+use crate::robot_modules::robot_salient_links_module::RobotSalientLinksModule;
+use crate::robot_modules::robot_core_collision_module::LinkGeometryType;
+use crate::robot_modules::robot::Robot;
+use termion::{color, style};
+use nalgebra::DVector;
+use xml::Xml::*;
+use xml::{Element, ElementBuilder, Parser};
+
+/* SRDF (semantic robot description format, as used by MoveIt) support.  Planning groups map onto lynx robot
+configurations (all joints outside of the group are inactive), group states map onto saved joint states in the
+corresponding configuration, end effectors map onto EndEffector salient links (their own groups, which only hold the end
+effector's links, are not turned into configurations, so their group states are skipped with a warning), and disabled
+collision pairs map onto manual collision check skips. */
+
+#[derive(Clone, Debug)]
+pub struct SRDFData {
+    pub robot_name: String,
+    pub groups: Vec<SRDFGroup>,
+    pub group_states: Vec<SRDFGroupState>,
+    pub end_effectors: Vec<SRDFEndEffector>,
+    pub virtual_joints: Vec<SRDFVirtualJoint>,
+    pub passive_joints: Vec<String>,
+    pub disable_collisions: Vec<SRDFDisableCollisions>
+}
+
+impl SRDFData {
+    pub fn new_empty(robot_name: &str) -> Self {
+        return Self { robot_name: robot_name.to_string(), groups: Vec::new(), group_states: Vec::new(), end_effectors: Vec::new(),
+            virtual_joints: Vec::new(), passive_joints: Vec::new(), disable_collisions: Vec::new() };
+    }
+
+    pub fn get_group_ref(&self, group_name: &str) -> Option<&SRDFGroup> {
+        for g in &self.groups {
+            if g.name == group_name { return Some(g); }
+        }
+        return None;
+    }
+
+    // all joint names in the given group, following chains, links, and subgroups
+    pub fn get_group_joint_names(&self, group_name: &str, robot_model_module: &RobotModelModule) -> Result<Vec<String>, String> {
+        let mut out_vec = Vec::new();
+        self._add_group_joint_names(group_name, robot_model_module, &mut out_vec, &mut Vec::new())?;
+        return Ok(out_vec);
+    }
+
+    fn _add_group_joint_names(&self, group_name: &str, robot_model_module: &RobotModelModule, out_vec: &mut Vec<String>, visited_groups: &mut Vec<String>) -> Result<(), String> {
+        if visited_groups.contains(&group_name.to_string()) { return Ok(()); }
+        visited_groups.push(group_name.to_string());
+
+        let group = self.get_group_ref(group_name);
+        if group.is_none() { return Err(format!("group {:?} is not defined in srdf", group_name)); }
+        let group = group.unwrap();
+
+        for j in &group.joints {
+            if robot_model_module.get_joint_idx_from_name(j).is_none() { return Err(format!("joint {:?} in group {:?} was not found in robot", j, group_name)); }
+            if !out_vec.contains(j) { out_vec.push(j.clone()); }
+        }
+
+        for l in &group.links {
+            let link_idx = robot_model_module.get_link_idx_from_name(l);
+            if link_idx.is_none() { return Err(format!("link {:?} in group {:?} was not found in robot", l, group_name)); }
+            let preceding_joint_idx = robot_model_module.links[link_idx.unwrap()].preceding_joint_idx;
+            if preceding_joint_idx.is_some() {
+                let joint_name = robot_model_module.joints[preceding_joint_idx.unwrap()].name.clone();
+                if !out_vec.contains(&joint_name) { out_vec.push(joint_name); }
+            }
+        }
+
+        for (base_link, tip_link) in &group.chains {
+            let base_link_idx = robot_model_module.get_link_idx_from_name(base_link);
+            if base_link_idx.is_none() { return Err(format!("chain base link {:?} in group {:?} was not found in robot", base_link, group_name)); }
+            let tip_link_idx = robot_model_module.get_link_idx_from_name(tip_link);
+            if tip_link_idx.is_none() { return Err(format!("chain tip link {:?} in group {:?} was not found in robot", tip_link, group_name)); }
+
+            let mut chain_joint_names = Vec::new();
+            let mut curr_link_idx = tip_link_idx.unwrap();
+            while curr_link_idx != base_link_idx.unwrap() {
+                let link = &robot_model_module.links[curr_link_idx];
+                if link.preceding_joint_idx.is_none() || link.preceding_link_idx.is_none() {
+                    return Err(format!("{:?} is not an ancestor of {:?} in group {:?}", base_link, tip_link, group_name));
+                }
+                chain_joint_names.push( robot_model_module.joints[link.preceding_joint_idx.unwrap()].name.clone() );
+                curr_link_idx = link.preceding_link_idx.unwrap();
+            }
+
+            chain_joint_names.reverse();
+            for j in chain_joint_names {
+                if !out_vec.contains(&j) { out_vec.push(j); }
+            }
+        }
+
+        for s in &group.subgroups {
+            self._add_group_joint_names(s, robot_model_module, out_vec, visited_groups)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SRDFGroup {
+    pub name: String,
+    pub chains: Vec<(String, String)>, // (base_link, tip_link)
+    pub joints: Vec<String>,
+    pub links: Vec<String>,
+    pub subgroups: Vec<String>
+}
+
+#[derive(Clone, Debug)]
+pub struct SRDFGroupState {
+    pub name: String,
+    pub group: String,
+    pub joint_values: Vec<(String, Vec<f64>)> // multi-dof joints have one value per dof
+}
+
+#[derive(Clone, Debug)]
+pub struct SRDFEndEffector {
+    pub name: String,
+    pub parent_link: String,
+    pub group: String,
+    pub parent_group: Option<String>
+}
+
+#[derive(Clone, Debug)]
+pub struct SRDFVirtualJoint {
+    pub name: String,
+    pub joint_type: String, // fixed, floating, or planar
+    pub parent_frame: String,
+    pub child_link: String
+}
+
+#[derive(Clone, Debug)]
+pub struct SRDFDisableCollisions {
+    pub link1: String,
+    pub link2: String,
+    pub reason: String
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn read_srdf_file(fp: String) -> Result<SRDFData, String> {
+    let contents = read_file_contents(fp.clone());
+    if contents.is_none() { return Err(format!("srdf file {:?} could not be read", fp)); }
+    return parse_srdf_string(&contents.unwrap());
+}
+
+pub fn parse_srdf_string(s: &String) -> Result<SRDFData, String> {
+    let mut parser = Parser::new();
+    let mut element_builder = ElementBuilder::new();
+    parser.feed_str(s.as_str());
+
+    let mut root: Option<Element> = None;
+    for event in parser {
+        match element_builder.handle_event(event) {
+            Some(Ok(e)) => { root = Some(e); break; }
+            Some(Err(e)) => { return Err(format!("srdf could not be parsed: {:?}", e)); }
+            None => { }
+        }
+    }
+
+    if root.is_none() { return Err("srdf did not contain a root element".to_string()); }
+    let root = root.unwrap();
+    if root.name != "robot" { return Err(format!("srdf root element must be robot (got {:?})", root.name)); }
+
+    let mut out_data = SRDFData::new_empty( &_get_attribute(&root, "name").unwrap_or("".to_string()) );
+
+    for a in &root.children {
+        match a {
+            ElementNode(e) => {
+                if e.name == "group" {
+                    let mut group = SRDFGroup { name: _get_required_attribute(e, "name")?, chains: Vec::new(), joints: Vec::new(), links: Vec::new(), subgroups: Vec::new() };
+                    for b in &e.children {
+                        match b {
+                            ElementNode(c) => {
+                                if c.name == "chain" { group.chains.push( (_get_required_attribute(c, "base_link")?, _get_required_attribute(c, "tip_link")?) ); }
+                                else if c.name == "joint" { group.joints.push( _get_required_attribute(c, "name")? ); }
+                                else if c.name == "link" { group.links.push( _get_required_attribute(c, "name")? ); }
+                                else if c.name == "group" { group.subgroups.push( _get_required_attribute(c, "name")? ); }
+                            },
+                            _ => { }
+                        }
+                    }
+                    out_data.groups.push(group);
+                } else if e.name == "group_state" {
+                    let mut group_state = SRDFGroupState { name: _get_required_attribute(e, "name")?, group: _get_required_attribute(e, "group")?, joint_values: Vec::new() };
+                    for b in &e.children {
+                        match b {
+                            ElementNode(c) => {
+                                if c.name == "joint" {
+                                    let value_string = _get_required_attribute(c, "value")?;
+                                    // multi-dof joints list several space separated values
+                                    let mut values = Vec::new();
+                                    for v in value_string.split_whitespace() {
+                                        let value = v.parse::<f64>();
+                                        if value.is_err() { return Err(format!("joint value {:?} in group_state {:?} is not a number", value_string, group_state.name)); }
+                                        values.push(value.unwrap());
+                                    }
+                                    if values.is_empty() { return Err(format!("joint value in group_state {:?} is empty", group_state.name)); }
+                                    group_state.joint_values.push( (_get_required_attribute(c, "name")?, values) );
+                                }
+                            },
+                            _ => { }
+                        }
+                    }
+                    out_data.group_states.push(group_state);
+                } else if e.name == "end_effector" {
+                    out_data.end_effectors.push( SRDFEndEffector { name: _get_required_attribute(e, "name")?, parent_link: _get_required_attribute(e, "parent_link")?,
+                        group: _get_required_attribute(e, "group")?, parent_group: _get_attribute(e, "parent_group") } );
+                } else if e.name == "virtual_joint" {
+                    out_data.virtual_joints.push( SRDFVirtualJoint { name: _get_required_attribute(e, "name")?, joint_type: _get_required_attribute(e, "type")?,
+                        parent_frame: _get_required_attribute(e, "parent_frame")?, child_link: _get_required_attribute(e, "child_link")? } );
+                } else if e.name == "passive_joint" {
+                    out_data.passive_joints.push( _get_required_attribute(e, "name")? );
+                } else if e.name == "disable_collisions" {
+                    out_data.disable_collisions.push( SRDFDisableCollisions { link1: _get_required_attribute(e, "link1")?, link2: _get_required_attribute(e, "link2")?,
+                        reason: _get_attribute(e, "reason").unwrap_or("".to_string()) } );
+                }
+            },
+            _ => { }
+        }
+    }
+
+    return Ok(out_data);
+}
+
+pub fn write_srdf_file(fp: String, srdf_data: &SRDFData) -> Result<(), String> {
+    let path = std::path::Path::new(&fp);
+    let fp_to_dir = path.parent();
+    let file_name = path.file_name();
+    if fp_to_dir.is_none() || file_name.is_none() { return Err(format!("{:?} is not a valid srdf file path", fp)); }
+
+    write_string_to_file(fp_to_dir.unwrap().to_str().unwrap().to_string(), file_name.unwrap().to_str().unwrap().to_string(), srdf_data_to_string(srdf_data), true);
+    Ok(())
+}
+
+pub fn srdf_data_to_string(srdf_data: &SRDFData) -> String {
+    let mut out_string = "<?xml version=\"1.0\" ?>\n".to_string();
+    out_string += format!("<robot name=\"{}\">\n", xml::escape(&srdf_data.robot_name)).as_str();
+
+    for g in &srdf_data.groups {
+        out_string += format!("    <group name=\"{}\">\n", xml::escape(&g.name)).as_str();
+        for (base_link, tip_link) in &g.chains { out_string += format!("        <chain base_link=\"{}\" tip_link=\"{}\" />\n", xml::escape(base_link), xml::escape(tip_link)).as_str(); }
+        for j in &g.joints { out_string += format!("        <joint name=\"{}\" />\n", xml::escape(j)).as_str(); }
+        for l in &g.links { out_string += format!("        <link name=\"{}\" />\n", xml::escape(l)).as_str(); }
+        for s in &g.subgroups { out_string += format!("        <group name=\"{}\" />\n", xml::escape(s)).as_str(); }
+        out_string += "    </group>\n";
+    }
+
+    for s in &srdf_data.group_states {
+        out_string += format!("    <group_state name=\"{}\" group=\"{}\">\n", xml::escape(&s.name), xml::escape(&s.group)).as_str();
+        for (joint_name, values) in &s.joint_values {
+            let value_string = values.iter().map(|v| format!("{:?}", v)).collect::<Vec<String>>().join(" ");
+            out_string += format!("        <joint name=\"{}\" value=\"{}\" />\n", xml::escape(joint_name), value_string).as_str();
+        }
+        out_string += "    </group_state>\n";
+    }
+
+    for e in &srdf_data.end_effectors {
+        out_string += format!("    <end_effector name=\"{}\" parent_link=\"{}\" group=\"{}\"", xml::escape(&e.name), xml::escape(&e.parent_link), xml::escape(&e.group)).as_str();
+        if e.parent_group.is_some() { out_string += format!(" parent_group=\"{}\"", xml::escape(e.parent_group.as_ref().unwrap())).as_str(); }
+        out_string += " />\n";
+    }
+
+    for v in &srdf_data.virtual_joints {
+        out_string += format!("    <virtual_joint name=\"{}\" type=\"{}\" parent_frame=\"{}\" child_link=\"{}\" />\n", xml::escape(&v.name), xml::escape(&v.joint_type), xml::escape(&v.parent_frame), xml::escape(&v.child_link)).as_str();
+    }
+
+    for p in &srdf_data.passive_joints {
+        out_string += format!("    <passive_joint name=\"{}\" />\n", xml::escape(p)).as_str();
+    }
+
+    for d in &srdf_data.disable_collisions {
+        out_string += format!("    <disable_collisions link1=\"{}\" link2=\"{}\" reason=\"{}\" />\n", xml::escape(&d.link1), xml::escape(&d.link2), xml::escape(&d.reason)).as_str();
+    }
+
+    out_string += "</robot>\n";
+    return out_string;
+}
+
+fn _get_attribute(e: &Element, name: &str) -> Option<String> {
+    return e.attributes.get(&(name.to_string(), None)).map(|s| s.clone());
+}
+
+fn _get_required_attribute(e: &Element, name: &str) -> Result<String, String> {
+    let res = _get_attribute(e, name);
+    if res.is_none() { return Err(format!("srdf element {:?} is missing required attribute {:?}", e.name, name)); }
+    return Ok(res.unwrap());
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/* writes one configuration per planning group (named after the group), saves group states into those configurations,
+adds end effector parent links as EndEffector salient links, and adds manual collision check skips for all disabled
+collision pairs. */
+pub fn import_srdf_into_robot_directory(robot_name: &str, srdf_fp: String) -> Result<(), String> {
+    let srdf_data = read_srdf_file(srdf_fp)?;
+    let base_configuration_module = RobotConfigurationModule::new_base_configuration(robot_name);
+    let robot_model_module = &base_configuration_module.robot_model_module;
+
+    let mut mobile_base_mode = "static".to_string();
+    for v in &srdf_data.virtual_joints {
+        if v.joint_type == "floating" { mobile_base_mode = "floating".to_string(); }
+        else if v.joint_type == "planar" { mobile_base_mode = "planar_translation_and_rotation".to_string(); }
+    }
+
+    for g in &srdf_data.groups {
+        if srdf_data.end_effectors.iter().any(|e| e.group == g.name) {
+            for s in &srdf_data.group_states {
+                if s.group != g.name { continue; }
+                println!("{}{}WARNING: group_state {:?} belongs to end effector group {:?}, which is not turned into a configuration.  Skipping it.{}", style::Bold, color::Fg(color::Yellow), s.name, g.name, style::Reset);
+            }
+            continue;
+        }
+        let group_joint_names = srdf_data.get_group_joint_names(&g.name, robot_model_module)?;
+
+        let mut inactive_joint_names = Vec::new();
+        for j in &robot_model_module.joints {
+            if !group_joint_names.contains(&j.name) || srdf_data.passive_joints.contains(&j.name) { inactive_joint_names.push(j.name.clone()); }
+        }
+
+        let robot_configuration_module = RobotConfigurationModule::new_manual_inputs(robot_name, g.name.as_str(), ImplicitDualQuaternion::new_identity(), Vec::new(), inactive_joint_names, mobile_base_mode.clone(), None);
+        robot_configuration_module.save_robot_configuration_yaml();
+        robot_configuration_module.save_robot_configuration_module();
+
+        let robot_dof_module = RobotDOFModule::new(&robot_configuration_module);
+        let mut robot_saved_joint_states_module = RobotSavedJointStatesModule::new(&robot_configuration_module, &robot_dof_module);
+
+        for s in &srdf_data.group_states {
+            if s.group != g.name { continue; }
+            let mut state = DVector::from_element(robot_dof_module.get_num_dofs(), 0.0);
+            for (joint_name, values) in &s.joint_values {
+                let joint_idx = robot_configuration_module.robot_model_module.get_joint_idx_from_name(joint_name);
+                if joint_idx.is_none() {
+                    println!("{}{}WARNING: joint {:?} in group_state {:?} was not found in robot {:?}.  Skipping it.{}", style::Bold, color::Fg(color::Yellow), joint_name, s.name, robot_name, style::Reset);
+                    continue;
+                }
+                let joint = &robot_dof_module.get_joints_copy_ref()[joint_idx.unwrap()];
+                if !joint.active || joint.num_dofs == 0 { continue; }
+                if values.len() != joint.num_dofs {
+                    return Err(format!("joint {:?} in group_state {:?} has {:?} values, but the joint has {:?} dofs", joint_name, s.name, values.len(), joint.num_dofs));
+                }
+                let start_idx = robot_dof_module.get_input_x_starting_idx_from_joint_idx(joint_idx.unwrap());
+                for k in 0..values.len() { state[start_idx + k] = values[k]; }
+            }
+            robot_saved_joint_states_module.add_state(&state, s.name.clone());
+        }
+    }
+
+    if !srdf_data.end_effectors.is_empty() {
+        let mut robot_salient_links_module = RobotSalientLinksModule::new(&base_configuration_module);
+        for e in &srdf_data.end_effectors {
+            let link_idx = robot_model_module.get_link_idx_from_name(&e.parent_link);
+            if link_idx.is_none() { return Err(format!("end effector parent link {:?} was not found in robot {:?}", e.parent_link, robot_name)); }
+            robot_salient_links_module.add_salient_link( LinkInfo::new(e.parent_link.clone(), link_idx.unwrap(), None, None, None, SalientLinkType::EndEffector) );
+        }
+        robot_salient_links_module.save_to_file();
+    }
+
+    if !srdf_data.disable_collisions.is_empty() {
+        let mut robot = Robot::new(robot_name, None)?;
+        let robot_configuration_module = robot.get_configuration_module_ref().clone();
+        for d in &srdf_data.disable_collisions {
+            let res = robot.get_core_collision_module_mut_ref().add_manual_collision_check_skip_between_links_from_link_names(&robot_configuration_module, d.link1.clone(), d.link2.clone());
+            if res.is_err() {
+                println!("{}{}WARNING: could not disable collisions between {:?} and {:?}: {:?}{}", style::Bold, color::Fg(color::Yellow), d.link1, d.link2, res.err().unwrap(), style::Reset);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/* inverse of import_srdf_into_robot_directory.  Every configuration in the configurations directory becomes a group of
+its active joints, saved joint states become group states, EndEffector salient links become end effectors (with a group
+of the end effector's links, attached to the configuration group that moves it), and all link pairs that are skipped in
+the OBBs skip tensor become disabled collision pairs. */
+pub fn export_srdf_from_robot_directory(robot_name: &str, srdf_fp: String) -> Result<(), String> {
+    let mut srdf_data = SRDFData::new_empty(robot_name);
+
+    let mut configuration_names = get_all_files_in_directory_with_extension_relative_to_robot_directory(robot_name.to_string(), "configurations".to_string(), "yaml".to_string());
+    configuration_names.sort();
+
+    let mut group_joint_names = Vec::new();
+    let mut mobile_base_mode = "static".to_string();
+    for c in &configuration_names {
+        let configuration_name = get_filename_without_extension(c.clone());
+        if configuration_name == "sample_config" { continue; }
+
+        let robot_configuration_module = RobotConfigurationModule::new(robot_name, Some(configuration_name.as_str()))?;
+        if robot_configuration_module.mobile_base_mode != "static" { mobile_base_mode = robot_configuration_module.mobile_base_mode.clone(); }
+
+        let mut joint_names = Vec::new();
+        for j in &robot_configuration_module.robot_model_module.joints {
+            if j.active && j.num_dofs > 0 && j.name != "mobile_base_joint" { joint_names.push(j.name.clone()); }
+        }
+        srdf_data.groups.push( SRDFGroup { name: configuration_name.clone(), chains: Vec::new(), joints: joint_names.clone(), links: Vec::new(), subgroups: Vec::new() } );
+
+        let robot_dof_module = RobotDOFModule::new(&robot_configuration_module);
+        let robot_saved_joint_states_module = RobotSavedJointStatesModule::new(&robot_configuration_module, &robot_dof_module);
+        let mut state_names = robot_saved_joint_states_module.get_all_joint_state_name_options();
+        state_names.sort();
+        for s in state_names {
+            let state = robot_saved_joint_states_module.get_state_by_name(&s).unwrap();
+            let mut joint_values = Vec::new();
+            for j in &joint_names {
+                let joint_idx = robot_configuration_module.robot_model_module.get_joint_idx_from_name(j).unwrap();
+                let start_idx = robot_dof_module.get_input_x_starting_idx_from_joint_idx(joint_idx);
+                let num_dofs = robot_configuration_module.robot_model_module.joints[joint_idx].num_dofs;
+                joint_values.push( (j.clone(), (start_idx..start_idx + num_dofs).map(|k| state[k]).collect()) );
+            }
+            srdf_data.group_states.push( SRDFGroupState { name: s, group: configuration_name.clone(), joint_values } );
+        }
+
+        group_joint_names.push( (configuration_name, joint_names) );
+    }
+
+    let base_configuration_module = RobotConfigurationModule::new_base_configuration(robot_name);
+    let robot_model_module = &base_configuration_module.robot_model_module;
+
+    if mobile_base_mode == "floating" || mobile_base_mode == "planar_translation_and_rotation" {
+        let joint_type = if mobile_base_mode == "floating" { "floating" } else { "planar" };
+        srdf_data.virtual_joints.push( SRDFVirtualJoint { name: "mobile_base_joint".to_string(), joint_type: joint_type.to_string(),
+            parent_frame: "world".to_string(), child_link: robot_model_module.links[robot_model_module.world_link_idx].name.clone() } );
+    }
+
+    let robot_salient_links_module = RobotSalientLinksModule::new(&base_configuration_module);
+    for s in robot_salient_links_module.get_all_salient_links_ref() {
+        match s.salient_link_type {
+            SalientLinkType::EndEffector => {
+                // the end effector is attached to the first group that moves its nearest movable ancestor joint
+                let mut parent_group: Option<String> = None;
+                let mut curr_link_idx = Some(s.link_idx);
+                while parent_group.is_none() && curr_link_idx.is_some() {
+                    let link = &robot_model_module.links[curr_link_idx.unwrap()];
+                    if link.preceding_joint_idx.is_some() {
+                        let joint_name = &robot_model_module.joints[link.preceding_joint_idx.unwrap()].name;
+                        for (g, joint_names) in &group_joint_names {
+                            if joint_names.contains(joint_name) { parent_group = Some(g.clone()); break; }
+                        }
+                    }
+                    curr_link_idx = link.preceding_link_idx;
+                }
+                if parent_group.is_none() { parent_group = group_joint_names.get(0).map(|g| g.0.clone()); }
+                if parent_group.is_none() { continue; }
+
+                // the end effector's own group holds the end effector link and all links below it
+                let mut links = Vec::new();
+                let mut stack = vec![ s.link_idx ];
+                while !stack.is_empty() {
+                    let link = &robot_model_module.links[stack.pop().unwrap()];
+                    links.push(link.name.clone());
+                    for c in link.children_link_idxs.iter().rev() { stack.push(*c); }
+                }
+                let group_name = format!("{}_end_effector", s.link_name);
+                srdf_data.groups.push( SRDFGroup { name: group_name.clone(), chains: Vec::new(), joints: Vec::new(), links, subgroups: Vec::new() } );
+
+                srdf_data.end_effectors.push( SRDFEndEffector { name: s.link_name.clone(), parent_link: s.link_name.clone(), group: group_name, parent_group } );
+            },
+            _ => { }
+        }
+    }
+
+    let robot = Robot::new(robot_name, None)?;
+    let skipped_link_pairs = robot.get_core_collision_module_ref().get_skipped_link_pairs(robot.get_configuration_module_ref(), LinkGeometryType::OBBs)?;
+    for p in skipped_link_pairs {
+        let link_idx_1 = robot_model_module.get_link_idx_from_name(&p[0]).unwrap();
+        let link_idx_2 = robot_model_module.get_link_idx_from_name(&p[1]).unwrap();
+        let adjacent = robot_model_module.links[link_idx_1].preceding_link_idx == Some(link_idx_2) || robot_model_module.links[link_idx_2].preceding_link_idx == Some(link_idx_1);
+        let reason = if adjacent { "Adjacent" } else { "Never" };
+        srdf_data.disable_collisions.push( SRDFDisableCollisions { link1: p[0].clone(), link2: p[1].clone(), reason: reason.to_string() } );
+    }
+
+    return write_srdf_file(srdf_fp, &srdf_data);
+}
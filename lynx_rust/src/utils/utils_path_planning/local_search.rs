@@ -66,32 +66,37 @@ impl LocalSearch for StraightLineLocalSearch {
         let mut dir = q_goal - q_init;
         dir = &dir / dir.norm();
 
+        let in_collision_res = self._collision_checker.in_collision(q_init, lynx_vars)?;
+        match in_collision_res {
+            CollisionCheckResult::NotInCollision => { out_path.add_waypoint(q_init); }
+            CollisionCheckResult::InCollision(s) => { return Ok(PathPlannerResult::SolutionNotFoundButPartialSolutionReturned(out_path)); }
+            CollisionCheckResult::Error(s) => { return Err(s); }
+        }
+
         let mut curr_point = q_init.clone();
-        let mut is_goal = false;
 
         loop {
             if terminate.get_terminate() {
                 return Ok(PathPlannerResult::SolutionNotFoundButPartialSolutionReturned(out_path));
             }
 
-            let in_collision_res = self._collision_checker.in_collision(&curr_point, lynx_vars)?;
+            let mut is_goal = false;
+            let mut new_point = &curr_point + self._lambda * &dir;
+            let d1 = (&curr_point - q_goal).norm();
+            let d2 = (&new_point - q_goal).norm();
+            if d1 <= self._lambda || d1 < d2 {
+                new_point = q_goal.clone();
+                is_goal = true;
+            }
+
+            let in_collision_res = self._collision_checker.segment_in_collision(&curr_point, &new_point, lynx_vars)?;
             match in_collision_res {
                 CollisionCheckResult::NotInCollision => {
-                    out_path.add_waypoint(&curr_point);
+                    out_path.add_waypoint(&new_point);
                     if is_goal {
                         return Ok(PathPlannerResult::SolutionFound(out_path));
                     }
-
-                    let new_point = &curr_point + self._lambda * &dir;
-
-                    let d1 = (&curr_point - q_goal).norm();
-                    let d2 = (&new_point - q_goal).norm();
-                    if d1 < d2 {
-                        curr_point = q_goal.clone();
-                        is_goal = true;
-                    } else {
-                        curr_point = new_point.clone();
-                    }
+                    curr_point = new_point;
                 }
                 CollisionCheckResult::InCollision(s) => { return Ok(PathPlannerResult::SolutionNotFoundButPartialSolutionReturned(out_path)); }
                 CollisionCheckResult::Error(s) => { return Err(s); }
@@ -2,6 +2,7 @@ pub mod utils_planning_graphs;
 pub mod path_planner_result;
 pub mod local_search;
 pub mod global_search;
+pub mod path_cost;
 pub mod utils_surge;
 pub mod local_planner_gym;
 pub mod path_planning_query;
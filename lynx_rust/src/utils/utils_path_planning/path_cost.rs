@@ -0,0 +1,106 @@
+use crate::utils::utils_paths::linear_spline_path::LinearSplinePath;
+use nalgebra::DVector;
+
+pub trait PathCost: Send + Sync + PathCostClone {
+    fn segment_cost(&self, q_a: &DVector<f64>, q_b: &DVector<f64>) -> f64;
+    fn path_cost(&self, path: &LinearSplinePath) -> f64 {
+        let mut out_cost = 0.0;
+        let l = path.waypoints.len();
+        for i in 1..l { out_cost += self.segment_cost(&path.waypoints[i - 1], &path.waypoints[i]); }
+        return out_cost;
+    }
+    /* if Some(w), the cost of any path from q_a to q_b is at least the euclidean norm of w .* (q_b - q_a).  Informed
+    samplers need this bound, and fall back to uniform sampling when it is None. */
+    fn get_heuristic_scaling(&self, dim: usize) -> Option<DVector<f64>> { return None; }
+    fn check_dimension(&self, dim: usize) -> Result<(), String> { return Ok(()); }
+    fn to_path_cost_box(&self) -> PathCostBox {
+        return PathCostBox(self.clone_box());
+    }
+    fn name(&self) -> String;
+}
+
+pub trait PathCostClone {
+    fn clone_box(&self) -> Box<dyn PathCost>;
+}
+impl<T> PathCostClone for T where T: 'static + PathCost + Clone {
+    fn clone_box(&self) -> Box<dyn PathCost> {
+        Box::new(self.clone())
+    }
+}
+
+pub struct PathCostBox(Box<dyn PathCost>);
+impl PathCostBox {
+    pub fn new(path_cost: &dyn PathCost) -> Self {
+        return Self( path_cost.clone_box() );
+    }
+
+    pub fn new_joint_space_length() -> Self {
+        return JointSpaceLengthPathCost::new().to_path_cost_box();
+    }
+
+    pub fn segment_cost(&self, q_a: &DVector<f64>, q_b: &DVector<f64>) -> f64 { return self.0.segment_cost(q_a, q_b); }
+
+    pub fn path_cost(&self, path: &LinearSplinePath) -> f64 { return self.0.path_cost(path); }
+
+    pub fn get_heuristic_scaling(&self, dim: usize) -> Option<DVector<f64>> { return self.0.get_heuristic_scaling(dim); }
+
+    pub fn check_dimension(&self, dim: usize) -> Result<(), String> { return self.0.check_dimension(dim); }
+
+    pub fn name(&self) -> String { return self.0.name(); }
+}
+impl Clone for PathCostBox {
+    fn clone(&self) -> Self {
+        let c = self.0.clone_box();
+        return Self(c);
+    }
+}
+unsafe impl Send for PathCostBox { }
+unsafe impl Sync for PathCostBox { }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/* length of the path in joint space.  With weights, each joint's displacement is scaled by its weight before taking the
+norm, e.g., to make base joints more expensive to move than wrist joints. */
+#[derive(Clone)]
+pub struct JointSpaceLengthPathCost {
+    _weights: Option<DVector<f64>>
+}
+impl JointSpaceLengthPathCost {
+    pub fn new() -> Self {
+        return Self { _weights: None };
+    }
+
+    pub fn new_weighted(weights: Vec<f64>) -> Result<Self, String> {
+        for w in &weights {
+            if !(*w > 0.0) { return Err(format!("all weights in JointSpaceLengthPathCost must be positive (got {:?})", weights)); }
+        }
+        return Ok(Self { _weights: Some(DVector::from_vec(weights)) });
+    }
+
+    pub fn get_weights_ref(&self) -> &Option<DVector<f64>> {
+        return &self._weights;
+    }
+}
+impl PathCost for JointSpaceLengthPathCost {
+    fn segment_cost(&self, q_a: &DVector<f64>, q_b: &DVector<f64>) -> f64 {
+        return match &self._weights {
+            None => { (q_b - q_a).norm() }
+            Some(w) => { (q_b - q_a).component_mul(w).norm() }
+        }
+    }
+    fn get_heuristic_scaling(&self, dim: usize) -> Option<DVector<f64>> {
+        return match &self._weights {
+            None => { Some(DVector::from_element(dim, 1.0)) }
+            Some(w) => { Some(w.clone()) }
+        }
+    }
+    fn check_dimension(&self, dim: usize) -> Result<(), String> {
+        if self._weights.is_some() && self._weights.as_ref().unwrap().len() != dim {
+            return Err(format!("JointSpaceLengthPathCost has {:?} weights, but states have dimension {:?}", self._weights.as_ref().unwrap().len(), dim));
+        }
+        return Ok(());
+    }
+    fn name(&self) -> String {
+        return if self._weights.is_some() { "weighted_joint_space_length".to_string() } else { "joint_space_length".to_string() };
+    }
+}
@@ -1,3 +1,3 @@
 pub use crate::utils::utils_path_planning::{utils_planning_graphs::prelude::*,
                                             utils_surge::prelude::*, global_search::*,
-                                            local_search::*, path_planner_result::*, local_planner_gym::*};
\ No newline at end of file
+                                            local_search::*, path_planner_result::*, local_planner_gym::*, path_cost::*};
\ No newline at end of file
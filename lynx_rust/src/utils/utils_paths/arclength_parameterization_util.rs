@@ -60,3 +60,27 @@ impl ArclengthParameterizationUtil {
         }
     }
 }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn get_waypoints_length(waypoints: &Vec<DVector<f64>>) -> f64 {
+    let mut out_val = 0.0;
+    let l = waypoints.len();
+    for i in 1..l { out_val += (&waypoints[i] - &waypoints[i-1]).norm(); }
+    return out_val;
+}
+
+// returns the index of the segment containing the point at arclength s (segment i goes from waypoint i to i + 1), and the point
+pub fn get_point_at_arclength(waypoints: &Vec<DVector<f64>>, s: f64) -> (usize, DVector<f64>) {
+    let mut accumulated = 0.0;
+    let l = waypoints.len();
+    for i in 0..l - 1 {
+        let segment_length = (&waypoints[i+1] - &waypoints[i]).norm();
+        if s <= accumulated + segment_length || i == l - 2 {
+            let t = if segment_length > 0.0 { ((s - accumulated) / segment_length).max(0.0).min(1.0) } else { 0.0 };
+            return (i, (1.0 - t) * &waypoints[i] + t * &waypoints[i+1]);
+        }
+        accumulated += segment_length;
+    }
+    return (0, waypoints[0].clone());
+}
@@ -3,3 +3,8 @@ pub mod path_segment_window;
 pub mod path_resample;
 pub mod path_splitter_and_gluer;
 pub mod arclength_parameterization_util;
+pub mod timed_path;
+pub mod path_validation_report;
+pub mod path_shortcutting;
+pub mod timed_trajectory;
+pub mod time_parameterization;
@@ -0,0 +1,182 @@
+use crate::utils::utils_paths::{linear_spline_path::LinearSplinePath, arclength_parameterization_util::{get_waypoints_length, get_point_at_arclength}};
+use crate::utils::utils_collisions::{collision_checker::*, collision_check_result_enum::CollisionCheckResult};
+use crate::utils::utils_vars::lynx_vars_generic::LynxVarsGeneric;
+use nalgebra::DVector;
+use rand::distributions::{Distribution, Uniform};
+use std::time::Instant;
+
+/* post-processing for jagged paths (e.g., from RRTConnect or Surge).  Every new straight segment is checked by calling the
+collision checker on states at most lambda apart (both ends included), and a change is only kept if all of those states are
+collision free and the path gets shorter.  Untouched parts of the input path are never rechecked, so the output is collision
+free at lambda resolution as long as the input path is. */
+#[derive(Clone)]
+pub struct PathShortcutter {
+    _collision_checker: CollisionCheckerBox,
+    _lambda: f64
+}
+impl PathShortcutter {
+    pub fn new(collision_checker: CollisionCheckerBox, lambda: f64) -> Result<Self, String> {
+        if !(lambda > 0.0) { return Err(format!("lambda must be positive in PathShortcutter (got {:?})", lambda)); }
+        return Ok(Self { _collision_checker: collision_checker, _lambda: lambda });
+    }
+
+    // pruning, then random shortcutting and partial shortcutting (sharing the budget), then pruning again
+    pub fn shortcut(&self, path: &LinearSplinePath, lynx_vars: &mut LynxVarsGeneric, max_num_iterations: usize, time_budget_in_seconds: Option<f64>) -> Result<LinearSplinePath, String> {
+        let start = Instant::now();
+        let mut out_path = self.prune_waypoints(path, lynx_vars)?;
+        let half_time_budget = time_budget_in_seconds.map(|t| t / 2.0);
+        out_path = self.random_shortcutting(&out_path, lynx_vars, max_num_iterations / 2, half_time_budget)?;
+        let remaining_time_budget = time_budget_in_seconds.map(|t| (t - start.elapsed().as_secs_f64()).max(0.0));
+        out_path = self.partial_shortcutting(&out_path, lynx_vars, max_num_iterations - max_num_iterations / 2, remaining_time_budget)?;
+        return self.prune_waypoints(&out_path, lynx_vars);
+    }
+
+    /* repeatedly picks two random points along the path (by arclength) and replaces the path between them with a straight
+    segment if that segment is collision free */
+    pub fn random_shortcutting(&self, path: &LinearSplinePath, lynx_vars: &mut LynxVarsGeneric, max_num_iterations: usize, time_budget_in_seconds: Option<f64>) -> Result<LinearSplinePath, String> {
+        let start = Instant::now();
+        let mut waypoints = path.waypoints.clone();
+        let mut rng = rand::thread_rng();
+        let uniform = Uniform::new(0.0, 1.0);
+
+        for _ in 0..max_num_iterations {
+            if time_budget_in_seconds.is_some() && start.elapsed().as_secs_f64() >= time_budget_in_seconds.unwrap() { break; }
+            if waypoints.len() < 3 { break; }
+
+            let (a, b) = get_random_arclength_pair(&waypoints, &mut rng, &uniform);
+            let (seg_a, p_a) = get_point_at_arclength(&waypoints, a);
+            let (seg_b, p_b) = get_point_at_arclength(&waypoints, b);
+            if seg_a == seg_b { continue; }
+
+            let old_length = (&p_a - &waypoints[seg_a + 1]).norm() + get_waypoints_length(&waypoints[seg_a + 1..seg_b + 1].to_vec()) + (&waypoints[seg_b] - &p_b).norm();
+            if (&p_b - &p_a).norm() >= old_length - 0.0000001 { continue; }
+            if !self.is_segment_collision_free(&p_a, &p_b, lynx_vars)? { continue; }
+
+            let mut new_waypoints = waypoints[0..seg_a + 1].to_vec();
+            push_if_new(&mut new_waypoints, &p_a);
+            push_if_new(&mut new_waypoints, &p_b);
+            for w in &waypoints[seg_b + 1..] { push_if_new(&mut new_waypoints, w); }
+            waypoints = new_waypoints;
+        }
+
+        return Ok(LinearSplinePath::new(waypoints));
+    }
+
+    /* shortcutting on one random dof at a time: between two random points along the path, that dof is linearly
+    interpolated (by arclength) while all other dofs keep their values.  This straightens individual joints in cases where
+    a full shortcut would collide */
+    pub fn partial_shortcutting(&self, path: &LinearSplinePath, lynx_vars: &mut LynxVarsGeneric, max_num_iterations: usize, time_budget_in_seconds: Option<f64>) -> Result<LinearSplinePath, String> {
+        let start = Instant::now();
+        let mut waypoints = path.waypoints.clone();
+        if waypoints.is_empty() { return Ok(LinearSplinePath::new(waypoints)); }
+        let dim = waypoints[0].len();
+        let mut rng = rand::thread_rng();
+        let uniform = Uniform::new(0.0, 1.0);
+
+        for _ in 0..max_num_iterations {
+            if time_budget_in_seconds.is_some() && start.elapsed().as_secs_f64() >= time_budget_in_seconds.unwrap() { break; }
+            if waypoints.len() < 3 || dim == 0 { break; }
+
+            let dof = ((uniform.sample(&mut rng) * dim as f64) as usize).min(dim - 1);
+            let (a, b) = get_random_arclength_pair(&waypoints, &mut rng, &uniform);
+            let (seg_a, p_a) = get_point_at_arclength(&waypoints, a);
+            let (seg_b, p_b) = get_point_at_arclength(&waypoints, b);
+            if seg_a == seg_b { continue; }
+
+            let mut old_section = vec![p_a.clone()];
+            for w in &waypoints[seg_a + 1..seg_b + 1] { old_section.push(w.clone()); }
+            old_section.push(p_b.clone());
+            let old_length = get_waypoints_length(&old_section);
+            if !(old_length > 0.0) { continue; }
+
+            let mut new_section = old_section.clone();
+            let mut s = 0.0;
+            let l = old_section.len();
+            for i in 1..l - 1 {
+                s += (&old_section[i] - &old_section[i-1]).norm();
+                let t = s / old_length;
+                new_section[i][dof] = (1.0 - t) * p_a[dof] + t * p_b[dof];
+            }
+            if get_waypoints_length(&new_section) >= old_length - 0.0000001 { continue; }
+
+            let mut free = true;
+            for i in 1..l {
+                if !self.is_segment_collision_free(&new_section[i-1], &new_section[i], lynx_vars)? { free = false; break; }
+            }
+            if !free { continue; }
+
+            let mut new_waypoints = waypoints[0..seg_a + 1].to_vec();
+            for w in &new_section { push_if_new(&mut new_waypoints, w); }
+            for w in &waypoints[seg_b + 1..] { push_if_new(&mut new_waypoints, w); }
+            waypoints = new_waypoints;
+        }
+
+        return Ok(LinearSplinePath::new(waypoints));
+    }
+
+    // greedily connects each kept waypoint to the farthest later waypoint it can reach in a straight line
+    pub fn prune_waypoints(&self, path: &LinearSplinePath, lynx_vars: &mut LynxVarsGeneric) -> Result<LinearSplinePath, String> {
+        let waypoints = &path.waypoints;
+        let l = waypoints.len();
+        if l < 3 { return Ok(path.clone()); }
+
+        let mut out_path = LinearSplinePath::new(vec![waypoints[0].clone()]);
+        let mut curr_idx = 0;
+        while curr_idx < l - 1 {
+            let mut next_idx = curr_idx + 1;
+            let mut j = l - 1;
+            while j > curr_idx + 1 {
+                if self.is_segment_collision_free(&waypoints[curr_idx], &waypoints[j], lynx_vars)? { next_idx = j; break; }
+                j -= 1;
+            }
+            out_path.add_waypoint(&waypoints[next_idx]);
+            curr_idx = next_idx;
+        }
+
+        return Ok(out_path);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn is_segment_collision_free(&self, q_a: &DVector<f64>, q_b: &DVector<f64>, lynx_vars: &mut LynxVarsGeneric) -> Result<bool, String> {
+        let num_steps = ((q_b - q_a).norm() / self._lambda).ceil().max(1.0) as usize;
+        for i in 0..num_steps + 1 {
+            let t = i as f64 / num_steps as f64;
+            let q = (1.0 - t) * q_a + t * q_b;
+            let res = self._collision_checker.in_collision(&q, lynx_vars)?;
+            match res {
+                CollisionCheckResult::NotInCollision => { }
+                CollisionCheckResult::InCollision(_) => { return Ok(false); }
+                CollisionCheckResult::Error(s) => { return Err(s); }
+            }
+        }
+        return Ok(true);
+    }
+
+    pub fn is_path_collision_free(&self, path: &LinearSplinePath, lynx_vars: &mut LynxVarsGeneric) -> Result<bool, String> {
+        let l = path.waypoints.len();
+        if l == 1 { return self.is_segment_collision_free(&path.waypoints[0], &path.waypoints[0], lynx_vars); }
+        for i in 1..l {
+            if !self.is_segment_collision_free(&path.waypoints[i-1], &path.waypoints[i], lynx_vars)? { return Ok(false); }
+        }
+        return Ok(true);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn get_linear_spline_path_length(path: &LinearSplinePath) -> f64 {
+    return get_waypoints_length(&path.waypoints);
+}
+
+// returns (a, b) with a < b, both in [0, path length]
+fn get_random_arclength_pair(waypoints: &Vec<DVector<f64>>, rng: &mut rand::rngs::ThreadRng, uniform: &Uniform<f64>) -> (f64, f64) {
+    let length = get_waypoints_length(waypoints);
+    let a = uniform.sample(rng) * length;
+    let b = uniform.sample(rng) * length;
+    return if a < b { (a, b) } else { (b, a) };
+}
+
+fn push_if_new(waypoints: &mut Vec<DVector<f64>>, w: &DVector<f64>) {
+    if waypoints.is_empty() || (&waypoints[waypoints.len() - 1] - w).norm() > 0.000001 { waypoints.push(w.clone()); }
+}
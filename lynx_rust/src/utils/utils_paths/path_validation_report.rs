@@ -0,0 +1,90 @@
+use crate::utils::utils_files_and_strings::file_utils::*;
+use serde::{Serialize, Deserialize};
+
+/* result of RobotWorld::validate_path.  Each segment covers the straight joint space line between two consecutive
+waypoints, and parameters are in [0, 1] along that segment.  Everything here is plain data so reports on stored
+trajectories can be saved as json and diffed against later runs. */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PathValidationReport {
+    pub link_geometry_type: String,
+    pub resolution: f64,
+    pub num_waypoints: usize,
+    pub segments: Vec<PathSegmentValidationResult>
+}
+
+impl PathValidationReport {
+    pub fn is_valid(&self) -> bool {
+        return self.segments.iter().all(|s| s.is_valid());
+    }
+
+    pub fn get_first_invalid_segment_idx(&self) -> Option<usize> {
+        for s in &self.segments {
+            if !s.is_valid() { return Some(s.segment_idx); }
+        }
+        return None;
+    }
+
+    // None if no robot link came within the broad phase distance cutoff of anything along the whole path
+    pub fn get_min_clearance(&self) -> Option<f64> {
+        let mut out_val: Option<f64> = None;
+        for s in &self.segments {
+            if s.min_clearance.is_some() {
+                let c = s.min_clearance.unwrap();
+                if out_val.is_none() || c < out_val.unwrap() { out_val = Some(c); }
+            }
+        }
+        return out_val;
+    }
+
+    pub fn print_summary(&self) {
+        println!("path validation report: {:?} waypoints, {:?} segments, resolution {:?}, {:?}", self.num_waypoints, self.segments.len(), self.resolution, self.link_geometry_type);
+        for s in &self.segments {
+            println!("   segment {:?} ---> valid: {:?}, first collision: {:?}, colliding pairs: {:?}, min clearance: {:?}, out of bounds states: {:?}", s.segment_idx, s.is_valid(), s.first_colliding_parameter, s.colliding_pairs, s.min_clearance, s.out_of_bounds_states.len());
+        }
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn to_json_string(&self) -> String {
+        return serde_json::to_string_pretty(&self).unwrap();
+    }
+
+    pub fn new_from_json_string(json_string: &str) -> Result<Self, String> {
+        let out_self = serde_json::from_str(json_string);
+        if out_self.is_err() { return Err(format!("could not parse json string as a PathValidationReport: {:?}", out_self.err().unwrap().to_string())); }
+        return Ok(out_self.unwrap());
+    }
+
+    pub fn save_to_file(&self, fp_to_dir: String, file_name: String) {
+        write_string_to_file(fp_to_dir, file_name, self.to_json_string(), true);
+    }
+
+    pub fn load_from_file(fp_to_dir: String, file_name: String) -> Result<Self, String> {
+        let json_string = read_file_contents_separated_args(fp_to_dir.clone(), file_name.clone());
+        if json_string.is_none() { return Err(format!("no file {:?} found when trying to load a PathValidationReport", fp_to_dir + "/" + file_name.as_str())); }
+        return Self::new_from_json_string(&json_string.unwrap());
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PathSegmentValidationResult {
+    pub segment_idx: usize,
+    pub num_states_checked: usize,
+    pub first_colliding_parameter: Option<f64>,
+    pub colliding_pairs: Vec<[String; 2]>, // unique pairs over all colliding states on the segment
+    pub min_clearance: Option<f64>, // 0.0 if any state is in collision
+    pub out_of_bounds_states: Vec<PathOutOfBoundsState>
+}
+
+impl PathSegmentValidationResult {
+    pub fn is_valid(&self) -> bool {
+        return self.first_colliding_parameter.is_none() && self.out_of_bounds_states.is_empty();
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PathOutOfBoundsState {
+    pub parameter: f64,
+    pub state: Vec<f64>,
+    pub message: String
+}
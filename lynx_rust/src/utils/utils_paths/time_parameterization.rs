@@ -0,0 +1,349 @@
+use crate::utils::utils_paths::{linear_spline_path::LinearSplinePath, timed_trajectory::TimedTrajectory, arclength_parameterization_util::{get_waypoints_length, get_point_at_arclength}};
+use nalgebra::DVector;
+
+/* per dof velocity, acceleration, and (optionally) jerk limits, all symmetric about zero */
+#[derive(Clone, Debug)]
+pub struct TimeParameterizationLimits {
+    _velocity_limits: DVector<f64>,
+    _acceleration_limits: DVector<f64>,
+    _jerk_limits: Option<DVector<f64>>
+}
+impl TimeParameterizationLimits {
+    pub fn new(velocity_limits: Vec<f64>, acceleration_limits: Vec<f64>, jerk_limits: Option<Vec<f64>>) -> Result<Self, String> {
+        let dim = velocity_limits.len();
+        if acceleration_limits.len() != dim {
+            return Err(format!("got {:?} velocity limits but {:?} acceleration limits", dim, acceleration_limits.len()));
+        }
+        if jerk_limits.is_some() && jerk_limits.as_ref().unwrap().len() != dim {
+            return Err(format!("got {:?} velocity limits but {:?} jerk limits", dim, jerk_limits.as_ref().unwrap().len()));
+        }
+
+        let mut all_limits = velocity_limits.clone();
+        all_limits.extend(acceleration_limits.iter());
+        if jerk_limits.is_some() { all_limits.extend(jerk_limits.as_ref().unwrap().iter()); }
+        for l in &all_limits {
+            if !(*l > 0.0) || !l.is_finite() { return Err(format!("all time parameterization limits must be positive and finite (got {:?})", l)); }
+        }
+
+        return Ok(Self { _velocity_limits: DVector::from_vec(velocity_limits), _acceleration_limits: DVector::from_vec(acceleration_limits),
+            _jerk_limits: jerk_limits.map(|j| DVector::from_vec(j)) });
+    }
+
+    pub fn get_velocity_limits_ref(&self) -> &DVector<f64> {
+        return &self._velocity_limits;
+    }
+
+    pub fn get_acceleration_limits_ref(&self) -> &DVector<f64> {
+        return &self._acceleration_limits;
+    }
+
+    pub fn get_jerk_limits_ref(&self) -> &Option<DVector<f64>> {
+        return &self._jerk_limits;
+    }
+
+    pub fn get_dim(&self) -> usize {
+        return self._velocity_limits.len();
+    }
+
+    /* checks the velocities and accelerations stored at every knot, allowing each limit to be exceeded by the given fraction
+    (e.g., 0.01) to absorb numerical error.  Jerk is not checked, since accelerations may jump at knots. */
+    pub fn check_trajectory(&self, trajectory: &TimedTrajectory, tolerance: f64) -> Result<(), String> {
+        let dim = self.get_dim();
+        let l = trajectory.get_num_knots();
+        for i in 0..l {
+            if trajectory.velocities[i].len() != dim { return Err(format!("timed trajectory has dimension {:?}, but limits have dimension {:?}", trajectory.velocities[i].len(), dim)); }
+            for j in 0..dim {
+                if trajectory.velocities[i][j].abs() > self._velocity_limits[j] * (1.0 + tolerance) {
+                    return Err(format!("velocity {:?} of dof {:?} at time {:?} exceeds limit {:?}", trajectory.velocities[i][j], j, trajectory.times[i], self._velocity_limits[j]));
+                }
+                if trajectory.accelerations[i][j].abs() > self._acceleration_limits[j] * (1.0 + tolerance) {
+                    return Err(format!("acceleration {:?} of dof {:?} at time {:?} exceeds limit {:?}", trajectory.accelerations[i][j], j, trajectory.times[i], self._acceleration_limits[j]));
+                }
+            }
+        }
+        return Ok(());
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/* turns a geometric LinearSplinePath into a TimedTrajectory that starts and ends at rest and respects per dof limits.
+
+The time optimal method is a path-velocity decomposition in the style of TOPP-RA: the path is discretized into evenly spaced
+gridpoints by arclength s, and with x = sdot^2 and u = sddot constant between gridpoints, the joint limits become linear
+constraints on (x, u) at each gridpoint.  A backward pass computes the largest x at each gridpoint from which the end can
+still be reached at rest, and a forward pass then accelerates as hard as possible while staying under those values.
+Path derivatives come from finite differences over the grid, so corners of the linear spline are taken as if rounded over
+one grid step; finer grids slow down more at corners.  Jerk is not limited.
+
+The trapezoidal and jerk limited (seven phase s-curve) methods stop at every waypoint and move along each segment with the
+fastest profile allowed by the limits projected onto that segment's direction.  They are useful as fallbacks, and for
+sparse paths (e.g., after shortcutting) that a robot should follow exactly. */
+#[derive(Clone, Debug)]
+pub struct TimeParameterizer {
+    _limits: TimeParameterizationLimits,
+    _num_gridpoints: usize
+}
+impl TimeParameterizer {
+    pub fn new(limits: TimeParameterizationLimits, num_gridpoints: usize) -> Result<Self, String> {
+        if num_gridpoints < 2 { return Err(format!("time parameterizer needs at least 2 gridpoints ({:?} given)", num_gridpoints)); }
+        return Ok(Self { _limits: limits, _num_gridpoints: num_gridpoints });
+    }
+
+    /* jerk limited if jerk limits are given (the time optimal method cannot limit jerk), otherwise time optimal, falling
+    back to trapezoidal if the time optimal method fails */
+    pub fn parameterize(&self, path: &LinearSplinePath) -> Result<TimedTrajectory, String> {
+        if self._limits.get_jerk_limits_ref().is_some() { return self.parameterize_jerk_limited(path); }
+        let res = self.parameterize_time_optimal(path);
+        if res.is_ok() { return res; }
+        return self.parameterize_trapezoidal(path);
+    }
+
+    pub fn parameterize_time_optimal(&self, path: &LinearSplinePath) -> Result<TimedTrajectory, String> {
+        let waypoints = self._get_cleaned_waypoints(path)?;
+        if waypoints.len() == 1 { return Ok(get_stationary_trajectory(&waypoints[0])); }
+
+        let n = self._num_gridpoints;
+        let ds = get_waypoints_length(&waypoints) / (n - 1) as f64;
+        let mut grid = Vec::new();
+        for i in 0..n { grid.push(get_point_at_arclength(&waypoints, i as f64 * ds).1); }
+
+        // first and second derivatives of the path with respect to arclength at each gridpoint
+        let dim = self._limits.get_dim();
+        let mut dq = Vec::new();
+        let mut ddq = Vec::new();
+        for i in 0..n {
+            if i == 0 {
+                dq.push((&grid[1] - &grid[0]) / ds);
+                ddq.push(DVector::from_element(dim, 0.0));
+            } else if i == n - 1 {
+                dq.push((&grid[n-1] - &grid[n-2]) / ds);
+                ddq.push(DVector::from_element(dim, 0.0));
+            } else {
+                dq.push((&grid[i+1] - &grid[i-1]) / (2.0 * ds));
+                ddq.push((&grid[i+1] - 2.0 * &grid[i] + &grid[i-1]) / (ds * ds));
+            }
+        }
+
+        // largest admissible x at each gridpoint from the velocity limits and from acceleration feasibility
+        let mut x_admissible = Vec::new();
+        for i in 0..n {
+            let x_upper = self._get_max_x_from_velocity_limits(&dq[i], &ddq[i]);
+            if !x_upper.is_finite() { return Err(format!("path is degenerate at gridpoint {:?} in time optimal parameterization", i)); }
+            x_admissible.push(get_max_feasible(x_upper, |x| self._get_u_bounds(&dq[i], &ddq[i], x).is_some()));
+        }
+
+        // backward pass: controllable x values, ending at rest
+        let mut x_controllable = vec![0.0; n];
+        for i in (0..n-1).rev() {
+            let x_next = x_controllable[i+1];
+            x_controllable[i] = get_max_feasible(x_admissible[i], |x| {
+                match self._get_u_bounds(&dq[i], &ddq[i], x) {
+                    Some((u_lo, u_hi)) => { x + 2.0 * ds * u_lo <= x_next && x + 2.0 * ds * u_hi >= 0.0 }
+                    None => { false }
+                }
+            });
+        }
+
+        // forward pass: greedy acceleration from rest, staying controllable
+        let mut x = vec![0.0; n];
+        let mut u = vec![0.0; n];
+        for i in 0..n-1 {
+            let (u_lo, u_hi) = match self._get_u_bounds(&dq[i], &ddq[i], x[i]) {
+                Some(b) => { b }
+                None => { return Err(format!("time optimal parameterization left the feasible set at gridpoint {:?}", i)); }
+            };
+            let u_i = u_hi.min((x_controllable[i+1] - x[i]) / (2.0 * ds)).max(u_lo);
+            x[i+1] = (x[i] + 2.0 * ds * u_i).max(0.0).min(x_controllable[i+1]);
+            u[i] = (x[i+1] - x[i]) / (2.0 * ds);
+        }
+        // the last knot stores the acceleration just before it, clamped to what is feasible at rest there
+        u[n-1] = match self._get_u_bounds(&dq[n-1], &ddq[n-1], 0.0) {
+            Some((u_lo, u_hi)) => { u[n-2].max(u_lo).min(u_hi) }
+            None => { 0.0 }
+        };
+
+        let mut times = vec![0.0];
+        for i in 0..n-1 {
+            let sdot_sum = x[i].sqrt() + x[i+1].sqrt();
+            if !(sdot_sum > 0.000000000001) { return Err(format!("time optimal parameterization stalls between gridpoints {:?} and {:?}", i, i+1)); }
+            times.push(times[i] + 2.0 * ds / sdot_sum);
+        }
+
+        let mut velocities = Vec::new();
+        let mut accelerations = Vec::new();
+        for i in 0..n {
+            velocities.push(x[i].sqrt() * &dq[i]);
+            accelerations.push(u[i] * &dq[i] + x[i] * &ddq[i]);
+        }
+
+        return TimedTrajectory::new(times, grid, velocities, accelerations);
+    }
+
+    pub fn parameterize_trapezoidal(&self, path: &LinearSplinePath) -> Result<TimedTrajectory, String> {
+        let waypoints = self._get_cleaned_waypoints(path)?;
+        return self._parameterize_segment_wise(&waypoints, |d, e| {
+            let v = get_directional_limit(self._limits.get_velocity_limits_ref(), e);
+            let a = get_directional_limit(self._limits.get_acceleration_limits_ref(), e);
+            let (t_a, t_v) = if d >= v * v / a { (v / a, (d - v * v / a) / v) } else { ((d / a).sqrt(), 0.0) };
+            return vec![(t_a, a, 0.0), (t_v, 0.0, 0.0), (t_a, -a, 0.0)];
+        });
+    }
+
+    pub fn parameterize_jerk_limited(&self, path: &LinearSplinePath) -> Result<TimedTrajectory, String> {
+        if self._limits.get_jerk_limits_ref().is_none() { return Err("jerk limited time parameterization needs jerk limits".to_string()); }
+        let waypoints = self._get_cleaned_waypoints(path)?;
+        return self._parameterize_segment_wise(&waypoints, |d, e| {
+            let v = get_directional_limit(self._limits.get_velocity_limits_ref(), e);
+            let a = get_directional_limit(self._limits.get_acceleration_limits_ref(), e);
+            let j = get_directional_limit(self._limits.get_jerk_limits_ref().as_ref().unwrap(), e);
+
+            // rest to rest double s profile: t_j is each jerk phase, t_a the whole acceleration phase, t_v the cruise phase
+            let (mut t_j, mut t_a) = if v * j >= a * a { (a / j, a / j + v / a) } else { ((v / j).sqrt(), 2.0 * (v / j).sqrt()) };
+            let mut t_v = d / v - t_a;
+            if t_v < 0.0 {
+                // max velocity is not reached
+                t_v = 0.0;
+                t_j = a / j;
+                t_a = (t_j + (t_j * t_j + 4.0 * d / a).sqrt()) / 2.0;
+                if t_a < 2.0 * t_j {
+                    // max acceleration is not reached either
+                    t_j = (d / (2.0 * j)).cbrt();
+                    t_a = 2.0 * t_j;
+                }
+            }
+            let a_peak = j * t_j;
+            let t_const_a = (t_a - 2.0 * t_j).max(0.0);
+
+            return vec![(t_j, 0.0, j), (t_const_a, a_peak, 0.0), (t_j, a_peak, -j), (t_v, 0.0, 0.0),
+                        (t_j, 0.0, -j), (t_const_a, -a_peak, 0.0), (t_j, -a_peak, j)];
+        });
+    }
+
+    pub fn get_limits_ref(&self) -> &TimeParameterizationLimits {
+        return &self._limits;
+    }
+
+    pub fn get_num_gridpoints(&self) -> usize {
+        return self._num_gridpoints;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    /* profile(d, e) gives the phases of a rest to rest motion over distance d along unit direction e, as (duration, path
+    acceleration at the start of the phase, path jerk during the phase) */
+    fn _parameterize_segment_wise<F>(&self, waypoints: &Vec<DVector<f64>>, profile: F) -> Result<TimedTrajectory, String> where F: Fn(f64, &DVector<f64>) -> Vec<(f64, f64, f64)> {
+        let dim = self._limits.get_dim();
+        let zeros = DVector::from_element(dim, 0.0);
+        let mut out_trajectory = get_stationary_trajectory(&waypoints[0]);
+
+        let l = waypoints.len();
+        for k in 0..l-1 {
+            let d = (&waypoints[k+1] - &waypoints[k]).norm();
+            let e = (&waypoints[k+1] - &waypoints[k]) / d;
+
+            let (mut t, mut s, mut sdot) = (out_trajectory.get_end_time(), 0.0, 0.0);
+            let mut last_sddot = 0.0;
+            for (duration, sddot, sdddot) in profile(d, &e) {
+                if !(duration > 0.000000001) { continue; }
+                let position = &waypoints[k] + s * &e;
+                push_or_replace_knot(&mut out_trajectory, t, position, sdot * &e, sddot * &e);
+                s += sdot * duration + sddot * duration * duration / 2.0 + sdddot * duration * duration * duration / 6.0;
+                sdot += sddot * duration + sdddot * duration * duration / 2.0;
+                last_sddot = sddot + sdddot * duration;
+                t += duration;
+            }
+            push_or_replace_knot(&mut out_trajectory, t, waypoints[k+1].clone(), zeros.clone(), last_sddot * &e);
+        }
+
+        return Ok(out_trajectory);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // drops repeated waypoints, so every segment has a direction
+    fn _get_cleaned_waypoints(&self, path: &LinearSplinePath) -> Result<Vec<DVector<f64>>, String> {
+        if path.waypoints.is_empty() { return Err("cannot time parameterize a path with no waypoints".to_string()); }
+        let dim = self._limits.get_dim();
+        let mut out_vec: Vec<DVector<f64>> = Vec::new();
+        for w in &path.waypoints {
+            if w.len() != dim { return Err(format!("path waypoint has dimension {:?}, but time parameterization limits have dimension {:?}", w.len(), dim)); }
+            if out_vec.is_empty() || (&out_vec[out_vec.len() - 1] - w).norm() > 0.000001 { out_vec.push(w.clone()); }
+        }
+        return Ok(out_vec);
+    }
+
+    // bounds on x from |dq_j| * sdot <= v_j, and from |ddq_j| * x <= a_j for dofs that do not move along the path
+    fn _get_max_x_from_velocity_limits(&self, dq: &DVector<f64>, ddq: &DVector<f64>) -> f64 {
+        let v = self._limits.get_velocity_limits_ref();
+        let a = self._limits.get_acceleration_limits_ref();
+        let mut out_val = std::f64::INFINITY;
+        for j in 0..dq.len() {
+            if dq[j].abs() > 0.000000001 { out_val = out_val.min((v[j] / dq[j]).powi(2)); }
+            else if ddq[j].abs() > 0.000000001 { out_val = out_val.min(a[j] / ddq[j].abs()); }
+        }
+        return out_val;
+    }
+
+    // the interval of u such that |dq_j * u + ddq_j * x| <= a_j for all dofs j, or None if it is empty
+    fn _get_u_bounds(&self, dq: &DVector<f64>, ddq: &DVector<f64>, x: f64) -> Option<(f64, f64)> {
+        let a = self._limits.get_acceleration_limits_ref();
+        let mut u_lo = -std::f64::INFINITY;
+        let mut u_hi = std::f64::INFINITY;
+        for j in 0..dq.len() {
+            if dq[j].abs() > 0.000000001 {
+                let b1 = (-a[j] - ddq[j] * x) / dq[j];
+                let b2 = (a[j] - ddq[j] * x) / dq[j];
+                u_lo = u_lo.max(b1.min(b2));
+                u_hi = u_hi.min(b1.max(b2));
+            } else if ddq[j].abs() * x > a[j] {
+                return None;
+            }
+        }
+        if u_lo > u_hi { return None; }
+        return Some((u_lo, u_hi));
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn get_stationary_trajectory(state: &DVector<f64>) -> TimedTrajectory {
+    let zeros = DVector::from_element(state.len(), 0.0);
+    return TimedTrajectory { times: vec![0.0], positions: vec![state.clone()], velocities: vec![zeros.clone()], accelerations: vec![zeros] };
+}
+
+// a knot at (numerically) the same time as the last knot replaces it, so accelerations stay right continuous
+fn push_or_replace_knot(trajectory: &mut TimedTrajectory, time: f64, position: DVector<f64>, velocity: DVector<f64>, acceleration: DVector<f64>) {
+    let l = trajectory.get_num_knots();
+    if l > 0 && time <= trajectory.times[l-1] + 0.000000001 {
+        trajectory.positions[l-1] = position;
+        trajectory.velocities[l-1] = velocity;
+        trajectory.accelerations[l-1] = acceleration;
+        return;
+    }
+    trajectory.times.push(time);
+    trajectory.positions.push(position);
+    trajectory.velocities.push(velocity);
+    trajectory.accelerations.push(acceleration);
+}
+
+// the largest scalar limit along unit direction e such that every dof stays within its own limit
+fn get_directional_limit(limits: &DVector<f64>, e: &DVector<f64>) -> f64 {
+    let mut out_val = std::f64::INFINITY;
+    for j in 0..e.len() {
+        if e[j].abs() > 0.000000001 { out_val = out_val.min(limits[j] / e[j].abs()); }
+    }
+    return out_val;
+}
+
+// largest x in [0, upper] with feasible(x), assuming feasible(0) holds and the feasible set is an interval
+fn get_max_feasible<F>(upper: f64, feasible: F) -> f64 where F: Fn(f64) -> bool {
+    if feasible(upper) { return upper; }
+    let mut lo = 0.0;
+    let mut hi = upper;
+    for _ in 0..100 {
+        let mid = (lo + hi) / 2.0;
+        if feasible(mid) { lo = mid; } else { hi = mid; }
+    }
+    return lo;
+}
@@ -0,0 +1,104 @@
+use nalgebra::DVector;
+use crate::utils::utils_math::nalgebra_utils::vec_to_dvec;
+use crate::utils::utils_paths::linear_spline_path::LinearSplinePath;
+
+/* robot states with a time stamp on each waypoint, for planning against time-varying environments.  States between
+waypoints are linearly interpolated, and the path holds its first and last states outside of its time range. */
+#[derive(Debug, Clone)]
+pub struct TimedPath {
+    pub waypoints: Vec<DVector<f64>>,
+    pub times: Vec<f64>
+}
+
+impl TimedPath {
+    pub fn new(waypoints: Vec<DVector<f64>>, times: Vec<f64>) -> Result<Self, String> {
+        if waypoints.len() != times.len() {
+            return Err(format!("number of waypoints ({:?}) must match number of times ({:?}) in timed path", waypoints.len(), times.len()));
+        }
+        let l = times.len();
+        for i in 1..l {
+            if !(times[i] > times[i-1]) {
+                return Err(format!("timed path times must be strictly increasing (got {:?} after {:?})", times[i], times[i-1]));
+            }
+        }
+        return Ok(Self { waypoints, times });
+    }
+
+    pub fn new_from_vecs(waypoints: Vec<Vec<f64>>, times: Vec<f64>) -> Result<Self, String> {
+        let waypoints_ = waypoints.iter().map(|x| vec_to_dvec(x)).collect();
+        return Self::new(waypoints_, times);
+    }
+
+    pub fn new_empty() -> Self {
+        return Self { waypoints: Vec::new(), times: Vec::new() };
+    }
+
+    // times are assigned proportionally to arclength, so the path is traversed at constant speed in state space
+    pub fn new_from_linear_spline_path(linear_spline_path: &LinearSplinePath, start_time: f64, duration: f64) -> Result<Self, String> {
+        let waypoints = linear_spline_path.waypoints.clone();
+        let l = waypoints.len();
+        if l == 0 { return Ok(Self::new_empty()); }
+        if l == 1 { return Self::new(waypoints, vec![start_time]); }
+
+        let mut cumulative_lengths = vec![0.0];
+        for i in 1..l {
+            cumulative_lengths.push( cumulative_lengths[i-1] + (&waypoints[i] - &waypoints[i-1]).norm() );
+        }
+        let total_length = cumulative_lengths[l-1];
+
+        let mut times = Vec::new();
+        for i in 0..l {
+            let u = if total_length > 0.0 { cumulative_lengths[i] / total_length } else { i as f64 / (l - 1) as f64 };
+            times.push(start_time + u * duration);
+        }
+
+        return Self::new(waypoints, times);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn add_waypoint(&mut self, waypoint: &DVector<f64>, time: f64) -> Result<(), String> {
+        if !self.times.is_empty() && !(time > self.times[self.times.len()-1]) {
+            return Err(format!("waypoint time {:?} must be greater than the last waypoint time {:?}", time, self.times[self.times.len()-1]));
+        }
+        self.waypoints.push( waypoint.clone() );
+        self.times.push(time);
+        Ok(())
+    }
+
+    pub fn get_state_at_time(&self, time: f64) -> Result<DVector<f64>, String> {
+        let l = self.waypoints.len();
+        if l == 0 { return Err("cannot get state on timed path with no waypoints".to_string()); }
+
+        if time <= self.times[0] { return Ok(self.waypoints[0].clone()); }
+        if time >= self.times[l-1] { return Ok(self.waypoints[l-1].clone()); }
+
+        let idx = self.times.iter().position(|t| *t > time).unwrap();
+        let u = (time - self.times[idx-1]) / (self.times[idx] - self.times[idx-1]);
+        return Ok( &self.waypoints[idx-1] + u * (&self.waypoints[idx] - &self.waypoints[idx-1]) );
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn get_num_waypoints(&self) -> usize {
+        return self.waypoints.len();
+    }
+
+    pub fn get_start_time(&self) -> f64 {
+        if self.times.is_empty() { return 0.0; }
+        return self.times[0];
+    }
+
+    pub fn get_end_time(&self) -> f64 {
+        if self.times.is_empty() { return 0.0; }
+        return self.times[self.times.len()-1];
+    }
+
+    pub fn get_duration(&self) -> f64 {
+        return self.get_end_time() - self.get_start_time();
+    }
+
+    pub fn to_linear_spline_path(&self) -> LinearSplinePath {
+        return LinearSplinePath::new(self.waypoints.clone());
+    }
+}
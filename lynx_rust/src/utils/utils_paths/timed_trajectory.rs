@@ -0,0 +1,132 @@
+use nalgebra::DVector;
+use serde::{Serialize, Deserialize};
+use crate::utils::utils_paths::{linear_spline_path::LinearSplinePath, timed_path::TimedPath};
+
+/* positions, velocities, and accelerations at time stamped knots, e.g., the output of a TimeParameterizer.  Between knots,
+positions are cubic hermite interpolated from the bracketing positions and velocities, and velocities are the derivative of
+that cubic, so they match the knot velocities and are exact wherever the knots come from a polynomial of degree three or less
+(e.g., the constant acceleration and constant jerk phases of the segment-wise methods).  Outside of its time range, the
+trajectory holds its first or last knot.
+
+Accelerations stored at a knot are the accelerations just after that knot (the last knot stores the acceleration just before
+it), since accelerations may jump at knots.  Between knots, the acceleration of the earlier knot is held, so sampled
+accelerations never exceed per dof limits that hold at the knots. */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedTrajectory {
+    pub times: Vec<f64>,
+    pub positions: Vec<DVector<f64>>,
+    pub velocities: Vec<DVector<f64>>,
+    pub accelerations: Vec<DVector<f64>>
+}
+
+impl TimedTrajectory {
+    pub fn new(times: Vec<f64>, positions: Vec<DVector<f64>>, velocities: Vec<DVector<f64>>, accelerations: Vec<DVector<f64>>) -> Result<Self, String> {
+        let l = times.len();
+        if positions.len() != l || velocities.len() != l || accelerations.len() != l {
+            return Err(format!("timed trajectory needs the same number of times ({:?}), positions ({:?}), velocities ({:?}), and accelerations ({:?})", l, positions.len(), velocities.len(), accelerations.len()));
+        }
+        for i in 1..l {
+            if !(times[i] > times[i-1]) {
+                return Err(format!("timed trajectory times must be strictly increasing (got {:?} after {:?})", times[i], times[i-1]));
+            }
+        }
+        if l > 0 {
+            let dim = positions[0].len();
+            for i in 0..l {
+                if positions[i].len() != dim || velocities[i].len() != dim || accelerations[i].len() != dim {
+                    return Err(format!("all knots in a timed trajectory must have dimension {:?} (knot {:?} does not)", dim, i));
+                }
+            }
+        }
+        return Ok(Self { times, positions, velocities, accelerations });
+    }
+
+    pub fn new_empty() -> Self {
+        return Self { times: Vec::new(), positions: Vec::new(), velocities: Vec::new(), accelerations: Vec::new() };
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // returns (position, velocity, acceleration) at the given time
+    pub fn get_state_at_time(&self, time: f64) -> Result<(DVector<f64>, DVector<f64>, DVector<f64>), String> {
+        let l = self.times.len();
+        if l == 0 { return Err("cannot get state on timed trajectory with no knots".to_string()); }
+
+        if time <= self.times[0] { return Ok((self.positions[0].clone(), self.velocities[0].clone(), self.accelerations[0].clone())); }
+        if time >= self.times[l-1] { return Ok((self.positions[l-1].clone(), self.velocities[l-1].clone(), self.accelerations[l-1].clone())); }
+
+        let idx = self.times.iter().position(|t| *t > time).unwrap();
+        let h = self.times[idx] - self.times[idx-1];
+        let u = (time - self.times[idx-1]) / h;
+        let (p0, v0, p1, v1) = (&self.positions[idx-1], &self.velocities[idx-1], &self.positions[idx], &self.velocities[idx]);
+
+        let u2 = u * u;
+        let u3 = u2 * u;
+        let position = (2.0*u3 - 3.0*u2 + 1.0) * p0 + ((u3 - 2.0*u2 + u) * h) * v0 + (-2.0*u3 + 3.0*u2) * p1 + ((u3 - u2) * h) * v1;
+        let velocity = ((6.0*u2 - 6.0*u) / h) * p0 + (3.0*u2 - 4.0*u + 1.0) * v0 + ((6.0*u - 6.0*u2) / h) * p1 + (3.0*u2 - 2.0*u) * v1;
+        let acceleration = self.accelerations[idx-1].clone();
+
+        return Ok((position, velocity, acceleration));
+    }
+
+    // knots every 1 / control_rate_hz seconds from the start time, plus the end time if it does not land on that grid
+    pub fn resample_at_control_rate(&self, control_rate_hz: f64) -> Result<TimedTrajectory, String> {
+        if !(control_rate_hz > 0.0) { return Err(format!("control rate must be positive to resample timed trajectory (got {:?})", control_rate_hz)); }
+        let l = self.times.len();
+        if l < 2 { return Ok(self.clone()); }
+
+        let dt = 1.0 / control_rate_hz;
+        let start_time = self.get_start_time();
+        let end_time = self.get_end_time();
+
+        let mut out_trajectory = TimedTrajectory::new_empty();
+        let mut i = 0;
+        loop {
+            let t = start_time + i as f64 * dt;
+            if t > end_time - 0.000000001 { break; }
+            let (p, v, a) = self.get_state_at_time(t)?;
+            out_trajectory._push_knot(t, p, v, a);
+            i += 1;
+        }
+        out_trajectory._push_knot(end_time, self.positions[l-1].clone(), self.velocities[l-1].clone(), self.accelerations[l-1].clone());
+
+        return Ok(out_trajectory);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn get_num_knots(&self) -> usize {
+        return self.times.len();
+    }
+
+    pub fn get_start_time(&self) -> f64 {
+        if self.times.is_empty() { return 0.0; }
+        return self.times[0];
+    }
+
+    pub fn get_end_time(&self) -> f64 {
+        if self.times.is_empty() { return 0.0; }
+        return self.times[self.times.len()-1];
+    }
+
+    pub fn get_duration(&self) -> f64 {
+        return self.get_end_time() - self.get_start_time();
+    }
+
+    pub fn to_timed_path(&self) -> Result<TimedPath, String> {
+        return TimedPath::new(self.positions.clone(), self.times.clone());
+    }
+
+    pub fn to_linear_spline_path(&self) -> LinearSplinePath {
+        return LinearSplinePath::new(self.positions.clone());
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    fn _push_knot(&mut self, time: f64, position: DVector<f64>, velocity: DVector<f64>, acceleration: DVector<f64>) {
+        self.times.push(time);
+        self.positions.push(position);
+        self.velocities.push(velocity);
+        self.accelerations.push(acceleration);
+    }
+}
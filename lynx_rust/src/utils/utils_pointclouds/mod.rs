@@ -1,2 +1,3 @@
 pub mod pointcloud_data_utils;
-pub mod pointcloud_principal_axes_utils;
\ No newline at end of file
+pub mod pointcloud_principal_axes_utils;
+pub mod pointcloud;
\ No newline at end of file
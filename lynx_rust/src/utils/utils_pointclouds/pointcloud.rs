@@ -0,0 +1,447 @@
+use crate::utils::utils_files_and_strings::ply_utils::*;
+use crate::utils::utils_pointclouds::pointcloud_data_utils::find_pointcloud_centroid;
+use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
+use nalgebra::{Vector3, DVector};
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+
+/*
+This struct reads and writes .ply (ascii and binary), .pcd (ascii and binary), and .xyz files.  Normals and colors
+are optional, but if present, there is exactly one per point.
+*/
+#[derive(Clone, Debug)]
+pub struct PointCloud {
+    _points: Vec<Vector3<f64>>,
+    _normals: Option<Vec<Vector3<f64>>>,
+    _colors: Option<Vec<[u8; 3]>>
+}
+
+impl PointCloud {
+    pub fn new(points: Vec<Vector3<f64>>) -> Self {
+        return Self { _points: points, _normals: None, _colors: None };
+    }
+
+    pub fn new_with_normals_and_colors(points: Vec<Vector3<f64>>, normals: Option<Vec<Vector3<f64>>>, colors: Option<Vec<[u8; 3]>>) -> Result<Self, String> {
+        if normals.is_some() && normals.as_ref().unwrap().len() != points.len() {
+            return Err(format!("number of normals ({:?}) does not match number of points ({:?}) in PointCloud", normals.as_ref().unwrap().len(), points.len()));
+        }
+        if colors.is_some() && colors.as_ref().unwrap().len() != points.len() {
+            return Err(format!("number of colors ({:?}) does not match number of points ({:?}) in PointCloud", colors.as_ref().unwrap().len(), points.len()));
+        }
+        return Ok(Self { _points: points, _normals: normals, _colors: colors });
+    }
+
+    pub fn new_from_path(fp: String) -> Result<Self, String> {
+        let ext = Path::new(fp.as_str()).extension();
+        if ext.is_none() {
+            return Err(format!("{:?} invalid file type in PointCloud parser", fp));
+        }
+
+        let ext_unwrap = ext.unwrap().to_str().unwrap_or("").to_lowercase();
+        if ext_unwrap == "ply" {
+            return Self::new_from_ply_path(fp);
+        } else if ext_unwrap == "pcd" {
+            return Self::new_from_pcd_path(fp);
+        } else if ext_unwrap == "xyz" || ext_unwrap == "txt" {
+            return Self::new_from_xyz_path(fp);
+        } else {
+            return Err(format!("{:?} invalid file type in PointCloud parser", fp));
+        }
+    }
+
+    pub fn new_from_ply_path(fp: String) -> Result<Self, String> {
+        let ply_data = read_ply_file(fp.clone())?;
+        let vertex = ply_data.get_element_ref("vertex");
+        if vertex.is_none() { return Err(format!("no vertex element found in ply file {:?}", fp)); }
+        let vertex = vertex.unwrap();
+
+        let xyz_idxs = [vertex.get_property_idx("x"), vertex.get_property_idx("y"), vertex.get_property_idx("z")];
+        if xyz_idxs.iter().any(|x| x.is_none()) { return Err(format!("vertex element in ply file {:?} must have x, y, and z properties", fp)); }
+        let normal_idxs = [vertex.get_property_idx("nx"), vertex.get_property_idx("ny"), vertex.get_property_idx("nz")];
+        let has_normals = normal_idxs.iter().all(|x| x.is_some());
+        let color_idxs = [vertex.get_property_idx("red"), vertex.get_property_idx("green"), vertex.get_property_idx("blue")];
+        let has_colors = color_idxs.iter().all(|x| x.is_some());
+        let colors_are_floats = has_colors && (vertex.properties[color_idxs[0].unwrap()].data_type.starts_with("float") || vertex.properties[color_idxs[0].unwrap()].data_type == "double");
+
+        let mut points = Vec::new();
+        let mut normals = Vec::new();
+        let mut colors = Vec::new();
+        for row in &vertex.rows {
+            points.push( Vector3::new(row[xyz_idxs[0].unwrap()][0], row[xyz_idxs[1].unwrap()][0], row[xyz_idxs[2].unwrap()][0]) );
+            if has_normals {
+                normals.push( Vector3::new(row[normal_idxs[0].unwrap()][0], row[normal_idxs[1].unwrap()][0], row[normal_idxs[2].unwrap()][0]) );
+            }
+            if has_colors {
+                let mut c = [0 as u8; 3];
+                for k in 0..3 {
+                    let v = row[color_idxs[k].unwrap()][0];
+                    c[k] = if colors_are_floats { (v * 255.0).round().max(0.0).min(255.0) as u8 } else { v.max(0.0).min(255.0) as u8 };
+                }
+                colors.push(c);
+            }
+        }
+
+        return Self::new_with_normals_and_colors(points, if has_normals { Some(normals) } else { None }, if has_colors { Some(colors) } else { None });
+    }
+
+    pub fn new_from_pcd_path(fp: String) -> Result<Self, String> {
+        let bytes = fs::read(fp.clone());
+        if bytes.is_err() { return Err(format!("could not open pcd file {:?}", fp)); }
+        let bytes = bytes.unwrap();
+
+        let mut fields: Vec<String> = Vec::new();
+        let mut sizes: Vec<usize> = Vec::new();
+        let mut types: Vec<String> = Vec::new();
+        let mut counts: Vec<usize> = Vec::new();
+        let mut num_points = 0 as usize;
+        let mut data_format = "".to_string();
+
+        // header is ascii, one "KEY values..." per line, and ends with the DATA line
+        let mut cursor = 0 as usize;
+        let l = bytes.len();
+        while cursor < l {
+            let mut line_end = cursor;
+            while line_end < l && bytes[line_end] != b'\n' { line_end += 1; }
+            let line = String::from_utf8_lossy(&bytes[cursor..line_end]).to_string();
+            cursor = (line_end + 1).min(l);
+
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.is_empty() || tokens[0].starts_with("#") { continue; }
+            match tokens[0] {
+                "FIELDS" => { fields = tokens[1..].iter().map(|x| x.to_string()).collect(); }
+                "SIZE" => { sizes = tokens[1..].iter().map(|x| x.parse::<usize>().unwrap_or(0)).collect(); }
+                "TYPE" => { types = tokens[1..].iter().map(|x| x.to_string()).collect(); }
+                "COUNT" => { counts = tokens[1..].iter().map(|x| x.parse::<usize>().unwrap_or(1)).collect(); }
+                "POINTS" => { num_points = tokens.get(1).and_then(|x| x.parse::<usize>().ok()).unwrap_or(0); }
+                "DATA" => { data_format = tokens.get(1).unwrap_or(&"").to_string(); break; }
+                _ => { }
+            }
+        }
+
+        if counts.is_empty() { counts = vec![1; fields.len()]; }
+        if !(fields.len() == sizes.len() && fields.len() == types.len() && fields.len() == counts.len()) {
+            return Err(format!("FIELDS, SIZE, TYPE, and COUNT do not match in pcd file {:?}", fp));
+        }
+        let binary_types: Vec<String> = (0..fields.len()).map(|i| format!("{}{}", types[i], sizes[i])).collect();
+
+        // fields read into the point cloud hold one value per point.  All values of other fields are skipped over.
+        for (f, field) in fields.iter().enumerate() {
+            let is_read = ["x", "y", "z", "normal_x", "normal_y", "normal_z"].contains(&field.as_str()) || Self::_is_packed_color_field(field);
+            if is_read && counts[f] != 1 {
+                return Err(format!("field {:?} must have COUNT 1 (found {:?}) in pcd file {:?}", field, counts[f], fp));
+            }
+        }
+
+        let mut rows: Vec<Vec<f64>> = Vec::new();
+        match data_format.as_str() {
+            "ascii" => {
+                let body_string = String::from_utf8_lossy(&bytes[cursor..]).to_string();
+                let mut tokens = body_string.split_whitespace();
+                for _ in 0..num_points {
+                    let mut row = Vec::new();
+                    let num_fields = fields.len();
+                    for f in 0..num_fields {
+                        for c in 0..counts[f] {
+                            let token = tokens.next();
+                            if token.is_none() { return Err(format!("unexpected end of ascii data in pcd file {:?}", fp)); }
+                            let value = Self::_parse_pcd_ascii_value(token.unwrap(), &fields[f], &types[f]);
+                            if value.is_none() { return Err(format!("could not parse {:?} in pcd file {:?}", token.unwrap(), fp)); }
+                            if c == 0 { row.push(value.unwrap()); }
+                        }
+                    }
+                    rows.push(row);
+                }
+            }
+            "binary" => {
+                let body = &bytes[cursor..];
+                let mut body_cursor = 0 as usize;
+                for _ in 0..num_points {
+                    let mut row = Vec::new();
+                    let num_fields = fields.len();
+                    for f in 0..num_fields {
+                        for c in 0..counts[f] {
+                            let start = body_cursor;
+                            let mut value = read_binary_value(body, &mut body_cursor, &binary_types[f], true)?;
+                            if Self::_is_packed_color_field(&fields[f]) && binary_types[f] == "F4" {
+                                value = u32::from_le_bytes([body[start], body[start + 1], body[start + 2], body[start + 3]]) as f64;
+                            }
+                            if c == 0 { row.push(value); }
+                        }
+                    }
+                    rows.push(row);
+                }
+            }
+            _ => { return Err(format!("unsupported pcd DATA format {:?} in file {:?}", data_format, fp)); }
+        }
+
+        let get_field_idx = |name: &str| fields.iter().position(|x| x.as_str() == name);
+        let xyz_idxs = [get_field_idx("x"), get_field_idx("y"), get_field_idx("z")];
+        if xyz_idxs.iter().any(|x| x.is_none()) { return Err(format!("pcd file {:?} must have x, y, and z fields", fp)); }
+        let normal_idxs = [get_field_idx("normal_x"), get_field_idx("normal_y"), get_field_idx("normal_z")];
+        let has_normals = normal_idxs.iter().all(|x| x.is_some());
+        let color_idx = get_field_idx("rgb").or(get_field_idx("rgba"));
+
+        let mut points = Vec::new();
+        let mut normals = Vec::new();
+        let mut colors = Vec::new();
+        for row in &rows {
+            points.push( Vector3::new(row[xyz_idxs[0].unwrap()], row[xyz_idxs[1].unwrap()], row[xyz_idxs[2].unwrap()]) );
+            if has_normals {
+                normals.push( Vector3::new(row[normal_idxs[0].unwrap()], row[normal_idxs[1].unwrap()], row[normal_idxs[2].unwrap()]) );
+            }
+            if color_idx.is_some() {
+                let packed = row[color_idx.unwrap()] as u32;
+                colors.push( [ ((packed >> 16) & 0xff) as u8, ((packed >> 8) & 0xff) as u8, (packed & 0xff) as u8 ] );
+            }
+        }
+
+        return Self::new_with_normals_and_colors(points, if has_normals { Some(normals) } else { None }, if color_idx.is_some() { Some(colors) } else { None });
+    }
+
+    // each non-empty line is "x y z", "x y z nx ny nz", or "x y z nx ny nz r g b" (whitespace or comma separated).
+    // Lines starting with # are skipped, except for a column header such as "# x y z r g b", which sets the columns
+    // of all following lines (e.g., colors without normals).
+    pub fn new_from_xyz_path(fp: String) -> Result<Self, String> {
+        let contents = fs::read_to_string(fp.clone());
+        if contents.is_err() { return Err(format!("could not open xyz file {:?}", fp)); }
+        let contents = contents.unwrap();
+
+        let mut points = Vec::new();
+        let mut normals = Vec::new();
+        let mut colors = Vec::new();
+        let mut columns: Option<Vec<String>> = None;
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() { continue; }
+            if line.starts_with("#") {
+                let header_columns = Self::_parse_xyz_column_header(line);
+                if header_columns.is_some() {
+                    if !points.is_empty() { return Err(format!("column header in xyz file {:?} must come before all points", fp)); }
+                    columns = header_columns;
+                }
+                continue;
+            }
+
+            let mut values = Vec::new();
+            for t in line.split(|c: char| c.is_whitespace() || c == ',').filter(|x| !x.is_empty()) {
+                let v = t.parse::<f64>();
+                if v.is_err() { return Err(format!("could not parse {:?} as a number in xyz file {:?}", t, fp)); }
+                values.push(v.unwrap());
+            }
+
+            if columns.is_none() {
+                columns = match values.len() {
+                    3 => Some(Self::_get_xyz_columns(false, false)),
+                    6 => Some(Self::_get_xyz_columns(true, false)),
+                    9 => Some(Self::_get_xyz_columns(true, true)),
+                    _ => { return Err(format!("lines in xyz file {:?} must have 3, 6, or 9 values (found {:?})", fp, values.len())); }
+                };
+            }
+            let columns = columns.as_ref().unwrap();
+            if columns.len() != values.len() {
+                return Err(format!("lines in xyz file {:?} do not all have the same number of values ({:?} expected, found {:?})", fp, columns.len(), values.len()));
+            }
+
+            let has_normals = columns.iter().any(|c| c.as_str() == "nx");
+            let color_start = if has_normals { 6 } else { 3 };
+            points.push( Vector3::new(values[0], values[1], values[2]) );
+            if has_normals { normals.push( Vector3::new(values[3], values[4], values[5]) ); }
+            if values.len() > color_start { colors.push( [ values[color_start].max(0.0).min(255.0) as u8, values[color_start + 1].max(0.0).min(255.0) as u8, values[color_start + 2].max(0.0).min(255.0) as u8 ] ); }
+        }
+
+        let columns = columns.unwrap_or(Self::_get_xyz_columns(false, false));
+        let has_normals = columns.contains(&"nx".to_string());
+        let has_colors = columns.contains(&"r".to_string());
+        return Self::new_with_normals_and_colors(points, if has_normals { Some(normals) } else { None }, if has_colors { Some(colors) } else { None });
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn output_to_path(&self, out_fp: String, binary: bool) -> Result<(), String> {
+        let ext = Path::new(out_fp.as_str()).extension();
+        if ext.is_none() {
+            return Err(format!("{:?} invalid file type in PointCloud writer", out_fp));
+        }
+
+        let ext_unwrap = ext.unwrap().to_str().unwrap_or("").to_lowercase();
+        if ext_unwrap == "ply" {
+            return self.output_to_ply(out_fp, binary);
+        } else if ext_unwrap == "pcd" {
+            return self.output_to_pcd(out_fp, binary);
+        } else if ext_unwrap == "xyz" || ext_unwrap == "txt" {
+            return self.output_to_xyz(out_fp);
+        } else {
+            return Err(format!("{:?} invalid file type in PointCloud writer", out_fp));
+        }
+    }
+
+    pub fn output_to_ply(&self, out_fp: String, binary: bool) -> Result<(), String> {
+        let mut properties = vec![ PlyProperty::new_scalar("x", "double"), PlyProperty::new_scalar("y", "double"), PlyProperty::new_scalar("z", "double") ];
+        if self._normals.is_some() {
+            properties.append(&mut vec![ PlyProperty::new_scalar("nx", "double"), PlyProperty::new_scalar("ny", "double"), PlyProperty::new_scalar("nz", "double") ]);
+        }
+        if self._colors.is_some() {
+            properties.append(&mut vec![ PlyProperty::new_scalar("red", "uchar"), PlyProperty::new_scalar("green", "uchar"), PlyProperty::new_scalar("blue", "uchar") ]);
+        }
+
+        let mut vertex = PlyElement::new("vertex", properties);
+        let l = self._points.len();
+        for i in 0..l {
+            let p = &self._points[i];
+            let mut row = vec![ vec![p[0]], vec![p[1]], vec![p[2]] ];
+            if self._normals.is_some() {
+                let n = &self._normals.as_ref().unwrap()[i];
+                row.append(&mut vec![ vec![n[0]], vec![n[1]], vec![n[2]] ]);
+            }
+            if self._colors.is_some() {
+                let c = &self._colors.as_ref().unwrap()[i];
+                row.append(&mut vec![ vec![c[0] as f64], vec![c[1] as f64], vec![c[2] as f64] ]);
+            }
+            vertex.rows.push(row);
+        }
+
+        let mut ply_data = PlyData::new_empty();
+        ply_data.elements.push(vertex);
+        return write_ply_file(out_fp, &ply_data, binary);
+    }
+
+    pub fn output_to_pcd(&self, out_fp: String, binary: bool) -> Result<(), String> {
+        let mut fields = vec!["x", "y", "z"];
+        let mut sizes = vec!["8", "8", "8"];
+        let mut types = vec!["F", "F", "F"];
+        if self._normals.is_some() {
+            fields.append(&mut vec!["normal_x", "normal_y", "normal_z"]);
+            sizes.append(&mut vec!["8", "8", "8"]);
+            types.append(&mut vec!["F", "F", "F"]);
+        }
+        if self._colors.is_some() {
+            fields.push("rgb");
+            sizes.push("4");
+            types.push("U");
+        }
+
+        let num_points = self._points.len();
+        let mut header = "# .PCD v0.7 - Point Cloud Data file format\nVERSION 0.7\n".to_string();
+        header += format!("FIELDS {}\n", fields.join(" ")).as_str();
+        header += format!("SIZE {}\n", sizes.join(" ")).as_str();
+        header += format!("TYPE {}\n", types.join(" ")).as_str();
+        header += format!("COUNT {}\n", vec!["1"; fields.len()].join(" ")).as_str();
+        header += format!("WIDTH {}\nHEIGHT 1\nVIEWPOINT 0 0 0 1 0 0 0\nPOINTS {}\n", num_points, num_points).as_str();
+        header += if binary { "DATA binary\n" } else { "DATA ascii\n" };
+
+        let mut out_bytes = header.into_bytes();
+        for i in 0..num_points {
+            let mut values = vec![ self._points[i][0], self._points[i][1], self._points[i][2] ];
+            if self._normals.is_some() {
+                let n = &self._normals.as_ref().unwrap()[i];
+                values.append(&mut vec![ n[0], n[1], n[2] ]);
+            }
+            let mut packed_color = None;
+            if self._colors.is_some() {
+                let c = &self._colors.as_ref().unwrap()[i];
+                packed_color = Some( ((c[0] as u32) << 16) | ((c[1] as u32) << 8) | (c[2] as u32) );
+            }
+
+            if binary {
+                for v in &values { write_binary_value(&mut out_bytes, *v, "F8", true)?; }
+                if packed_color.is_some() { write_binary_value(&mut out_bytes, packed_color.unwrap() as f64, "U4", true)?; }
+            } else {
+                let mut tokens: Vec<String> = values.iter().map(|x| format!("{}", x)).collect();
+                if packed_color.is_some() { tokens.push(format!("{}", packed_color.unwrap())); }
+                out_bytes.extend_from_slice(tokens.join(" ").as_bytes());
+                out_bytes.push(b'\n');
+            }
+        }
+
+        return Self::_write_bytes_to_file(out_fp, &out_bytes);
+    }
+
+    pub fn output_to_xyz(&self, out_fp: String) -> Result<(), String> {
+        let mut out_string = format!("# {}\n", Self::_get_xyz_columns(self._normals.is_some(), self._colors.is_some()).join(" "));
+        let l = self._points.len();
+        for i in 0..l {
+            let p = &self._points[i];
+            out_string += format!("{} {} {}", p[0], p[1], p[2]).as_str();
+            if self._normals.is_some() {
+                let n = &self._normals.as_ref().unwrap()[i];
+                out_string += format!(" {} {} {}", n[0], n[1], n[2]).as_str();
+            }
+            if self._colors.is_some() {
+                let c = &self._colors.as_ref().unwrap()[i];
+                out_string += format!(" {} {} {}", c[0], c[1], c[2]).as_str();
+            }
+            out_string += "\n";
+        }
+
+        return Self::_write_bytes_to_file(out_fp, out_string.as_bytes());
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    pub fn transform(&mut self, transform: &ImplicitDualQuaternion) {
+        self._points.iter_mut().for_each(|p| *p = transform.multiply_by_vector3(p));
+        if self._normals.is_some() {
+            self._normals.as_mut().unwrap().iter_mut().for_each(|n| *n = transform.quat * *n);
+        }
+    }
+
+    pub fn get_centroid(&self) -> Vector3<f64> {
+        return find_pointcloud_centroid(&self._points);
+    }
+
+    pub fn get_points_as_dvecs(&self) -> Vec<DVector<f64>> {
+        return self._points.iter().map(|p| DVector::from_column_slice(p.as_slice())).collect();
+    }
+
+    pub fn get_points_ref(&self) -> &Vec<Vector3<f64>> { return &self._points; }
+
+    pub fn get_normals_ref(&self) -> &Option<Vec<Vector3<f64>>> { return &self._normals; }
+
+    pub fn get_colors_ref(&self) -> &Option<Vec<[u8; 3]>> { return &self._colors; }
+
+    pub fn get_num_points(&self) -> usize { return self._points.len(); }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // rgb(a) fields pack 8 bit channels into 32 bits as 0x00RRGGBB.  When declared as float, the bits are reinterpreted.
+    fn _parse_pcd_ascii_value(token: &str, field: &String, data_type: &String) -> Option<f64> {
+        if Self::_is_packed_color_field(field) && data_type.as_str() == "F" {
+            return token.parse::<f32>().ok().map(|x| x.to_bits() as f64);
+        }
+        return token.parse::<f64>().ok();
+    }
+
+    fn _get_xyz_columns(has_normals: bool, has_colors: bool) -> Vec<String> {
+        let mut out_vec = vec!["x", "y", "z"];
+        if has_normals { out_vec.append(&mut vec!["nx", "ny", "nz"]); }
+        if has_colors { out_vec.append(&mut vec!["r", "g", "b"]); }
+        return out_vec.iter().map(|x| x.to_string()).collect();
+    }
+
+    // Some if the comment line lists one of the supported column layouts
+    fn _parse_xyz_column_header(line: &str) -> Option<Vec<String>> {
+        let tokens: Vec<String> = line.trim_start_matches('#').split(|c: char| c.is_whitespace() || c == ',').filter(|x| !x.is_empty()).map(|x| x.to_string()).collect();
+        for has_normals in &[false, true] {
+            for has_colors in &[false, true] {
+                let columns = Self::_get_xyz_columns(*has_normals, *has_colors);
+                if tokens == columns { return Some(columns); }
+            }
+        }
+        return None;
+    }
+
+    fn _is_packed_color_field(field: &String) -> bool {
+        return field.as_str() == "rgb" || field.as_str() == "rgba";
+    }
+
+    fn _write_bytes_to_file(out_fp: String, bytes: &[u8]) -> Result<(), String> {
+        let file = File::create(out_fp.clone());
+        if file.is_err() { return Err(format!("could not create file {:?}", out_fp)); }
+        let res = file.unwrap().write_all(bytes);
+        if res.is_err() { return Err(format!("could not write file {:?}", out_fp)); }
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use crate::utils::utils_files_and_strings::robot_folder_utils::*;
+use serde::{Serialize, Deserialize};
+use termion::{color, style};
+use yaml_rust::{Yaml, YamlLoader};
+use yaml_rust::yaml::Yaml::{BadValue, Null};
+
+/* per-link settings for link preprocessing, read from link_preprocessing_overrides/link_preprocessing_overrides.yaml in the
+robot directory.  Any field that is left out falls back on the robot-wide default.  If primitive_replacement is given, the
+link is represented by that single primitive (fit around the link mesh) instead of being decomposed, so the other fields
+are ignored. */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LinkPreprocessingOverride {
+    pub link_name: String,
+    pub decomposition_error: Option<f64>,
+    pub max_subcomponents: Option<usize>,
+    pub simplification_target: Option<usize>, // max number of triangles in the link mesh before decomposition
+    pub primitive_replacement: Option<PrimitiveReplacementType>
+}
+
+impl LinkPreprocessingOverride {
+    pub fn new_empty(link_name: &str) -> Self {
+        return Self { link_name: link_name.to_string(), decomposition_error: None, max_subcomponents: None, simplification_target: None, primitive_replacement: None };
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PrimitiveReplacementType {
+    Box,
+    Capsule,
+    Cylinder
+}
+
+impl PrimitiveReplacementType {
+    pub fn from_string(s: &String) -> Result<Self, String> {
+        return match s.to_lowercase().as_str() {
+            "box" => Ok(Self::Box),
+            "capsule" => Ok(Self::Capsule),
+            "cylinder" => Ok(Self::Cylinder),
+            _ => Err(format!("{:?} is not a valid primitive replacement type (options are box, capsule, and cylinder)", s))
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        return match self {
+            Self::Box => "box".to_string(),
+            Self::Capsule => "capsule".to_string(),
+            Self::Cylinder => "cylinder".to_string()
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+const OVERRIDES_PARTIAL_FP: &str = "link_preprocessing_overrides";
+const OVERRIDES_FILE_NAME: &str = "link_preprocessing_overrides.yaml";
+// overrides that the current link_convex_subcomponents were computed with, so only links whose overrides change are recomputed
+const APPLIED_OVERRIDES_PARTIAL_FP: &str = "autogenerated_metadata";
+const APPLIED_OVERRIDES_FILE_NAME: &str = "link_convex_subcomponents_applied_overrides.json";
+
+pub fn load_link_preprocessing_overrides(robot_name: String) -> Result<Vec<LinkPreprocessingOverride>, String> {
+    let exists = check_if_path_exists_relative_to_robot_directory(robot_name.clone(), OVERRIDES_PARTIAL_FP.to_string());
+    if !exists {
+        _create_link_preprocessing_overrides_directory_with_example(robot_name.clone());
+    }
+
+    let contents = read_file_contents_separated_args_relative_to_robot_directory(robot_name.clone(), OVERRIDES_PARTIAL_FP.to_string(), OVERRIDES_FILE_NAME.to_string());
+    if contents.is_none() { return Ok(Vec::new()); }
+    return parse_link_preprocessing_overrides_yaml_string(&contents.unwrap());
+}
+
+pub fn parse_link_preprocessing_overrides_yaml_string(s: &String) -> Result<Vec<LinkPreprocessingOverride>, String> {
+    let docs = YamlLoader::load_from_str(s.as_str());
+    if docs.is_err() { return Err(format!("link preprocessing overrides yaml could not be parsed: {:?}", docs.err().unwrap().to_string())); }
+    let docs = docs.ok().unwrap();
+    if docs.is_empty() || docs[0] == Null { return Ok(Vec::new()); }
+
+    let arr = docs[0].as_vec();
+    if arr.is_none() { return Err("link preprocessing overrides yaml must be a list of entries".to_string()); }
+
+    let mut out_vec: Vec<LinkPreprocessingOverride> = Vec::new();
+    for entry in arr.unwrap() {
+        let link_name = entry["link_name"].as_str();
+        if link_name.is_none() { return Err("the field link_name is required on all entries in the link preprocessing overrides yaml".to_string()); }
+        let link_name = link_name.unwrap().to_string();
+        if out_vec.iter().any(|o| o.link_name == link_name) { return Err(format!("link {:?} appears more than once in the link preprocessing overrides yaml", link_name)); }
+
+        let mut o = LinkPreprocessingOverride::new_empty(link_name.as_str());
+        o.decomposition_error = _get_positive_f64(&entry["decomposition_error"], "decomposition_error", &link_name)?;
+        o.max_subcomponents = _get_positive_usize(&entry["max_subcomponents"], "max_subcomponents", &link_name)?;
+        o.simplification_target = _get_positive_usize(&entry["simplification_target"], "simplification_target", &link_name)?;
+
+        let res = &entry["primitive_replacement"];
+        if !(*res == BadValue) && !(*res == Null) {
+            if res.as_str().is_none() { return Err(format!("primitive_replacement for link {:?} must be a string", link_name)); }
+            o.primitive_replacement = Some(PrimitiveReplacementType::from_string(&res.as_str().unwrap().to_string())?);
+        }
+
+        out_vec.push(o);
+    }
+
+    return Ok(out_vec);
+}
+
+pub fn get_link_preprocessing_override<'a>(overrides: &'a Vec<LinkPreprocessingOverride>, link_name: &String) -> Option<&'a LinkPreprocessingOverride> {
+    return overrides.iter().find(|o| &o.link_name == link_name);
+}
+
+pub fn save_link_preprocessing_overrides(robot_name: String, overrides: &Vec<LinkPreprocessingOverride>) {
+    let mut out_string = "# this file is used to specify per-link preprocessing settings for a particular robot.  See the lynx documentation for the format. \n\n".to_string();
+
+    for o in overrides {
+        out_string += format!("- link_name: \"{}\" \n", o.link_name).as_str();
+        if o.decomposition_error.is_some() { out_string += format!("  decomposition_error: {:?} \n", o.decomposition_error.unwrap()).as_str(); }
+        if o.max_subcomponents.is_some() { out_string += format!("  max_subcomponents: {} \n", o.max_subcomponents.unwrap()).as_str(); }
+        if o.simplification_target.is_some() { out_string += format!("  simplification_target: {} \n", o.simplification_target.unwrap()).as_str(); }
+        if o.primitive_replacement.is_some() { out_string += format!("  primitive_replacement: \"{}\" \n", o.primitive_replacement.as_ref().unwrap().to_string()).as_str(); }
+        out_string += "\n";
+    }
+
+    write_string_to_file_relative_to_robot_directory(robot_name, OVERRIDES_PARTIAL_FP.to_string(), OVERRIDES_FILE_NAME.to_string(), out_string, true);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn load_applied_link_preprocessing_overrides(robot_name: String) -> Vec<LinkPreprocessingOverride> {
+    let contents = read_file_contents_separated_args_relative_to_robot_directory(robot_name, APPLIED_OVERRIDES_PARTIAL_FP.to_string(), APPLIED_OVERRIDES_FILE_NAME.to_string());
+    if contents.is_none() { return Vec::new(); }
+    let res: Result<Vec<LinkPreprocessingOverride>, _> = serde_json::from_str(&contents.unwrap());
+    return res.unwrap_or(Vec::new());
+}
+
+pub fn save_applied_link_preprocessing_overrides(robot_name: String, overrides: &Vec<LinkPreprocessingOverride>) {
+    let out_string = serde_json::to_string(overrides).unwrap();
+    write_string_to_file_relative_to_robot_directory(robot_name, APPLIED_OVERRIDES_PARTIAL_FP.to_string(), APPLIED_OVERRIDES_FILE_NAME.to_string(), out_string, true);
+}
+
+// names of links whose override was added, removed, or changed relative to the applied overrides
+pub fn get_links_with_changed_preprocessing_overrides(current_overrides: &Vec<LinkPreprocessingOverride>, applied_overrides: &Vec<LinkPreprocessingOverride>) -> Vec<String> {
+    let mut out_vec = Vec::new();
+    for o in current_overrides {
+        if get_link_preprocessing_override(applied_overrides, &o.link_name) != Some(o) { out_vec.push(o.link_name.clone()); }
+    }
+    for o in applied_overrides {
+        if get_link_preprocessing_override(current_overrides, &o.link_name).is_none() { out_vec.push(o.link_name.clone()); }
+    }
+    return out_vec;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn _get_positive_f64(y: &Yaml, field_name: &str, link_name: &String) -> Result<Option<f64>, String> {
+    if *y == BadValue || *y == Null { return Ok(None); }
+    let val = match y {
+        Yaml::Real(_) => y.as_f64(),
+        Yaml::Integer(i) => Some(*i as f64),
+        _ => None
+    };
+    if val.is_none() || !(val.unwrap() > 0.0) {
+        println!("{}{}ERROR: {} for link {:?} must be a positive number.{}", style::Bold, color::Fg(color::Red), field_name, link_name, style::Reset);
+        return Err(format!("{} for link {:?} must be a positive number", field_name, link_name));
+    }
+    return Ok(val);
+}
+
+fn _get_positive_usize(y: &Yaml, field_name: &str, link_name: &String) -> Result<Option<usize>, String> {
+    if *y == BadValue || *y == Null { return Ok(None); }
+    let val = match y {
+        Yaml::Integer(i) => Some(*i),
+        _ => None
+    };
+    if val.is_none() || !(val.unwrap() > 0) {
+        println!("{}{}ERROR: {} for link {:?} must be a positive integer.{}", style::Bold, color::Fg(color::Red), field_name, link_name, style::Reset);
+        return Err(format!("{} for link {:?} must be a positive integer (got {:?})", field_name, link_name, y));
+    }
+    return Ok(Some(val.unwrap() as usize));
+}
+
+fn _create_link_preprocessing_overrides_directory_with_example(robot_name: String) {
+    let mut out_string = "".to_string();
+
+    out_string += "# this file is used to specify per-link preprocessing settings for a particular robot.\n";
+    out_string += "# here is the format for two example links (can support any number): \n";
+    out_string += "\n";
+    out_string += "# /////////////////////////////////////////////////////////////////////////////\n";
+    out_string += "# - link_name: \"left_finger\" \n";
+    out_string += "#   decomposition_error: 0.1 \n";
+    out_string += "#   max_subcomponents: 3 \n";
+    out_string += "#   simplification_target: 500 \n";
+    out_string += "# \n";
+    out_string += "# - link_name: \"camera_mount\" \n";
+    out_string += "#   primitive_replacement: \"box\" \n";
+    out_string += "# /////////////////////////////////////////////////////////////////////////////\n";
+    out_string += "\n";
+    out_string += "# Here, link_name is a string of the link's name (as specified in the urdf).  All other fields are optional. \n";
+    out_string += "# decomposition_error is the first error setting tried in convex decomposition (larger values give fewer, coarser subcomponents). \n";
+    out_string += "# max_subcomponents is the maximum number of convex subcomponents for the link. \n";
+    out_string += "# simplification_target is the maximum number of triangles the link mesh is simplified to before decomposition. \n";
+    out_string += "# primitive_replacement has the options { \"box\", \"capsule\", \"cylinder\" }, and replaces the decomposition with a single fitted primitive. \n";
+    out_string += "# Only links whose entries change are recomputed the next time the robot is loaded. \n";
+
+    write_string_to_file_relative_to_robot_directory(robot_name, OVERRIDES_PARTIAL_FP.to_string(), OVERRIDES_FILE_NAME.to_string(), out_string, true);
+}
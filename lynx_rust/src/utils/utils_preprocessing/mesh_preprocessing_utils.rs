@@ -7,22 +7,40 @@ use crate::utils::utils_files_and_strings::string_utils::*;
 use termion::{color, style};
 use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
 use nalgebra::{Vector3};
+use ncollide3d::math::Point;
+use std::collections::HashMap;
+use std::fs;
+use crate::utils::utils_preprocessing::link_preprocessing_overrides::*;
+use ncollide3d::procedural;
+use serde::{Serialize, Deserialize};
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-fn _decompose_trimesh_into_convex_subcomponents_and_save_mesh_files(link_name: String, trimesh: &TriMeshEngine, out_fp: String, max_compoenents_per_link: usize) {
+fn _decompose_trimesh_into_convex_subcomponents_and_save_mesh_files(link_name: String, trimesh: &TriMeshEngine, out_fp: String, max_compoenents_per_link: usize, link_override: Option<&LinkPreprocessingOverride>) {
+    let mut trimesh = trimesh.clone();
+    let mut max_compoenents_per_link = max_compoenents_per_link;
+    let mut errors = vec![0.3, 1.0, 5.0, 40.0];
+
+    if link_override.is_some() {
+        let o = link_override.unwrap();
+        if o.simplification_target.is_some() { trimesh = trimesh.simplify_to_num_triangles(o.simplification_target.unwrap()); }
+        if o.max_subcomponents.is_some() { max_compoenents_per_link = o.max_subcomponents.unwrap(); }
+        // the override error is tried first, then any coarser default errors
+        if o.decomposition_error.is_some() {
+            let e = o.decomposition_error.unwrap();
+            errors = vec![e];
+            errors.extend( [0.3, 1.0, 5.0, 40.0].iter().filter(|x| **x > e) );
+        }
+    }
+
     let mut res = vec![ trimesh.compute_convex_hull() ];
 
-    if max_compoenents_per_link > 1 {
-        res = trimesh.split_into_convex_components(0.3, 0);
-        if res.len() > max_compoenents_per_link {
-            res = trimesh.split_into_convex_components(1.0, 0);
-        }
-        if res.len() > max_compoenents_per_link {
-            res = trimesh.split_into_convex_components(5.0, 0);
-        }
-        if res.len() > max_compoenents_per_link {
-            res = trimesh.split_into_convex_components(40.0, 0);
+    if link_override.is_some() && link_override.unwrap().primitive_replacement.is_some() {
+        res = vec![ fit_primitive_to_trimesh(&trimesh, link_override.unwrap().primitive_replacement.as_ref().unwrap()) ];
+    } else if max_compoenents_per_link > 1 {
+        for e in &errors {
+            res = trimesh.split_into_convex_components(*e, 0);
+            if res.len() <= max_compoenents_per_link { break; }
         }
         if res.len() > max_compoenents_per_link {
             res = vec![trimesh.compute_convex_hull()];
@@ -42,7 +60,84 @@ fn _decompose_trimesh_into_convex_subcomponents_and_save_mesh_files(link_name: S
     }
 }
 
-pub fn decompose_all_links_into_convex_subcomponents_and_save_mesh_files(robot_name: String, max_components_per_link: usize) -> Result<(), String> {
+fn _decompose_link_into_convex_subcomponents_and_save_mesh_files(robot_name: &String, link: &URDFLink, max_components_per_link: usize, link_override: Option<&LinkPreprocessingOverride>, mesh_repair_options: &MeshRepairOptions) -> Result<(), String> {
+    let out_fp = get_path_to_particular_robot_directory(robot_name.clone()) + "/autogenerated_metadata/link_convex_subcomponents/";
+    let visual_fp = get_path_to_particular_robot_directory(robot_name.clone()) + "/base_meshes/visual/";
+    let collision_fp = get_path_to_particular_robot_directory(robot_name.clone()) + "/base_meshes/collision/";
+
+    let has_visual = link.visual.len() > 0;
+    let has_collision = link.collision.len() > 0;
+
+    let mut decomposed = false;
+    if has_collision {
+        let has_mesh_file = link.collision[0].filename.is_some();
+        if has_mesh_file {
+            let fp = collision_fp.clone() + link.collision[0].filename.as_ref().unwrap();
+
+            let mut trimesh = TriMeshEngine::new_from_path(fp.clone())?;
+            let origin_xyz = link.collision[0].origin_xyz.clone();
+            let origin_rpy = link.collision[0].origin_rpy.clone();
+            if !(origin_xyz[0] == 0.0) || !(origin_xyz[1] == 0.0) || !(origin_xyz[2] == 0.0) || !(origin_rpy[0] == 0.0) || !(origin_rpy[1] == 0.0) || !(origin_rpy[2] == 0.0) {
+                trimesh.transform_vertices( &ImplicitDualQuaternion::new_from_euler_angles( origin_rpy[0], origin_rpy[1], origin_rpy[2], Vector3::new(origin_xyz[0], origin_xyz[1], origin_xyz[2])) );
+            }
+            let scale_xyz = link.collision[0].scale.clone();
+            if scale_xyz.is_some() {
+                let scale_xyz_unwrap = scale_xyz.as_ref().unwrap();
+                trimesh.scale_vertices(scale_xyz_unwrap[0], scale_xyz_unwrap[1], scale_xyz_unwrap[2]);
+            }
+            let mesh_offset = &link.link_collision_mesh_offset;
+            let mesh_scaling = &link.link_collision_mesh_scaling;
+            if mesh_offset.is_some() {
+                trimesh.transform_vertices(&mesh_offset.as_ref().unwrap());
+            }
+            if mesh_scaling.is_some() {
+                let s = mesh_scaling.as_ref().unwrap();
+                trimesh.scale_vertices(s[0], s[1], s[2]);
+            }
+
+            let trimesh = _validate_and_repair_link_trimesh(&link.name, &trimesh, mesh_repair_options);
+            _decompose_trimesh_into_convex_subcomponents_and_save_mesh_files(link.name.clone(), &trimesh, out_fp.clone(), max_components_per_link, link_override);
+            decomposed = true;
+        }
+    }
+
+    if has_visual && !decomposed {
+        let has_mesh_file = link.visual[0].filename.is_some();
+
+        if has_mesh_file {
+            let fp = visual_fp.clone() + link.visual[0].filename.as_ref().unwrap();
+
+            let mut trimesh = TriMeshEngine::new_from_path(fp.clone())?;
+            let origin_xyz = link.visual[0].origin_xyz.clone();
+            let origin_rpy = link.visual[0].origin_rpy.clone();
+            if !(origin_xyz[0] == 0.0) || !(origin_xyz[1] == 0.0) || !(origin_xyz[2] == 0.0) || !(origin_rpy[0] == 0.0) || !(origin_rpy[1] == 0.0) || !(origin_rpy[2] == 0.0)  {
+                trimesh.transform_vertices( &ImplicitDualQuaternion::new_from_euler_angles( origin_rpy[0], origin_rpy[1], origin_rpy[2], Vector3::new(origin_xyz[0], origin_xyz[1], origin_xyz[2])) );
+            }
+            let scale_xyz = link.visual[0].scale.clone();
+            if scale_xyz.is_some() {
+                let scale_xyz_unwrap = scale_xyz.as_ref().unwrap();
+                trimesh.scale_vertices(scale_xyz_unwrap[0], scale_xyz_unwrap[1], scale_xyz_unwrap[2]);
+            }
+            let mesh_offset = &link.link_visual_mesh_offset;
+            let mesh_scaling = &link.link_visual_mesh_scaling;
+            if mesh_offset.is_some() {
+                trimesh.transform_vertices(&mesh_offset.as_ref().unwrap());
+            }
+            if mesh_scaling.is_some() {
+                let s = mesh_scaling.as_ref().unwrap();
+                trimesh.scale_vertices(s[0], s[1], s[2]);
+            }
+
+            let trimesh = _validate_and_repair_link_trimesh(&link.name, &trimesh, mesh_repair_options);
+            _decompose_trimesh_into_convex_subcomponents_and_save_mesh_files(link.name.clone(), &trimesh, out_fp.clone(), 15, link_override);
+            decomposed = true;
+        }
+    }
+
+    Ok(())
+}
+
+fn _check_for_base_meshes_before_decomposition(robot_name: &String) -> Result<(), String> {
     let includes_base_meshes = robot_directory_includes_base_meshes(robot_name.clone());
     if !(includes_base_meshes) {
         copy_all_visual_mesh_filepaths_to_robot_directory(robot_name.clone());
@@ -55,89 +150,69 @@ pub fn decompose_all_links_into_convex_subcomponents_and_save_mesh_files(robot_n
         return Err(format!("It looks like there weren't any meshes found for robot {}. Cannot convert links into convex shapes. ", robot_name.clone()));
     }
 
+    Ok(())
+}
+
+// per-link settings in the robot's link_preprocessing_overrides file take precedence over max_components_per_link
+pub fn decompose_all_links_into_convex_subcomponents_and_save_mesh_files(robot_name: String, max_components_per_link: usize, mesh_repair_options: &MeshRepairOptions) -> Result<(), String> {
+    _check_for_base_meshes_before_decomposition(&robot_name)?;
+    let overrides = load_link_preprocessing_overrides(robot_name.clone())?;
+
     delete_directory_all_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_convex_subcomponents/".to_string());
     create_directories_recursively_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_convex_subcomponents".to_string());
 
-    let out_fp = get_path_to_particular_robot_directory(robot_name.clone()) + "/autogenerated_metadata/link_convex_subcomponents/";
-    let visual_fp = get_path_to_particular_robot_directory(robot_name.clone()) + "/base_meshes/visual/";
-    let collision_fp = get_path_to_particular_robot_directory(robot_name.clone()) + "/base_meshes/collision/";
-
     let links = get_all_urdf_links_from_robot_name(robot_name.clone());
 
     let l = links.len();
 
     for i in 0..l {
         println!("{}{}Decomposing link {}.  {} of {}. {}", color::Fg(color::Blue), style::Bold, links[i].name, i+1, l, style::Reset);
+        _decompose_link_into_convex_subcomponents_and_save_mesh_files(&robot_name, &links[i], max_components_per_link, get_link_preprocessing_override(&overrides, &links[i].name), mesh_repair_options)?;
+    }
 
-        let has_visual = links[i].visual.len() > 0;
-        let has_collision = links[i].collision.len() > 0;
-
-        let mut decomposed = false;
-        if has_collision {
-            let has_mesh_file = links[i].collision[0].filename.is_some();
-            if has_mesh_file {
-                let fp = collision_fp.clone() + links[i].collision[0].filename.as_ref().unwrap();
+    save_applied_link_preprocessing_overrides(robot_name.clone(), &overrides);
 
-                let mut trimesh = TriMeshEngine::new_from_path(fp.clone())?;
-                let origin_xyz = links[i].collision[0].origin_xyz.clone();
-                let origin_rpy = links[i].collision[0].origin_rpy.clone();
-                if !(origin_xyz[0] == 0.0) || !(origin_xyz[1] == 0.0) || !(origin_xyz[2] == 0.0) || !(origin_rpy[0] == 0.0) || !(origin_rpy[1] == 0.0) || !(origin_rpy[2] == 0.0) {
-                    trimesh.transform_vertices( &ImplicitDualQuaternion::new_from_euler_angles( origin_rpy[0], origin_rpy[1], origin_rpy[2], Vector3::new(origin_xyz[0], origin_xyz[1], origin_xyz[2])) );
-                }
-                let scale_xyz = links[i].collision[0].scale.clone();
-                if scale_xyz.is_some() {
-                    let scale_xyz_unwrap = scale_xyz.as_ref().unwrap();
-                    trimesh.scale_vertices(scale_xyz_unwrap[0], scale_xyz_unwrap[1], scale_xyz_unwrap[2]);
-                }
-                let mesh_offset = &links[i].link_collision_mesh_offset;
-                let mesh_scaling = &links[i].link_collision_mesh_scaling;
-                if mesh_offset.is_some() {
-                    trimesh.transform_vertices(&mesh_offset.as_ref().unwrap());
-                }
-                if mesh_scaling.is_some() {
-                    let s = mesh_scaling.as_ref().unwrap();
-                    trimesh.scale_vertices(s[0], s[1], s[2]);
-                }
+    Ok(())
+}
 
-                _decompose_trimesh_into_convex_subcomponents_and_save_mesh_files(links[i].name.clone(), &trimesh, out_fp.clone(), max_components_per_link);
-                decomposed = true;
-            }
-        }
+/* only recomputes subcomponents for links whose entry in the link_preprocessing_overrides file was added, removed, or changed
+since the subcomponents were last computed.  Returns the names of the links that were recomputed. */
+pub fn update_link_convex_subcomponents_for_changed_overrides(robot_name: String, max_components_per_link: usize, mesh_repair_options: &MeshRepairOptions) -> Result<Vec<String>, String> {
+    let exists = check_if_path_exists_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_convex_subcomponents".to_string());
+    let links = get_all_urdf_links_from_robot_name(robot_name.clone());
+    if !exists {
+        decompose_all_links_into_convex_subcomponents_and_save_mesh_files(robot_name.clone(), max_components_per_link, mesh_repair_options)?;
+        return Ok(links.iter().map(|x| x.name.clone()).collect());
+    }
 
-        if has_visual && !decomposed {
-            let has_mesh_file = links[i].visual[0].filename.is_some();
+    let overrides = load_link_preprocessing_overrides(robot_name.clone())?;
+    let changed_links = get_links_with_changed_preprocessing_overrides(&overrides, &load_applied_link_preprocessing_overrides(robot_name.clone()));
+    if changed_links.is_empty() { return Ok(changed_links); }
+    _check_for_base_meshes_before_decomposition(&robot_name)?;
 
-            if has_mesh_file {
-                let fp = visual_fp.clone() + links[i].visual[0].filename.as_ref().unwrap();
+    let subcomponents_fp = get_path_to_particular_robot_directory(robot_name.clone()) + "/autogenerated_metadata/link_convex_subcomponents/";
+    let file_names = get_all_files_in_directory_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_convex_subcomponents".to_string());
 
-                let mut trimesh = TriMeshEngine::new_from_path(fp.clone())?;
-                let origin_xyz = links[i].visual[0].origin_xyz.clone();
-                let origin_rpy = links[i].visual[0].origin_rpy.clone();
-                if !(origin_xyz[0] == 0.0) || !(origin_xyz[1] == 0.0) || !(origin_xyz[2] == 0.0) || !(origin_rpy[0] == 0.0) || !(origin_rpy[1] == 0.0) || !(origin_rpy[2] == 0.0)  {
-                    trimesh.transform_vertices( &ImplicitDualQuaternion::new_from_euler_angles( origin_rpy[0], origin_rpy[1], origin_rpy[2], Vector3::new(origin_xyz[0], origin_xyz[1], origin_xyz[2])) );
-                }
-                let scale_xyz = links[i].visual[0].scale.clone();
-                if scale_xyz.is_some() {
-                    let scale_xyz_unwrap = scale_xyz.as_ref().unwrap();
-                    trimesh.scale_vertices(scale_xyz_unwrap[0], scale_xyz_unwrap[1], scale_xyz_unwrap[2]);
-                }
-                let mesh_offset = &links[i].link_visual_mesh_offset;
-                let mesh_scaling = &links[i].link_visual_mesh_scaling;
-                if mesh_offset.is_some() {
-                    trimesh.transform_vertices(&mesh_offset.as_ref().unwrap());
-                }
-                if mesh_scaling.is_some() {
-                    let s = mesh_scaling.as_ref().unwrap();
-                    trimesh.scale_vertices(s[0], s[1], s[2]);
-                }
+    let mut out_vec = Vec::new();
+    for link in &links {
+        if !changed_links.contains(&link.name) { continue; }
+        println!("{}{}Preprocessing overrides changed for link {}.  Decomposing again. {}", color::Fg(color::Blue), style::Bold, link.name, style::Reset);
 
-                _decompose_trimesh_into_convex_subcomponents_and_save_mesh_files(links[i].name.clone(), &trimesh, out_fp.clone(), 15);
-                decomposed = true;
+        // old files are <link name>_<idx>.stl and .obj
+        for f in &file_names {
+            let stem = get_filename_without_extension(f.clone());
+            if stem.len() > link.name.len() + 1 && stem.starts_with(&(link.name.clone() + "_")) && stem[link.name.len() + 1..].chars().all(|c| c.is_ascii_digit()) {
+                fs::remove_file(subcomponents_fp.clone() + f.as_str()).ok();
             }
         }
+
+        _decompose_link_into_convex_subcomponents_and_save_mesh_files(&robot_name, link, max_components_per_link, get_link_preprocessing_override(&overrides, &link.name), mesh_repair_options)?;
+        out_vec.push(link.name.clone());
     }
 
-    Ok(())
+    save_applied_link_preprocessing_overrides(robot_name.clone(), &overrides);
+
+    return Ok(out_vec);
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -153,7 +228,7 @@ fn _convert_trimesh_into_convex_shapes_and_save_mesh_files(link_name: String, tr
     res.output_to_obj(obj_fp.clone());
 }
 
-pub fn convert_all_links_into_convex_shapes_and_save_mesh_files(robot_name: String) -> Result<(), String> {
+pub fn convert_all_links_into_convex_shapes_and_save_mesh_files(robot_name: String, mesh_repair_options: &MeshRepairOptions) -> Result<(), String> {
     let includes_base_meshes = robot_directory_includes_base_meshes(robot_name.clone());
     if !(includes_base_meshes) {
         copy_all_visual_mesh_filepaths_to_robot_directory(robot_name.clone());
@@ -210,6 +285,7 @@ pub fn convert_all_links_into_convex_shapes_and_save_mesh_files(robot_name: Stri
                     trimesh.scale_vertices(s[0], s[1], s[2]);
                 }
 
+                let trimesh = _validate_and_repair_link_trimesh(&links[i].name, &trimesh, mesh_repair_options);
                 _convert_trimesh_into_convex_shapes_and_save_mesh_files(links[i].name.clone(), &trimesh, out_fp.clone());
                 decomposed = true;
             }
@@ -242,6 +318,7 @@ pub fn convert_all_links_into_convex_shapes_and_save_mesh_files(robot_name: Stri
                     trimesh.scale_vertices(s[0], s[1], s[2]);
                 }
 
+                let trimesh = _validate_and_repair_link_trimesh(&links[i].name, &trimesh, mesh_repair_options);
                 _convert_trimesh_into_convex_shapes_and_save_mesh_files(links[i].name.clone(), &trimesh, out_fp.clone());
                 decomposed = true;
             }
@@ -253,7 +330,7 @@ pub fn convert_all_links_into_convex_shapes_and_save_mesh_files(robot_name: Stri
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-pub fn save_all_links_as_triangle_meshes(robot_name: String) -> Result<(), String> {
+pub fn save_all_links_as_triangle_meshes(robot_name: String, mesh_repair_options: &MeshRepairOptions) -> Result<(), String> {
     let includes_base_meshes = robot_directory_includes_base_meshes(robot_name.clone());
     if !(includes_base_meshes) {
         copy_all_visual_mesh_filepaths_to_robot_directory(robot_name.clone());
@@ -319,6 +396,7 @@ pub fn save_all_links_as_triangle_meshes(robot_name: String) -> Result<(), Strin
                     trimesh.scale_vertices(s[0], s[1], s[2]);
                 }
 
+                let trimesh = _validate_and_repair_link_trimesh(&links[i].name, &trimesh, mesh_repair_options);
                 let out_stl_fp = get_path_to_particular_robot_directory(robot_name.clone()) + "/autogenerated_metadata/link_triangle_meshes_collision/" + links[i].name.as_str() + ".stl";
                 trimesh.output_to_stl(out_stl_fp);
                 let out_obj_fp = get_path_to_particular_robot_directory(robot_name.clone()) + "/autogenerated_metadata/link_triangle_meshes_collision/" + links[i].name.as_str() + ".obj";
@@ -354,6 +432,7 @@ pub fn save_all_links_as_triangle_meshes(robot_name: String) -> Result<(), Strin
                 }
 
 
+                let trimesh = _validate_and_repair_link_trimesh(&links[i].name, &trimesh, mesh_repair_options);
                 let out_stl_fp = get_path_to_particular_robot_directory(robot_name.clone()) + "/autogenerated_metadata/link_triangle_meshes_visual/" + links[i].name.as_str() + ".stl";
                 trimesh.output_to_stl(out_stl_fp);
                 let out_obj_fp = get_path_to_particular_robot_directory(robot_name.clone()) + "/autogenerated_metadata/link_triangle_meshes_visual/" + links[i].name.as_str() + ".obj";
@@ -366,3 +445,460 @@ pub fn save_all_links_as_triangle_meshes(robot_name: String) -> Result<(), Strin
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/* fits a single primitive around a triangle mesh, in the mesh's frame, and returns it as a triangle mesh.  Like link OBBs,
+boxes are aligned with the mesh frame axes.  Cylinders and capsules are aligned with the longest of those axes, through the
+center of the box, and are grown until they contain every vertex. */
+pub fn fit_primitive_to_trimesh(trimesh: &TriMeshEngine, primitive_replacement_type: &PrimitiveReplacementType) -> TriMeshEngine {
+    let mut mins = Vector3::new(std::f64::INFINITY, std::f64::INFINITY, std::f64::INFINITY);
+    let mut maxs = -mins.clone();
+    for v in &trimesh.vertices {
+        mins = mins.inf(&v.coords);
+        maxs = maxs.sup(&v.coords);
+    }
+    if trimesh.vertices.is_empty() { mins = Vector3::zeros(); maxs = Vector3::zeros(); }
+    let center = (&mins + &maxs) / 2.0;
+    let half_extents = (&maxs - &mins) / 2.0;
+
+    let mut axis = 0;
+    for k in 1..3 { if half_extents[k] > half_extents[axis] { axis = k; } }
+
+    // radial and axial coordinates of every vertex relative to the primitive's axis
+    let mut radius: f64 = 0.0;
+    let mut coordinates = Vec::new();
+    for v in &trimesh.vertices {
+        let d = &v.coords - &center;
+        let a = d[axis];
+        let r = (d.norm_squared() - a * a).max(0.0).sqrt();
+        radius = radius.max(r);
+        coordinates.push( (a, r) );
+    }
+    radius = radius.max(0.000001);
+
+    let mut out_trimesh = match primitive_replacement_type {
+        PrimitiveReplacementType::Box => {
+            let mut t = procedural::cuboid(&(2.0 * half_extents.map(|x| x.max(0.000001))));
+            t.unify_index_buffer();
+            return _translate_trimesh(TriMeshEngine::new_from_procedural_trimesh(&t), &center);
+        }
+        PrimitiveReplacementType::Cylinder => {
+            let mut t = procedural::cylinder(2.0 * radius, 2.0 * half_extents[axis].max(0.000001), 24);
+            t.unify_index_buffer();
+            TriMeshEngine::new_from_procedural_trimesh(&t)
+        }
+        PrimitiveReplacementType::Capsule => {
+            // the straight section only has to reach far enough that each vertex is inside one of the end caps
+            let mut half_height: f64 = 0.0;
+            for (a, r) in &coordinates { half_height = half_height.max( a.abs() - (radius * radius - r * r).max(0.0).sqrt() ); }
+            let mut t = procedural::capsule(&(2.0 * radius), &(2.0 * half_height), 24, 12);
+            t.unify_index_buffer();
+            TriMeshEngine::new_from_procedural_trimesh(&t)
+        }
+    };
+
+    // procedural cylinders and capsules are aligned with the y axis
+    if axis == 0 { out_trimesh.transform_vertices(&ImplicitDualQuaternion::new_from_euler_angles(0., 0., -std::f64::consts::FRAC_PI_2, Vector3::zeros())); }
+    if axis == 2 { out_trimesh.transform_vertices(&ImplicitDualQuaternion::new_from_euler_angles(std::f64::consts::FRAC_PI_2, 0., 0., Vector3::zeros())); }
+    return _translate_trimesh(out_trimesh, &center);
+}
+
+fn _translate_trimesh(trimesh: TriMeshEngine, translation: &Vector3<f64>) -> TriMeshEngine {
+    let mut out_trimesh = trimesh;
+    out_trimesh.transform_vertices(&ImplicitDualQuaternion::new_from_euler_angles(0., 0., 0., translation.clone()));
+    return out_trimesh;
+}
+
+/* fits up to num_spheres spheres to a triangle mesh.  Every triangle is assigned to exactly one sphere, and that sphere's
+radius is grown until it contains all three of the triangle's vertices (and thus, since balls are convex, the whole
+triangle), so the spheres are guaranteed to cover the full mesh surface.  Output spheres are (center, radius) in the
+mesh's frame. */
+pub fn fit_spheres_to_trimesh(trimesh: &TriMeshEngine, num_spheres: usize) -> Vec<(Vector3<f64>, f64)> {
+    let mut out_vec = Vec::new();
+
+    let num_triangles = trimesh.indices.len();
+    if num_triangles == 0 || num_spheres == 0 { return out_vec; }
+
+    let mut triangles = Vec::new();
+    let mut centroids = Vec::new();
+    for t in &trimesh.indices {
+        let tri = [trimesh.vertices[t[0]].coords, trimesh.vertices[t[1]].coords, trimesh.vertices[t[2]].coords];
+        centroids.push( (tri[0] + tri[1] + tri[2]) / 3.0 );
+        triangles.push(tri);
+    }
+
+    // farthest point initialization, starting from the centroid farthest from the mesh center
+    let k = num_spheres.min(num_triangles);
+    let mesh_center = trimesh.compute_center();
+    let mut centers = Vec::new();
+    let mut first_idx = 0;
+    for i in 0..num_triangles {
+        if (centroids[i] - mesh_center).norm() > (centroids[first_idx] - mesh_center).norm() { first_idx = i; }
+    }
+    centers.push( centroids[first_idx].clone() );
+    while centers.len() < k {
+        let mut farthest_idx = 0;
+        let mut farthest_dis = -1.0;
+        for i in 0..num_triangles {
+            let dis = centers.iter().map(|c| (centroids[i] - c).norm()).fold(std::f64::INFINITY, f64::min);
+            if dis > farthest_dis { farthest_dis = dis; farthest_idx = i; }
+        }
+        centers.push( centroids[farthest_idx].clone() );
+    }
+
+    // lloyd-style refinement.  Each center moves to the middle of its cluster's bounding box, which keeps radii small.
+    let mut assignments = vec![0 as usize; num_triangles];
+    for _ in 0..15 {
+        for i in 0..num_triangles {
+            let mut best = 0;
+            for j in 1..centers.len() {
+                if (centroids[i] - centers[j]).norm() < (centroids[i] - centers[best]).norm() { best = j; }
+            }
+            assignments[i] = best;
+        }
+
+        for j in 0..centers.len() {
+            let mut mins = Vector3::new(std::f64::INFINITY, std::f64::INFINITY, std::f64::INFINITY);
+            let mut maxs = -mins.clone();
+            let mut found = false;
+            for i in 0..num_triangles {
+                if assignments[i] != j { continue; }
+                found = true;
+                for v in &triangles[i] {
+                    mins = mins.inf(v);
+                    maxs = maxs.sup(v);
+                }
+            }
+            if found { centers[j] = (mins + maxs) / 2.0; }
+        }
+    }
+
+    for j in 0..centers.len() {
+        let mut radius: f64 = -1.0;
+        for i in 0..num_triangles {
+            if assignments[i] != j { continue; }
+            for v in &triangles[i] { radius = radius.max( (v - centers[j]).norm() ); }
+        }
+        // clusters that ended up empty are dropped
+        if radius >= 0.0 { out_vec.push( (centers[j].clone(), radius) ); }
+    }
+
+    return out_vec;
+}
+
+// fits spheres to every link's convex shape (so link_convex_shapes must already exist) and saves them as json files
+// of [x, y, z, radius] entries in autogenerated_metadata/link_spheres.
+pub fn fit_spheres_to_all_links_and_save_files(robot_name: String, num_spheres_per_link: usize) -> Result<(), String> {
+    let exists = check_if_path_exists_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_convex_shapes".to_string());
+    if !exists {
+        return Err(format!("link convex shapes have not been computed for robot {}.  Cannot fit link spheres.", robot_name.clone()));
+    }
+
+    delete_directory_all_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_spheres/".to_string());
+    create_directories_recursively_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_spheres".to_string());
+
+    let convex_shapes_fp = get_path_to_particular_robot_directory(robot_name.clone()) + "/autogenerated_metadata/link_convex_shapes/";
+
+    let links = get_all_urdf_links_from_robot_name(robot_name.clone());
+    let l = links.len();
+    for i in 0..l {
+        let fp = convex_shapes_fp.clone() + links[i].name.as_str() + ".stl";
+        if !check_if_path_exists(fp.clone()) { continue; }
+
+        let trimesh = TriMeshEngine::new_from_path(fp)?;
+        let spheres = fit_spheres_to_trimesh(&trimesh, num_spheres_per_link);
+        println!("{}{}Fit {} spheres to link {}.  {} of {}. {}", color::Fg(color::Green), style::Bold, spheres.len(), links[i].name, i+1, l, style::Reset);
+
+        let out_vec: Vec<[f64; 4]> = spheres.iter().map(|(c, r)| [c[0], c[1], c[2], *r]).collect();
+        let out_string = serde_json::to_string(&out_vec).unwrap();
+        write_string_to_file_relative_to_robot_directory(robot_name.clone(), "autogenerated_metadata/link_spheres".to_string(), links[i].name.clone() + ".json", out_string, true);
+    }
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/* summary of problems in a triangle mesh that make convex decomposition and volume computation unreliable.  Open edges are
+used by only one triangle, and non-manifold edges by more than two.  Flipped triangles are the ones whose winding disagrees
+with the rest of their connected piece of surface (or, for closed pieces, whose normals point inward).  Duplicate vertices
+are distinct vertices within the weld tolerance of an earlier vertex. */
+#[derive(Clone, Debug)]
+pub struct MeshValidationReport {
+    pub num_vertices: usize,
+    pub num_triangles: usize,
+    pub num_open_edges: usize,
+    pub num_non_manifold_edges: usize,
+    pub num_degenerate_triangles: usize,
+    pub num_duplicate_vertices: usize,
+    pub num_flipped_triangles: usize
+}
+
+impl MeshValidationReport {
+    pub fn is_watertight(&self) -> bool {
+        return self.num_open_edges == 0 && self.num_non_manifold_edges == 0;
+    }
+
+    pub fn is_valid(&self) -> bool {
+        return self.is_watertight() && self.num_degenerate_triangles == 0 && self.num_duplicate_vertices == 0 && self.num_flipped_triangles == 0;
+    }
+
+    pub fn print_summary(&self) {
+        let c = if self.is_valid() { format!("{}", color::Fg(color::Green)) } else { format!("{}", color::Fg(color::Yellow)) };
+        println!("{}{}vertices: {}, triangles: {}, open edges: {}, non-manifold edges: {}, degenerate triangles: {}, duplicate vertices: {}, flipped triangles: {}{}",
+                 c, style::Bold, self.num_vertices, self.num_triangles, self.num_open_edges, self.num_non_manifold_edges,
+                 self.num_degenerate_triangles, self.num_duplicate_vertices, self.num_flipped_triangles, style::Reset);
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MeshRepairOptions {
+    pub validate: bool,
+    pub weld_vertices: bool,
+    pub weld_tolerance: f64,
+    pub remove_degenerate_triangles: bool,
+    pub reorient_faces: bool
+}
+
+impl MeshRepairOptions {
+    pub fn new(weld_vertices: bool, weld_tolerance: f64, remove_degenerate_triangles: bool, reorient_faces: bool) -> Self {
+        return Self { validate: true, weld_vertices, weld_tolerance, remove_degenerate_triangles, reorient_faces };
+    }
+
+    pub fn new_default() -> Self {
+        return Self::new(true, 0.000001, true, true);
+    }
+
+    // only validates; meshes are saved as they are
+    pub fn new_validate_only() -> Self {
+        return Self::new(false, 0.000001, false, false);
+    }
+
+    // neither validates nor repairs
+    pub fn new_off() -> Self {
+        let mut out_self = Self::new_validate_only();
+        out_self.validate = false;
+        return out_self;
+    }
+
+    // mode can be repair, validate_only, or off
+    pub fn new_from_mode(mode: &str) -> Result<Self, String> {
+        return match mode {
+            "repair" => Ok(Self::new_default()),
+            "validate_only" => Ok(Self::new_validate_only()),
+            "off" => Ok(Self::new_off()),
+            _ => Err(format!("mesh repair mode {} is not valid.  Options are repair, validate_only, or off.", mode))
+        };
+    }
+
+    pub fn get_mode(&self) -> &'static str {
+        if !self.validate { return "off"; }
+        if self.weld_vertices || self.remove_degenerate_triangles || self.reorient_faces { return "repair"; }
+        return "validate_only";
+    }
+}
+
+impl Default for MeshRepairOptions {
+    fn default() -> Self {
+        return Self::new_default();
+    }
+}
+
+pub fn validate_trimesh(trimesh: &TriMeshEngine, weld_tolerance: f64) -> MeshValidationReport {
+    let (_, num_unique_vertices) = _get_welded_vertex_map(trimesh, weld_tolerance);
+    let (open_edges, non_manifold_edges) = _count_open_and_non_manifold_edges(trimesh);
+    let flips = _get_triangle_orientation_flips(trimesh);
+
+    return MeshValidationReport {
+        num_vertices: trimesh.vertices.len(),
+        num_triangles: trimesh.indices.len(),
+        num_open_edges: open_edges,
+        num_non_manifold_edges: non_manifold_edges,
+        num_degenerate_triangles: (0..trimesh.indices.len()).filter(|i| _is_degenerate_triangle(trimesh, *i)).count(),
+        num_duplicate_vertices: trimesh.vertices.len() - num_unique_vertices,
+        num_flipped_triangles: flips.iter().filter(|f| **f).count()
+    };
+}
+
+// repairs are applied in order (weld, then remove degenerates, then re-orient), since welding is what connects
+// triangles that only touch geometrically and degenerate triangles would otherwise add spurious edges
+pub fn repair_trimesh(trimesh: &TriMeshEngine, mesh_repair_options: &MeshRepairOptions) -> TriMeshEngine {
+    let mut out_trimesh = trimesh.clone();
+
+    if mesh_repair_options.weld_vertices {
+        let (vertex_map, _) = _get_welded_vertex_map(&out_trimesh, mesh_repair_options.weld_tolerance);
+        let mut new_idxs = vec![0 as usize; out_trimesh.vertices.len()];
+        let mut vertices = Vec::new();
+        let l = out_trimesh.vertices.len();
+        for i in 0..l {
+            if vertex_map[i] == i {
+                new_idxs[i] = vertices.len();
+                vertices.push(out_trimesh.vertices[i].clone());
+            }
+        }
+        let indices = out_trimesh.indices.iter().map(|t| Point::new(new_idxs[vertex_map[t[0]]], new_idxs[vertex_map[t[1]]], new_idxs[vertex_map[t[2]]])).collect();
+        out_trimesh = TriMeshEngine::new(vertices, indices);
+    }
+
+    if mesh_repair_options.remove_degenerate_triangles {
+        let indices = (0..out_trimesh.indices.len()).filter(|i| !_is_degenerate_triangle(&out_trimesh, *i)).map(|i| out_trimesh.indices[i].clone()).collect();
+        out_trimesh.indices = indices;
+    }
+
+    if mesh_repair_options.reorient_faces {
+        let flips = _get_triangle_orientation_flips(&out_trimesh);
+        let l = out_trimesh.indices.len();
+        for i in 0..l {
+            if flips[i] { out_trimesh.indices[i] = Point::new(out_trimesh.indices[i][0], out_trimesh.indices[i][2], out_trimesh.indices[i][1]); }
+        }
+    }
+
+    return out_trimesh;
+}
+
+fn _validate_and_repair_link_trimesh(link_name: &String, trimesh: &TriMeshEngine, mesh_repair_options: &MeshRepairOptions) -> TriMeshEngine {
+    if !mesh_repair_options.validate { return trimesh.clone(); }
+
+    let report = validate_trimesh(trimesh, mesh_repair_options.weld_tolerance);
+    if report.is_valid() { return trimesh.clone(); }
+
+    println!("{}{}WARNING: mesh for link {} has problems. {}", color::Fg(color::Yellow), style::Bold, link_name, style::Reset);
+    report.print_summary();
+
+    let out_trimesh = repair_trimesh(trimesh, mesh_repair_options);
+    if mesh_repair_options.weld_vertices || mesh_repair_options.remove_degenerate_triangles || mesh_repair_options.reorient_faces {
+        println!("{}{}After repair: {}", color::Fg(color::Blue), style::Bold, style::Reset);
+        validate_trimesh(&out_trimesh, mesh_repair_options.weld_tolerance).print_summary();
+    }
+    return out_trimesh;
+}
+
+// maps every vertex to the first vertex within tolerance of it (possibly itself), and returns the number of distinct vertices
+fn _get_welded_vertex_map(trimesh: &TriMeshEngine, tolerance: f64) -> (Vec<usize>, usize) {
+    let cell_size = tolerance.max(std::f64::MIN_POSITIVE);
+    let mut grid: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+    let mut out_map = Vec::new();
+    let mut num_unique = 0;
+
+    let l = trimesh.vertices.len();
+    for i in 0..l {
+        let v = trimesh.vertices[i];
+        let cell = ((v[0] / cell_size).floor() as i64, (v[1] / cell_size).floor() as i64, (v[2] / cell_size).floor() as i64);
+
+        let mut found = None;
+        'search: for dx in -1..2 { for dy in -1..2 { for dz in -1..2 {
+            let neighbors = grid.get(&(cell.0 + dx, cell.1 + dy, cell.2 + dz));
+            if neighbors.is_none() { continue; }
+            for j in neighbors.unwrap() {
+                if (trimesh.vertices[*j] - v).norm() <= tolerance { found = Some(*j); break 'search; }
+            }
+        } } }
+
+        if found.is_some() { out_map.push(found.unwrap()); }
+        else {
+            out_map.push(i);
+            grid.entry(cell).or_insert(Vec::new()).push(i);
+            num_unique += 1;
+        }
+    }
+
+    return (out_map, num_unique);
+}
+
+fn _is_degenerate_triangle(trimesh: &TriMeshEngine, idx: usize) -> bool {
+    let t = &trimesh.indices[idx];
+    if t[0] == t[1] || t[1] == t[2] || t[0] == t[2] { return true; }
+    let a = trimesh.vertices[t[1]] - trimesh.vertices[t[0]];
+    let b = trimesh.vertices[t[2]] - trimesh.vertices[t[0]];
+    let longest_edge = a.norm().max(b.norm()).max((b - a).norm());
+    // zero area relative to the triangle's own size, so this works at any mesh scale
+    return a.cross(&b).norm() <= 0.0000000001 * longest_edge * longest_edge;
+}
+
+// edge (smaller vertex idx, larger vertex idx) -> (triangle idx, whether the triangle traverses the edge from smaller to larger)
+fn _get_edge_map(trimesh: &TriMeshEngine) -> HashMap<(usize, usize), Vec<(usize, bool)>> {
+    let mut out_map: HashMap<(usize, usize), Vec<(usize, bool)>> = HashMap::new();
+    let l = trimesh.indices.len();
+    for i in 0..l {
+        let t = &trimesh.indices[i];
+        for k in 0..3 {
+            let a = t[k];
+            let b = t[(k + 1) % 3];
+            if a == b { continue; }
+            out_map.entry((a.min(b), a.max(b))).or_insert(Vec::new()).push((i, a < b));
+        }
+    }
+    return out_map;
+}
+
+fn _count_open_and_non_manifold_edges(trimesh: &TriMeshEngine) -> (usize, usize) {
+    let edge_map = _get_edge_map(trimesh);
+    let open_edges = edge_map.values().filter(|e| e.len() == 1).count();
+    let non_manifold_edges = edge_map.values().filter(|e| e.len() > 2).count();
+    return (open_edges, non_manifold_edges);
+}
+
+/* for every triangle, whether it has to be flipped to agree with its connected piece of surface.  Orientation is propagated
+across manifold edges from a seed triangle; then, closed pieces keep whichever orientation has outward normals (positive
+signed volume), and open pieces keep whichever orientation the majority of their triangles already have. */
+fn _get_triangle_orientation_flips(trimesh: &TriMeshEngine) -> Vec<bool> {
+    let num_triangles = trimesh.indices.len();
+    let edge_map = _get_edge_map(trimesh);
+    let mut out_flips = vec![false; num_triangles];
+    let mut visited = vec![false; num_triangles];
+
+    let mut neighbors: Vec<Vec<(usize, (usize, usize))>> = vec![Vec::new(); num_triangles];
+    for (edge, triangles) in &edge_map {
+        if triangles.len() != 2 { continue; }
+        neighbors[triangles[0].0].push( (triangles[1].0, *edge) );
+        neighbors[triangles[1].0].push( (triangles[0].0, *edge) );
+    }
+
+    let traverses_forward = |triangle_idx: usize, edge: &(usize, usize)| -> bool {
+        return edge_map.get(edge).unwrap().iter().find(|x| x.0 == triangle_idx).unwrap().1;
+    };
+
+    for seed in 0..num_triangles {
+        if visited[seed] { continue; }
+
+        let mut component = vec![seed];
+        let mut closed = true;
+        visited[seed] = true;
+        let mut stack = vec![seed];
+        while !stack.is_empty() {
+            let t1 = stack.pop().unwrap();
+            for k in 0..3 {
+                let (a, b) = (trimesh.indices[t1][k], trimesh.indices[t1][(k + 1) % 3]);
+                if a != b && edge_map.get(&(a.min(b), a.max(b))).unwrap().len() != 2 { closed = false; }
+            }
+            for (t2, edge) in &neighbors[t1] {
+                if visited[*t2] { continue; }
+                // neighbors with consistent winding traverse their shared edge in opposite directions
+                let forward1 = traverses_forward(t1, edge) != out_flips[t1];
+                out_flips[*t2] = traverses_forward(*t2, edge) == forward1;
+                visited[*t2] = true;
+                component.push(*t2);
+                stack.push(*t2);
+            }
+        }
+
+        let keep_seed_orientation = if closed {
+            let mut signed_volume = 0.0;
+            for t in &component {
+                let idxs = &trimesh.indices[*t];
+                let (v0, v1, v2) = (trimesh.vertices[idxs[0]].coords, trimesh.vertices[idxs[1]].coords, trimesh.vertices[idxs[2]].coords);
+                let volume = v0.dot(&v1.cross(&v2)) / 6.0;
+                signed_volume += if out_flips[*t] { -volume } else { volume };
+            }
+            signed_volume >= 0.0
+        } else {
+            let num_flipped = component.iter().filter(|t| out_flips[**t]).count();
+            2 * num_flipped <= component.len()
+        };
+
+        if !keep_seed_orientation {
+            for t in &component { out_flips[*t] = !out_flips[*t]; }
+        }
+    }
+
+    return out_flips;
+}
@@ -1 +1,2 @@
-pub mod mesh_preprocessing_utils;
\ No newline at end of file
+pub mod mesh_preprocessing_utils;
+pub mod link_preprocessing_overrides;
\ No newline at end of file
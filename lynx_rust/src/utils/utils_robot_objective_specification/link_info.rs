@@ -30,6 +30,17 @@ impl SalientLinkType {
         else if s == "Base" { return Ok(SalientLinkType::Base); }
         else { return Err(format!("{:?} is not a valid salient link type", s)) }
     }
+
+    pub fn to_string(&self) -> String {
+        return match self {
+            SalientLinkType::EndEffector => "EndEffector".to_string(),
+            SalientLinkType::Foot => "Foot".to_string(),
+            SalientLinkType::Elbow => "Elbow".to_string(),
+            SalientLinkType::Head => "Head".to_string(),
+            SalientLinkType::Knee => "Knee".to_string(),
+            SalientLinkType::Base => "Base".to_string()
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -48,6 +59,17 @@ impl LinkAxis {
         else { return Err(format!("{:?} is not a valid link axis", s)) }
     }
 
+    pub fn to_string(&self) -> String {
+        return match self {
+            LinkAxis::X => "X".to_string(),
+            LinkAxis::Y => "Y".to_string(),
+            LinkAxis::Z => "Z".to_string(),
+            LinkAxis::NegX => "NegX".to_string(),
+            LinkAxis::NegY => "NegY".to_string(),
+            LinkAxis::NegZ => "NegZ".to_string()
+        }
+    }
+
     pub fn to_vector_from_rotation_matrix(&self, rotation_matrix: &Rotation3<f64>) -> Vector3<f64> {
         match self {
             LinkAxis::X => return Vector3::new( rotation_matrix[(0,0)], rotation_matrix[(1,0)], rotation_matrix[(2,0)] ),
@@ -41,6 +41,9 @@ impl LynxFloatVecSampler for FreeSpaceSampler {
             num_tries+=1;
         }
     }
+    fn lynx_float_vec_sampler_bounds(&self) -> Option<Vec<(f64, f64)>> {
+        return self._base_sampler.lynx_float_vec_sampler_bounds();
+    }
 }
 impl LynxMultiFloatVecSampler for FreeSpaceSampler { }
 impl LynxVarsUser for FreeSpaceSampler {
@@ -54,6 +54,8 @@ unsafe impl Sync for FloatVecSamplerBox { }
 
 pub trait LynxFloatVecSampler: Send + Sync + LynxFloatVecSamplerClone + LynxVarsUser + AsLynxVarsUser {
     fn lynx_float_vec_sampler_sample(&self, lynx_vars: &mut LynxVarsGeneric) -> Result<DVector<f64>, String>;
+    // (lower, upper) bound per dimension that all samples fall within, if the sampler has such bounds
+    fn lynx_float_vec_sampler_bounds(&self) -> Option<Vec<(f64, f64)>> { return None; }
     fn to_lynx_float_vec_sampler_box(&self) -> LynxFloatVecSamplerBox {
         return LynxFloatVecSamplerBox(self.lynx_float_vec_sampler_clone_box());
     }
@@ -86,6 +88,10 @@ impl LynxFloatVecSamplerBox {
     pub fn lynx_float_vec_sampler_sample(&self, lynx_vars: &mut LynxVarsGeneric) -> Result<DVector<f64>, String> {
         return self.0.lynx_float_vec_sampler_sample(lynx_vars);
     }
+
+    pub fn lynx_float_vec_sampler_bounds(&self) -> Option<Vec<(f64, f64)>> {
+        return self.0.lynx_float_vec_sampler_bounds();
+    }
 }
 impl Clone for LynxFloatVecSamplerBox {
     fn clone(&self) -> Self {
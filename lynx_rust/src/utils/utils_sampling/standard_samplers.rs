@@ -66,6 +66,9 @@ impl LynxFloatVecSampler for RangeFloatVecSampler {
         }
         return Ok(v);
     }
+    fn lynx_float_vec_sampler_bounds(&self) -> Option<Vec<(f64, f64)>> {
+        return Some(vec![(self.lower_bound, self.upper_bound); self.dim]);
+    }
 }
 impl LynxVarsUser for RangeFloatVecSampler { }
 
@@ -104,6 +107,9 @@ impl LynxFloatVecSampler for RangeFloatVecSamplerSeedable {
         }
         return Ok(v);
     }
+    fn lynx_float_vec_sampler_bounds(&self) -> Option<Vec<(f64, f64)>> {
+        return Some(vec![(self.lower_bound, self.upper_bound); self.dim]);
+    }
 }
 impl LynxVarsUser for RangeFloatVecSamplerSeedable { }
 
@@ -137,6 +143,9 @@ impl LynxFloatVecSampler for RectangleFloatVecSampler {
         }
         return Ok(v);
     }
+    fn lynx_float_vec_sampler_bounds(&self) -> Option<Vec<(f64, f64)>> {
+        return Some(self.bounds.clone());
+    }
 }
 impl LynxVarsUser for RectangleFloatVecSampler { }
 
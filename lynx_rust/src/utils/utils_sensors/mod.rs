@@ -0,0 +1,2 @@
+pub mod virtual_depth_camera;
+pub mod prelude;
\ No newline at end of file
@@ -0,0 +1 @@
+pub use crate::utils::utils_sensors::virtual_depth_camera::*;
\ No newline at end of file
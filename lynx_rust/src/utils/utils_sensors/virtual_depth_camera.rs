@@ -0,0 +1,287 @@
+use crate::robot_modules::robot_world::RobotWorld;
+use crate::robot_modules::robot_core_collision_module::LinkGeometryType;
+use crate::robot_modules::robot_fk_module::VecOfRobotFKResult;
+use crate::utils::utils_collisions::collision_object::CollisionObject;
+use crate::utils::utils_collisions::collision_object_group_queries::ray_cast_between_multiple_collision_objects;
+use crate::utils::utils_pointclouds::pointcloud::PointCloud;
+use crate::utils::utils_se3::implicit_dual_quaternion::ImplicitDualQuaternion;
+use nalgebra::{DVector, Vector3, Point3};
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::Write;
+
+/*
+Simulated depth camera that ray casts a RobotWorld (robot links and the collision environment), one ray per pixel.
+The camera frame follows the usual optical frame convention: +z looks forward, +x is to the right in the image, and +y
+is down in the image.  Depths are measured along +z (not along the ray), like most real depth cameras.
+*/
+#[derive(Clone, Debug)]
+pub struct CameraIntrinsics {
+    pub width: usize,
+    pub height: usize,
+    pub fx: f64,
+    pub fy: f64,
+    pub cx: f64,
+    pub cy: f64
+}
+
+impl CameraIntrinsics {
+    pub fn new(width: usize, height: usize, fx: f64, fy: f64, cx: f64, cy: f64) -> Result<Self, String> {
+        if width == 0 || height == 0 { return Err(format!("camera width and height must be positive (got {:?} x {:?})", width, height)); }
+        if !(fx > 0.0) || !(fy > 0.0) { return Err(format!("camera focal lengths must be positive (got fx {:?}, fy {:?})", fx, fy)); }
+        return Ok(Self { width, height, fx, fy, cx, cy });
+    }
+
+    // square pixels with the principal point in the center of the image
+    pub fn new_from_horizontal_field_of_view(width: usize, height: usize, horizontal_fov_radians: f64) -> Result<Self, String> {
+        if !(horizontal_fov_radians > 0.0) || !(horizontal_fov_radians < std::f64::consts::PI) {
+            return Err(format!("horizontal field of view must be between 0 and pi (got {:?})", horizontal_fov_radians));
+        }
+        let f = (width as f64 / 2.0) / (horizontal_fov_radians / 2.0).tan();
+        return Self::new(width, height, f, f, width as f64 / 2.0, height as f64 / 2.0);
+    }
+
+    // direction through the center of pixel (u, v) in the camera frame, scaled so that its z component is 1
+    pub fn get_pixel_ray_direction(&self, u: usize, v: usize) -> Vector3<f64> {
+        return Vector3::new( (u as f64 + 0.5 - self.cx) / self.fx, (v as f64 + 0.5 - self.cy) / self.fy, 1.0 );
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum VirtualDepthCameraMount {
+    Fixed { pose: ImplicitDualQuaternion },
+    // camera pose is the link's frame (from fk) times offset.  Rays ignore the mount link itself.
+    Link { robot_idx: usize, link_idx: usize, offset: ImplicitDualQuaternion }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DepthCameraHitLabel {
+    RobotLink { robot_idx: usize, link_idx: usize, name: String },
+    EnvironmentObject { object_idx: usize, name: String }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug)]
+pub struct VirtualDepthCamera {
+    _intrinsics: CameraIntrinsics,
+    _mount: VirtualDepthCameraMount,
+    _min_range: f64,
+    _max_range: f64,
+    _link_geometry_type: LinkGeometryType
+}
+
+impl VirtualDepthCamera {
+    pub fn new(intrinsics: CameraIntrinsics, mount: VirtualDepthCameraMount) -> Self {
+        return Self { _intrinsics: intrinsics, _mount: mount, _min_range: 0.05, _max_range: 10.0, _link_geometry_type: LinkGeometryType::ConvexShapes };
+    }
+
+    pub fn new_fixed(intrinsics: CameraIntrinsics, pose: ImplicitDualQuaternion) -> Self {
+        return Self::new(intrinsics, VirtualDepthCameraMount::Fixed { pose });
+    }
+
+    pub fn new_attached_to_link(intrinsics: CameraIntrinsics, robot_world: &RobotWorld, robot_idx: usize, link_name: &str, offset: ImplicitDualQuaternion) -> Result<Self, String> {
+        let robots = robot_world.get_robot_set_ref().get_robots_ref();
+        if robot_idx >= robots.len() { return Err(format!("robot idx {:?} is too high for number of robots ({:?})", robot_idx, robots.len())); }
+        let link_idx = robots[robot_idx].get_configuration_module_ref().robot_model_module.get_link_idx_from_name(&link_name.to_string());
+        if link_idx.is_none() { return Err(format!("link {:?} not found on robot {:?} when attaching VirtualDepthCamera", link_name, robot_idx)); }
+        return Ok(Self::new(intrinsics, VirtualDepthCameraMount::Link { robot_idx, link_idx: link_idx.unwrap(), offset }));
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // hits closer than min_range (in depth) are ignored, e.g., the camera housing
+    pub fn set_range(&mut self, min_range: f64, max_range: f64) -> Result<(), String> {
+        if min_range < 0.0 || !(max_range > min_range) { return Err(format!("invalid camera range [{:?}, {:?}]", min_range, max_range)); }
+        self._min_range = min_range;
+        self._max_range = max_range;
+        return Ok(());
+    }
+
+    pub fn set_link_geometry_type(&mut self, link_geometry_type: LinkGeometryType) {
+        self._link_geometry_type = link_geometry_type;
+    }
+
+    pub fn set_mount(&mut self, mount: VirtualDepthCameraMount) {
+        self._mount = mount;
+    }
+
+    pub fn get_camera_pose(&self, fk_res: &VecOfRobotFKResult) -> Result<ImplicitDualQuaternion, String> {
+        return match &self._mount {
+            VirtualDepthCameraMount::Fixed { pose } => Ok(pose.clone()),
+            VirtualDepthCameraMount::Link { robot_idx, link_idx, offset } => {
+                let robot_fk_results = fk_res.get_robot_fk_results_ref();
+                if *robot_idx >= robot_fk_results.len() { return Err(format!("camera is mounted on robot {:?}, but there are only {:?} robots", robot_idx, robot_fk_results.len())); }
+                let link_frames = robot_fk_results[*robot_idx].get_link_frames_ref();
+                if *link_idx >= link_frames.len() || link_frames[*link_idx].is_none() { return Err(format!("camera mount link {:?} on robot {:?} does not have a frame in the fk result", link_idx, robot_idx)); }
+                Ok(link_frames[*link_idx].as_ref().unwrap().multiply(offset))
+            }
+        }
+    }
+
+    pub fn get_intrinsics_ref(&self) -> &CameraIntrinsics { return &self._intrinsics; }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    // renders the world at the given full robot set state.  Rays are cast in parallel over pixels.
+    pub fn render(&self, robot_world: &mut RobotWorld, full_state_vec: &DVector<f64>) -> Result<DepthCameraRenderResult, String> {
+        let fk_res = robot_world.get_robot_set_ref().compute_fk(full_state_vec)?;
+        let camera_pose = self.get_camera_pose(&fk_res)?;
+
+        robot_world.set_poses_on_links(&fk_res, &self._link_geometry_type);
+
+        // link collision objects are copied so the mount link can be switched off without touching the robot world
+        let mut robot_link_groups: Vec<Vec<Vec<CollisionObject>>> = Vec::new();
+        let mut robot_link_names: Vec<Vec<String>> = Vec::new();
+        for robot in robot_world.get_robot_set_ref().get_robots_ref() {
+            robot_link_groups.push(robot.get_core_collision_module_ref().get_link_geometry_collision_objects_ref(&self._link_geometry_type).clone());
+            robot_link_names.push(robot.get_configuration_module_ref().robot_model_module.links.iter().map(|x| x.name.clone()).collect());
+        }
+        if let VirtualDepthCameraMount::Link { robot_idx, link_idx, .. } = &self._mount {
+            if *link_idx < robot_link_groups[*robot_idx].len() {
+                robot_link_groups[*robot_idx][*link_idx].iter_mut().for_each(|x| x.active = false);
+            }
+        }
+
+        let collision_environment = robot_world.get_collision_environment_option_ref();
+
+        let width = self._intrinsics.width;
+        let height = self._intrinsics.height;
+        let min_range = self._min_range;
+        let max_range = self._max_range;
+        let origin = Point3::from(camera_pose.translation.clone());
+
+        let hits: Vec<Option<(f64, Vector3<f64>, DepthCameraHitLabel)>> = (0..width * height).into_par_iter().map(|idx| {
+            let u = idx % width;
+            let v = idx / width;
+            let direction = camera_pose.quat * self._intrinsics.get_pixel_ray_direction(u, v);
+            // the ray starts at the min range so that nothing closer can block it
+            let start = origin + direction * min_range;
+            let max_toi = max_range - min_range;
+
+            let mut best: Option<(f64, Vector3<f64>, DepthCameraHitLabel)> = None;
+            let mut best_toi = max_toi;
+
+            for (r, group) in robot_link_groups.iter().enumerate() {
+                let hit = ray_cast_between_multiple_collision_objects(group, &start, &direction, best_toi);
+                if hit.is_some() {
+                    let (idxs, intersection) = hit.unwrap();
+                    best_toi = intersection.toi;
+                    best = Some((intersection.toi, intersection.normal, DepthCameraHitLabel::RobotLink { robot_idx: r, link_idx: idxs[0], name: robot_link_names[r][idxs[0]].clone() }));
+                }
+            }
+
+            if collision_environment.is_some() {
+                let e = collision_environment.as_ref().unwrap();
+                let hit = ray_cast_between_multiple_collision_objects(&e.environment_obbs, &start, &direction, best_toi);
+                if hit.is_some() {
+                    let (idxs, intersection) = hit.unwrap();
+                    best = Some((intersection.toi, intersection.normal, DepthCameraHitLabel::EnvironmentObject { object_idx: idxs[0], name: e.object_names[idxs[0]].clone() }));
+                }
+            }
+
+            best.map(|(toi, normal, label)| (toi + min_range, normal, label))
+        }).collect();
+
+        let mut depths = Vec::with_capacity(hits.len());
+        let mut points = Vec::with_capacity(hits.len());
+        let mut normals = Vec::with_capacity(hits.len());
+        let mut labels = Vec::with_capacity(hits.len());
+        for (idx, h) in hits.into_iter().enumerate() {
+            match h {
+                Some((depth, normal, label)) => {
+                    let direction = camera_pose.quat * self._intrinsics.get_pixel_ray_direction(idx % width, idx / width);
+                    depths.push(Some(depth));
+                    points.push(Some(camera_pose.translation + direction * depth));
+                    normals.push(Some(normal));
+                    labels.push(Some(label));
+                }
+                None => {
+                    depths.push(None);
+                    points.push(None);
+                    normals.push(None);
+                    labels.push(None);
+                }
+            }
+        }
+
+        return Ok(DepthCameraRenderResult { _width: width, _height: height, _camera_pose: camera_pose, _depths: depths, _points: points, _normals: normals, _labels: labels });
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// all per-pixel vectors are row major (idx = v * width + u), with None where the ray did not hit anything in range
+#[derive(Clone, Debug)]
+pub struct DepthCameraRenderResult {
+    _width: usize,
+    _height: usize,
+    _camera_pose: ImplicitDualQuaternion,
+    _depths: Vec<Option<f64>>,
+    _points: Vec<Option<Vector3<f64>>>, // world frame
+    _normals: Vec<Option<Vector3<f64>>>, // world frame
+    _labels: Vec<Option<DepthCameraHitLabel>>
+}
+
+impl DepthCameraRenderResult {
+    pub fn get_depth(&self, u: usize, v: usize) -> Option<f64> {
+        if u >= self._width || v >= self._height { return None; }
+        return self._depths[v * self._width + u];
+    }
+
+    pub fn get_label(&self, u: usize, v: usize) -> Option<&DepthCameraHitLabel> {
+        if u >= self._width || v >= self._height { return None; }
+        return self._labels[v * self._width + u].as_ref();
+    }
+
+    pub fn get_num_hits(&self) -> usize {
+        return self._depths.iter().filter(|x| x.is_some()).count();
+    }
+
+    // one point (with normal) per pixel that hit something, in the world frame or the camera frame
+    pub fn to_point_cloud(&self, in_camera_frame: bool) -> PointCloud {
+        let inverse_pose = self._camera_pose.inverse();
+        let mut points = Vec::new();
+        let mut normals = Vec::new();
+        for i in 0..self._points.len() {
+            if self._points[i].is_none() { continue; }
+            if in_camera_frame {
+                points.push(inverse_pose.multiply_by_vector3(self._points[i].as_ref().unwrap()));
+                normals.push(inverse_pose.quat * self._normals[i].as_ref().unwrap());
+            } else {
+                points.push(self._points[i].unwrap());
+                normals.push(self._normals[i].unwrap());
+            }
+        }
+        return PointCloud::new_with_normals_and_colors(points, Some(normals), None).unwrap();
+    }
+
+    // 16 bit binary pgm with depth in millimeters (0 where nothing was hit), the format most depth datasets use
+    pub fn output_depth_image_to_pgm(&self, out_fp: String) -> Result<(), String> {
+        let mut bytes: Vec<u8> = format!("P5\n{} {}\n65535\n", self._width, self._height).into_bytes();
+        for d in &self._depths {
+            let mm = match d { Some(d) => (d * 1000.0).round().min(65535.0) as u16, None => 0 };
+            bytes.extend_from_slice(&mm.to_be_bytes());
+        }
+
+        let f = File::create(&out_fp);
+        if f.is_err() { return Err(format!("could not create file {:?}", out_fp)); }
+        let res = f.unwrap().write_all(&bytes);
+        if res.is_err() { return Err(format!("could not write depth image to {:?}", out_fp)); }
+        return Ok(());
+    }
+
+    pub fn get_width(&self) -> usize { return self._width; }
+
+    pub fn get_height(&self) -> usize { return self._height; }
+
+    pub fn get_camera_pose_ref(&self) -> &ImplicitDualQuaternion { return &self._camera_pose; }
+
+    pub fn get_depths_ref(&self) -> &Vec<Option<f64>> { return &self._depths; }
+
+    pub fn get_points_ref(&self) -> &Vec<Option<Vector3<f64>>> { return &self._points; }
+
+    pub fn get_normals_ref(&self) -> &Vec<Option<Vector3<f64>>> { return &self._normals; }
+
+    pub fn get_labels_ref(&self) -> &Vec<Option<DepthCameraHitLabel>> { return &self._labels; }
+}
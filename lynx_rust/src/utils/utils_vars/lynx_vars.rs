@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use crate::utils::utils_collisions::{collision_environment::CollisionEnvironment, collision_multiple_results::*};
 use crate::robot_modules::{robot_world::RobotWorld, robot::Robot};
 use crate::utils::utils_image_environments::image_environment::ImageEnvironment;
+use crate::utils::utils_collisions::signed_distance_field::SignedDistanceField;
 use crate::robot_modules::robot_fk_module::*;
 use crate::utils::utils_collisions::collision_object_group_queries::*;
 use nalgebra::{DVector};
@@ -22,6 +23,7 @@ pub struct LynxVars {
     pub CollisionEnvironment: (Vec<CollisionEnvironment>, HashMap<&'static str, usize>, usize),
     pub CollisionEnvironmentOption: (Vec<Option<CollisionEnvironment>>, HashMap<&'static str, usize>, usize),
     pub ImageEnvironment: (Vec<ImageEnvironment>, HashMap<&'static str, usize>, usize),
+    pub SignedDistanceField: (Vec<SignedDistanceField>, HashMap<&'static str, usize>, usize),
     pub RobotFKResult: (Vec<RobotFKResult>, HashMap<&'static str, usize>, usize),
     pub RobotFKResultVec: (Vec<Vec<RobotFKResult>>, HashMap<&'static str, usize>, usize),
     pub RobotFKGradientPerturbationsResult: (Vec<RobotFKGradientPerturbationsResult>, HashMap<&'static str, usize>, usize),
@@ -53,6 +55,7 @@ impl LynxVars{
             CollisionEnvironment: (Vec::new(), HashMap::new(), 0),
             CollisionEnvironmentOption: (Vec::new(), HashMap::new(), 0),
             ImageEnvironment: (Vec::new(), HashMap::new(), 0),
+            SignedDistanceField: (Vec::new(), HashMap::new(), 0),
             RobotFKResult: (Vec::new(), HashMap::new(), 0),
             RobotFKResultVec: (Vec::new(), HashMap::new(), 0),
             RobotFKGradientPerturbationsResult: (Vec::new(), HashMap::new(), 0),